@@ -49,15 +49,15 @@ fn main() {
         outer_iters: 2,
     };
 
-    let out = balance_idle_genre(core_env, curve_env, prestige_env, (), tgt, cfg);
+    let out = balance_idle_genre(core_env, curve_env, prestige_env, (), tgt, cfg, IdleGenreHooks::default());
 
     println!("== Idle Genre Outcome ==");
     println!("Core   θ -> {:?}", out.core.theta);
-    println!("Core   π -> {:?}", out.core.obs);
+    println!("Core     -> {}", out.core.explain());
     println!("Curve  θ -> {:?}", out.curve.theta);
-    println!("Curve  π -> {:?}", out.curve.obs);
+    println!("Curve    -> {}", out.curve.explain());
     println!("Prest  θ -> {:?}", out.prestige.theta);
-    println!("Prest  π -> {:?}", out.prestige.obs);
+    println!("Prest    -> {}", out.prestige.explain());
     println!("Offline θ -> {:?}", out.offline.theta);
-    println!("Offline π -> {:?}", out.offline.obs);
+    println!("Offline   -> {}", out.offline.explain());
 }
@@ -9,7 +9,7 @@ use game_balance::genres::idle::*;
 use game_balance::systems::{
     production_spend as ps,
     draft_choice as draft,
-    sdk::{Hook, NominalTargets, TargetAdjust},
+    sdk::{Hook, MultMode, NominalTargets, Signals, TargetAdjust},
 };
 
 // ---------- Adapter: dyn Hook -> dyn ps::Mechanic ----------
@@ -19,6 +19,18 @@ impl Hook<ps::Params, ps::Env, ps::Targets, ps::Obs> for HookAdapter {
     fn income_multiplier(&mut self, base: f64, th: &ps::Params, env: &ps::Env) -> f64 {
         self.0.income_multiplier(base, th, env)
     }
+    fn multiplier_mode(&self) -> MultMode {
+        self.0.multiplier_mode()
+    }
+    fn cost_multiplier(&mut self, th: &ps::Params, env: &ps::Env) -> f64 {
+        self.0.cost_multiplier(th, env)
+    }
+    fn flat_income(&mut self, th: &ps::Params, env: &ps::Env) -> f64 {
+        self.0.flat_income(th, env)
+    }
+    fn priority(&self) -> i32 {
+        self.0.priority()
+    }
     fn on_observe(&mut self, o: &ps::Obs, th: &ps::Params, env: &ps::Env, tgt: &ps::Targets) {
         self.0.on_observe(o, th, env, tgt)
     }
@@ -31,6 +43,18 @@ impl Hook<ps::Params, ps::Env, ps::Targets, ps::Obs> for HookAdapter {
     ) -> TargetAdjust {
         self.0.adjust_targets(th, env, tgt, nom)
     }
+    fn adjust_mode(&self) -> MultMode {
+        self.0.adjust_mode()
+    }
+    fn post_step(&mut self, th: &mut ps::Params, env: &ps::Env) {
+        self.0.post_step(th, env)
+    }
+    fn project(&mut self, th: &mut ps::Params, env: &ps::Env) {
+        self.0.project(th, env)
+    }
+    fn on_signals(&mut self, signals: &mut Signals) {
+        self.0.on_signals(signals)
+    }
 }
 // Blanket impl in ps turns any Hook into a Mechanic automatically.
 // (ps::Mechanic: Hook<...>; impl<T: Hook<...>> Mechanic for T {})
@@ -157,11 +181,11 @@ fn main() {
 
     println!("== Idle + Draft Outcome ==");
     println!("Core   θ -> {:?}", out.core.theta);
-    println!("Core   π -> {:?}", out.core.obs);
+    println!("Core     -> {}", out.core.explain());
     println!("Curve  θ -> {:?}", out.curve.theta);
-    println!("Curve  π -> {:?}", out.curve.obs);
+    println!("Curve    -> {}", out.curve.explain());
     println!("Prest  θ -> {:?}", out.prestige.theta);
-    println!("Prest  π -> {:?}", out.prestige.obs);
+    println!("Prest    -> {}", out.prestige.explain());
     println!("Offline θ -> {:?}", out.offline.theta);
-    println!("Offline π -> {:?}", out.offline.obs);
+    println!("Offline   -> {}", out.offline.explain());
 }
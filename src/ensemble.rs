@@ -0,0 +1,78 @@
+//! Parallel seeded replicate runs (`parallel` feature).
+//!
+//! `refine_ensemble` runs the same refinement under M different seeds on
+//! rayon's thread pool and summarizes where the final θ and π land, which
+//! shows how stable a balance point is under RNG noise.
+
+use crate::{Flat, stream_rng};
+use rand_core::RngCore;
+use rayon::prelude::*;
+
+/// Distribution of one coordinate across the ensemble.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Summary {
+    pub mean: f64,
+    /// Sample standard deviation (0 for a single run).
+    pub stddev: f64,
+    /// All values, ascending.
+    pub sorted: Vec<f64>,
+}
+
+impl Summary {
+    pub fn from_values(mut values: Vec<f64>) -> Self {
+        values.sort_by(f64::total_cmp);
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let stddev = if values.len() < 2 {
+            0.0
+        } else {
+            (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+        };
+        Self { mean, stddev, sorted: values }
+    }
+
+    /// Linear-interpolated quantile, `p` in `[0, 1]`.
+    pub fn quantile(&self, p: f64) -> f64 {
+        let last = self.sorted.len().saturating_sub(1);
+        let pos = p.clamp(0.0, 1.0) * last as f64;
+        let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+        let w = pos - lo as f64;
+        self.sorted[lo] * (1.0 - w) + self.sorted[hi] * w
+    }
+}
+
+/// Per-coordinate summaries of the final θ and π over all runs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ensemble {
+    pub theta: Vec<Summary>,
+    pub pi: Vec<Summary>,
+    pub runs: usize,
+}
+
+/// Run `run(seed_i)` for `i in 0..runs` in parallel and summarize the final
+/// `(θ, π)` pairs. `seed_i` is drawn from `stream_rng(seed, i)`, so results
+/// depend only on `seed`, never on thread scheduling. `run` is typically a
+/// closure around `refine_stoch` that also measures the final θ.
+///
+/// # Panics
+/// If `runs == 0`.
+pub fn refine_ensemble<Theta, Pi, Run>(runs: usize, seed: u64, run: Run) -> Ensemble
+where
+    Theta: Flat + Send,
+    Pi: Flat + Send,
+    Run: Fn(u64) -> (Theta, Pi) + Sync,
+{
+    assert!(runs > 0, "an ensemble needs at least one run");
+    let (thetas, pis): (Vec<Vec<f64>>, Vec<Vec<f64>>) = (0..runs)
+        .into_par_iter()
+        .map(|i| {
+            let (th, pi) = run(stream_rng(seed, i as u64).next_u64());
+            (th.flatten(), pi.flatten())
+        })
+        .unzip();
+    Ensemble { theta: columns(&thetas), pi: columns(&pis), runs }
+}
+
+fn columns(rows: &[Vec<f64>]) -> Vec<Summary> {
+    (0..rows[0].len()).map(|j| Summary::from_values(rows.iter().map(|r| r[j]).collect())).collect()
+}
@@ -0,0 +1,120 @@
+//! Crate error type for configuration checks and loading saved data.
+
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /// A `[min, max]` pair is non-finite or has `min > max`.
+    InvalidBounds { field: &'static str, min: f64, max: f64 },
+    /// [`Validate`] found impossible inputs; every problem is listed.
+    Invalid(Vec<Problem>),
+    /// Serialized data is not valid JSON for the expected shape.
+    #[cfg(feature = "serde")]
+    Json(String),
+    /// Serialized data carries a schema version this crate cannot read.
+    #[cfg(feature = "serde")]
+    SchemaVersion { found: u64, supported: u64 },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidBounds { field, min, max } => {
+                write!(f, "invalid bounds for `{field}`: min {min}, max {max} (need finite min ≤ max)")
+            }
+            Error::Invalid(problems) => {
+                write!(f, "invalid configuration:")?;
+                for p in problems {
+                    write!(f, " `{}` {};", p.field, p.reason)?;
+                }
+                Ok(())
+            }
+            #[cfg(feature = "serde")]
+            Error::Json(msg) => write!(f, "malformed JSON: {msg}"),
+            #[cfg(feature = "serde")]
+            Error::SchemaVersion { found, supported } => {
+                write!(f, "schema_version {found} is newer than supported ({supported})")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Validate one `[min, max]` pair.
+pub(crate) fn check_range(field: &'static str, min: f64, max: f64) -> Result<(), Error> {
+    if min.is_finite() && max.is_finite() && min <= max {
+        Ok(())
+    } else {
+        Err(Error::InvalidBounds { field, min, max })
+    }
+}
+
+/// One impossible input found by [`Validate`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Problem {
+    pub field: &'static str,
+    pub reason: String,
+}
+
+/// Up-front checks for a system's `Env`, `Targets` and `Bounds`, so an
+/// impossible configuration fails with every problem named instead of
+/// running `max_iters` toward a target it cannot reach.
+pub trait Validate {
+    /// Everything wrong with `self` (empty if valid).
+    fn problems(&self) -> Vec<Problem>;
+
+    fn validate(&self) -> Result<(), Error> {
+        let problems = self.problems();
+        if problems.is_empty() { Ok(()) } else { Err(Error::Invalid(problems)) }
+    }
+}
+
+/// Validate several parts together, reporting the problems of all of them.
+pub fn validate_all(parts: &[&dyn Validate]) -> Result<(), Error> {
+    let problems: Vec<Problem> = parts.iter().flat_map(|p| p.problems()).collect();
+    if problems.is_empty() { Ok(()) } else { Err(Error::Invalid(problems)) }
+}
+
+/// Collects [`Problem`]s for a [`Validate`] impl:
+/// `Checks::default().positive("rate", self.rate).done()`.
+#[derive(Default)]
+pub struct Checks(Vec<Problem>);
+
+impl Checks {
+    pub fn positive(mut self, field: &'static str, x: f64) -> Self {
+        if !(x.is_finite() && x > 0.0) {
+            self.0.push(Problem { field, reason: format!("must be finite and > 0 (got {x})") });
+        }
+        self
+    }
+    pub fn non_negative(mut self, field: &'static str, x: f64) -> Self {
+        if !(x.is_finite() && x >= 0.0) {
+            self.0.push(Problem { field, reason: format!("must be finite and ≥ 0 (got {x})") });
+        }
+        self
+    }
+    /// `x` in `[lo, hi]`.
+    pub fn within(mut self, field: &'static str, x: f64, lo: f64, hi: f64) -> Self {
+        if !(lo..=hi).contains(&x) {
+            self.0.push(Problem { field, reason: format!("must be in [{lo}, {hi}] (got {x})") });
+        }
+        self
+    }
+    /// A `(min, max)` pair, as [`check_range`] checks it.
+    pub fn range(mut self, field: &'static str, min: f64, max: f64) -> Self {
+        if check_range(field, min, max).is_err() {
+            self.0.push(Problem { field, reason: format!("needs finite min ≤ max (got [{min}, {max}])") });
+        }
+        self
+    }
+    pub fn check(mut self, field: &'static str, ok: bool, reason: &str) -> Self {
+        if !ok {
+            self.0.push(Problem { field, reason: reason.into() });
+        }
+        self
+    }
+    pub fn done(self) -> Vec<Problem> {
+        self.0
+    }
+}
@@ -10,12 +10,14 @@
 //! - offline_accumulation     → AFK retain ratio
 //!
 //! You can inject *core* mechanics (e.g., draft-picked effects) via
-//! [`IdleGenreHooks::core_mechs`]. These are passed into the `production_spend`
-//! system on the first outer iteration. Subsequent iterations run without
-//! consuming them again, avoiding the need for `Clone` on trait objects.
+//! [`IdleGenreHooks::core_mechs`]. They run in the `production_spend` system
+//! on every outer iteration; each pass warm-starts from the previous one, so
+//! the same hook objects (and their internal caches) carry over without
+//! needing `Clone` on trait objects.
 
 use crate::genres::sdk::{run_with_outer_iters, Signals};
-use crate::systems::sdk::Outcome;
+use crate::mechanics::control::lerp;
+use crate::systems::sdk::{BalanceArena, Outcome};
 use crate::systems::{
     offline_accumulation as off,
     production_spend as ps,
@@ -43,6 +45,60 @@ pub struct IdleGenreTargets {
     pub typical_afk_minutes: f64,
 }
 
+impl IdleGenreTargets {
+    /// Easy endpoint (difficulty 0): 15 s TTU, 85% util, ×3 growth,
+    /// 4–6 s per level (+10%/level), 15 min ×5 prestige, 80% AFK retain over 2 h.
+    pub fn easy() -> Self {
+        Self {
+            ttu_target_secs: 15.0,
+            util_target: 0.85,
+            growth_target: 3.0,
+            ttu_band_per_level: (4.0, 6.0),
+            ttu_slope_pref: 1.10,
+            prestige_cycle_minutes: 15.0,
+            prestige_growth: 5.0,
+            offline_retain_ratio: 0.80,
+            typical_afk_minutes: 120.0,
+        }
+    }
+
+    /// Hard endpoint (difficulty 1): 60 s TTU, 95% util, ×8 growth,
+    /// 15–20 s per level (+25%/level), 40 min ×20 prestige, 50% AFK retain over 8 h.
+    pub fn hard() -> Self {
+        Self {
+            ttu_target_secs: 60.0,
+            util_target: 0.95,
+            growth_target: 8.0,
+            ttu_band_per_level: (15.0, 20.0),
+            ttu_slope_pref: 1.25,
+            prestige_cycle_minutes: 40.0,
+            prestige_growth: 20.0,
+            offline_retain_ratio: 0.50,
+            typical_afk_minutes: 480.0,
+        }
+    }
+
+    /// Single-knob difficulty dial: interpolates every target between
+    /// [`easy`](Self::easy) (`d = 0`) and [`hard`](Self::hard) (`d = 1`).
+    pub fn from_difficulty(d: f64) -> Self {
+        let (e, h) = (Self::easy(), Self::hard());
+        Self {
+            ttu_target_secs: lerp(e.ttu_target_secs, h.ttu_target_secs, d),
+            util_target: lerp(e.util_target, h.util_target, d),
+            growth_target: lerp(e.growth_target, h.growth_target, d),
+            ttu_band_per_level: (
+                lerp(e.ttu_band_per_level.0, h.ttu_band_per_level.0, d),
+                lerp(e.ttu_band_per_level.1, h.ttu_band_per_level.1, d),
+            ),
+            ttu_slope_pref: lerp(e.ttu_slope_pref, h.ttu_slope_pref, d),
+            prestige_cycle_minutes: lerp(e.prestige_cycle_minutes, h.prestige_cycle_minutes, d),
+            prestige_growth: lerp(e.prestige_growth, h.prestige_growth, d),
+            offline_retain_ratio: lerp(e.offline_retain_ratio, h.offline_retain_ratio, d),
+            typical_afk_minutes: lerp(e.typical_afk_minutes, h.typical_afk_minutes, d),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct IdleGenreConfig {
     pub max_iters_per_system: usize,
@@ -69,6 +125,65 @@ pub struct IdleGenreOutcome {
     pub offline:  Outcome<off::Params, off::Obs>,
 }
 
+impl IdleGenreOutcome {
+    /// Graphviz DOT view of the orchestration: one node per system (green if
+    /// converged, red otherwise) and the `ref_income` signal edges from core.
+    pub fn to_dot(&self) -> String {
+        fn node(id: &str, system: &str, converged: bool, iters: usize) -> String {
+            let (status, color) = if converged { ("converged", "palegreen") } else { ("not converged", "lightpink") };
+            format!(
+                "  {id} [label=\"{system}\\n{status} ({iters} iters)\", style=filled, fillcolor={color}];\n"
+            )
+        }
+        let ref_income = (self.core.theta.gen_per_sec * self.core.theta.multiplier).max(0.0);
+
+        let mut dot = String::from("digraph idle_genre {\n  rankdir=LR;\n  node [shape=box];\n");
+        dot += &node("core", "production_spend", self.core.converged, self.core.iters);
+        dot += &node("curve", "upgrade_cost_curve", self.curve.converged, self.curve.iters);
+        dot += &node("prestige", "reset_prestige", self.prestige.converged, self.prestige.iters);
+        dot += &node("offline", "offline_accumulation", self.offline.converged, self.offline.iters);
+        dot += &format!("  core -> curve [label=\"ref_income = {ref_income:.3}\"];\n");
+        dot += &format!("  core -> prestige [label=\"ref_income = {ref_income:.3}\"];\n");
+        dot += "  core -> offline [style=dotted, label=\"order only\"];\n";
+        dot += "}\n";
+        dot
+    }
+
+    /// Genre score: sum of each system's unitless `normalized_error`
+    /// (0 = every system on target). Raw errors mix seconds, ratios and
+    /// multipliers, so only these normalized terms are summed.
+    pub fn normalized_error(&self, tgt: &IdleGenreTargets) -> f64 {
+        ps::normalized_error(
+            &self.core.obs,
+            &ps::Targets {
+                ttu_target: tgt.ttu_target_secs,
+                util_target: tgt.util_target,
+                growth_target: tgt.growth_target,
+                weights: ps::TargetWeights::default(),
+            },
+        ) + ucc::normalized_error(
+            &self.curve.obs,
+            &ucc::Targets { ttu_band: tgt.ttu_band_per_level, slope_pref: tgt.ttu_slope_pref },
+        ) + pr::normalized_error(
+            &self.prestige.obs,
+            &pr::Targets { cycle_minutes: tgt.prestige_cycle_minutes, reward_growth: tgt.prestige_growth },
+        ) + off::normalized_error(&self.offline.obs, &off::Targets { retain_ratio: tgt.offline_retain_ratio })
+    }
+}
+
+/// Projected income over a day: online play at the core income rate plus AFK
+/// time earning that rate scaled by the retain curve over the span (see
+/// [`off::retained_minutes`]; nothing accrues past the offline cap).
+pub fn daily_income(
+    online: &ps::Params,
+    offline: &off::Params,
+    online_minutes: f64,
+    afk_minutes: f64,
+) -> f64 {
+    let per_minute = (online.gen_per_sec * online.multiplier).max(0.0) * 60.0;
+    per_minute * (online_minutes.max(0.0) + off::retained_minutes(offline, afk_minutes))
+}
+
 pub fn balance_idle_genre(
     core_env: ps::Env,
     curve_env: ucc::Env,
@@ -79,7 +194,13 @@ pub fn balance_idle_genre(
     hooks: IdleGenreHooks,
 ) -> IdleGenreOutcome {
     // Seeds (could also be provided by caller)
-    let mut core_theta     = ps::Params  { gen_per_sec: 10.0, spend_rate: 10.0, multiplier: 1.0 };
+    let core_tgt = ps::Targets {
+        ttu_target: tgt.ttu_target_secs,
+        util_target: tgt.util_target,
+        growth_target: tgt.growth_target,
+        weights: ps::TargetWeights::default(),
+    };
+    let mut core_theta     = ps::steady_state_seed(&core_env, &core_tgt);
     let mut curve_theta    = ucc::Params { base: 10.0, growth: 1.15, track_mult: 1.0 };
     let mut prestige_theta = pr::Params  { reward_mult: 1.0, decay: 0.02, req_score: 1_000.0 };
     let mut offline_theta  = off::Params { cap_minutes: 12.0 * 60.0, decay: 0.02, efficiency: 0.6 };
@@ -88,28 +209,23 @@ pub fn balance_idle_genre(
     let (mut last_core, mut last_curve, mut last_prestige, mut last_offline) =
         (None, None, None, None);
 
-    // We consume core_mechs on the first outer-iter; then run without them.
-    // This avoids requiring Clone on Box<dyn Mechanic>.
-    let mut core_mechs_once: Option<Vec<Box<dyn ps::Mechanic>>> = Some(hooks.core_mechs);
+    // Core mechanics move into each pass and come back out of the core
+    // arena as a warm start, state intact.
+    let mut core_mechs: Vec<Box<dyn ps::Mechanic>> = hooks.core_mechs;
+    let mut core_arena = BalanceArena::new();
 
     // One outer-loop step: run all systems once and update `Signals`.
     let step = |signals_in: Signals| {
-        // 1) Core production/spend — defines ref_income for the pass.
-        let mechs_for_this_pass = core_mechs_once.take().unwrap_or_default();
-        let core_out = ps::balance_ext(
-            core_theta,
-            core_env,
-            ps::Targets {
-                ttu_target: tgt.ttu_target_secs,
-                util_target: tgt.util_target,
-                growth_target: tgt.growth_target,
-            },
-            ps::Bounds::soft_defaults(),
-            ps::Gains::default(),
-            mechs_for_this_pass,
-            cfg.max_iters_per_system,
-        );
-        core_theta = core_out.theta;
+        // 1) Core production/spend — defines ref_income for the pass. Its
+        //    hooks see the incoming signals (e.g. last pass's prestige cycle).
+        *core_arena.signals_mut() = signals_in;
+        let core_out = ps::Runner::new(core_theta, core_env, core_tgt)
+            .hooks(std::mem::take(&mut core_mechs))
+            .max_iters(cfg.max_iters_per_system)
+            .run_in(&mut core_arena);
+        let warm = core_out.clone().into_warm_start(&mut core_arena);
+        core_theta = warm.theta;
+        core_mechs = warm.hooks.into_iter().map(|h| Box::new(h) as Box<dyn ps::Mechanic>).collect();
         last_core = Some(core_out.clone());
 
         // The *new* reference income from the core system:
@@ -125,48 +241,37 @@ pub fn balance_idle_genre(
         };
 
         // 2) Upgrade cost curve — consumes ref_income signal.
-        let curve_out = ucc::balance_ext(
-            curve_theta,
-            curve_env,
-            ucc::Targets { ttu_band: tgt.ttu_band_per_level, slope_pref: tgt.ttu_slope_pref },
-            ucc::Bounds::soft(),
-            ucc::Gains::default(),
-            Vec::<Box<dyn ucc::Mechanic>>::new(),
-            cfg.max_iters_per_system,
-            ref_income_for_downstream,
-        );
+        let curve_tgt = ucc::Targets { ttu_band: tgt.ttu_band_per_level, slope_pref: tgt.ttu_slope_pref };
+        let curve_out = ucc::Runner::new(curve_theta, curve_env, curve_tgt, ref_income_for_downstream)
+            .max_iters(cfg.max_iters_per_system)
+            .run();
         curve_theta = curve_out.theta;
         last_curve = Some(curve_out.clone());
 
         // 3) Prestige — consumes ref_income signal.
-        let prestige_out = pr::balance_ext(
-            prestige_theta,
-            prestige_env,
-            pr::Targets { cycle_minutes: tgt.prestige_cycle_minutes, reward_growth: tgt.prestige_growth },
-            pr::Bounds::soft(),
-            pr::Gains::default(),
-            Vec::<Box<dyn pr::Mechanic>>::new(),
-            cfg.max_iters_per_system,
-            ref_income_for_downstream,
-        );
+        let prestige_tgt = pr::Targets { cycle_minutes: tgt.prestige_cycle_minutes, reward_growth: tgt.prestige_growth };
+        let prestige_out = pr::Runner::new(prestige_theta, prestige_env, prestige_tgt, ref_income_for_downstream)
+            .max_iters(cfg.max_iters_per_system)
+            .run();
         prestige_theta = prestige_out.theta;
         last_prestige = Some(prestige_out.clone());
 
         // 4) Offline — independent in this simple model.
-        let offline_out = off::balance_ext(
+        let offline_out = off::Runner::new(
             offline_theta,
             off::Env { typical_afk_minutes: tgt.typical_afk_minutes },
             off::Targets { retain_ratio: tgt.offline_retain_ratio },
-            off::Bounds::soft(),
-            off::Gains::default(),
-            Vec::<Box<dyn off::Mechanic>>::new(),
-            cfg.max_iters_per_system,
-        );
+        )
+        .max_iters(cfg.max_iters_per_system)
+        .run();
         offline_theta = offline_out.theta;
         last_offline = Some(offline_out.clone());
 
-        // Signals OUT for the next outer pass (expose the fresh core value).
-        let signals_out = Signals { ref_income: ref_income_cur };
+        // Signals OUT for the next outer pass: what core hooks published,
+        // with the fresh core income and prestige cycle on top.
+        let mut signals_out = *core_arena.signals();
+        signals_out.ref_income = ref_income_cur;
+        signals_out.cycle_minutes = prestige_out.obs.cycle_mins;
 
         // Return some Outcome (SDK runner wants one). Core is representative.
         (signals_out, core_out)
@@ -26,7 +26,8 @@
 //!   `roguelike` genres.
 //! - `Signals` provides a light way to pass shared quantities (like reference
 //!   income, cycle length, or winrate) between systems. Extend it only if you
-//!   really need more fields.
+//!   really need more fields. Hooks see it through `Hook::on_signals` when a
+//!   system runs in a `BalanceArena` seeded with `with_signals`.
 //! - The `run_with_outer_iters` helper standardizes multi-pass balancing when
 //!   you need systems to converge together. Each step returns both an `Outcome`
 //!   and updated `Signals` for the next pass.
@@ -47,12 +48,8 @@
 
 use crate::systems::sdk::Outcome;
 
-/// Shared signals you may pass around between systems in a genre pass.
-/// Add fields only when you actually need them.
-#[derive(Clone, Copy, Debug, Default)]
-pub struct Signals {
-    pub ref_income: f64,
-}
+/// Lives with the hook protocol so hooks can read it; re-exported here.
+pub use crate::systems::sdk::Signals;
 
 /// Minimal step result to thread through the orchestrator loop.
 #[derive(Clone, Debug)]
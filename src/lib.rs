@@ -9,18 +9,74 @@ What it does
 - Repeats until a caller-supplied stopping predicate holds.
 
 How to use (call surface only)
-- Provide an initial parameter token `Params` as θ₀.
-- Provide four pure functions with these signatures:
-  * `simulate : &Params -> Data`
-  * `measure  : &Data -> Metrics`
-  * `update   : (&Params, &Metrics) -> Params`
-  * `converged: (&Params, &Params) -> bool`
-- Call `refine_det(θ₀, simulate, measure, update, converged, max_iters) -> Params`.
+- Pick your own types for Θ, D and Π (any structs; no cells needed) and
+  provide θ₀ plus four functions with these signatures:
+  * `simulate : &Θ -> D`
+  * `measure  : &D -> Π`
+  * `update   : (&Θ, &Π) -> Θ`
+  * `converged: (&Θ, &Θ) -> bool`
+- Call `refine_det(θ₀, simulate, measure, update, converged, max_iters)`.
+  It returns a `RefineReport`: the final θ plus how many iterations ran,
+  the last π, and whether `converged` fired or `max_iters` ran out.
+- To log or plot a run, `refine_observed` takes the same closures plus
+  `on_iter(t, &θ_t, &π_t)`, called once per iteration before `update`.
+- The unit markers `Params`/`Data`/`Metrics` remain for callers that keep
+  their state outside the loop in captured cells.
+- For step-at-a-time control (game loops, event loops), wrap the same
+  closures in `RefineIter::new(θ₀, simulate, measure, update)` and pull θ
+  with `next()`.
+- `refine_guarded` checks θ and π for NaN/∞ every step (via the
+  `AllFinite` trait) and reports `RefineStatus::Diverged { iter }`.
+- `refine_resumable` hands a `Checkpoint` (θ, iteration, seed) to a
+  caller-supplied sink every N iterations; feeding the last one back in
+  continues the run exactly where it stopped.
+- `refine_det_async` awaits `simulate` (a headless game server, a
+  database) each iteration; any executor works, and steps stay strictly
+  sequential, so its `RefineReport` matches `refine_det`'s.
+- `refine_traced` runs the same loop and also returns every `(θ_t, π_t)`.
+- When a step can fail, `try_refine_det` takes `Result`-returning closures
+  and stops at the first `Err`, reporting which iteration failed.
+- For Monte Carlo simulations, `refine_stoch(θ₀, seed, …)` hands `simulate`
+  a fresh `WyRand` per iteration, derived from `(seed, iter)` only, so any
+  run (or single iteration) replays exactly. `refine_replicated` runs K
+  seeded replicates per iteration, averages their metrics, and converges
+  only when the mean is confidently inside the band.
+- `with_momentum(update, β)` wraps any `update` with a heavy-ball term,
+  `θ_{t+1} = update(θ_t, π_t) + β·(θ_t − θ_{t−1})`, for θ types that
+  implement `Extrapolate`.
+- `with_projection(update, p)` applies a `Project` constraint after every
+  update, so θ never leaves a feasible set (e.g. `spend ≤ gen·mult`).
+- `refine_with(…, &RefineOptions)` is `refine_det` with opt-in
+  accelerators and safeguards for θ types that implement `Flat` (damping
+  for chattery loops; Anderson mixing for slow, smooth fixed points; a
+  per-step trust-region cap).
+- `refine_anneal` is simulated annealing over the same simulate/measure
+  pair: a seeded `propose` step, a scalar `energy(π)` and the temperature
+  schedule in `Anneal` decide when a worse θ is accepted.
+- `refine_scalar(bracket, target, simulate, measure, tol, max_evals)`
+  solves one-knob, one-metric monotone problems (base cost → mean TTU) by
+  bracketed secant steps in tens of evaluations.
+- `refine_multistart` reruns any refinement from `n` seeded perturbations
+  of θ₀ and keeps the result with the lowest caller-supplied score.
+- `optim::refine_es` is a population search (evolution strategy) over a
+  flat `Vec<f64>` θ: it needs only a scalar loss and a `done(π)` check,
+  so it copes with non-smooth band predicates. With `optim-cmaes`,
+  `optim::refine_cmaes` adds CMA-ES over box-bounded θ for 10+ tunables.
+- With the `parallel` feature, `refine_ensemble` runs M seeded
+  refinements on rayon and summarizes the spread of the final θ and π.
+- With the `timing` feature, `refine_timed(…, max_iters, budget)` also stops
+  when a wall-clock `Duration` runs out.
+- `refine_until(…, &StopCondition)` takes the stopping rule as data
+  (iterations, wall-clock time, or any combination) and reports which
+  one ended the run in its `RefineStatus`. `refine_until_residual` adds a
+  residual so `StopCondition::Plateau` can stop loops that stop improving.
 
 What it does NOT do
-- No domain, no objectives, no randomness. You define those externally.
+- No domain, no objectives. Randomness only through the seeded streams of
+  `refine_stoch`; everything else you define externally.
 */
 
+/// Unit Θ for loops whose real state lives in captured cells.
 #[derive(Clone, Debug)]
 pub struct Params {}
 
@@ -30,33 +86,1169 @@ pub struct Data {}
 #[derive(Clone, Debug)]
 pub struct Metrics {}
 
+/// How a run of [`refine_det`] or one of its variants ended; every
+/// fixed-point `refine_*` loop returns one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RefineReport<Theta, Pi> {
+    /// Final parameters (the last finite ones on divergence).
+    pub theta: Theta,
+    /// Iterations started, i.e. `simulate` calls.
+    pub iters: usize,
+    /// π of the last iteration — the final residual when `measure` returns
+    /// gaps. `None` if no iteration ran.
+    pub residual: Option<Pi>,
+    pub status: RefineStatus,
+}
+
+impl<Theta, Pi> RefineReport<Theta, Pi> {
+    /// `true` if the `converged` predicate fired.
+    pub fn converged(&self) -> bool {
+        matches!(self.status, RefineStatus::Converged { .. })
+    }
+}
+
 /// Deterministic refinement: θ_{t+1} = update(θ_t, measure(simulate(θ_t))).
-pub fn refine_det<Sim, Meas, Upd, Conv>(
-    mut theta: Params,
+pub fn refine_det<Theta, D, Pi, Sim, Meas, Upd, Conv>(
+    theta: Theta,
+    simulate: Sim,
+    measure: Meas,
+    update: Upd,
+    converged: Conv,
+    max_iters: usize,
+) -> RefineReport<Theta, Pi>
+where
+    Sim: FnMut(&Theta) -> D,
+    Meas: FnMut(&D) -> Pi,
+    Upd: FnMut(&Theta, &Pi) -> Theta,
+    Conv: Fn(&Theta, &Theta) -> bool,
+{
+    refine_observed(theta, simulate, measure, update, converged, max_iters, |_, _, _| {})
+}
+
+/// [`refine_det`] that shows every `(t, θ_t, π_t)` to `on_iter` before
+/// `update` runs, for logging, plotting or recording a run without side
+/// effects in `update`.
+pub fn refine_observed<Theta, D, Pi, Sim, Meas, Upd, Conv, Obs>(
+    mut theta: Theta,
     mut simulate: Sim,
     mut measure: Meas,
     mut update: Upd,
     converged: Conv,
     max_iters: usize,
-) -> Params
+    mut on_iter: Obs,
+) -> RefineReport<Theta, Pi>
 where
-    Sim: FnMut(&Params) -> Data,
-    Meas: FnMut(&Data) -> Metrics,
-    Upd: FnMut(&Params, &Metrics) -> Params,
-    Conv: Fn(&Params, &Params) -> bool,
+    Sim: FnMut(&Theta) -> D,
+    Meas: FnMut(&D) -> Pi,
+    Upd: FnMut(&Theta, &Pi) -> Theta,
+    Conv: Fn(&Theta, &Theta) -> bool,
+    Obs: FnMut(usize, &Theta, &Pi),
 {
+    let mut residual = None;
+    for iter in 0..max_iters {
+        let data = simulate(&theta);
+        let pi = measure(&data);
+        on_iter(iter, &theta, &pi);
+        let theta_next = update(&theta, &pi);
+        let done = converged(&theta, &theta_next);
+        theta = theta_next;
+        residual = Some(pi);
+        if done {
+            return RefineReport { theta, iters: iter + 1, residual, status: RefineStatus::Converged { iter } };
+        }
+    }
+    RefineReport { theta, iters: max_iters, residual, status: RefineStatus::MaxIters }
+}
+
+/// Finiteness check used by [`refine_guarded`]. Implement it for your θ/π
+/// by and-ing the fields that can blow up.
+pub trait AllFinite {
+    fn all_finite(&self) -> bool;
+}
+
+impl AllFinite for f64 {
+    fn all_finite(&self) -> bool {
+        self.is_finite()
+    }
+}
+
+impl AllFinite for f32 {
+    fn all_finite(&self) -> bool {
+        self.is_finite()
+    }
+}
+
+impl<T: AllFinite> AllFinite for [T] {
+    fn all_finite(&self) -> bool {
+        self.iter().all(AllFinite::all_finite)
+    }
+}
+
+impl<T: AllFinite, const N: usize> AllFinite for [T; N] {
+    fn all_finite(&self) -> bool {
+        self.iter().all(AllFinite::all_finite)
+    }
+}
+
+impl<T: AllFinite> AllFinite for Vec<T> {
+    fn all_finite(&self) -> bool {
+        self.iter().all(AllFinite::all_finite)
+    }
+}
+
+impl<A: AllFinite, B: AllFinite> AllFinite for (A, B) {
+    fn all_finite(&self) -> bool {
+        self.0.all_finite() && self.1.all_finite()
+    }
+}
+
+impl AllFinite for Params {
+    fn all_finite(&self) -> bool {
+        true
+    }
+}
+
+impl AllFinite for Metrics {
+    fn all_finite(&self) -> bool {
+        true
+    }
+}
+
+/// Why a refinement stopped; see [`RefineReport`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RefineStatus {
+    /// `converged` held at iteration `iter` (0-based).
+    Converged { iter: usize },
+    /// Ran all `max_iters` without converging.
+    MaxIters,
+    /// Iteration `iter` produced a non-finite π or θ
+    /// ([`refine_guarded`] only).
+    Diverged { iter: usize },
+    /// The wall-clock budget ran out ([`StopCondition::Time`]).
+    #[cfg(feature = "timing")]
+    Time,
+    /// The residual stopped improving ([`StopCondition::Plateau`]).
+    Plateau,
+}
+
+/// [`refine_det`] that stops as soon as π or the next θ is non-finite,
+/// instead of iterating on garbage. On divergence the reported θ is the
+/// last finite one (the input to the failing iteration) and `residual` is
+/// the offending π when that is what went bad.
+pub fn refine_guarded<Theta, D, Pi, Sim, Meas, Upd, Conv>(
+    mut theta: Theta,
+    mut simulate: Sim,
+    mut measure: Meas,
+    mut update: Upd,
+    converged: Conv,
+    max_iters: usize,
+) -> RefineReport<Theta, Pi>
+where
+    Theta: AllFinite,
+    Pi: AllFinite,
+    Sim: FnMut(&Theta) -> D,
+    Meas: FnMut(&D) -> Pi,
+    Upd: FnMut(&Theta, &Pi) -> Theta,
+    Conv: Fn(&Theta, &Theta) -> bool,
+{
+    let mut residual = None;
+    for iter in 0..max_iters {
+        let data = simulate(&theta);
+        let pi = measure(&data);
+        let diverged = RefineStatus::Diverged { iter };
+        if !pi.all_finite() {
+            return RefineReport { theta, iters: iter + 1, residual: Some(pi), status: diverged };
+        }
+        let theta_next = update(&theta, &pi);
+        residual = Some(pi);
+        if !theta_next.all_finite() {
+            return RefineReport { theta, iters: iter + 1, residual, status: diverged };
+        }
+        let done = converged(&theta, &theta_next);
+        theta = theta_next;
+        if done {
+            return RefineReport { theta, iters: iter + 1, residual, status: RefineStatus::Converged { iter } };
+        }
+    }
+    RefineReport { theta, iters: max_iters, residual, status: RefineStatus::MaxIters }
+}
+
+/// [`refine_det`] that also records the path: entry `t` is `(θ_t, π_t)`,
+/// the parameters fed to iteration `t` and the metrics they produced.
+/// The final θ is returned separately (it has no π yet).
+pub fn refine_traced<Theta, D, Pi, Sim, Meas, Upd, Conv>(
+    mut theta: Theta,
+    mut simulate: Sim,
+    mut measure: Meas,
+    mut update: Upd,
+    converged: Conv,
+    max_iters: usize,
+) -> (Theta, Vec<(Theta, Pi)>)
+where
+    Sim: FnMut(&Theta) -> D,
+    Meas: FnMut(&D) -> Pi,
+    Upd: FnMut(&Theta, &Pi) -> Theta,
+    Conv: Fn(&Theta, &Theta) -> bool,
+{
+    let mut trace = Vec::new();
     for _ in 0..max_iters {
         let data = simulate(&theta);
         let pi = measure(&data);
         let theta_next = update(&theta, &pi);
+        let done = converged(&theta, &theta_next);
+        trace.push((std::mem::replace(&mut theta, theta_next), pi));
+        if done {
+            break;
+        }
+    }
+    (theta, trace)
+}
+
+/// [`refine_det`] with an async `simulate`. Iterations run strictly in
+/// order (one simulation in flight at a time), so the θ path is the same
+/// as the synchronous loop's. The returned future must not borrow `θ`:
+/// clone what the request needs inside `simulate`. No runtime is assumed.
+pub async fn refine_det_async<Theta, D, Pi, Sim, Fut, Meas, Upd, Conv>(
+    mut theta: Theta,
+    mut simulate: Sim,
+    mut measure: Meas,
+    mut update: Upd,
+    converged: Conv,
+    max_iters: usize,
+) -> RefineReport<Theta, Pi>
+where
+    Sim: FnMut(&Theta) -> Fut,
+    Fut: std::future::Future<Output = D>,
+    Meas: FnMut(&D) -> Pi,
+    Upd: FnMut(&Theta, &Pi) -> Theta,
+    Conv: Fn(&Theta, &Theta) -> bool,
+{
+    let mut residual = None;
+    for iter in 0..max_iters {
+        let data = simulate(&theta).await;
+        let pi = measure(&data);
+        let theta_next = update(&theta, &pi);
+        residual = Some(pi);
         if converged(&theta, &theta_next) {
-            return theta_next;
+            return RefineReport { theta: theta_next, iters: iter + 1, residual, status: RefineStatus::Converged { iter } };
         }
         theta = theta_next;
     }
-    theta
+    RefineReport { theta, iters: max_iters, residual, status: RefineStatus::MaxIters }
+}
+
+/// A step of [`try_refine_det`] failed at iteration `iter` (0-based).
+#[derive(Clone, Debug, PartialEq)]
+pub struct RefineError<E> {
+    pub iter: usize,
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for RefineError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "refinement failed at iteration {}: {}", self.iter, self.error)
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for RefineError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Fallible [`refine_det`]: the first `Err` from simulate, measure or update
+/// aborts the loop and comes back tagged with its iteration.
+pub fn try_refine_det<Theta, D, Pi, E, Sim, Meas, Upd, Conv>(
+    mut theta: Theta,
+    mut simulate: Sim,
+    mut measure: Meas,
+    mut update: Upd,
+    converged: Conv,
+    max_iters: usize,
+) -> Result<RefineReport<Theta, Pi>, RefineError<E>>
+where
+    Sim: FnMut(&Theta) -> Result<D, E>,
+    Meas: FnMut(&D) -> Result<Pi, E>,
+    Upd: FnMut(&Theta, &Pi) -> Result<Theta, E>,
+    Conv: Fn(&Theta, &Theta) -> bool,
+{
+    let mut residual = None;
+    for iter in 0..max_iters {
+        let step = simulate(&theta)
+            .and_then(|data| measure(&data))
+            .and_then(|pi| update(&theta, &pi).map(|theta_next| (pi, theta_next)));
+        let (pi, theta_next) = step.map_err(|error| RefineError { iter, error })?;
+        residual = Some(pi);
+        if converged(&theta, &theta_next) {
+            return Ok(RefineReport { theta: theta_next, iters: iter + 1, residual, status: RefineStatus::Converged { iter } });
+        }
+        theta = theta_next;
+    }
+    Ok(RefineReport { theta, iters: max_iters, residual, status: RefineStatus::MaxIters })
+}
+
+/// RNG for iteration `iter` of a run seeded with `seed`. Streams are
+/// independent of how many draws earlier iterations made, so a replay of
+/// iteration `i` needs only `(seed, i)`.
+pub fn stream_rng(seed: u64, iter: u64) -> bevy_prng::WyRand {
+    use rand_core::SeedableRng;
+    // splitmix64 finalizer over the pair, so adjacent seeds/iters decorrelate.
+    let mut z = seed ^ iter.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    bevy_prng::WyRand::from_seed(z.to_le_bytes())
+}
+
+/// Stochastic refinement: like [`refine_det`], but `simulate` also gets
+/// `&mut stream_rng(seed, t)` for iteration `t`. Same seed → same θ path.
+pub fn refine_stoch<Theta, D, Pi, Sim, Meas, Upd, Conv>(
+    mut theta: Theta,
+    seed: u64,
+    mut simulate: Sim,
+    mut measure: Meas,
+    mut update: Upd,
+    converged: Conv,
+    max_iters: usize,
+) -> RefineReport<Theta, Pi>
+where
+    Sim: FnMut(&Theta, &mut bevy_prng::WyRand) -> D,
+    Meas: FnMut(&D) -> Pi,
+    Upd: FnMut(&Theta, &Pi) -> Theta,
+    Conv: Fn(&Theta, &Theta) -> bool,
+{
+    let mut residual = None;
+    for t in 0..max_iters {
+        let mut rng = stream_rng(seed, t as u64);
+        let data = simulate(&theta, &mut rng);
+        let pi = measure(&data);
+        let theta_next = update(&theta, &pi);
+        residual = Some(pi);
+        if converged(&theta, &theta_next) {
+            return RefineReport { theta: theta_next, iters: t + 1, residual, status: RefineStatus::Converged { iter: t } };
+        }
+        theta = theta_next;
+    }
+    RefineReport { theta, iters: max_iters, residual, status: RefineStatus::MaxIters }
+}
+
+/// `self + beta·(a − b)`, the vector arithmetic [`with_momentum`] needs.
+pub trait Extrapolate: Clone {
+    fn extrapolate(&self, a: &Self, b: &Self, beta: f64) -> Self;
+}
+
+impl Extrapolate for f64 {
+    fn extrapolate(&self, a: &Self, b: &Self, beta: f64) -> Self {
+        self + beta * (a - b)
+    }
+}
+
+impl<const N: usize> Extrapolate for [f64; N] {
+    fn extrapolate(&self, a: &Self, b: &Self, beta: f64) -> Self {
+        std::array::from_fn(|i| self[i].extrapolate(&a[i], &b[i], beta))
+    }
+}
+
+impl Extrapolate for Vec<f64> {
+    fn extrapolate(&self, a: &Self, b: &Self, beta: f64) -> Self {
+        self.iter().zip(a).zip(b).map(|((x, a), b)| x.extrapolate(a, b, beta)).collect()
+    }
+}
+
+/// Heavy-ball wrapper around an `update` closure:
+/// `θ_{t+1} = update(θ_t, π_t) + β·(θ_t − θ_{t−1})`. The first call has no
+/// θ_{t−1} and returns `update` unchanged. Use `β` in `[0, 1)`; ~0.5–0.9
+/// speeds up slow, smooth loops but overshoots on stiff ones. Keeps the
+/// previous iterate internally, so use one wrapper per run.
+/// (Per-field SDK loops get the same effect from `Controller::Momentum`.)
+pub fn with_momentum<Theta, Pi, Upd>(mut update: Upd, beta: f64) -> impl FnMut(&Theta, &Pi) -> Theta
+where
+    Theta: Extrapolate,
+    Upd: FnMut(&Theta, &Pi) -> Theta,
+{
+    let mut prev: Option<Theta> = None;
+    move |theta, pi| {
+        let next = update(theta, pi);
+        let next = match &prev {
+            Some(p) => next.extrapolate(theta, p, beta),
+            None => next,
+        };
+        prev = Some(theta.clone());
+        next
+    }
+}
+
+/// Maps θ back into a feasible set (any constraint beyond box bounds).
+/// Should be idempotent: projecting a feasible θ returns it unchanged.
+/// Closures `Fn(Theta) -> Theta` implement it.
+pub trait Project<Theta> {
+    fn project(&self, theta: Theta) -> Theta;
+}
+
+impl<Theta, F: Fn(Theta) -> Theta> Project<Theta> for F {
+    fn project(&self, theta: Theta) -> Theta {
+        self(theta)
+    }
+}
+
+/// Wrap `update` so every new θ passes through `proj`. The SDK equivalent
+/// is `systems::sdk::ProjectHook`.
+pub fn with_projection<Theta, Pi, Upd, P>(mut update: Upd, proj: P) -> impl FnMut(&Theta, &Pi) -> Theta
+where
+    Upd: FnMut(&Theta, &Pi) -> Theta,
+    P: Project<Theta>,
+{
+    move |theta, pi| proj.project(update(theta, pi))
+}
+
+/// θ viewed as a flat `f64` vector, for accelerators in [`refine_with`].
+/// `unflatten` uses `self` as a template, so non-numeric fields survive.
+pub trait Flat {
+    fn flatten(&self) -> Vec<f64>;
+    fn unflatten(&self, v: &[f64]) -> Self;
+}
+
+impl Flat for f64 {
+    fn flatten(&self) -> Vec<f64> {
+        vec![*self]
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        v[0]
+    }
+}
+
+impl<const N: usize> Flat for [f64; N] {
+    fn flatten(&self) -> Vec<f64> {
+        self.to_vec()
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        std::array::from_fn(|i| v[i])
+    }
+}
+
+impl Flat for Vec<f64> {
+    fn flatten(&self) -> Vec<f64> {
+        self.clone()
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        v.to_vec()
+    }
+}
+
+/// Opt-in extras for [`refine_with`]. `Default` is a plain [`refine_det`].
+#[derive(Clone, Debug, Default)]
+pub struct RefineOptions {
+    /// Relaxation λ: `θ_next = (1 − λ)·θ + λ·update(θ, π)`. `λ < 1` calms
+    /// loops that overshoot and chatter without retuning every system's
+    /// gains; `None` (or `1.0`) is the plain step. Applied first.
+    pub damping: Option<f64>,
+    /// Anderson mixing depth `m`: extrapolate from the last `m` steps.
+    /// `0` turns it off. 3–5 suits slow, smooth loops such as
+    /// `upgrade_cost_curve`; it can destabilize loops with hard clamps.
+    pub anderson_depth: usize,
+    /// Trust region: cap each coordinate's move per iteration, so one bad
+    /// update cannot fling θ across its range. Applied last (after mixing).
+    pub max_step: Option<StepCap>,
+}
+
+/// Per-coordinate limit on `|θ_next − θ|` for [`RefineOptions::max_step`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StepCap {
+    /// `|Δθᵢ| ≤ c`.
+    Absolute(f64),
+    /// `|Δθᵢ| ≤ r·|θᵢ|`, with `|θᵢ|` floored at 1e-9 so zeros can still move.
+    Relative(f64),
+}
+
+impl StepCap {
+    fn clamp(&self, from: &[f64], to: &mut [f64]) {
+        for (x, y) in from.iter().zip(to.iter_mut()) {
+            let cap = match *self {
+                StepCap::Absolute(c) => c.abs(),
+                StepCap::Relative(r) => r.abs() * x.abs().max(1e-9),
+            };
+            *y = x + (*y - x).clamp(-cap, cap);
+        }
+    }
 }
 
+/// [`refine_det`] with [`RefineOptions`]. With `anderson_depth = m > 0` the
+/// next θ is the combination of the last `m + 1` update outputs whose
+/// residuals `update(θ) − θ` cancel best (least squares), instead of just
+/// `update(θ)`. Falls back to the plain step if that yields non-finite values.
+/// `damping` blends each update with the current θ before mixing; with
+/// `max_step`, the resulting move is then clamped per coordinate.
+pub fn refine_with<Theta, D, Pi, Sim, Meas, Upd, Conv>(
+    mut theta: Theta,
+    mut simulate: Sim,
+    mut measure: Meas,
+    mut update: Upd,
+    converged: Conv,
+    max_iters: usize,
+    opts: &RefineOptions,
+) -> RefineReport<Theta, Pi>
+where
+    Theta: Flat,
+    Sim: FnMut(&Theta) -> D,
+    Meas: FnMut(&D) -> Pi,
+    Upd: FnMut(&Theta, &Pi) -> Theta,
+    Conv: Fn(&Theta, &Theta) -> bool,
+{
+    let mut hist = AndersonHistory::new();
+    let mut residual = None;
+    for iter in 0..max_iters {
+        let data = simulate(&theta);
+        let pi = measure(&data);
+        let mut theta_next = update(&theta, &pi);
+        if let Some(lambda) = opts.damping {
+            let x = theta.flatten();
+            let v: Vec<f64> = theta_next.flatten().iter().zip(&x).map(|(g, x)| x + lambda * (g - x)).collect();
+            theta_next = theta_next.unflatten(&v);
+        }
+        if opts.anderson_depth > 0 {
+            let x = theta.flatten();
+            let g = theta_next.flatten();
+            let f: Vec<f64> = g.iter().zip(&x).map(|(g, x)| g - x).collect();
+            hist.push_back((g, f));
+            if hist.len() > opts.anderson_depth + 1 {
+                hist.pop_front();
+            }
+            if let Some(mixed) = anderson_mix(&hist) {
+                theta_next = theta_next.unflatten(&mixed);
+            }
+        }
+        if let Some(cap) = opts.max_step {
+            let mut v = theta_next.flatten();
+            cap.clamp(&theta.flatten(), &mut v);
+            theta_next = theta_next.unflatten(&v);
+        }
+        residual = Some(pi);
+        if converged(&theta, &theta_next) {
+            return RefineReport { theta: theta_next, iters: iter + 1, residual, status: RefineStatus::Converged { iter } };
+        }
+        theta = theta_next;
+    }
+    RefineReport { theta, iters: max_iters, residual, status: RefineStatus::MaxIters }
+}
+
+/// `(g_k, f_k = g_k − x_k)` for the last `m + 1` steps, oldest first.
+type AndersonHistory = std::collections::VecDeque<(Vec<f64>, Vec<f64>)>;
+
+/// Anderson type-II step: with ΔG/ΔF the column differences of the stored
+/// g/f, solve `min ‖f_k − ΔF·γ‖` and return `g_k − ΔG·γ`.
+fn anderson_mix(hist: &AndersonHistory) -> Option<Vec<f64>> {
+    let m = hist.len().checked_sub(1).filter(|&m| m > 0)?;
+    let (g_k, f_k) = hist.back()?;
+    let diff = |a: &[f64], b: &[f64]| -> Vec<f64> { a.iter().zip(b).map(|(a, b)| a - b).collect() };
+    let df: Vec<Vec<f64>> = (0..m).map(|i| diff(&hist[i + 1].1, &hist[i].1)).collect();
+    let dg: Vec<Vec<f64>> = (0..m).map(|i| diff(&hist[i + 1].0, &hist[i].0)).collect();
+    let dot = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f64>();
+
+    // Normal equations (ΔFᵀΔF + λI)·γ = ΔFᵀ f_k, tiny ridge for conditioning.
+    let mut a: Vec<Vec<f64>> = (0..m).map(|i| (0..m).map(|j| dot(&df[i], &df[j])).collect()).collect();
+    let ridge = 1e-10 * (0..m).map(|i| a[i][i]).sum::<f64>().max(1e-300);
+    for (i, row) in a.iter_mut().enumerate() {
+        row[i] += ridge;
+    }
+    let mut b: Vec<f64> = df.iter().map(|c| dot(c, f_k)).collect();
+    let gamma = solve_small(&mut a, &mut b)?;
+
+    let out: Vec<f64> =
+        (0..g_k.len()).map(|r| g_k[r] - (0..m).map(|i| gamma[i] * dg[i][r]).sum::<f64>()).collect();
+    out.iter().all(|v| v.is_finite()).then_some(out)
+}
+
+/// Gaussian elimination with partial pivoting; `None` if singular.
+fn solve_small(a: &mut [Vec<f64>], b: &mut [f64]) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let piv = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[piv][col].abs() < 1e-300 {
+            return None;
+        }
+        a.swap(col, piv);
+        b.swap(col, piv);
+        let pivot = a[col].clone();
+        for row in col + 1..n {
+            let k = a[row][col] / pivot[col];
+            for (x, p) in a[row][col..].iter_mut().zip(&pivot[col..]) {
+                *x -= k * p;
+            }
+            b[row] -= k * b[col];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for i in (0..n).rev() {
+        x[i] = (b[i] - (i + 1..n).map(|c| a[i][c] * x[c]).sum::<f64>()) / a[i][i];
+    }
+    Some(x)
+}
+
+/// Temperature `t0 · alpha^t` for [`refine_anneal`].
+pub fn geometric_schedule(t0: f64, alpha: f64) -> impl Fn(usize) -> f64 {
+    move |t| t0 * alpha.powi(t.min(i32::MAX as usize) as i32)
+}
+
+/// Run settings for [`refine_anneal`].
+#[derive(Clone, Debug)]
+pub struct Anneal<Temp> {
+    /// Iteration `t` draws from `stream_rng(seed, t)`.
+    pub seed: u64,
+    /// Temperature at iteration `t`, e.g. [`geometric_schedule`].
+    pub temperature: Temp,
+    pub max_iters: usize,
+}
+
+/// Simulated annealing. Each iteration `t` draws a candidate
+/// `propose(θ, π, &mut stream_rng(seed, t))`, simulates it, and accepts it
+/// if its energy is no worse, or otherwise with probability
+/// `exp(-Δ / temperature(t))`. Worse moves let the walk leave a local basin;
+/// as the temperature falls it settles. Returns the lowest-energy θ seen
+/// and its energy. The same seed replays the same walk.
+pub fn refine_anneal<Theta, D, Pi, Sim, Meas, Prop, En, Temp>(
+    theta: Theta,
+    mut simulate: Sim,
+    mut measure: Meas,
+    mut propose: Prop,
+    energy: En,
+    cfg: &Anneal<Temp>,
+) -> (Theta, f64)
+where
+    Theta: Clone,
+    Sim: FnMut(&Theta) -> D,
+    Meas: FnMut(&D) -> Pi,
+    Prop: FnMut(&Theta, &Pi, &mut bevy_prng::WyRand) -> Theta,
+    En: Fn(&Pi) -> f64,
+    Temp: Fn(usize) -> f64,
+{
+    use rand_core::RngCore;
+
+    let mut pi = measure(&simulate(&theta));
+    let mut e = energy(&pi);
+    let (mut best, mut best_e) = (theta.clone(), e);
+    let mut theta = theta;
+    for t in 0..cfg.max_iters {
+        let mut rng = stream_rng(cfg.seed, t as u64);
+        let cand = propose(&theta, &pi, &mut rng);
+        let cand_pi = measure(&simulate(&cand));
+        let cand_e = energy(&cand_pi);
+        let temp = (cfg.temperature)(t);
+        let accept = cand_e <= e || {
+            let u = (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+            temp > 0.0 && u < (-(cand_e - e) / temp).exp()
+        };
+        if accept {
+            theta = cand;
+            pi = cand_pi;
+            e = cand_e;
+            if e < best_e {
+                best = theta.clone();
+                best_e = e;
+            }
+        }
+    }
+    (best, best_e)
+}
+
+/// Result of [`refine_scalar`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScalarRoot {
+    pub x: f64,
+    /// Metric at `x`.
+    pub value: f64,
+    pub evals: usize,
+    /// `|value − target| ≤ tol`. `false` also when the target is outside
+    /// the bracket (then `x` is the closer endpoint).
+    pub converged: bool,
+}
+
+/// Solve `measure(simulate(x)) = target` for a scalar knob whose metric is
+/// monotone on `bracket = (lo, hi)` (increasing or decreasing). Uses
+/// regula falsi with the Illinois fix: secant steps that always keep the
+/// root bracketed, halving a stale endpoint's weight so convergence stays
+/// superlinear. Typically 10–40 evaluations where the fixed-point loop
+/// needs thousands.
+pub fn refine_scalar<D, Sim, Meas>(
+    bracket: (f64, f64),
+    target: f64,
+    mut simulate: Sim,
+    mut measure: Meas,
+    tol: f64,
+    max_evals: usize,
+) -> ScalarRoot
+where
+    Sim: FnMut(f64) -> D,
+    Meas: FnMut(&D) -> f64,
+{
+    let mut f = |x: f64| measure(&simulate(x));
+    let (mut a, mut b) = bracket;
+    let (va, vb) = (f(a), f(b));
+    let (mut fa, mut fb) = (va - target, vb - target);
+    let done = |x: f64, v: f64, evals: usize| ScalarRoot { x, value: v, evals, converged: (v - target).abs() <= tol };
+    if fa.abs() <= tol || fb.abs() <= tol || fa.signum() == fb.signum() {
+        let (x, v) = if fa.abs() <= fb.abs() { (a, va) } else { (b, vb) };
+        return done(x, v, 2);
+    }
+
+    let (mut best_x, mut best_v) = if fa.abs() <= fb.abs() { (a, va) } else { (b, vb) };
+    let (mut side, mut evals) = (0, 2);
+    while evals < max_evals {
+        let c = (fa * b - fb * a) / (fa - fb);
+        let c = if c.is_finite() && c > a.min(b) && c < a.max(b) { c } else { 0.5 * (a + b) };
+        let vc = f(c);
+        evals += 1;
+        let fc = vc - target;
+        if fc.abs() < (best_v - target).abs() {
+            (best_x, best_v) = (c, vc);
+        }
+        if fc.abs() <= tol || c == a || c == b {
+            break;
+        }
+        if fc.signum() == fb.signum() {
+            (b, fb) = (c, fc);
+            if side == -1 {
+                fa *= 0.5;
+            }
+            side = -1;
+        } else {
+            (a, fa) = (c, fc);
+            if side == 1 {
+                fb *= 0.5;
+            }
+            side = 1;
+        }
+    }
+    done(best_x, best_v, evals)
+}
+
+/// Random restarts. Start 0 refines θ₀ as given; start `i > 0` refines
+/// `perturb(θ₀, &mut stream_rng(seed, i))`. `refine` is any full run (e.g. a
+/// closure around [`refine_det`] or a system's `balance_ext`). Returns the
+/// result with the lowest `score`, that score, and its start index; ties
+/// keep the earlier start. `starts == 0` is treated as 1.
+pub fn refine_multistart<Theta, R, Pert, Run, Score>(
+    theta0: &Theta,
+    seed: u64,
+    starts: usize,
+    mut perturb: Pert,
+    mut refine: Run,
+    score: Score,
+) -> (R, f64, usize)
+where
+    Theta: Clone,
+    Pert: FnMut(&Theta, &mut bevy_prng::WyRand) -> Theta,
+    Run: FnMut(Theta) -> R,
+    Score: Fn(&R) -> f64,
+{
+    let first = refine(theta0.clone());
+    let mut best = (score(&first), first, 0);
+    for i in 1..starts {
+        let start = perturb(theta0, &mut stream_rng(seed, i as u64));
+        let r = refine(start);
+        let sc = score(&r);
+        if sc < best.0 {
+            best = (sc, r, i);
+        }
+    }
+    (best.1, best.0, best.2)
+}
+
+/// Resumable loop state for [`refine_resumable`]. The RNG needs no saved
+/// state: iteration `t` always draws from `stream_rng(seed, t)`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Checkpoint<Theta> {
+    pub theta: Theta,
+    /// Iterations already completed.
+    pub iter: usize,
+    pub seed: u64,
+}
+
+impl<Theta> Checkpoint<Theta> {
+    /// A fresh run from θ₀.
+    pub fn new(theta: Theta, seed: u64) -> Self {
+        Self { theta, iter: 0, seed }
+    }
+}
+
+/// Where [`refine_resumable`] sends checkpoints, and how often.
+pub struct Checkpointer<Save> {
+    /// Save after every `every` completed iterations (0 = never).
+    pub every: usize,
+    pub save: Save,
+}
+
+/// [`refine_stoch`] that can be stopped and resumed. Starts at `start.iter`
+/// and runs until `max_iters` total iterations (counting those before the
+/// checkpoint) or `converged`. Every `ckpt.every` iterations it calls
+/// `ckpt.save` with the current state; restarting from any saved
+/// checkpoint reproduces the uninterrupted run exactly. The report's
+/// `iters` and `Converged { iter }` count from the start of the original
+/// run, like [`Checkpoint::iter`].
+pub fn refine_resumable<Theta, D, Pi, Sim, Meas, Upd, Conv, Save>(
+    start: Checkpoint<Theta>,
+    mut simulate: Sim,
+    mut measure: Meas,
+    mut update: Upd,
+    converged: Conv,
+    max_iters: usize,
+    mut ckpt: Checkpointer<Save>,
+) -> RefineReport<Theta, Pi>
+where
+    Theta: Clone,
+    Sim: FnMut(&Theta, &mut bevy_prng::WyRand) -> D,
+    Meas: FnMut(&D) -> Pi,
+    Upd: FnMut(&Theta, &Pi) -> Theta,
+    Conv: Fn(&Theta, &Theta) -> bool,
+    Save: FnMut(&Checkpoint<Theta>),
+{
+    let Checkpoint { mut theta, iter, seed } = start;
+    let mut residual = None;
+    for t in iter..max_iters {
+        let data = simulate(&theta, &mut stream_rng(seed, t as u64));
+        let pi = measure(&data);
+        let theta_next = update(&theta, &pi);
+        residual = Some(pi);
+        if converged(&theta, &theta_next) {
+            return RefineReport { theta: theta_next, iters: t + 1, residual, status: RefineStatus::Converged { iter: t } };
+        }
+        theta = theta_next;
+        if ckpt.every > 0 && (t + 1).is_multiple_of(ckpt.every) {
+            (ckpt.save)(&Checkpoint { theta: theta.clone(), iter: t + 1, seed });
+        }
+    }
+    RefineReport { theta, iters: max_iters.max(iter), residual, status: RefineStatus::MaxIters }
+}
+
+/// Replicates per iteration for [`refine_replicated`].
+#[derive(Clone, Copy, Debug)]
+pub struct ReplicateConfig {
+    /// Replicates per iteration; below 2 runs 2, since a single replicate
+    /// has no standard error.
+    pub count: usize,
+    /// Replicate `r` of iteration `t` draws from `stream_rng(seed, t·count + r)`.
+    pub seed: u64,
+}
+
+/// Per-metric mean and standard error over one iteration's replicates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replicated {
+    pub mean: Vec<f64>,
+    /// `sd / √K`; `∞` with fewer than two replicates.
+    pub stderr: Vec<f64>,
+}
+
+impl Replicated {
+    /// Average per-replicate metric vectors (all the same length).
+    pub fn from_samples(samples: &[Vec<f64>]) -> Self {
+        let k = samples.len() as f64;
+        let m = samples.first().map_or(0, Vec::len);
+        let mean: Vec<f64> = (0..m).map(|j| samples.iter().map(|s| s[j]).sum::<f64>() / k).collect();
+        let stderr = (0..m)
+            .map(|j| {
+                if samples.len() < 2 {
+                    return f64::INFINITY;
+                }
+                let var = samples.iter().map(|s| (s[j] - mean[j]).powi(2)).sum::<f64>() / (k - 1.0);
+                (var / k).sqrt()
+            })
+            .collect();
+        Self { mean, stderr }
+    }
+
+    /// Confident band check: `|mean − target| + z·stderr ≤ tol` for every
+    /// metric (z ≈ 2 for ~95%). Noise alone cannot make this flap on.
+    ///
+    /// `z = 0` checks the means alone, even where the stderr is `∞`; with
+    /// `z > 0` a single-sample summary never passes.
+    pub fn within(&self, target: &[f64], tol: &[f64], z: f64) -> bool {
+        self.mean
+            .iter()
+            .zip(&self.stderr)
+            .zip(target.iter().zip(tol))
+            .all(|((m, se), (t, tol))| {
+                let margin = if z == 0.0 { 0.0 } else { z * se };
+                (m - t).abs() + margin <= *tol
+            })
+    }
+}
+
+/// Noise-aware refinement: each iteration runs `reps.count` seeded
+/// simulations of the same θ, averages their metric vectors, and hands the
+/// [`Replicated`] summary to `update`. `converged` sees the summary too
+/// (typically [`Replicated::within`]); when it holds, the θ that produced
+/// it is returned, with that summary as the report's `residual`.
+pub fn refine_replicated<Theta, D, Sim, Meas, Upd, Conv>(
+    mut theta: Theta,
+    reps: ReplicateConfig,
+    mut simulate: Sim,
+    mut measure: Meas,
+    mut update: Upd,
+    converged: Conv,
+    max_iters: usize,
+) -> RefineReport<Theta, Replicated>
+where
+    Sim: FnMut(&Theta, &mut bevy_prng::WyRand) -> D,
+    Meas: FnMut(&D) -> Vec<f64>,
+    Upd: FnMut(&Theta, &Replicated) -> Theta,
+    Conv: Fn(&Replicated) -> bool,
+{
+    let k = reps.count.max(2);
+    let mut residual = None;
+    for t in 0..max_iters {
+        let samples: Vec<Vec<f64>> = (0..k)
+            .map(|r| measure(&simulate(&theta, &mut stream_rng(reps.seed, (t * k + r) as u64))))
+            .collect();
+        let pi = Replicated::from_samples(&samples);
+        if converged(&pi) {
+            return RefineReport { theta, iters: t + 1, residual: Some(pi), status: RefineStatus::Converged { iter: t } };
+        }
+        theta = update(&theta, &pi);
+        residual = Some(pi);
+    }
+    RefineReport { theta, iters: max_iters, residual, status: RefineStatus::MaxIters }
+}
+
+/// Step-at-a-time refinement: each `next()` runs one
+/// `update(θ, measure(simulate(θ)))` and yields the new θ.
+///
+/// Lets a host drive the loop at its own cadence (e.g. one step per frame).
+/// The iterator never ends on its own; check convergence between steps, or
+/// bound it with `take`/`nth`.
+pub struct RefineIter<Theta, Sim, Meas, Upd> {
+    theta: Theta,
+    simulate: Sim,
+    measure: Meas,
+    update: Upd,
+}
+
+impl<Theta, D, Pi, Sim, Meas, Upd> RefineIter<Theta, Sim, Meas, Upd>
+where
+    Sim: FnMut(&Theta) -> D,
+    Meas: FnMut(&D) -> Pi,
+    Upd: FnMut(&Theta, &Pi) -> Theta,
+{
+    pub fn new(theta: Theta, simulate: Sim, measure: Meas, update: Upd) -> Self {
+        Self { theta, simulate, measure, update }
+    }
+
+    /// The latest θ (θ₀ before the first step).
+    pub fn current(&self) -> &Theta {
+        &self.theta
+    }
+
+    pub fn into_current(self) -> Theta {
+        self.theta
+    }
+}
+
+impl<Theta, D, Pi, Sim, Meas, Upd> Iterator for RefineIter<Theta, Sim, Meas, Upd>
+where
+    Theta: Clone,
+    Sim: FnMut(&Theta) -> D,
+    Meas: FnMut(&D) -> Pi,
+    Upd: FnMut(&Theta, &Pi) -> Theta,
+{
+    type Item = Theta;
+
+    fn next(&mut self) -> Option<Theta> {
+        let data = (self.simulate)(&self.theta);
+        let pi = (self.measure)(&data);
+        self.theta = (self.update)(&self.theta, &pi);
+        Some(self.theta.clone())
+    }
+}
+
+/// How many iterations `refine_timed`/`refine_until` run between clock reads.
+#[cfg(feature = "timing")]
+pub const TIME_CHECK_EVERY: usize = 16;
+
+/// When [`refine_until`] gives up (besides its `converged` predicate).
+#[derive(Clone, Debug, PartialEq)]
+pub enum StopCondition {
+    /// Stop after this many iterations.
+    Iters(usize),
+    /// Stop once this much wall-clock time has passed (checked every
+    /// [`TIME_CHECK_EVERY`] iterations).
+    #[cfg(feature = "timing")]
+    Time(std::time::Duration),
+    /// Patience: stop when the residual has not dropped more than
+    /// `min_delta` below its best value for `patience` consecutive
+    /// iterations. Needs a residual, so only [`refine_until_residual`]
+    /// can fire it.
+    Plateau { patience: usize, min_delta: f64 },
+    /// Stop as soon as any of these fires. An empty list never fires.
+    Any(Vec<StopCondition>),
+}
+
+/// Loop-side state for [`StopCondition::check`].
+struct StopState {
+    #[cfg(feature = "timing")]
+    start: std::time::Instant,
+    /// Residual of the previous iteration, if the caller supplies one.
+    residual: Option<f64>,
+    /// (best residual, iterations without improvement) per `Plateau`, in
+    /// depth-first order.
+    plateaus: Vec<(f64, usize)>,
+}
+
+impl StopCondition {
+    /// Checked once before iteration `i` runs; `slot` numbers the
+    /// `Plateau` nodes visited so far.
+    fn check(&self, i: usize, st: &mut StopState, slot: &mut usize) -> Option<RefineStatus> {
+        match self {
+            StopCondition::Iters(n) => (i >= *n).then_some(RefineStatus::MaxIters),
+            #[cfg(feature = "timing")]
+            StopCondition::Time(budget) => {
+                (i.is_multiple_of(TIME_CHECK_EVERY) && i > 0 && st.start.elapsed() >= *budget).then_some(RefineStatus::Time)
+            }
+            StopCondition::Plateau { patience, min_delta } => {
+                let k = *slot;
+                *slot += 1;
+                if st.plateaus.len() <= k {
+                    st.plateaus.push((f64::INFINITY, 0));
+                }
+                let r = st.residual?;
+                let (best, stale) = &mut st.plateaus[k];
+                if r < *best - min_delta {
+                    *best = r;
+                    *stale = 0;
+                } else {
+                    *stale += 1;
+                }
+                (*stale >= *patience).then_some(RefineStatus::Plateau)
+            }
+            StopCondition::Any(all) => all.iter().find_map(|c| c.check(i, st, slot)),
+        }
+    }
+}
+
+/// Deterministic refinement that runs until `converged` holds or `stop`
+/// fires, e.g. `StopCondition::Any(vec![Iters(120_000), Time(budget)])`.
+/// The report's status says which rule ended the run (`Iters` reports as
+/// [`RefineStatus::MaxIters`]). With no iteration bound in `stop`, only
+/// `converged` (or the clock) ends the loop.
+pub fn refine_until<Theta, D, Pi, Sim, Meas, Upd, Conv>(
+    theta: Theta,
+    simulate: Sim,
+    measure: Meas,
+    update: Upd,
+    converged: Conv,
+    stop: &StopCondition,
+) -> RefineReport<Theta, Pi>
+where
+    Sim: FnMut(&Theta) -> D,
+    Meas: FnMut(&D) -> Pi,
+    Upd: FnMut(&Theta, &Pi) -> Theta,
+    Conv: Fn(&Theta, &Theta) -> bool,
+{
+    run_until(theta, simulate, measure, update, converged, None::<fn(&Theta, &Pi) -> f64>, stop)
+}
+
+/// [`refine_until`] with a scalar `residual(θ_t, π_t)` (lower is better),
+/// which lets [`StopCondition::Plateau`] end loops that stall outside the
+/// tolerance band instead of burning the whole iteration budget.
+pub fn refine_until_residual<Theta, D, Pi, Sim, Meas, Upd, Conv, Res>(
+    theta: Theta,
+    simulate: Sim,
+    measure: Meas,
+    update: Upd,
+    converged: Conv,
+    residual: Res,
+    stop: &StopCondition,
+) -> RefineReport<Theta, Pi>
+where
+    Sim: FnMut(&Theta) -> D,
+    Meas: FnMut(&D) -> Pi,
+    Upd: FnMut(&Theta, &Pi) -> Theta,
+    Conv: Fn(&Theta, &Theta) -> bool,
+    Res: FnMut(&Theta, &Pi) -> f64,
+{
+    run_until(theta, simulate, measure, update, converged, Some(residual), stop)
+}
+
+fn run_until<Theta, D, Pi, Sim, Meas, Upd, Conv, Res>(
+    mut theta: Theta,
+    mut simulate: Sim,
+    mut measure: Meas,
+    mut update: Upd,
+    converged: Conv,
+    mut residual: Option<Res>,
+    stop: &StopCondition,
+) -> RefineReport<Theta, Pi>
+where
+    Sim: FnMut(&Theta) -> D,
+    Meas: FnMut(&D) -> Pi,
+    Upd: FnMut(&Theta, &Pi) -> Theta,
+    Conv: Fn(&Theta, &Theta) -> bool,
+    Res: FnMut(&Theta, &Pi) -> f64,
+{
+    let mut st = StopState {
+        #[cfg(feature = "timing")]
+        start: std::time::Instant::now(),
+        residual: None,
+        plateaus: Vec::new(),
+    };
+    let mut last = None;
+    for i in 0.. {
+        if let Some(status) = stop.check(i, &mut st, &mut 0) {
+            return RefineReport { theta, iters: i, residual: last, status };
+        }
+        let data = simulate(&theta);
+        let pi = measure(&data);
+        if let Some(res) = residual.as_mut() {
+            st.residual = Some(res(&theta, &pi));
+        }
+        let theta_next = update(&theta, &pi);
+        last = Some(pi);
+        if converged(&theta, &theta_next) {
+            return RefineReport { theta: theta_next, iters: i + 1, residual: last, status: RefineStatus::Converged { iter: i } };
+        }
+        theta = theta_next;
+    }
+    unreachable!("unbounded loop exits only by return")
+}
+
+/// Deterministic refinement bounded by a wall-clock `budget` as well as
+/// `max_iters`. The clock is read every [`TIME_CHECK_EVERY`] iterations.
+/// Shorthand for [`refine_until`] with `Any([Iters(max_iters), Time(budget)])`.
+///
+/// Like the other fixed-point loops it reports the last θ, not the best one
+/// seen: there is no error to rank iterates by. When the budget cuts a run
+/// short, that θ is simply where the loop had got to.
+#[cfg(feature = "timing")]
+pub fn refine_timed<Theta, D, Pi, Sim, Meas, Upd, Conv>(
+    theta: Theta,
+    simulate: Sim,
+    measure: Meas,
+    update: Upd,
+    converged: Conv,
+    max_iters: usize,
+    budget: std::time::Duration,
+) -> RefineReport<Theta, Pi>
+where
+    Sim: FnMut(&Theta) -> D,
+    Meas: FnMut(&D) -> Pi,
+    Upd: FnMut(&Theta, &Pi) -> Theta,
+    Conv: Fn(&Theta, &Theta) -> bool,
+{
+    let stop = StopCondition::Any(vec![StopCondition::Iters(max_iters), StopCondition::Time(budget)]);
+    refine_until(theta, simulate, measure, update, converged, &stop)
+}
+
+pub mod error;
+pub use error::{validate_all, Error, Problem, Validate};
+
 pub mod mechanics;
+pub mod optim;
+#[cfg(feature = "parallel")]
+pub mod ensemble;
+#[cfg(feature = "parallel")]
+pub use ensemble::refine_ensemble;
 pub mod systems;
-pub mod genres;
\ No newline at end of file
+pub mod genres;
+
+#[cfg(feature = "selftest")]
+pub mod selftest;
+#[cfg(feature = "selftest")]
+pub use selftest::selftest;
\ No newline at end of file
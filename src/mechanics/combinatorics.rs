@@ -0,0 +1,38 @@
+//! Combinatorics: binomials and hypergeometric draws (deck math).
+
+/// Binomial coefficient C(n, k) as f64 (multiplicative form; 0 if k > n).
+pub fn choose(n: u64, k: u64) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+    let k = k.min(n - k);
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+/// P(exactly `k` successes) drawing `draws` from `pop` holding `succ` successes.
+pub fn hypergeom_pmf(pop: u64, succ: u64, draws: u64, k: u64) -> f64 {
+    if succ > pop || draws > pop || k > succ || k > draws || draws - k > pop - succ {
+        return 0.0;
+    }
+    choose(succ, k) * choose(pop - succ, draws - k) / choose(pop, draws)
+}
+
+/// P(no success in the first `draws` cards) of a shuffled `pop` holding
+/// `succ` successes. Counts may be fractional (expected deck contents).
+pub fn miss_prob(pop: f64, succ: f64, draws: u32) -> f64 {
+    miss_windows(pop, &[(succ, draws)])
+}
+
+/// P(every group's cards lie outside its window): group `(k, n)` has `k`
+/// cards in a shuffled `pop`, none of which may be among the first `n`.
+/// Exact: at draw `j` the cards still forbidden are all in the deck, so
+/// the joint miss is `Π_j (1 − F_j / (pop − j + 1))` with `F_j` the cards
+/// of groups whose window reaches `j`.
+pub fn miss_windows(pop: f64, windows: &[(f64, u32)]) -> f64 {
+    let last = windows.iter().map(|&(_, n)| n).max().unwrap_or(0);
+    (1..=last).fold(1.0, |p, j| {
+        let left = pop - (j - 1) as f64;
+        let forbidden: f64 = windows.iter().filter(|&&(_, n)| n >= j).map(|&(k, _)| k).sum();
+        if left <= 0.0 { p } else { p * ((left - forbidden) / left).max(0.0) }
+    })
+}
@@ -6,8 +6,240 @@ pub fn approach(x: f64, target: f64, k: f64, lo: f64, hi: f64) -> f64 {
     (x + k * (target - x)).clamp(lo, hi)
 }
 
+/// What a parameter does at its bounds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ClampMode {
+    /// Stop at the bound (what `approach` does).
+    #[default]
+    Saturate,
+    /// Cyclic domain (angles, phases): `hi` is `lo`; targets are approached
+    /// along the shorter way round.
+    Wrap,
+    /// Bounce off the bound by the overshoot.
+    Reflect,
+}
+
+impl ClampMode {
+    /// Map `v` into `[lo, hi]` (into `[lo, hi)` for `Wrap`).
+    pub fn apply(self, v: f64, lo: f64, hi: f64) -> f64 {
+        let w = hi - lo;
+        if w <= 0.0 || !w.is_finite() || !v.is_finite() {
+            return v.clamp(lo, hi.max(lo));
+        }
+        match self {
+            ClampMode::Saturate => v.clamp(lo, hi),
+            ClampMode::Wrap => lo + (v - lo).rem_euclid(w),
+            ClampMode::Reflect => {
+                let m = (v - lo).rem_euclid(2.0 * w);
+                lo + if m <= w { m } else { 2.0 * w - m }
+            }
+        }
+    }
+}
+
+/// `approach` with a per-field bound behavior: x' = mode(x + k * (target − x)).
+/// With `Wrap`, (target − x) is taken the short way round the cycle.
+#[inline]
+pub fn approach_mode(x: f64, target: f64, k: f64, lo: f64, hi: f64, mode: ClampMode) -> f64 {
+    let w = hi - lo;
+    let delta = match mode {
+        ClampMode::Wrap if w > 0.0 => (target - x + 0.5 * w).rem_euclid(w) - 0.5 * w,
+        _ => target - x,
+    };
+    mode.apply(x + k * delta, lo, hi)
+}
+
+/// Linear interpolation: a at t = 0, b at t = 1 (t clamped to [0, 1]).
+#[inline]
+pub fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t.clamp(0.0, 1.0)
+}
+
+/// Unitless relative error |x − target| / |target| (target floored at 1e-9).
+#[inline]
+pub fn pct_error(x: f64, target: f64) -> f64 {
+    (x - target).abs() / target.abs().max(1e-9)
+}
+
+/// Relational converge predicate: |a / b − target_ratio| ≤ tol.
+/// For goals like “offline income ≈ 30% of online”. False when b ≈ 0.
+#[inline]
+pub fn ratio_within(a: f64, b: f64, target_ratio: f64, tol: f64) -> bool {
+    if b.abs() < 1e-12 {
+        return false;
+    }
+    (a / b - target_ratio).abs() <= tol
+}
+
+/// Monotone projection by forward fill: v[i] = max(v[i], v[i-1]).
+/// Cheap; never lowers a value, so it can only make a curve pricier.
+pub fn forward_fill_nondecreasing(v: &mut [f64]) {
+    for i in 1..v.len() {
+        if v[i] < v[i - 1] {
+            v[i] = v[i - 1];
+        }
+    }
+}
+
+/// Monotone projection by isotonic regression (pool adjacent violators):
+/// the closest non-decreasing sequence in least squares. Use after a
+/// per-level update so `cost[l+1] ≥ cost[l]` always holds.
+pub fn isotonic_nondecreasing(v: &mut [f64]) {
+    // Blocks of (mean, len); merge backwards while they violate order.
+    let mut blocks: Vec<(f64, usize)> = Vec::with_capacity(v.len());
+    for &x in v.iter() {
+        blocks.push((x, 1));
+        while blocks.len() > 1 && blocks[blocks.len() - 2].0 > blocks[blocks.len() - 1].0 {
+            let (m2, n2) = blocks.pop().unwrap();
+            let (m1, n1) = blocks.pop().unwrap();
+            let n = n1 + n2;
+            blocks.push(((m1 * n1 as f64 + m2 * n2 as f64) / n as f64, n));
+        }
+    }
+    let mut i = 0;
+    for (m, n) in blocks {
+        for x in &mut v[i..i + n] {
+            *x = m;
+        }
+        i += n;
+    }
+}
+
+/// Regularized target: argmin_x (x - target)² + λ (x - baseline)².
+/// `lambda = 0` returns `target`; large `lambda` stays near `baseline`.
+#[inline]
+pub fn regularize(target: f64, baseline: f64, lambda: f64) -> f64 {
+    let l = lambda.max(0.0);
+    (target + l * baseline) / (1.0 + l)
+}
+
 /// Proportional against signed error: x' = clamp(x - k * error).
 #[inline]
 pub fn p_against_error(x: f64, error: f64, k: f64, lo: f64, hi: f64) -> f64 {
     (x - k * error).clamp(lo, hi)
 }
+
+/// Proportional approach with hysteresis against direction reversals.
+///
+/// `dir` is the last move direction (−1, 0, +1). A move that would reverse
+/// it is skipped (x held) until the error exceeds `margin`; this stops
+/// parameters pinned at a bound from flip-flopping across it.
+/// Returns `(x', dir')`.
+#[inline]
+pub fn approach_hysteresis(
+    x: f64,
+    target: f64,
+    k: f64,
+    lo: f64,
+    hi: f64,
+    margin: f64,
+    dir: i8,
+) -> (f64, i8) {
+    let err = target.clamp(lo, hi) - x;
+    let err_dir: i8 = if err > 0.0 {
+        1
+    } else if err < 0.0 {
+        -1
+    } else {
+        0
+    };
+    let reversing = dir != 0 && err_dir == -dir;
+    if reversing && err.abs() <= margin.max(0.0) {
+        return (x.clamp(lo, hi), dir);
+    }
+    let next = approach(x, target, k, lo, hi);
+    let moved = if next > x {
+        1
+    } else if next < x {
+        -1
+    } else {
+        dir
+    };
+    (next, moved)
+}
+
+/// Selectable controller backend for system `step` closures.
+///
+/// Gains inside each variant are relative to the system's per-field gain
+/// (`Gains`), so `Proportional { k: 1.0 }` reproduces plain [`approach`].
+#[derive(Clone, Copy, Debug)]
+pub enum Controller {
+    /// x' = x + k·g·e.
+    Proportional { k: f64 },
+    /// PID on the error e = target − x; the integral is frozen while the
+    /// output saturates (anti-windup).
+    Pid { kp: f64, ki: f64, kd: f64 },
+    /// Heavy-ball: v' = β·v + k·g·e, x' = x + v'.
+    Momentum { k: f64, beta: f64 },
+    /// Proportional with a gain that grows by `up` while the error keeps its
+    /// sign and shrinks by `down` when it flips, capped at `k_max`.
+    AdaptiveGain { k: f64, up: f64, down: f64, k_max: f64 },
+    /// Joint mode: descend the weighted sum of squared target errors using a
+    /// finite-difference Jacobian of the system's model, so jointly
+    /// unreachable targets settle at the best compromise instead of
+    /// thrashing. Needs model access, so only systems that support it
+    /// (production_spend) use it; per-field `step` treats it as
+    /// `Proportional { k }`.
+    WeightedLeastSquares { k: f64 },
+}
+
+impl Default for Controller {
+    fn default() -> Self {
+        Controller::Proportional { k: 1.0 }
+    }
+}
+
+/// Per-field memory for stateful controllers (PID, momentum, adaptive).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ControllerState {
+    pub integral: f64,
+    pub prev_err: f64,
+    pub velocity: f64,
+    pub gain: f64,
+    pub started: bool,
+}
+
+impl Controller {
+    /// Move `x` toward `target` with field gain `g`, clamped to [lo, hi].
+    pub fn step(&self, st: &mut ControllerState, x: f64, target: f64, g: f64, lo: f64, hi: f64) -> f64 {
+        let target = target.clamp(lo, hi);
+        let err = target - x;
+        let next = match *self {
+            Controller::Proportional { k } | Controller::WeightedLeastSquares { k } => {
+                approach(x, target, k * g, lo, hi)
+            }
+            Controller::Pid { kp, ki, kd } => {
+                let deriv = if st.started { err - st.prev_err } else { 0.0 };
+                let raw = x + g * (kp * err + ki * (st.integral + err) + kd * deriv);
+                let out = raw.clamp(lo, hi);
+                if out == raw {
+                    st.integral += err;
+                }
+                out
+            }
+            Controller::Momentum { k, beta } => {
+                st.velocity = beta * st.velocity + k * g * err;
+                let raw = x + st.velocity;
+                let out = raw.clamp(lo, hi);
+                if out != raw {
+                    st.velocity = 0.0;
+                }
+                out
+            }
+            Controller::AdaptiveGain { k, up, down, k_max } => {
+                if !st.started {
+                    st.gain = k;
+                } else if err * st.prev_err > 0.0 {
+                    st.gain *= up.max(0.0);
+                } else if err * st.prev_err < 0.0 {
+                    st.gain *= down.max(0.0);
+                }
+                st.gain = st.gain.clamp(0.0, k_max.max(0.0));
+                approach(x, target, st.gain * g, lo, hi)
+            }
+        };
+        st.prev_err = err;
+        st.started = true;
+        next
+    }
+}
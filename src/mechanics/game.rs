@@ -0,0 +1,97 @@
+//! Game mechanics: mixed-strategy solver for two-player zero-sum games.
+//!
+//! Multiplicative weights with uniform mutation; the time-average of the
+//! iterates approaches a Nash equilibrium even when the iterates cycle.
+
+/// Result of [`solve_zero_sum`].
+#[derive(Clone, Debug)]
+pub struct ZeroSumSolution<const M: usize, const N: usize> {
+    /// Time-averaged row strategy.
+    pub row: [f64; M],
+    /// Time-averaged column strategy.
+    pub col: [f64; N],
+    /// L1 distance between the time-average and one more MW step applied to
+    /// it (zero at the fixed point).
+    pub l1: f64,
+    pub iters: usize,
+    pub converged: bool,
+}
+
+/// One MW step with mutation: w_i ∝ p_i · exp(η u_i), then mix with uniform.
+fn mw_step<const K: usize>(p: &[f64; K], u: &[f64; K], eta: f64, mu: f64) -> [f64; K] {
+    let mut w = [0.0; K];
+    let mut sum = 0.0;
+    for i in 0..K {
+        w[i] = p[i] * (eta * u[i]).exp();
+        sum += w[i];
+    }
+    if sum <= 0.0 || !sum.is_finite() {
+        return [1.0 / K as f64; K];
+    }
+    for x in w.iter_mut() {
+        *x = (1.0 - mu) * (*x / sum) + mu / K as f64;
+    }
+    w
+}
+
+/// Payoffs: row gets `A q`, column gets `-(Aᵀ p)`.
+fn payoffs<const M: usize, const N: usize>(
+    a: &[[f64; N]; M],
+    p: &[f64; M],
+    q: &[f64; N],
+) -> ([f64; M], [f64; N]) {
+    let mut ur = [0.0; M];
+    let mut uc = [0.0; N];
+    for i in 0..M {
+        for j in 0..N {
+            ur[i] += a[i][j] * q[j];
+            uc[j] -= a[i][j] * p[i];
+        }
+    }
+    (ur, uc)
+}
+
+fn l1<const K: usize>(a: &[f64; K], b: &[f64; K]) -> f64 {
+    a.iter().zip(b.iter()).map(|(x, y)| (x - y).abs()).sum()
+}
+
+/// Solve a zero-sum game with row payoff matrix `a` (column gets `-a`).
+///
+/// Stops when the time-averaged strategies are within `tol` (L1, row + col)
+/// of their own MW update, or after `max_iters` steps.
+pub fn solve_zero_sum<const M: usize, const N: usize>(
+    a: &[[f64; N]; M],
+    row0: [f64; M],
+    col0: [f64; N],
+    eta: f64,
+    mu: f64,
+    tol: f64,
+    max_iters: usize,
+) -> ZeroSumSolution<M, N> {
+    let mu = mu.clamp(0.0, 1.0);
+    let (mut p, mut q) = (row0, col0);
+    let (mut avg_p, mut avg_q) = (row0, col0);
+    let mut dist = f64::INFINITY;
+
+    for t in 0..max_iters {
+        let (ur, uc) = payoffs(a, &p, &q);
+        p = mw_step(&p, &ur, eta, mu);
+        q = mw_step(&q, &uc, eta, mu);
+
+        let n = t as f64;
+        for i in 0..M {
+            avg_p[i] = (avg_p[i] * n + p[i]) / (n + 1.0);
+        }
+        for j in 0..N {
+            avg_q[j] = (avg_q[j] * n + q[j]) / (n + 1.0);
+        }
+
+        let (ar, ac) = payoffs(a, &avg_p, &avg_q);
+        dist = l1(&mw_step(&avg_p, &ar, eta, mu), &avg_p) + l1(&mw_step(&avg_q, &ac, eta, mu), &avg_q);
+        if dist < tol {
+            return ZeroSumSolution { row: avg_p, col: avg_q, l1: dist, iters: t + 1, converged: true };
+        }
+    }
+
+    ZeroSumSolution { row: avg_p, col: avg_q, l1: dist, iters: max_iters, converged: false }
+}
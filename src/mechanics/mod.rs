@@ -1,15 +1,19 @@
 pub mod actions;
+pub mod combinatorics;
 pub mod control;
 pub mod econ;
 pub mod energy;
 pub mod fees;
+pub mod game;
 pub mod stoch;
 pub mod wr;
 
 pub use actions::*;
+pub use combinatorics::*;
 pub use control::*;
 pub use econ::*;
 pub use energy::*;
 pub use fees::*;
+pub use game::*;
 pub use stoch::*;
 pub use wr::*;
@@ -1,13 +1,51 @@
 /// Stochastic mechanics: RNG helpers and crit/jitter multipliers.
 /// Note: uses `bevy_prng::WyRand` with `Rc<RefCell<>>` so callers
-/// can keep closures `Fn` while mutating RNG state.
-use bevy_prng::WyRand;
+/// can keep closures `Fn` while mutating RNG state. Helpers accept any
+/// `RngCore`, so a [`CountingRng`] wrapper works too.
 use rand_core::RngCore;
 use std::cell::RefCell;
 
-/// Gaussian(0,1) via BoxMuller using WyRand.
+/// RNG wrapper that counts consumed `u64` draws (its stream position).
+#[derive(Clone, Debug)]
+pub struct CountingRng<R> {
+    inner: R,
+    draws: u64,
+}
+
+impl<R: RngCore> CountingRng<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner, draws: 0 }
+    }
+    /// Number of `u64` outputs consumed so far.
+    pub fn draws(&self) -> u64 {
+        self.draws
+    }
+    /// Discard the next `n` outputs.
+    pub fn skip(&mut self, n: u64) {
+        for _ in 0..n {
+            self.next_u64();
+        }
+    }
+}
+
+impl<R: RngCore> RngCore for CountingRng<R> {
+    fn next_u32(&mut self) -> u32 {
+        self.draws += 1;
+        self.inner.next_u32()
+    }
+    fn next_u64(&mut self) -> u64 {
+        self.draws += 1;
+        self.inner.next_u64()
+    }
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.draws += dst.len().div_ceil(8) as u64;
+        self.inner.fill_bytes(dst)
+    }
+}
+
+/// Gaussian(0,1) via BoxMuller (two draws).
 #[inline]
-pub fn gaussian01(rng: &RefCell<WyRand>) -> f64 {
+pub fn gaussian01<R: RngCore>(rng: &RefCell<R>) -> f64 {
     let mut r = rng.borrow_mut();
     let u1 = ((r.next_u64() >> 11) as f64) / ((1u64 << 53) as f64);
     let u2 = ((r.next_u64() >> 11) as f64) / ((1u64 << 53) as f64);
@@ -17,9 +55,9 @@ pub fn gaussian01(rng: &RefCell<WyRand>) -> f64 {
     r * t.cos()
 }
 
-/// Bernoulli(p) with WyRand.
+/// Bernoulli(p) (one draw).
 #[inline]
-pub fn bernoulli(rng: &RefCell<WyRand>, p: f64) -> bool {
+pub fn bernoulli<R: RngCore>(rng: &RefCell<R>, p: f64) -> bool {
     let mut r = rng.borrow_mut();
     let u = ((r.next_u64() >> 11) as f64) / ((1u64 << 53) as f64);
     drop(r);
@@ -28,12 +66,12 @@ pub fn bernoulli(rng: &RefCell<WyRand>, p: f64) -> bool {
 
 /// Crit multiplier factor (1 or mult).
 #[inline]
-pub fn crit_factor(rng: &RefCell<WyRand>, chance: f64, mult: f64) -> f64 {
+pub fn crit_factor<R: RngCore>(rng: &RefCell<R>, chance: f64, mult: f64) -> f64 {
     if bernoulli(rng, chance) { mult } else { 1.0 }
 }
 
 /// Multiplicative damage jitter: max(0, 1 + N(0,1)*jitter).
 #[inline]
-pub fn dmg_noise(rng: &RefCell<WyRand>, jitter: f64) -> f64 {
+pub fn dmg_noise<R: RngCore>(rng: &RefCell<R>, jitter: f64) -> f64 {
     (1.0 + gaussian01(rng) * jitter).max(0.0)
 }
@@ -0,0 +1,262 @@
+//! Bayesian optimization for expensive simulations.
+//!
+//! A Gaussian-process surrogate (RBF kernel on the normalized box) is fit to
+//! every evaluation so far; the next θ maximizes expected improvement over
+//! a batch of random and near-best candidates. Intended for simulations that
+//! cost seconds each, where a budget of ~50–200 runs is all there is.
+
+use super::Search;
+use crate::stream_rng;
+use rand_core::RngCore;
+
+/// Settings for [`refine_bayes`].
+#[derive(Clone, Debug)]
+pub struct BayesConfig {
+    /// Total simulations, including the initial design.
+    pub budget: usize,
+    /// Space-filling evaluations before the surrogate takes over;
+    /// `None` → `2n + 1`. θ₀ is always the first of them.
+    pub init: Option<usize>,
+    /// Random candidates scored by the acquisition per step.
+    pub candidates: usize,
+    pub seed: u64,
+    /// Stop early once a loss at or below this is seen.
+    pub tol: f64,
+}
+
+impl Default for BayesConfig {
+    fn default() -> Self {
+        Self { budget: 200, init: None, candidates: 2_000, seed: 0, tol: 0.0 }
+    }
+}
+
+/// Minimize `loss(measure(simulate(θ)))` over `θ ∈ bounds` with at most
+/// `cfg.budget` simulations. `generations` in the result counts simulations.
+///
+/// # Panics
+/// If `theta0` and `bounds` differ in length, or a bound has `hi ≤ lo`.
+pub fn refine_bayes<D, Pi, Sim, Meas, Loss>(
+    theta0: &[f64],
+    bounds: &[(f64, f64)],
+    mut simulate: Sim,
+    mut measure: Meas,
+    loss: Loss,
+    cfg: &BayesConfig,
+) -> Search
+where
+    Sim: FnMut(&[f64]) -> D,
+    Meas: FnMut(&D) -> Pi,
+    Loss: Fn(&Pi) -> f64,
+{
+    let n = theta0.len();
+    assert_eq!(n, bounds.len(), "theta0 and bounds must have the same length");
+    assert!(bounds.iter().all(|(lo, hi)| hi > lo), "every bound needs hi > lo");
+
+    let to_theta = |u: &[f64]| -> Vec<f64> { u.iter().zip(bounds).map(|(x, (lo, hi))| lo + x * (hi - lo)).collect() };
+    let mut eval = |u: &[f64]| loss(&measure(&simulate(&to_theta(u))));
+    let mut rng = stream_rng(cfg.seed, 0);
+    let mut unit = move || (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+
+    let budget = cfg.budget.max(1);
+    let init = cfg.init.unwrap_or(2 * n + 1).clamp(1, budget);
+    let mut xs: Vec<Vec<f64>> = Vec::with_capacity(budget);
+    let mut ys: Vec<f64> = Vec::with_capacity(budget);
+    let record = |x: Vec<f64>, y: f64, xs: &mut Vec<Vec<f64>>, ys: &mut Vec<f64>| {
+        xs.push(x);
+        ys.push(y);
+        y <= cfg.tol
+    };
+
+    // Initial design: θ₀, then a Latin hypercube over the rest.
+    let u0: Vec<f64> =
+        theta0.iter().zip(bounds).map(|(t, (lo, hi))| ((t - lo) / (hi - lo)).clamp(0.0, 1.0)).collect();
+    let y0 = eval(&u0);
+    let mut done = record(u0, y0, &mut xs, &mut ys);
+    let m = init - 1;
+    let strata: Vec<Vec<usize>> = (0..n)
+        .map(|_| {
+            let mut p: Vec<usize> = (0..m).collect();
+            for i in (1..m).rev() {
+                p.swap(i, (unit() * (i + 1) as f64) as usize % (i + 1));
+            }
+            p
+        })
+        .collect();
+    let design: Vec<Vec<f64>> =
+        (0..m).map(|k| strata.iter().map(|s| (s[k] as f64 + unit()) / m as f64).collect()).collect();
+    for u in design {
+        if done {
+            break;
+        }
+        let y = eval(&u);
+        done = record(u, y, &mut xs, &mut ys);
+    }
+
+    // Surrogate-guided steps. With no finite loss to fit yet, or no finite
+    // acquisition, the next point is uniform at random.
+    while !done && xs.len() < budget {
+        let best_i = argmin(&ys);
+        let mut pick: Option<(f64, Vec<f64>)> = None;
+        if let Some(gp) = Gp::fit(&xs, &ys) {
+            let y_best = gp.normalize(ys[best_i]);
+            for c in 0..cfg.candidates.max(1) {
+                // Half global, half local around the incumbent.
+                let u: Vec<f64> = if c % 2 == 0 {
+                    (0..n).map(|_| unit()).collect()
+                } else {
+                    xs[best_i].iter().map(|x| (x + 0.1 * (unit() - 0.5)).clamp(0.0, 1.0)).collect()
+                };
+                let (mu, sd) = gp.predict(&u);
+                let ei = expected_improvement(y_best, mu, sd);
+                if ei.is_finite() && pick.as_ref().is_none_or(|(best, _)| ei > *best) {
+                    pick = Some((ei, u));
+                }
+            }
+        }
+        let u = match pick {
+            Some((_, u)) => u,
+            None => (0..n).map(|_| unit()).collect(),
+        };
+        let y = eval(&u);
+        done = record(u, y, &mut xs, &mut ys);
+    }
+
+    let i = argmin(&ys);
+    Search { theta: to_theta(&xs[i]), loss: ys[i], generations: xs.len(), done }
+}
+
+/// Index of the smallest finite value (0 if there is none).
+fn argmin(v: &[f64]) -> usize {
+    let key = |y: f64| if y.is_finite() { y } else { f64::INFINITY };
+    (0..v.len()).min_by(|&a, &b| key(v[a]).total_cmp(&key(v[b]))).unwrap_or(0)
+}
+
+/// Zero-mean GP on standardized targets with an isotropic RBF kernel. The
+/// length scale is picked from a small grid by marginal likelihood.
+struct Gp {
+    xs: Vec<Vec<f64>>,
+    alpha: Vec<f64>,
+    chol: Vec<Vec<f64>>,
+    ell: f64,
+    jitter: f64,
+    y_mean: f64,
+    y_std: f64,
+}
+
+/// Diagonal jitter, escalated until the kernel matrix factors.
+const JITTERS: [f64; 5] = [1e-6, 1e-4, 1e-2, 1e-1, 1.0];
+
+impl Gp {
+    /// Fit to the samples with a finite loss; `None` if there are none.
+    /// Clustered or duplicated samples make the kernel matrix near
+    /// singular, so a failed Cholesky retries with more jitter.
+    fn fit(xs: &[Vec<f64>], ys: &[f64]) -> Option<Self> {
+        let (xs, ys): (Vec<Vec<f64>>, Vec<f64>) =
+            xs.iter().zip(ys).filter(|(_, y)| y.is_finite()).map(|(x, y)| (x.clone(), *y)).unzip();
+        if ys.is_empty() {
+            return None;
+        }
+        let k = ys.len() as f64;
+        let y_mean = ys.iter().sum::<f64>() / k;
+        let y_std = (ys.iter().map(|y| (y - y_mean).powi(2)).sum::<f64>() / k).sqrt().max(1e-12);
+        let z: Vec<f64> = ys.iter().map(|y| (y - y_mean) / y_std).collect();
+
+        for jitter in JITTERS {
+            let mut best: Option<(f64, Gp)> = None;
+            for ell in [0.05, 0.1, 0.2, 0.35, 0.6, 1.0] {
+                let Some(chol) = cholesky(&kernel_matrix(&xs, ell, jitter)) else { continue };
+                let alpha = chol_solve(&chol, &z);
+                let log_det: f64 = chol.iter().enumerate().map(|(i, row)| row[i].ln()).sum();
+                let lml = -0.5 * z.iter().zip(&alpha).map(|(a, b)| a * b).sum::<f64>() - log_det;
+                if best.as_ref().is_none_or(|(b, _)| lml > *b) {
+                    best = Some((lml, Gp { xs: xs.clone(), alpha, chol, ell, jitter, y_mean, y_std }));
+                }
+            }
+            if let Some((_, gp)) = best {
+                return Some(gp);
+            }
+        }
+        None
+    }
+
+    fn normalize(&self, y: f64) -> f64 {
+        (y - self.y_mean) / self.y_std
+    }
+
+    /// Posterior mean and standard deviation (standardized units) at `u`.
+    fn predict(&self, u: &[f64]) -> (f64, f64) {
+        let ks: Vec<f64> = self.xs.iter().map(|x| rbf(x, u, self.ell)).collect();
+        let mu = ks.iter().zip(&self.alpha).map(|(a, b)| a * b).sum();
+        let v = forward(&self.chol, &ks);
+        let var = (1.0 + self.jitter - v.iter().map(|x| x * x).sum::<f64>()).max(1e-12);
+        (mu, var.sqrt())
+    }
+}
+
+fn rbf(a: &[f64], b: &[f64], ell: f64) -> f64 {
+    let d2: f64 = a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum();
+    (-0.5 * d2 / (ell * ell)).exp()
+}
+
+fn kernel_matrix(xs: &[Vec<f64>], ell: f64, jitter: f64) -> Vec<Vec<f64>> {
+    xs.iter()
+        .enumerate()
+        .map(|(i, a)| xs.iter().enumerate().map(|(j, b)| rbf(a, b, ell) + if i == j { jitter } else { 0.0 }).collect())
+        .collect()
+}
+
+/// Lower-triangular L with L·Lᵀ = A, or `None` if A is not positive definite.
+fn cholesky(a: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let n = a.len();
+    let mut l = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let s: f64 = a[i][j] - (0..j).map(|k| l[i][k] * l[j][k]).sum::<f64>();
+            if i == j {
+                if s <= 0.0 {
+                    return None;
+                }
+                l[i][i] = s.sqrt();
+            } else {
+                l[i][j] = s / l[j][j];
+            }
+        }
+    }
+    Some(l)
+}
+
+/// Solve L·x = b.
+fn forward(l: &[Vec<f64>], b: &[f64]) -> Vec<f64> {
+    let mut x = vec![0.0; b.len()];
+    for i in 0..b.len() {
+        x[i] = (b[i] - (0..i).map(|k| l[i][k] * x[k]).sum::<f64>()) / l[i][i];
+    }
+    x
+}
+
+/// Solve (L·Lᵀ)·x = b.
+fn chol_solve(l: &[Vec<f64>], b: &[f64]) -> Vec<f64> {
+    let y = forward(l, b);
+    let n = y.len();
+    let mut x = vec![0.0; n];
+    for i in (0..n).rev() {
+        x[i] = (y[i] - (i + 1..n).map(|k| l[k][i] * x[k]).sum::<f64>()) / l[i][i];
+    }
+    x
+}
+
+/// EI for minimization, in standardized units.
+fn expected_improvement(best: f64, mu: f64, sd: f64) -> f64 {
+    let gain = best - mu;
+    let z = gain / sd;
+    gain * normal_cdf(z) + sd * (-0.5 * z * z).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+/// Φ(z) via the Abramowitz–Stegun 7.1.26 erf approximation (|ε| < 1.5e-7).
+fn normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t * (0.254_829_592 + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    if z >= 0.0 { 0.5 * (1.0 + erf) } else { 0.5 * (1.0 - erf) }
+}
@@ -0,0 +1,227 @@
+//! CMA-ES (covariance matrix adaptation) over a box-bounded θ.
+//!
+//! The search runs in normalized coordinates `u ∈ [0, 1]ⁿ` (`θ = lo +
+//! u·(hi − lo)`), so tunables with very different scales share one step
+//! size. Candidates outside the box are evaluated at the clamped point plus
+//! a quadratic penalty on the distance, which keeps the mean inside.
+//! Standard parameter choices follow Hansen's tutorial (2016).
+
+use crate::mechanics::stoch::gaussian01;
+use crate::stream_rng;
+use super::Search;
+use std::cell::RefCell;
+
+/// Settings for [`refine_cmaes`].
+#[derive(Clone, Debug)]
+pub struct CmaesConfig {
+    /// Initial step as a fraction of each bound's width.
+    pub sigma0: f64,
+    /// Samples per generation (λ); `None` → `4 + ⌊3 ln n⌋`.
+    pub population: Option<usize>,
+    pub generations: usize,
+    pub seed: u64,
+    /// Stop once every residual is within `±tol`.
+    pub tol: f64,
+}
+
+impl Default for CmaesConfig {
+    fn default() -> Self {
+        Self { sigma0: 0.3, population: None, generations: 500, seed: 0, tol: 1e-6 }
+    }
+}
+
+/// Minimize `Σ rᵢ²` over `θ ∈ bounds`, where `r = residuals(measure(simulate(θ)))`
+/// (e.g. `(obs − target) / target` per metric). Stops when all `|rᵢ| ≤ tol`
+/// or after `generations`. `theta0` is clamped into the box. Returns the best
+/// in-bounds θ seen.
+///
+/// # Panics
+/// If `theta0` and `bounds` differ in length, or a bound has `hi ≤ lo`.
+pub fn refine_cmaes<D, Pi, Sim, Meas, Res>(
+    theta0: &[f64],
+    bounds: &[(f64, f64)],
+    mut simulate: Sim,
+    mut measure: Meas,
+    residuals: Res,
+    cfg: &CmaesConfig,
+) -> Search
+where
+    Sim: FnMut(&[f64]) -> D,
+    Meas: FnMut(&D) -> Pi,
+    Res: Fn(&Pi) -> Vec<f64>,
+{
+    let n = theta0.len();
+    assert_eq!(n, bounds.len(), "theta0 and bounds must have the same length");
+    assert!(bounds.iter().all(|(lo, hi)| hi > lo), "every bound needs hi > lo");
+
+    let to_theta = |u: &[f64]| -> Vec<f64> {
+        u.iter().zip(bounds).map(|(x, (lo, hi))| lo + x.clamp(0.0, 1.0) * (hi - lo)).collect()
+    };
+    // (loss incl. penalty, raw loss, done) at normalized point u.
+    let mut eval = |u: &[f64]| {
+        let th = to_theta(u);
+        let r = residuals(&measure(&simulate(&th)));
+        let raw: f64 = r.iter().map(|x| x * x).sum();
+        let pen: f64 = u.iter().map(|x| (x - x.clamp(0.0, 1.0)).powi(2)).sum();
+        (raw + 1e3 * pen, raw, pen == 0.0 && r.iter().all(|x| x.abs() <= cfg.tol))
+    };
+
+    // Strategy parameters.
+    let nf = n as f64;
+    let lambda = cfg.population.unwrap_or(4 + (3.0 * nf.ln()).floor() as usize).max(2);
+    let mu = lambda / 2;
+    let raw_w: Vec<f64> = (0..mu).map(|i| (mu as f64 + 0.5).ln() - ((i + 1) as f64).ln()).collect();
+    let wsum: f64 = raw_w.iter().sum();
+    let w: Vec<f64> = raw_w.iter().map(|x| x / wsum).collect();
+    let mueff = 1.0 / w.iter().map(|x| x * x).sum::<f64>();
+    let cc = (4.0 + mueff / nf) / (nf + 4.0 + 2.0 * mueff / nf);
+    let cs = (mueff + 2.0) / (nf + mueff + 5.0);
+    let c1 = 2.0 / ((nf + 1.3).powi(2) + mueff);
+    let cmu = (1.0 - c1).min(2.0 * (mueff - 2.0 + 1.0 / mueff) / ((nf + 2.0).powi(2) + mueff));
+    let damps = 1.0 + 2.0 * (((mueff - 1.0) / (nf + 1.0)).sqrt() - 1.0).max(0.0) + cs;
+    let chi_n = nf.sqrt() * (1.0 - 1.0 / (4.0 * nf) + 1.0 / (21.0 * nf * nf));
+
+    // Dynamic state.
+    let mut mean: Vec<f64> =
+        theta0.iter().zip(bounds).map(|(t, (lo, hi))| ((t - lo) / (hi - lo)).clamp(0.0, 1.0)).collect();
+    let mut sigma = cfg.sigma0;
+    let mut pc = vec![0.0; n];
+    let mut ps = vec![0.0; n];
+    let mut c = identity(n);
+    let mut b = identity(n);
+    let mut d = vec![1.0; n];
+
+    let (_, raw0, done0) = eval(&mean);
+    let mut best = Search { theta: to_theta(&mean), loss: raw0, generations: 0, done: done0 };
+    if done0 || n == 0 {
+        return best;
+    }
+
+    for g in 0..cfg.generations {
+        best.generations = g + 1;
+        let rng = RefCell::new(stream_rng(cfg.seed, g as u64));
+
+        // Sample x_k = m + σ·B·(D ∘ z_k).
+        let mut pop: Vec<(f64, Vec<f64>)> = Vec::with_capacity(lambda);
+        for _ in 0..lambda {
+            let z: Vec<f64> = (0..n).map(|_| gaussian01(&rng)).collect();
+            let dz: Vec<f64> = z.iter().zip(&d).map(|(z, d)| z * d).collect();
+            let x: Vec<f64> = (0..n).map(|i| mean[i] + sigma * dot(&b[i], &dz)).collect();
+            let (l, raw, ok) = eval(&x);
+            if raw < best.loss && l == raw {
+                best.theta = to_theta(&x);
+                best.loss = raw;
+            }
+            if ok {
+                best.done = true;
+                return best;
+            }
+            pop.push((l, x));
+        }
+        pop.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        // Recombine.
+        let old = mean.clone();
+        mean = (0..n).map(|i| pop.iter().zip(&w).map(|((_, x), w)| w * x[i]).sum()).collect();
+        let step: Vec<f64> = (0..n).map(|i| (mean[i] - old[i]) / sigma).collect();
+
+        // Evolution paths. C^{-1/2}·step = B·D⁻¹·Bᵀ·step.
+        let bt_step: Vec<f64> = (0..n).map(|j| (0..n).map(|i| b[i][j] * step[i]).sum::<f64>() / d[j]).collect();
+        let csn = (cs * (2.0 - cs) * mueff).sqrt();
+        for i in 0..n {
+            ps[i] = (1.0 - cs) * ps[i] + csn * dot(&b[i], &bt_step);
+        }
+        let ps_norm = dot(&ps, &ps).sqrt();
+        let evals = ((g + 1) * lambda) as f64;
+        let hsig = ps_norm / (1.0 - (1.0 - cs).powf(2.0 * evals / lambda as f64)).sqrt() / chi_n
+            < 1.4 + 2.0 / (nf + 1.0);
+        let hs = if hsig { 1.0 } else { 0.0 };
+        let ccn = (cc * (2.0 - cc) * mueff).sqrt();
+        for i in 0..n {
+            pc[i] = (1.0 - cc) * pc[i] + hs * ccn * step[i];
+        }
+
+        // Covariance: rank-one + rank-μ.
+        let arts: Vec<Vec<f64>> =
+            pop.iter().take(mu).map(|(_, x)| (0..n).map(|i| (x[i] - old[i]) / sigma).collect()).collect();
+        for i in 0..n {
+            for j in 0..=i {
+                let rank_mu: f64 = arts.iter().zip(&w).map(|(a, w)| w * a[i] * a[j]).sum();
+                let v = (1.0 - c1 - cmu) * c[i][j]
+                    + c1 * (pc[i] * pc[j] + (1.0 - hs) * cc * (2.0 - cc) * c[i][j])
+                    + cmu * rank_mu;
+                c[i][j] = v;
+                c[j][i] = v;
+            }
+        }
+        sigma *= ((cs / damps) * (ps_norm / chi_n - 1.0)).exp();
+
+        let (vals, vecs) = jacobi_eigen(&c);
+        d = vals.iter().map(|v| v.max(1e-20).sqrt()).collect();
+        b = vecs;
+
+        let (l, raw, ok) = eval(&mean);
+        if raw < best.loss && l == raw {
+            best.theta = to_theta(&mean);
+            best.loss = raw;
+        }
+        if ok {
+            best.done = true;
+            return best;
+        }
+        if sigma * d.iter().cloned().fold(0.0, f64::max) < 1e-14 {
+            break;
+        }
+    }
+    best
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn identity(n: usize) -> Vec<Vec<f64>> {
+    (0..n).map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect()).collect()
+}
+
+/// Eigen-decomposition of a symmetric matrix by cyclic Jacobi rotations.
+/// Returns (eigenvalues, V) with eigenvectors in the columns of V.
+fn jacobi_eigen(m: &[Vec<f64>]) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = m.len();
+    let mut a = m.to_vec();
+    let mut v = identity(n);
+    for _sweep in 0..64 {
+        let off: f64 = (0..n).flat_map(|i| (0..i).map(move |j| (i, j))).map(|(i, j)| a[i][j] * a[i][j]).sum();
+        if off < 1e-30 {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[p][q].abs() < 1e-300 {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let cos = 1.0 / (t * t + 1.0).sqrt();
+                let sin = t * cos;
+                for row in a.iter_mut() {
+                    let (akp, akq) = (row[p], row[q]);
+                    row[p] = cos * akp - sin * akq;
+                    row[q] = sin * akp + cos * akq;
+                }
+                let (head, tail) = a.split_at_mut(q);
+                for (apk, aqk) in head[p].iter_mut().zip(tail[0].iter_mut()) {
+                    let (x, y) = (*apk, *aqk);
+                    *apk = cos * x - sin * y;
+                    *aqk = sin * x + cos * y;
+                }
+                for row in v.iter_mut() {
+                    let (vkp, vkq) = (row[p], row[q]);
+                    row[p] = cos * vkp - sin * vkq;
+                    row[q] = sin * vkp + cos * vkq;
+                }
+            }
+        }
+    }
+    ((0..n).map(|i| a[i][i]).collect(), v)
+}
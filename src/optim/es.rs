@@ -0,0 +1,103 @@
+//! (μ, λ) evolution strategy.
+
+use crate::mechanics::stoch::gaussian01;
+use crate::stream_rng;
+use super::Search;
+use std::cell::RefCell;
+
+/// Settings for [`refine_es`].
+#[derive(Clone, Debug)]
+pub struct EsConfig {
+    /// Children sampled per generation (λ).
+    pub population: usize,
+    /// Best children recombined into the next mean (μ ≤ λ).
+    pub parents: usize,
+    /// Initial mutation step (absolute, same for every coordinate).
+    pub sigma0: f64,
+    pub generations: usize,
+    pub seed: u64,
+}
+
+impl Default for EsConfig {
+    fn default() -> Self {
+        Self { population: 16, parents: 4, sigma0: 0.5, generations: 200, seed: 0 }
+    }
+}
+
+/// (μ, λ) evolution strategy. Each generation samples `population` children
+/// `mean + σ·N(0, I)`, keeps the `parents` lowest-loss ones, and moves the
+/// mean to their rank-weighted average. σ grows when more than a fifth of
+/// the children beat the current mean and shrinks otherwise (1/5th rule).
+///
+/// Stops early as soon as any evaluated θ satisfies `done(π)`, e.g. "every
+/// metric inside its band", which need not be smooth or even continuous.
+/// Returns the best θ seen.
+pub fn refine_es<D, Pi, Sim, Meas, Loss, Done>(
+    theta0: &[f64],
+    mut simulate: Sim,
+    mut measure: Meas,
+    loss: Loss,
+    done: Done,
+    cfg: &EsConfig,
+) -> Search
+where
+    Sim: FnMut(&[f64]) -> D,
+    Meas: FnMut(&D) -> Pi,
+    Loss: Fn(&Pi) -> f64,
+    Done: Fn(&Pi) -> bool,
+{
+    let lambda = cfg.population.max(1);
+    let mu = cfg.parents.clamp(1, lambda);
+    // Log-rank weights, normalized.
+    let raw: Vec<f64> = (0..mu).map(|i| ((mu as f64 + 0.5).ln() - ((i + 1) as f64).ln()).max(0.0)).collect();
+    let total: f64 = raw.iter().sum();
+    let weights: Vec<f64> = raw.iter().map(|w| w / total).collect();
+
+    let mut eval = |th: &[f64]| {
+        let pi = measure(&simulate(th));
+        (loss(&pi), done(&pi))
+    };
+
+    let mut mean = theta0.to_vec();
+    let (mut mean_loss, mean_done) = eval(&mean);
+    let mut best = Search { theta: mean.clone(), loss: mean_loss, generations: 0, done: mean_done };
+    if mean_done {
+        return best;
+    }
+    let mut sigma = cfg.sigma0;
+
+    for g in 0..cfg.generations {
+        best.generations = g + 1;
+        let rng = RefCell::new(stream_rng(cfg.seed, g as u64));
+        let mut kids: Vec<(f64, Vec<f64>)> = Vec::with_capacity(lambda);
+        for _ in 0..lambda {
+            let child: Vec<f64> = mean.iter().map(|m| m + sigma * gaussian01(&rng)).collect();
+            let (l, ok) = eval(&child);
+            if ok {
+                return Search { theta: child, loss: l, generations: g + 1, done: true };
+            }
+            kids.push((l, child));
+        }
+        kids.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let successes = kids.iter().filter(|(l, _)| *l < mean_loss).count();
+        sigma *= if successes * 5 > lambda { 1.22 } else { 0.82 };
+
+        if kids[0].0 < best.loss {
+            best.theta = kids[0].1.clone();
+            best.loss = kids[0].0;
+        }
+        mean = (0..mean.len()).map(|j| kids.iter().zip(&weights).map(|((_, c), w)| w * c[j]).sum()).collect();
+        let (l, ok) = eval(&mean);
+        mean_loss = l;
+        if l < best.loss || ok {
+            best.theta = mean.clone();
+            best.loss = l;
+        }
+        if ok {
+            best.done = true;
+            return best;
+        }
+    }
+    best
+}
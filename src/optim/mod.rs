@@ -0,0 +1,38 @@
+//! Population-based optimizers over a flat parameter vector.
+//!
+//! Unlike the fixed-point loops in the crate root, these never call an
+//! `update`: they sample candidate θ around a mean, score each through the
+//! caller's `simulate`/`measure`, and move the mean toward the best ones.
+//! That needs only a scalar loss, so jumpy or non-smooth band checks are
+//! fine. Randomness comes from [`crate::stream_rng`] (one stream per
+//! generation), so the same seed replays the same search.
+//!
+//! - [`refine_es`]: (μ, λ) evolution strategy, isotropic steps.
+//! - [`refine_nelder_mead`]: simplex search in a box, for small θ where
+//!   only `simulate` and a residual are known.
+//! - [`refine_bayes`]: Gaussian-process surrogate under a hard simulation
+//!   budget, for `simulate` calls that take seconds.
+//! - `refine_cmaes` (feature `optim-cmaes`): CMA-ES with box bounds,
+//!   for 10+ coupled tunables where isotropic steps stall.
+
+pub mod bayes;
+pub mod es;
+pub mod nelder_mead;
+#[cfg(feature = "optim-cmaes")] pub mod cmaes;
+
+pub use bayes::*;
+pub use es::*;
+pub use nelder_mead::*;
+#[cfg(feature = "optim-cmaes")] pub use cmaes::*;
+
+/// Best point found by the optimizers here.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Search {
+    pub theta: Vec<f64>,
+    pub loss: f64,
+    /// Generations run, including the one that satisfied `done`. Nelder–Mead
+    /// counts simplex iterations, Bayesian optimization counts simulations.
+    pub generations: usize,
+    /// `done(π)` held for `theta`.
+    pub done: bool,
+}
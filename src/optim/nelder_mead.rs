@@ -0,0 +1,101 @@
+//! Nelder–Mead simplex search inside box bounds.
+
+use super::Search;
+
+/// Settings for [`refine_nelder_mead`].
+#[derive(Clone, Debug)]
+pub struct NelderMeadConfig {
+    /// Initial simplex edge as a fraction of each bound's width.
+    pub step: f64,
+    /// Simplex iterations (each costs 1–n+2 evaluations).
+    pub max_iters: usize,
+    /// Converged once best and worst vertex residuals differ by at most this.
+    pub tol: f64,
+}
+
+impl Default for NelderMeadConfig {
+    fn default() -> Self {
+        Self { step: 0.1, max_iters: 2_000, tol: 1e-10 }
+    }
+}
+
+/// Derivative-free minimization of `residual(simulate(θ))` over the box
+/// `bounds`, for loops with no hand-derived `update`. Vertices are clamped
+/// into the box, so every simulated θ is feasible. Standard coefficients
+/// (reflect 1, expand 2, contract ½, shrink ½). `done` means the simplex
+/// collapsed to within `tol`, not that the residual is small.
+///
+/// # Panics
+/// If `theta0` and `bounds` differ in length, or a bound has `hi ≤ lo`.
+pub fn refine_nelder_mead<D, Sim, Res>(
+    theta0: &[f64],
+    bounds: &[(f64, f64)],
+    mut simulate: Sim,
+    residual: Res,
+    cfg: &NelderMeadConfig,
+) -> Search
+where
+    Sim: FnMut(&[f64]) -> D,
+    Res: Fn(&D) -> f64,
+{
+    let n = theta0.len();
+    assert_eq!(n, bounds.len(), "theta0 and bounds must have the same length");
+    assert!(bounds.iter().all(|(lo, hi)| hi > lo), "every bound needs hi > lo");
+
+    let clamp = |x: Vec<f64>| -> Vec<f64> { x.iter().zip(bounds).map(|(v, (lo, hi))| v.clamp(*lo, *hi)).collect() };
+    let mut f = |x: &[f64]| residual(&simulate(x));
+
+    // Initial simplex: θ₀ plus one step along each axis (inward at the upper edge).
+    let x0 = clamp(theta0.to_vec());
+    let mut simplex: Vec<(f64, Vec<f64>)> = vec![(f(&x0), x0.clone())];
+    for (i, (lo, hi)) in bounds.iter().enumerate() {
+        let mut x = x0.clone();
+        let h = cfg.step * (hi - lo);
+        x[i] = if x[i] + h <= *hi { x[i] + h } else { x[i] - h };
+        let x = clamp(x);
+        simplex.push((f(&x), x));
+    }
+
+    let along = |a: &[f64], b: &[f64], t: f64| -> Vec<f64> { a.iter().zip(b).map(|(a, b)| a + t * (b - a)).collect() };
+    let mut iters = 0;
+    let mut done = false;
+    while iters < cfg.max_iters {
+        simplex.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if simplex[n].0 - simplex[0].0 <= cfg.tol {
+            done = true;
+            break;
+        }
+        iters += 1;
+
+        let centroid: Vec<f64> =
+            (0..n).map(|j| simplex[..n].iter().map(|(_, x)| x[j]).sum::<f64>() / n as f64).collect();
+        let (f_worst, worst) = simplex[n].clone();
+
+        let xr = clamp(along(&centroid, &worst, -1.0));
+        let fr = f(&xr);
+        if fr < simplex[0].0 {
+            let xe = clamp(along(&centroid, &worst, -2.0));
+            let fe = f(&xe);
+            simplex[n] = if fe < fr { (fe, xe) } else { (fr, xr) };
+        } else if fr < simplex[n - 1].0 {
+            simplex[n] = (fr, xr);
+        } else {
+            // Contract toward the better of the worst and reflected points.
+            let (fo, outer) = if fr < f_worst { (fr, xr) } else { (f_worst, worst) };
+            let xc = clamp(along(&centroid, &outer, 0.5));
+            let fc = f(&xc);
+            if fc < fo {
+                simplex[n] = (fc, xc);
+            } else {
+                let best = simplex[0].1.clone();
+                for v in simplex.iter_mut().skip(1) {
+                    let x = clamp(along(&best, &v.1, 0.5));
+                    *v = (f(&x), x);
+                }
+            }
+        }
+    }
+    simplex.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let (loss, theta) = simplex.swap_remove(0);
+    Search { theta, loss, generations: iters, done }
+}
@@ -0,0 +1,88 @@
+//! Library self-test for downstream CI (`selftest` feature).
+//!
+//! Runs every enabled system once with a default, known-reachable config and
+//! reports the ones that fail to converge, so a game's test suite can assert
+//! “the balancing engine is healthy” in one call.
+
+const MAX_ITERS: usize = 20_000;
+
+/// Quick convergence check on each enabled system. `Err` lists one message
+/// per failing system.
+pub fn selftest() -> Result<(), Vec<String>> {
+    let mut failures: Vec<String> = Vec::new();
+    let mut check = |name: &str, converged: bool, iters: usize, obs: String| {
+        if !converged {
+            failures.push(format!("{name}: not converged after {iters} iters (obs: {obs})"));
+        }
+    };
+
+    #[cfg(feature = "system-production_spend")]
+    {
+        use crate::systems::production_spend as ps;
+        let out = ps::balance_ext(
+            ps::Params { gen_per_sec: 10.0, spend_rate: 10.0, multiplier: 1.0 },
+            ps::Env {
+                upgrade_cost_base: 10.0,
+                upgrade_cost_growth: 1.15,
+                gain_per_level: 0.05,
+                leak: 0.02,
+                storage_cap: 100_000.0,
+            },
+            ps::Targets { ttu_target: 30.0, util_target: 0.90, growth_target: 5.0, weights: ps::TargetWeights::default() },
+            ps::Options { max_iters: MAX_ITERS, ..Default::default() },
+        );
+        check("production_spend", out.converged, out.iters, format!("{:?}", out.obs));
+    }
+
+    #[cfg(feature = "system-upgrade_cost_curve")]
+    {
+        use crate::systems::upgrade_cost_curve as ucc;
+        let out = ucc::balance_ext(
+            ucc::Params { base: 10.0, growth: 1.15, track_mult: 1.0 },
+            ucc::Env { levels: 10, gain_per_level: 0.05 },
+            ucc::Targets { ttu_band: (7.5, 9.5), slope_pref: 1.15 },
+            10.0,
+            ucc::Options { max_iters: MAX_ITERS, ..Default::default() },
+        );
+        check("upgrade_cost_curve", out.converged, out.iters, format!("{:?}", out.obs));
+    }
+
+    #[cfg(feature = "system-reset_prestige")]
+    {
+        use crate::systems::reset_prestige as pr;
+        let out = pr::balance_ext(
+            pr::Params { reward_mult: 1.0, decay: 0.02, req_score: 1_000.0 },
+            pr::Env { session_goal_minutes: 20.0 },
+            pr::Targets { cycle_minutes: 20.0, reward_growth: 10.0 },
+            10.0,
+            pr::Options { max_iters: MAX_ITERS, ..Default::default() },
+        );
+        check("reset_prestige", out.converged, out.iters, format!("{:?}", out.obs));
+    }
+
+    #[cfg(feature = "system-offline_accumulation")]
+    {
+        use crate::systems::offline_accumulation as off;
+        let out = off::balance_ext(
+            off::Params { cap_minutes: 12.0 * 60.0, decay: 0.02, efficiency: 0.6 },
+            off::Env { typical_afk_minutes: 180.0 },
+            off::Targets { retain_ratio: 0.70 },
+            off::Options { max_iters: MAX_ITERS, ..Default::default() },
+        );
+        check("offline_accumulation", out.converged, out.iters, format!("{:?}", out.obs));
+    }
+
+    #[cfg(feature = "system-shop_pricing")]
+    {
+        use crate::systems::shop_pricing as shop;
+        let out = shop::balance_ext(
+            shop::Params { prices: vec![100.0; 4], restock_minutes: vec![60.0; 4] },
+            shop::Env { income_per_sec: 5.0, shop_share: 0.2 },
+            shop::Targets { cadence_minutes: 10.0, cadence_slope: 1.5, max_hoard: 0.1 },
+            shop::Options { max_iters: MAX_ITERS, ..Default::default() },
+        );
+        check("shop_pricing", out.converged, out.iters, format!("{:?}", out.obs));
+    }
+
+    if failures.is_empty() { Ok(()) } else { Err(failures) }
+}
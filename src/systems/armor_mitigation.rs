@@ -0,0 +1,228 @@
+//! Armor mitigation: tune the armor constant and the diminishing-returns
+//! knee so effective HP hits target multipliers at armor breakpoints
+//! ("1000 armor ≈ 2× EHP, 3000 ≈ 3×").
+//!
+//! Armor first bends at the knee, `a_eff = knee · ln(1 + a / knee)` (close
+//! to `a` below the knee, logarithmic above), then mitigates the usual way:
+//! damage taken is `K / (K + a_eff)`, so the EHP multiplier is
+//! `1 + a_eff / K`. A huge knee gives the familiar linear EHP curve. Given a
+//! knee, the best constant is closed form in log space; the knee is the 1-D
+//! least-squares fit over the breakpoints (exact for two of them). [`Obs`]
+//! samples EHP and its slope along the curve.
+
+use crate::error::{Checks, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::ControllerState;
+use crate::systems::sdk::{
+    balance_with_hooks, compose_income, within, Hook, NominalTargets, Outcome, Residual, RelTol,
+};
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Params {
+    pub armor_constant: f64, // K: effective armor that doubles EHP
+    pub knee: f64,           // armor where diminishing returns set in
+}
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        vec![self.armor_constant, self.knee]
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { armor_constant: v[0], knee: v[1] }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Env {
+    pub samples: Vec<f64>, // armor levels reported in [`Obs`]
+}
+
+#[derive(Clone, Debug)]
+pub struct Targets {
+    pub breakpoints: Vec<(f64, f64)>, // (armor, EHP multiplier), e.g. [(1000, 2), (3000, 3)]
+}
+
+crate::define_system! {
+    bounds {
+        armor_constant: constant_min..constant_max = (1e-6, 1e9) => .positive(constant_min),
+        knee: knee_min..knee_max = (1e-3, 1e9) => .positive(knee_min),
+    }
+    gains { k_constant = 0.5, k_knee = 0.5 }
+}
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        self.samples.iter().fold(Checks::default(), |c, &a| c.non_negative("samples", a)).done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        let c = Checks::default().check("breakpoints", !self.breakpoints.is_empty(), "must not be empty");
+        let c = self.breakpoints.iter().fold(c, |c, &(armor, ehp)| {
+            c.positive("breakpoints.armor", armor).check("breakpoints.ehp", ehp > 1.0, "must exceed 1")
+        });
+        self.breakpoints
+            .windows(2)
+            .fold(c, |c, w| c.check("breakpoints", w[1].0 > w[0].0 && w[1].1 > w[0].1, "must rise with armor"))
+            .done()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obs {
+    pub armor_mult: f64,       // armor factor from hooks
+    pub ehp: Vec<f64>,         // EHP multiplier at each sample
+    pub mitigation: Vec<f64>,  // damage share prevented at each sample
+    pub slope: Vec<f64>,       // EHP multiplier gained per armor point at each sample
+    pub breakpoints: Vec<f64>, // EHP multiplier at each target breakpoint
+}
+
+/// Armor after diminishing returns: `knee · ln(1 + armor / knee)`.
+pub fn effective_armor(armor: f64, knee: f64) -> f64 {
+    let knee = knee.max(1e-12);
+    knee * (armor.max(0.0) / knee).ln_1p()
+}
+
+/// EHP multiplier at `armor`: `1 + a_eff / K`.
+pub fn ehp_multiplier(armor: f64, th: &Params) -> f64 {
+    1.0 + effective_armor(armor, th.knee) / th.armor_constant.max(1e-12)
+}
+
+/// d(EHP multiplier) / d(armor) at `armor`.
+pub fn ehp_slope(armor: f64, th: &Params) -> f64 {
+    1.0 / (th.armor_constant.max(1e-12) * (1.0 + armor.max(0.0) / th.knee.max(1e-12)))
+}
+
+/// Least-squares constant for a knee, in log space: each breakpoint asks
+/// for `K = a_eff / (ehp − 1)`; the fit is their geometric mean.
+fn constant_for(breakpoints: &[(f64, f64)], knee: f64) -> f64 {
+    let n = breakpoints.len().max(1) as f64;
+    let ln_sum: f64 =
+        breakpoints.iter().map(|&(a, m)| (effective_armor(a, knee) / (m - 1.0).max(1e-12)).max(1e-300).ln()).sum();
+    (ln_sum / n).exp()
+}
+
+/// Squared log misfit of the breakpoints at `knee` with its best constant.
+fn misfit(breakpoints: &[(f64, f64)], knee: f64) -> f64 {
+    let k = constant_for(breakpoints, knee);
+    breakpoints
+        .iter()
+        .map(|&(a, m)| ((effective_armor(a, knee) / k).max(1e-300).ln() - (m - 1.0).max(1e-12).ln()).powi(2))
+        .sum()
+}
+
+/// `(armor_constant, knee)` that best fits `breakpoints`, searching knees in
+/// `[knee_min, knee_max]` (log grid, then golden section). One breakpoint
+/// cannot place the knee, so it keeps `knee`.
+pub fn fit(breakpoints: &[(f64, f64)], knee: f64, knee_min: f64, knee_max: f64) -> Option<(f64, f64)> {
+    if breakpoints.is_empty() {
+        return None;
+    }
+    if breakpoints.len() == 1 {
+        return Some((constant_for(breakpoints, knee), knee));
+    }
+    let (lo, hi) = (knee_min.max(1e-12).ln(), knee_max.max(knee_min.max(1e-12)).ln());
+    const GRID: usize = 200;
+    let at = |i: usize| lo + (hi - lo) * i as f64 / GRID as f64;
+    let best = (0..=GRID).min_by(|&i, &j| misfit(breakpoints, at(i).exp()).total_cmp(&misfit(breakpoints, at(j).exp())))?;
+    let (mut a, mut b) = (at(best.saturating_sub(1)), at((best + 1).min(GRID)));
+    let phi = 0.5 * (5f64.sqrt() - 1.0);
+    for _ in 0..80 {
+        let (c, d) = (b - phi * (b - a), a + phi * (b - a));
+        if misfit(breakpoints, c.exp()) < misfit(breakpoints, d.exp()) { b = d } else { a = c }
+    }
+    let knee = (0.5 * (a + b)).exp();
+    Some((constant_for(breakpoints, knee), knee))
+}
+
+/// The mitigation curve described in the module docs; hooks'
+/// `income_multiplier` scales armor (buffs above 1, percent penetration
+/// below). Implement [`SimModel`] for flat penetration, armor caps or per-
+/// level scaling.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        tgt: &Targets,
+        mechs: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let armor_mult = compose_income(1.0, mechs, th, env).max(0.0);
+        let ehp: Vec<f64> = env.samples.iter().map(|&a| ehp_multiplier(a * armor_mult, th)).collect();
+        Obs {
+            armor_mult,
+            mitigation: ehp.iter().map(|m| 1.0 - 1.0 / m).collect(),
+            slope: env.samples.iter().map(|&a| armor_mult * ehp_slope(a * armor_mult, th)).collect(),
+            breakpoints: tgt.breakpoints.iter().map(|&(a, _)| ehp_multiplier(a * armor_mult, th)).collect(),
+            ehp,
+        }
+    }
+}
+
+/// Unitless error vs targets: summed relative EHP misses at the
+/// breakpoints.
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    o.breakpoints.iter().zip(&tgt.breakpoints).map(|(&m, &(_, want))| (m / want - 1.0).abs()).sum()
+}
+
+/// Named residuals for [`Outcome::residuals`], one per breakpoint.
+pub fn residuals(o: &Obs, tgt: &Targets) -> Vec<Residual> {
+    o.breakpoints
+        .iter()
+        .zip(&tgt.breakpoints)
+        .map(|(&m, &(a, want))| Residual::new(format!("ehp@{a}"), m, want))
+        .collect()
+}
+
+pub fn balance_ext(
+    theta0: Params,
+    env: Env,
+    tgt: Targets,
+    opts: Options<impl SimModel>,
+) -> Outcome<Params, Obs> {
+    let Options { bounds: b, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = [ControllerState::default(); 2];
+    let res_tgt = tgt.clone();
+    balance_with_hooks(
+        theta0,
+        env,
+        tgt,
+        b,
+        g,
+        mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+        max_iters,
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, mechs),
+        // nominal: the breakpoint fit with armor scaled by the hooks (both
+        // the constant and the knee scale with armor); x = constant, y = knee
+        move |th, _env, tgt, o| {
+            let m = o.armor_mult.max(1e-12);
+            let scaled: Vec<(f64, f64)> = tgt.breakpoints.iter().map(|&(a, e)| (a * m, e)).collect();
+            match fit(&scaled, th.knee, b.knee_min, b.knee_max) {
+                Some((constant, knee)) => NominalTargets { x: constant, y: knee, z: 0.0 },
+                None => NominalTargets { x: th.armor_constant, y: th.knee, z: 0.0 },
+            }
+        },
+        move |th, b, g, nom, adj| {
+            let (constant_t, knee_t) = (nom.x * adj.a, nom.y * adj.b);
+
+            let (constant_t, knee_t) = match reg {
+                Some(r) => (r.pull(constant_t, |p| p.armor_constant), r.pull(knee_t, |p| p.knee)),
+                None => (constant_t, knee_t),
+            };
+
+            let st = &mut ctl_state;
+            Params {
+                armor_constant: controller.step(&mut st[0], th.armor_constant, constant_t, g.k_constant, b.constant_min, b.constant_max),
+                knee: controller.step(&mut st[1], th.knee, knee_t, g.k_knee, b.knee_min, b.knee_max),
+            }
+        },
+        // converged: every breakpoint's EHP within ±2% of its target
+        |o, tgt| o.breakpoints.iter().zip(&tgt.breakpoints).all(|(&m, &(_, want))| within(m, want, RelTol(0.02))),
+    )
+    .with_residuals(move |o| residuals(o, &res_tgt))
+}
@@ -0,0 +1,304 @@
+//! Autobattler economy: tune base income, streak gold and interest so two
+//! canonical strategies — greedy (bank gold for interest) and all-in tempo
+//! (spend everything) — follow target net-worth trajectories.
+//!
+//! Each round a player earns the base income, interest of `gold /
+//! interest_step` (capped at `interest_cap`) on the gold they banked, and
+//! `streak_gold` for every round their current win or loss streak has run
+//! past the first (up to `streak_cap`). Streaks are expected values under
+//! each strategy's per-round win rate. Greedy banks up to the interest
+//! breakpoint `interest_cap · interest_step` and spends the rest; tempo
+//! banks nothing. Net worth is banked gold plus everything spent on board.
+//!
+//! Four targets pin the four knobs: tempo's final net worth and the share of
+//! it paid by streaks set base income and streak gold; greedy's edge over
+//! tempo at the final round (greedy econ is viable) sets the interest cap;
+//! and tempo's board lead at the spike round (all-in is not strictly
+//! dominated) sets how much greedy must bank, hence the interest step.
+
+use crate::error::{Checks, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::{self, ControllerState};
+use crate::systems::sdk::{
+    balance_with_hooks, compose_income, within, AbsTol, Hook, NominalTargets, Outcome, Residual, RelTol,
+};
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Params {
+    pub base_income: f64,   // gold per round
+    pub streak_gold: f64,   // gold per round of streak past the first
+    pub interest_step: f64, // banked gold per 1 interest
+    pub interest_cap: f64,  // most interest per round
+}
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        vec![self.base_income, self.streak_gold, self.interest_step, self.interest_cap]
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { base_income: v[0], streak_gold: v[1], interest_step: v[2], interest_cap: v[3] }
+    }
+}
+
+impl Params {
+    /// Banked gold at which interest stops growing.
+    pub fn breakpoint(&self) -> f64 {
+        self.interest_step * self.interest_cap
+    }
+}
+
+/// A canonical line of play.
+#[derive(Clone, Copy, Debug)]
+pub struct Strategy {
+    pub win_rate: f64, // chance of winning each round
+    pub bank: f64,     // share of the interest breakpoint kept banked (1 = greedy, 0 = all-in)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Env {
+    pub rounds: u32,
+    pub spike_round: u32, // round where tempo's board lead is measured
+    pub streak_cap: u32,  // streak rounds that pay, e.g. 3
+    pub greedy: Strategy,
+    pub tempo: Strategy,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Targets {
+    pub tempo_worth: f64,  // tempo's net worth after the last round
+    pub streak_share: f64, // share of tempo's income from streaks, e.g. 0.15
+    pub greedy_edge: f64,  // greedy / tempo net worth after the last round, e.g. 1.2
+    pub tempo_lead: f64,   // tempo / greedy board at the spike round, e.g. 1.3
+}
+
+crate::define_system! {
+    bounds {
+        base_income: base_min..base_max = (1e-6, 1e9),
+        streak_gold: streak_min..streak_max = (0.0, 1e9),
+        interest_step: step_min..step_max = (1e-3, 1e9) => .positive(step_min),
+        interest_cap: cap_min..cap_max = (0.0, 1e9),
+    }
+    gains { k_base = 0.5, k_streak = 0.5, k_step = 0.4, k_cap = 0.4 }
+}
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .check("rounds", self.rounds > 0, "must be at least 1")
+            .check("spike_round", (1..=self.rounds).contains(&self.spike_round), "must be in 1..=rounds")
+            .within("greedy.win_rate", self.greedy.win_rate, 0.0, 1.0)
+            .within("greedy.bank", self.greedy.bank, 0.0, 1.0)
+            .within("tempo.win_rate", self.tempo.win_rate, 0.0, 1.0)
+            .within("tempo.bank", self.tempo.bank, 0.0, 1.0)
+            .done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .positive("tempo_worth", self.tempo_worth)
+            .check("streak_share", (0.0..1.0).contains(&self.streak_share), "must be in [0, 1)")
+            .positive("greedy_edge", self.greedy_edge)
+            .positive("tempo_lead", self.tempo_lead)
+            .done()
+    }
+}
+
+/// One strategy's game, round by round (index 0 = after round 1).
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Trajectory {
+    pub worth: Vec<f64>, // banked gold + board
+    pub board: Vec<f64>, // gold spent so far
+    pub interest: f64,   // total interest earned
+    pub streak: f64,     // total streak gold earned
+}
+impl Trajectory {
+    pub fn final_worth(&self) -> f64 {
+        self.worth.last().copied().unwrap_or(0.0)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obs {
+    pub base_income: f64, // per round, after hooks
+    pub greedy: Trajectory,
+    pub tempo: Trajectory,
+    pub streak_share: f64, // tempo
+    pub greedy_edge: f64,
+    pub tempo_lead: f64,
+}
+
+/// Expected paying streak rounds, `E[min(streak − 1, cap)]`, after `round`
+/// rounds won each with probability `win_rate`: a streak of length ≥ k needs
+/// the last k rounds to agree.
+pub fn streak_rounds(win_rate: f64, round: u32, streak_cap: u32) -> f64 {
+    let (p, q) = (win_rate.clamp(0.0, 1.0), 1.0 - win_rate.clamp(0.0, 1.0));
+    (2..=round.min(streak_cap.saturating_add(1))).map(|k| p.powi(k as i32) + q.powi(k as i32)).sum()
+}
+
+/// Play `s` for every round: earn, then spend everything above the bank.
+pub fn trajectory(th: &Params, env: &Env, s: &Strategy, base_income: f64) -> Trajectory {
+    let bank = s.bank.clamp(0.0, 1.0) * th.breakpoint().max(0.0);
+    let (mut gold, mut board) = (0.0_f64, 0.0_f64);
+    let mut t = Trajectory::default();
+    for r in 1..=env.rounds {
+        let interest = (gold / th.interest_step.max(1e-9)).min(th.interest_cap.max(0.0));
+        let streak = th.streak_gold.max(0.0) * streak_rounds(s.win_rate, r, env.streak_cap);
+        gold += base_income + interest + streak;
+        let spent = (gold - bank).max(0.0);
+        board += spent;
+        gold -= spent;
+        t.interest += interest;
+        t.streak += streak;
+        t.worth.push(gold + board);
+        t.board.push(board);
+    }
+    t
+}
+
+/// The expected-gold round loop described in the module docs; hooks'
+/// `income_multiplier` scales the base income. Implement [`SimModel`] for
+/// shop rolls, level costs or HP loss.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        _tgt: &Targets,
+        mechs: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let base = compose_income(th.base_income, mechs, th, env);
+        let greedy = trajectory(th, env, &env.greedy, base);
+        let tempo = trajectory(th, env, &env.tempo, base);
+        let spike = env.spike_round.clamp(1, env.rounds.max(1)) as usize - 1;
+        let lead = |g: &Trajectory, t: &Trajectory| {
+            t.board.get(spike).copied().unwrap_or(0.0) / g.board.get(spike).copied().unwrap_or(0.0).max(1e-12)
+        };
+        Obs {
+            base_income: base,
+            streak_share: tempo.streak / tempo.final_worth().max(1e-12),
+            greedy_edge: greedy.final_worth() / tempo.final_worth().max(1e-12),
+            tempo_lead: lead(&greedy, &tempo),
+            greedy,
+            tempo,
+        }
+    }
+}
+
+/// Smallest `x` in `[lo, hi]` with `f(x) ≥ want` for increasing `f`
+/// (an end point when the target is out of reach).
+fn bisect(lo: f64, hi: f64, want: f64, f: impl Fn(f64) -> f64) -> f64 {
+    if f(hi) <= want {
+        return hi;
+    }
+    let (mut lo, mut hi) = (lo, hi);
+    for _ in 0..60 {
+        let x = 0.5 * (lo + hi);
+        if f(x) < want { lo = x } else { hi = x }
+    }
+    hi
+}
+
+/// Unitless error vs targets: tempo's worth, its streak share, greedy's edge
+/// and tempo's board lead.
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    control::pct_error(o.tempo.final_worth(), tgt.tempo_worth)
+        + (o.streak_share - tgt.streak_share).abs()
+        + control::pct_error(o.greedy_edge, tgt.greedy_edge)
+        + control::pct_error(o.tempo_lead, tgt.tempo_lead)
+}
+
+/// Named residuals for [`Outcome::residuals`].
+pub fn residuals(o: &Obs, tgt: &Targets) -> Vec<Residual> {
+    vec![
+        Residual::new("tempo_worth", o.tempo.final_worth(), tgt.tempo_worth),
+        Residual::new("streak_share", o.streak_share, tgt.streak_share),
+        Residual::new("greedy_edge", o.greedy_edge, tgt.greedy_edge),
+        Residual::new("tempo_lead", o.tempo_lead, tgt.tempo_lead),
+    ]
+}
+
+pub fn balance_ext(
+    theta0: Params,
+    env: Env,
+    tgt: Targets,
+    opts: Options<impl SimModel>,
+) -> Outcome<Params, Obs> {
+    let Options { bounds: b, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = [ControllerState::default(); 4];
+    let streak_rounds_total: f64 =
+        (1..=env.rounds).map(|r| streak_rounds(env.tempo.win_rate, r, env.streak_cap)).sum();
+    let streak_t = tgt.tempo_worth * tgt.streak_share / streak_rounds_total.max(1e-12);
+    balance_with_hooks(
+        theta0,
+        env,
+        tgt,
+        b,
+        g,
+        mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+        max_iters,
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, mechs),
+        // nominal: x = base income for tempo's non-streak worth (scaled back
+        // by what hooks add), y = breakpoint (gold greedy banks) that gives
+        // tempo its board lead, z = interest cap that gives greedy its edge
+        // at that breakpoint
+        |th, env, tgt, o| {
+            let base = tgt.tempo_worth * (1.0 - tgt.streak_share) / f64::from(env.rounds.max(1));
+            let eff = o.base_income;
+            let at = |breakpoint: f64, cap: f64| {
+                let cap = cap.max(1e-9);
+                let th = Params { interest_step: breakpoint / cap, interest_cap: cap, ..*th };
+                (trajectory(&th, env, &env.greedy, eff), trajectory(&th, env, &env.tempo, eff))
+            };
+            let spike = env.spike_round.clamp(1, env.rounds.max(1)) as usize - 1;
+            let breakpoint = bisect(0.0, tgt.tempo_worth, tgt.tempo_lead, |h| {
+                let (gr, te) = at(h, th.interest_cap);
+                te.board[spike] / gr.board[spike].max(1e-12)
+            });
+            let cap = bisect(0.0, tgt.tempo_worth, tgt.greedy_edge, |c| {
+                let (gr, te) = at(breakpoint, c);
+                gr.final_worth() / te.final_worth().max(1e-12)
+            });
+            NominalTargets { x: base * th.base_income / eff.max(1e-12), y: breakpoint, z: cap }
+        },
+        // step: base and cap → nominal; step → breakpoint / cap; streak gold
+        //       → tempo's streak worth over its expected streak rounds
+        move |th, b, g, nom, adj| {
+            let (base_t, cap_t) = (nom.x * adj.a, nom.z * adj.c);
+            let step_t = nom.y * adj.b / cap_t.max(1e-9);
+
+            let (base_t, streak_t, step_t, cap_t) = match reg {
+                Some(r) => (
+                    r.pull(base_t, |p| p.base_income),
+                    r.pull(streak_t, |p| p.streak_gold),
+                    r.pull(step_t, |p| p.interest_step),
+                    r.pull(cap_t, |p| p.interest_cap),
+                ),
+                None => (base_t, streak_t, step_t, cap_t),
+            };
+
+            let st = &mut ctl_state;
+            Params {
+                base_income: controller.step(&mut st[0], th.base_income, base_t, g.k_base, b.base_min, b.base_max),
+                streak_gold: controller.step(&mut st[1], th.streak_gold, streak_t, g.k_streak, b.streak_min, b.streak_max),
+                interest_step: controller.step(&mut st[2], th.interest_step, step_t, g.k_step, b.step_min, b.step_max),
+                interest_cap: controller.step(&mut st[3], th.interest_cap, cap_t, g.k_cap, b.cap_min, b.cap_max),
+            }
+        },
+        // converged: tempo's worth and greedy's edge within ±2%, tempo's lead
+        // within ±3% and the streak share within 1 point
+        |o, tgt| {
+            within(o.tempo.final_worth(), tgt.tempo_worth, RelTol(0.02))
+                && within(o.streak_share, tgt.streak_share, AbsTol(0.01))
+                && within(o.greedy_edge, tgt.greedy_edge, RelTol(0.02))
+                && within(o.tempo_lead, tgt.tempo_lead, RelTol(0.03))
+        },
+    )
+    .with_residuals(|o| residuals(o, &tgt))
+}
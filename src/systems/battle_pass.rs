@@ -0,0 +1,234 @@
+//! Battle pass: tune XP per tier and the two XP sources (play minutes and
+//! weekly challenges) so each track finishes when the design wants it to.
+//!
+//! Two reference players are paced through the same season: the average
+//! player should finish the free track after `free_finish_share` of the
+//! season, and the dedicated player should finish the premium track with
+//! `spare_weeks` to go. A player earns `minutes · xp_per_minute +
+//! weekly_share · weekly_xp` per week, so both finish times are linear in
+//! the two sources and [`source_mix`] solves them exactly. Play XP favours
+//! whoever plays more and challenge XP is the same for everyone, so the
+//! mix is what separates the two players; `tier_xp` pins the overall scale.
+
+use crate::error::{Checks, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::{self, ControllerState};
+use crate::systems::sdk::{
+    balance_with_hooks, compose_income, within, AbsTol, Hook, NominalTargets, Outcome, Residual,
+};
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Params {
+    pub xp_per_tier: f64,
+    pub xp_per_minute: f64, // play XP
+    pub weekly_xp: f64,     // weekly challenge XP when all are completed
+}
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        vec![self.xp_per_tier, self.xp_per_minute, self.weekly_xp]
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { xp_per_tier: v[0], xp_per_minute: v[1], weekly_xp: v[2] }
+    }
+}
+
+/// How one reference player engages with the pass.
+#[derive(Clone, Copy, Debug)]
+pub struct Player {
+    pub sessions_per_week: f64,
+    pub minutes_per_session: f64,
+    pub weekly_share: f64, // share of weekly challenge XP completed
+}
+impl Player {
+    pub fn minutes_per_week(&self) -> f64 {
+        self.sessions_per_week * self.minutes_per_session
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Env {
+    pub season_weeks: f64,
+    pub free_tiers: f64,    // tiers to the last free reward
+    pub premium_tiers: f64, // tiers to the last premium reward
+    pub average: Player,
+    pub dedicated: Player,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Targets {
+    pub free_finish_share: f64, // average player finishes free after this share of the season, e.g. 0.8
+    pub spare_weeks: f64,       // dedicated player finishes premium this long before the end, e.g. 1
+    pub tier_xp: f64,           // XP per tier the design wants, e.g. 1000
+}
+
+crate::define_system! {
+    bounds {
+        xp_per_tier: tier_min..tier_max = (1.0, 1e9),
+        xp_per_minute: minute_min..minute_max = (0.0, 1e9),
+        weekly_xp: weekly_min..weekly_max = (0.0, 1e12),
+    }
+    gains { k_tier = 0.6, k_minute = 0.6, k_weekly = 0.6 }
+}
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        let c = Checks::default()
+            .positive("season_weeks", self.season_weeks)
+            .positive("free_tiers", self.free_tiers)
+            .positive("premium_tiers", self.premium_tiers);
+        [("average", self.average), ("dedicated", self.dedicated)]
+            .into_iter()
+            .fold(c, |c, (name, p)| {
+                c.check(name, p.minutes_per_week() > 0.0, "needs sessions_per_week and minutes_per_session > 0")
+                    .check(name, (0.0..=1.0).contains(&p.weekly_share), "weekly_share must be in [0, 1]")
+            })
+            .done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .check("free_finish_share", self.free_finish_share > 0.0 && self.free_finish_share <= 1.0, "must be in (0, 1]")
+            .non_negative("spare_weeks", self.spare_weeks)
+            .positive("tier_xp", self.tier_xp)
+            .done()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obs {
+    pub xp_per_minute: f64, // after hooks
+    pub average_weekly_xp: f64,
+    pub dedicated_weekly_xp: f64,
+    pub free_weeks: f64,       // average player, start → last free reward
+    pub premium_weeks: f64,    // dedicated player, start → last premium reward
+    pub average_premium: f64,  // share of the premium track the average player reaches
+    pub challenge_share: f64,  // share of the average player's XP from weekly challenges
+}
+
+/// XP per week a player earns from `xp_per_minute` play XP and `weekly_xp`
+/// of challenges.
+pub fn weekly_xp(p: &Player, xp_per_minute: f64, weekly_xp: f64) -> f64 {
+    p.minutes_per_week() * xp_per_minute + p.weekly_share * weekly_xp
+}
+
+/// `(xp_per_minute, weekly_xp)` that put both players on their finish
+/// times at `tier_xp` per tier, or `None` when no non-negative mix does
+/// (the two players' play time is too close, or too far apart, for the
+/// targets).
+pub fn source_mix(env: &Env, tgt: &Targets) -> Option<(f64, f64)> {
+    let (a, d) = (&env.average, &env.dedicated);
+    let need_a = env.free_tiers * tgt.tier_xp / (tgt.free_finish_share * env.season_weeks);
+    let need_d = env.premium_tiers * tgt.tier_xp / (env.season_weeks - tgt.spare_weeks);
+    let det = a.minutes_per_week() * d.weekly_share - d.minutes_per_week() * a.weekly_share;
+    if !need_d.is_finite() || need_d <= 0.0 || det.abs() < 1e-12 {
+        return None;
+    }
+    let per_minute = (need_a * d.weekly_share - need_d * a.weekly_share) / det;
+    let weekly = (a.minutes_per_week() * need_d - d.minutes_per_week() * need_a) / det;
+    (per_minute >= 0.0 && weekly >= 0.0).then_some((per_minute, weekly))
+}
+
+/// The linear pacing math described in the module docs; hooks'
+/// `income_multiplier` scales play XP (XP boosts, party bonuses). Implement
+/// [`SimModel`] for rising tier costs, catch-up XP or daily XP caps.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        _tgt: &Targets,
+        mechs: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let per_minute = compose_income(th.xp_per_minute, mechs, th, env).max(0.0);
+        let avg = weekly_xp(&env.average, per_minute, th.weekly_xp).max(1e-9);
+        let ded = weekly_xp(&env.dedicated, per_minute, th.weekly_xp).max(1e-9);
+        let season_tiers = avg * env.season_weeks / th.xp_per_tier.max(1e-9);
+        Obs {
+            xp_per_minute: per_minute,
+            average_weekly_xp: avg,
+            dedicated_weekly_xp: ded,
+            free_weeks: env.free_tiers * th.xp_per_tier / avg,
+            premium_weeks: env.premium_tiers * th.xp_per_tier / ded,
+            average_premium: (season_tiers / env.premium_tiers.max(1e-9)).min(1.0),
+            challenge_share: (env.average.weekly_share * th.weekly_xp / avg).clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Unitless error vs targets: both finish times against their target week.
+/// Needs `env` for the season length.
+pub fn normalized_error(o: &Obs, env: &Env, tgt: &Targets) -> f64 {
+    control::pct_error(o.free_weeks, tgt.free_finish_share * env.season_weeks)
+        + control::pct_error(o.premium_weeks, env.season_weeks - tgt.spare_weeks)
+}
+
+/// Named residuals for [`Outcome::residuals`], in weeks.
+pub fn residuals(o: &Obs, env: &Env, tgt: &Targets) -> Vec<Residual> {
+    vec![
+        Residual::new("free_weeks", o.free_weeks, tgt.free_finish_share * env.season_weeks),
+        Residual::new("premium_weeks", o.premium_weeks, env.season_weeks - tgt.spare_weeks),
+    ]
+}
+
+pub fn balance_ext(
+    theta0: Params,
+    env: Env,
+    tgt: Targets,
+    opts: Options<impl SimModel>,
+) -> Outcome<Params, Obs> {
+    let Options { bounds: b, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = [ControllerState::default(); 3];
+    balance_with_hooks(
+        theta0,
+        env,
+        tgt,
+        b,
+        g,
+        mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+        max_iters,
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, mechs),
+        // nominal: x = tier scale, y = play XP (scaled back by what hooks
+        // add), z = challenge XP from the exact source mix; with no feasible
+        // mix, play XP alone paces the average player
+        |th, env, tgt, o| {
+            let hook_gain = o.xp_per_minute / th.xp_per_minute.max(1e-12);
+            let (per_minute, weekly) = source_mix(env, tgt).unwrap_or_else(|| {
+                let need = env.free_tiers * tgt.tier_xp / (tgt.free_finish_share * env.season_weeks);
+                (need / env.average.minutes_per_week().max(1e-9), 0.0)
+            });
+            NominalTargets { x: tgt.tier_xp, y: per_minute / hook_gain.max(1e-12), z: weekly }
+        },
+        move |th, b, g, nom, adj| {
+            let (tier_t, minute_t, weekly_t) = (nom.x * adj.a, nom.y * adj.b, nom.z * adj.c);
+
+            let (tier_t, minute_t, weekly_t) = match reg {
+                Some(r) => (
+                    r.pull(tier_t, |p| p.xp_per_tier),
+                    r.pull(minute_t, |p| p.xp_per_minute),
+                    r.pull(weekly_t, |p| p.weekly_xp),
+                ),
+                None => (tier_t, minute_t, weekly_t),
+            };
+
+            let st = &mut ctl_state;
+            Params {
+                xp_per_tier: controller.step(&mut st[0], th.xp_per_tier, tier_t, g.k_tier, b.tier_min, b.tier_max),
+                xp_per_minute: controller.step(&mut st[1], th.xp_per_minute, minute_t, g.k_minute, b.minute_min, b.minute_max),
+                weekly_xp: controller.step(&mut st[2], th.weekly_xp, weekly_t, g.k_weekly, b.weekly_min, b.weekly_max),
+            }
+        },
+        // converged: both finish times within a fifth of a week
+        move |o, tgt| {
+            within(o.free_weeks, tgt.free_finish_share * env.season_weeks, AbsTol(0.2))
+                && within(o.premium_weeks, env.season_weeks - tgt.spare_weeks, AbsTol(0.2))
+        },
+    )
+    .with_residuals(|o| residuals(o, &env, &tgt))
+}
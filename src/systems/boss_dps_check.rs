@@ -0,0 +1,304 @@
+//! Boss DPS check: tune a raid boss's HP, hard enrage timer and soft-enrage
+//! ramp so the share of raid groups that clear follows target weeks (top 10%
+//! in week 1, the median by week 4, …) and the kill takes a target time.
+//!
+//! Raid DPS is log-logistic: the share of groups above `x` is logistic in
+//! `ln(x / median_dps)` with scale `dps_spread`, and gear lifts every group
+//! by `weekly_growth` a week. Under the soft enrage the raid loses
+//! `soft_ramp` of its DPS per minute (deaths to the ramping boss); at
+//! `enrage_seconds` it wipes. A group clears when its damage by the timer
+//! reaches the boss HP, so clears come down to one threshold DPS. That
+//! threshold is the log-mean of what each clear target asks for; the timer
+//! puts the median group's kill, in the week it first clears, at
+//! `kill_seconds`, and the ramp costs a threshold group `soft_loss` of its
+//! damage. Groups that miss wipe with a share of the boss's HP left; [`Obs`]
+//! reports that week by week.
+
+use crate::error::{Checks, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::{self, ControllerState};
+use crate::systems::sdk::{
+    balance_with_hooks, compose_income, within, AbsTol, Hook, NominalTargets, Outcome, Residual, RelTol,
+};
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Params {
+    pub hp: f64,
+    pub enrage_seconds: f64, // hard enrage: the raid wipes here
+    pub soft_ramp: f64,      // share of raid DPS lost per minute to the soft enrage
+}
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        vec![self.hp, self.enrage_seconds, self.soft_ramp]
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { hp: v[0], enrage_seconds: v[1], soft_ramp: v[2] }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Env {
+    pub median_dps: f64,    // week-1 raid DPS of the median group
+    pub dps_spread: f64,    // logistic scale of ln DPS across groups, e.g. 0.05
+    pub weekly_growth: f64, // DPS gained per week from gear, e.g. 0.04
+    pub weeks: u32,         // weeks reported in [`Obs::weeks`]
+}
+
+/// "`rate` of groups have cleared by `week`" (1-based).
+#[derive(Clone, Copy, Debug)]
+pub struct ClearTarget {
+    pub week: u32,
+    pub rate: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct Targets {
+    pub clears: Vec<ClearTarget>, // e.g. 10% in week 1, 50% in week 4
+    pub kill_seconds: f64,        // median group's kill time in the week it first clears
+    pub soft_loss: f64,           // share of a threshold group's damage lost to the soft enrage
+}
+
+crate::define_system! {
+    bounds {
+        hp: hp_min..hp_max = (1e-6, 1e15),
+        enrage_seconds: enrage_min..enrage_max = (1.0, 3600.0) => .positive(enrage_min),
+        soft_ramp: ramp_min..ramp_max = (0.0, 60.0),
+    }
+    gains { k_hp = 0.5, k_enrage = 0.5, k_ramp = 0.5 }
+}
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .positive("median_dps", self.median_dps)
+            .positive("dps_spread", self.dps_spread)
+            .non_negative("weekly_growth", self.weekly_growth)
+            .check("weeks", self.weeks > 0, "must be at least 1")
+            .done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        let c = self.clears.iter().fold(Checks::default(), |c, t| {
+            c.check("clears.week", t.week > 0, "must be at least 1")
+                .check("clears.rate", t.rate > 0.0 && t.rate < 1.0, "must be in (0, 1)")
+        });
+        c.positive("kill_seconds", self.kill_seconds).within("soft_loss", self.soft_loss, 0.0, 0.5).done()
+    }
+}
+
+/// One week of progression.
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WeekStats {
+    pub clear_rate: f64,     // share of groups that can clear
+    pub median_wipe_at: f64, // boss HP share left when the median group wipes; 0 once it clears
+    pub mean_wipe_at: f64,   // mean boss HP share left over the groups that still wipe
+}
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obs {
+    pub dps_mult: f64,      // raid DPS factor from hooks
+    pub threshold_dps: f64, // raw raid DPS needed to clear
+    pub soft_loss: f64,     // share of a threshold group's damage lost to the soft enrage
+    pub weeks: Vec<WeekStats>,
+    pub clears: Vec<f64>,       // clear rate at each clear target's week
+    pub median_clear_week: u32, // first week the median group clears (may pass `Env::weeks`; 0 = never)
+    pub median_kill_seconds: f64,
+}
+
+/// Damage a group of 1 DPS deals by `t` seconds under the soft enrage.
+pub fn damage_by(t: f64, soft_ramp: f64) -> f64 {
+    let k = soft_ramp.max(0.0) / 60.0;
+    let t = t.max(0.0);
+    if k * t <= 1.0 { t - 0.5 * k * t * t } else { 0.5 / k }
+}
+
+/// Seconds a group of `dps` needs to deal `hp` (`None` before the timer
+/// runs out, i.e. a wipe).
+pub fn kill_seconds(hp: f64, dps: f64, th: &Params) -> Option<f64> {
+    let (k, need) = (th.soft_ramp.max(0.0) / 60.0, hp / dps.max(1e-12));
+    if need > damage_by(th.enrage_seconds, th.soft_ramp) {
+        return None;
+    }
+    // t − k t²/2 = need, on the rising branch
+    Some(if k > 0.0 { (1.0 - (1.0 - 2.0 * k * need).max(0.0).sqrt()) / k } else { need })
+}
+
+/// Share of groups with raw DPS at least `dps` in week `week`.
+pub fn share_above(dps: f64, env: &Env, week: u32) -> f64 {
+    let at = env.median_dps * (1.0 + env.weekly_growth).powi(week.max(1) as i32 - 1);
+    1.0 / (1.0 + ((dps / at).max(1e-300).ln() / env.dps_spread.max(1e-9)).exp())
+}
+
+/// Raw DPS the top `rate` of groups reach in week `week`.
+pub fn dps_at_share(rate: f64, env: &Env, week: u32) -> f64 {
+    let r = rate.clamp(1e-9, 1.0 - 1e-9);
+    let at = env.median_dps * (1.0 + env.weekly_growth).powi(week.max(1) as i32 - 1);
+    at * ((1.0 - r) / r).powf(env.dps_spread)
+}
+
+/// First week the median group reaches `dps` (0 if it never does).
+fn median_week(dps: f64, env: &Env) -> u32 {
+    if dps <= env.median_dps {
+        return 1;
+    }
+    if env.weekly_growth <= 0.0 {
+        return 0;
+    }
+    let w = 1.0 + ((dps / env.median_dps).ln() / env.weekly_growth.ln_1p()).ceil();
+    if w < f64::from(u32::MAX) { w as u32 } else { 0 }
+}
+
+/// The DPS-check math described in the module docs; hooks'
+/// `income_multiplier` scales every group's DPS (raid buffs, nerfs to the
+/// boss's damage taken). Implement [`SimModel`] for phase transitions,
+/// execution variance or lockout-limited attempts.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        tgt: &Targets,
+        mechs: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let dps_mult = compose_income(1.0, mechs, th, env).max(1e-9);
+        let capacity = damage_by(th.enrage_seconds, th.soft_ramp).max(1e-12);
+        let threshold = th.hp.max(0.0) / capacity / dps_mult;
+        // boss HP share left when a group at `dps` hits the timer
+        let left = |dps: f64| (1.0 - dps / threshold.max(1e-12)).max(0.0);
+        const SLICES: usize = 200;
+        let weeks = (1..=env.weeks)
+            .map(|w| {
+                let clear_rate = share_above(threshold, env, w);
+                let wiping: Vec<f64> = (0..SLICES)
+                    .map(|i| (i as f64 + 0.5) / SLICES as f64)
+                    .filter(|&u| u > clear_rate)
+                    .map(|u| left(dps_at_share(u, env, w)))
+                    .collect();
+                WeekStats {
+                    clear_rate,
+                    median_wipe_at: left(dps_at_share(0.5, env, w)),
+                    mean_wipe_at: if wiping.is_empty() { 0.0 } else { wiping.iter().sum::<f64>() / wiping.len() as f64 },
+                }
+            })
+            .collect();
+        let median_clear_week = median_week(threshold, env);
+        let median_kill_seconds = match median_clear_week {
+            0 => th.enrage_seconds,
+            w => {
+                let dps = dps_at_share(0.5, env, w) * dps_mult;
+                kill_seconds(th.hp, dps, th).unwrap_or(th.enrage_seconds)
+            }
+        };
+        Obs {
+            dps_mult,
+            threshold_dps: threshold,
+            soft_loss: 1.0 - capacity / th.enrage_seconds.max(1e-12),
+            weeks,
+            clears: tgt.clears.iter().map(|c| share_above(threshold, env, c.week)).collect(),
+            median_clear_week,
+            median_kill_seconds,
+        }
+    }
+}
+
+/// Raw DPS threshold that best meets every clear target: the log-mean of
+/// what each asks for.
+pub fn threshold_for(env: &Env, tgt: &Targets) -> Option<f64> {
+    if tgt.clears.is_empty() {
+        return None;
+    }
+    let ln_sum: f64 = tgt.clears.iter().map(|c| dps_at_share(c.rate, env, c.week).ln()).sum();
+    Some((ln_sum / tgt.clears.len() as f64).exp())
+}
+
+/// Unitless error vs targets: each clear rate, the median kill time and the
+/// soft-enrage loss.
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    o.clears.iter().zip(&tgt.clears).map(|(&r, c)| (r - c.rate).abs()).sum::<f64>()
+        + control::pct_error(o.median_kill_seconds, tgt.kill_seconds)
+        + (o.soft_loss - tgt.soft_loss).abs()
+}
+
+/// Named residuals for [`Outcome::residuals`].
+pub fn residuals(o: &Obs, tgt: &Targets) -> Vec<Residual> {
+    let mut out: Vec<Residual> = o
+        .clears
+        .iter()
+        .zip(&tgt.clears)
+        .map(|(&r, c)| Residual::new(format!("clear_week{}", c.week), r, c.rate))
+        .collect();
+    out.push(Residual::new("median_kill_seconds", o.median_kill_seconds, tgt.kill_seconds));
+    out.push(Residual::new("soft_loss", o.soft_loss, tgt.soft_loss));
+    out
+}
+
+pub fn balance_ext(
+    theta0: Params,
+    env: Env,
+    tgt: Targets,
+    opts: Options<impl SimModel>,
+) -> Outcome<Params, Obs> {
+    let Options { bounds: b, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = [ControllerState::default(); 3];
+    let res_tgt = tgt.clone();
+    balance_with_hooks(
+        theta0,
+        env,
+        tgt,
+        b,
+        g,
+        mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+        max_iters,
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, mechs),
+        // nominal, all closed form in u = kill / timer for the median group
+        // in its clear week (u − s·u² = (1 − s)·threshold / its DPS):
+        // x = threshold · timer · (1 − s) HP, y = kill_seconds / u timer,
+        // z = 120 · s / timer ramp (a linear ramp loses half its slope)
+        |_th, env, tgt, o| {
+            let s = tgt.soft_loss.clamp(0.0, 0.5);
+            let threshold = threshold_for(env, tgt).unwrap_or(o.threshold_dps);
+            let r = match median_week(threshold, env) {
+                0 => 1.0,
+                w => (threshold / dps_at_share(0.5, env, w)).min(1.0),
+            };
+            let u = if s > 1e-9 { (1.0 - (1.0 - 4.0 * s * (1.0 - s) * r).max(0.0).sqrt()) / (2.0 * s) } else { r };
+            let timer = tgt.kill_seconds / u.max(1e-9);
+            NominalTargets { x: threshold * o.dps_mult * timer * (1.0 - s), y: timer, z: 120.0 * s / timer }
+        },
+        move |th, b, g, nom, adj| {
+            let (hp_t, enrage_t, ramp_t) = (nom.x * adj.a, nom.y * adj.b, nom.z * adj.c);
+
+            let (hp_t, enrage_t, ramp_t) = match reg {
+                Some(r) => (
+                    r.pull(hp_t, |p| p.hp),
+                    r.pull(enrage_t, |p| p.enrage_seconds),
+                    r.pull(ramp_t, |p| p.soft_ramp),
+                ),
+                None => (hp_t, enrage_t, ramp_t),
+            };
+
+            let st = &mut ctl_state;
+            Params {
+                hp: controller.step(&mut st[0], th.hp, hp_t, g.k_hp, b.hp_min, b.hp_max),
+                enrage_seconds: controller.step(&mut st[1], th.enrage_seconds, enrage_t, g.k_enrage, b.enrage_min, b.enrage_max),
+                soft_ramp: controller.step(&mut st[2], th.soft_ramp, ramp_t, g.k_ramp, b.ramp_min, b.ramp_max),
+            }
+        },
+        // converged: every clear rate within 3 points, the median kill
+        // within ±2% and the soft-enrage loss within 1 point
+        |o, tgt| {
+            o.clears.iter().zip(&tgt.clears).all(|(&r, c)| within(r, c.rate, AbsTol(0.03)))
+                && within(o.median_kill_seconds, tgt.kill_seconds, RelTol(0.02))
+                && within(o.soft_loss, tgt.soft_loss, AbsTol(0.01))
+        },
+    )
+    .with_residuals(move |o| residuals(o, &res_tgt))
+}
@@ -0,0 +1,206 @@
+//! Combat TTK: tune a unit's HP and DPS so fights against an opponent roster
+//! land in a time-to-kill (TTK) and time-to-die (TTD) window.
+//!
+//! Against opponent `i`, TTK = `hp_i / dps` and TTD = `hp / dps_i`; the
+//! roster averages (weighted) are what the bands apply to. Hooks'
+//! `income_multiplier` scales the unit's damage and `cost_multiplier` the
+//! damage it takes, which is how [`Crit`] and [`Mitigation`] plug in.
+
+use crate::error::{Checks, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::{self, ControllerState};
+use crate::systems::sdk::{
+    balance_with_hooks, compose_cost, compose_income, Band, Hook, NominalTargets, Outcome, Residual,
+};
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Params {
+    pub hp: f64,
+    pub dps: f64,
+}
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        vec![self.hp, self.dps]
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { hp: v[0], dps: v[1] }
+    }
+}
+
+/// One opponent on the roster; `weight` is how often the unit meets it.
+#[derive(Clone, Copy, Debug)]
+pub struct Opponent {
+    pub hp: f64,
+    pub dps: f64,
+    pub weight: f64,
+}
+
+impl Opponent {
+    /// An opponent with weight 1.
+    pub fn new(hp: f64, dps: f64) -> Self {
+        Self { hp, dps, weight: 1.0 }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Env {
+    pub opponents: Vec<Opponent>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Targets {
+    pub ttk_band: (f64, f64), // seconds to kill, roster average
+    pub ttd_band: (f64, f64), // seconds to die, roster average
+}
+
+crate::define_system! {
+    bounds {
+        hp: hp_min..hp_max = (1.0, 1e9),
+        dps: dps_min..dps_max = (1e-3, 1e9),
+    }
+    gains { k_hp = 0.6, k_dps = 0.6 }
+}
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        let c = Checks::default().check("opponents", !self.opponents.is_empty(), "must not be empty");
+        self.opponents
+            .iter()
+            .fold(c, |c, o| c.positive("opponents.hp", o.hp).positive("opponents.dps", o.dps).non_negative("opponents.weight", o.weight))
+            .check("opponents.weight", self.opponents.iter().any(|o| o.weight > 0.0), "needs a positive weight")
+            .done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .range("ttk_band", self.ttk_band.0, self.ttk_band.1)
+            .positive("ttk_band.0", self.ttk_band.0)
+            .range("ttd_band", self.ttd_band.0, self.ttd_band.1)
+            .positive("ttd_band.0", self.ttd_band.0)
+            .done()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obs {
+    pub dps: f64,          // after damage hooks
+    pub damage_taken: f64, // incoming damage factor after mitigation hooks
+    pub ttk: Vec<f64>,     // per opponent
+    pub ttd: Vec<f64>,     // per opponent
+    pub avg_ttk: f64,      // weighted over the roster
+    pub avg_ttd: f64,
+}
+
+/// Expected-value crit: damage × `1 + chance · (mult − 1)`.
+#[derive(Clone, Copy, Debug)]
+pub struct Crit {
+    pub chance: f64,
+    pub mult: f64,
+}
+
+impl Hook<Params, Env, Targets, Obs> for Crit {
+    fn income_multiplier(&mut self, _base_income: f64, _theta: &Params, _env: &Env) -> f64 {
+        1.0 + self.chance.clamp(0.0, 1.0) * (self.mult - 1.0)
+    }
+}
+
+/// Flat damage reduction: damage taken × `1 − reduction`.
+#[derive(Clone, Copy, Debug)]
+pub struct Mitigation {
+    pub reduction: f64,
+}
+
+impl Hook<Params, Env, Targets, Obs> for Mitigation {
+    fn cost_multiplier(&mut self, _theta: &Params, _env: &Env) -> f64 {
+        1.0 - self.reduction.clamp(0.0, 1.0)
+    }
+}
+
+/// Constant-DPS duels, averaged over the roster by weight. Implement
+/// [`SimModel`] for overkill, burst windows or a full fight sim.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        _tgt: &Targets,
+        mechs: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let dps = compose_income(th.dps, mechs, th, env).max(1e-9);
+        let taken = compose_cost(1.0, mechs, th, env).max(1e-9);
+        let ttk: Vec<f64> = env.opponents.iter().map(|o| o.hp / dps).collect();
+        let ttd: Vec<f64> = env.opponents.iter().map(|o| th.hp / (o.dps * taken).max(1e-9)).collect();
+        let w: f64 = env.opponents.iter().map(|o| o.weight).sum::<f64>().max(1e-12);
+        let avg = |xs: &[f64]| xs.iter().zip(&env.opponents).map(|(x, o)| x * o.weight).sum::<f64>() / w;
+        Obs { dps, damage_taken: taken, avg_ttk: avg(&ttk), avg_ttd: avg(&ttd), ttk, ttd }
+    }
+}
+
+fn mid((lo, hi): (f64, f64)) -> f64 {
+    0.5 * (lo + hi)
+}
+
+/// Unitless error vs targets: TTK and TTD errors against their band
+/// midpoints.
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    control::pct_error(o.avg_ttk, mid(tgt.ttk_band)) + control::pct_error(o.avg_ttd, mid(tgt.ttd_band))
+}
+
+/// Named residuals for [`Outcome::residuals`], against the band midpoints.
+pub fn residuals(o: &Obs, tgt: &Targets) -> Vec<Residual> {
+    vec![
+        Residual::new("avg_ttk", o.avg_ttk, mid(tgt.ttk_band)),
+        Residual::new("avg_ttd", o.avg_ttd, mid(tgt.ttd_band)),
+    ]
+}
+
+pub fn balance_ext(
+    theta0: Params,
+    env: Env,
+    tgt: Targets,
+    opts: Options<impl SimModel>,
+) -> Outcome<Params, Obs> {
+    let Options { bounds: b, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = [ControllerState::default(); 2];
+    balance_with_hooks(
+        theta0,
+        env,
+        tgt,
+        b,
+        g,
+        mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+        max_iters,
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, mechs),
+        // nominal: both times scale linearly, so x = hp and y = dps that put
+        // the roster averages on the band midpoints
+        |th, _env, tgt, o| NominalTargets {
+            x: th.hp * mid(tgt.ttd_band) / o.avg_ttd.max(1e-9),
+            y: th.dps * o.avg_ttk / mid(tgt.ttk_band).max(1e-9),
+            z: 0.0,
+        },
+        move |th, b, g, nom, adj| {
+            let (hp_t, dps_t) = (nom.x * adj.a, nom.y * adj.b);
+
+            let (hp_t, dps_t) = match reg {
+                Some(r) => (r.pull(hp_t, |p| p.hp), r.pull(dps_t, |p| p.dps)),
+                None => (hp_t, dps_t),
+            };
+
+            let st = &mut ctl_state;
+            Params {
+                hp: controller.step(&mut st[0], th.hp, hp_t, g.k_hp, b.hp_min, b.hp_max),
+                dps: controller.step(&mut st[1], th.dps, dps_t, g.k_dps, b.dps_min, b.dps_max),
+            }
+        },
+        // converged: both roster averages inside their bands
+        |o, tgt| Band::from(tgt.ttk_band).contains(o.avg_ttk) && Band::from(tgt.ttd_band).contains(o.avg_ttd),
+    )
+    .with_residuals(|o| residuals(o, &tgt))
+}
@@ -0,0 +1,268 @@
+//! Crafting chain: tune per-stage craft times and costs of a multi-stage
+//! pipeline (gather → refine → craft) toward an end-to-end throughput and a
+//! target dead time per stage.
+//!
+//! The pipeline is a small DAG: each [`Stage`] lists the upstream stages it
+//! consumes and how many units of each one craft takes, and the last stage
+//! makes the final product. Walking the edges backwards gives the
+//! [`demand`] — units of each stage per final unit. A stage of one station
+//! then runs at `1 / (demand · seconds)` final units per second, crafting
+//! costs `Σ demand · cost` per final unit, and the chain produces the
+//! smaller of its slowest stage and what the income can pay for. Each
+//! stage's dead time is the share of the clock its station stands idle.
+//!
+//! Costs keep the shape of θ and only move as a whole, so the relative
+//! price of stages stays a design choice.
+
+use crate::error::{Checks, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::{self, ControllerState};
+use crate::systems::sdk::{
+    balance_with_hooks, compose_income, within, AbsTol, Hook, NominalTargets, Outcome, Residual,
+    RelTol,
+};
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Params {
+    pub craft_seconds: Vec<f64>, // per unit, one per stage
+    pub craft_cost: Vec<f64>,    // currency per unit, one per stage
+}
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        self.craft_seconds.iter().chain(&self.craft_cost).copied().collect()
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        let (seconds, cost) = v.split_at(self.craft_seconds.len().min(v.len()));
+        Self { craft_seconds: seconds.to_vec(), craft_cost: cost.to_vec() }
+    }
+}
+
+/// One node of the pipeline.
+#[derive(Clone, Debug, Default)]
+pub struct Stage {
+    pub inputs: Vec<(usize, f64)>, // (upstream stage, units per craft); upstream comes first
+}
+impl Stage {
+    /// A stage with no inputs (gathering).
+    pub fn source() -> Self {
+        Self::default()
+    }
+    pub fn new(inputs: Vec<(usize, f64)>) -> Self {
+        Self { inputs }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Env {
+    pub stages: Vec<Stage>,  // in topological order; the last one is the final product
+    pub income_per_sec: f64, // currency available for crafting
+}
+
+#[derive(Clone, Debug)]
+pub struct Targets {
+    pub throughput_per_hour: f64, // final units per hour
+    pub dead_time: Vec<f64>,      // idle share per stage; a single entry applies to every stage
+}
+impl Targets {
+    /// Dead-time target of stage `i` (the last entry covers the rest).
+    pub fn dead_time_at(&self, i: usize) -> f64 {
+        self.dead_time.get(i).or(self.dead_time.last()).copied().unwrap_or(0.0)
+    }
+}
+
+crate::define_system! {
+    bounds {
+        craft_seconds: seconds_min..seconds_max = (1e-3, 1e6),
+        craft_cost: cost_min..cost_max = (1e-6, 1e12),
+    }
+    gains { k_seconds = 0.6, k_cost = 0.5 }
+}
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        let ordered = self.stages.iter().enumerate().all(|(i, s)| s.inputs.iter().all(|&(j, _)| j < i));
+        let demand = demand(self);
+        Checks::default()
+            .check("stages", !self.stages.is_empty(), "needs at least one stage")
+            .check("stages.inputs", ordered, "must reference an earlier stage")
+            .check("stages.inputs", self.stages.iter().flat_map(|s| &s.inputs).all(|&(_, q)| q > 0.0), "quantities must be > 0")
+            .check("stages", !ordered || demand.iter().all(|&d| d > 0.0), "every stage must feed the final stage")
+            .positive("income_per_sec", self.income_per_sec)
+            .done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        let c = Checks::default()
+            .positive("throughput_per_hour", self.throughput_per_hour)
+            .check("dead_time", !self.dead_time.is_empty(), "needs at least one entry");
+        self.dead_time
+            .iter()
+            .fold(c, |c, &d| c.check("dead_time", (0.0..1.0).contains(&d), "must be in [0, 1)"))
+            .done()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obs {
+    pub income_per_sec: f64, // after hooks
+    pub demand: Vec<f64>,    // units of each stage per final unit
+    pub cost_per_unit: f64,  // crafting spend per final unit
+    pub throughput_per_hour: f64,
+    pub dead_time: Vec<f64>,       // idle share per stage
+    pub bottleneck: Option<usize>, // slowest stage, or None when income limits the chain
+}
+
+/// Units of each stage consumed per final unit: 1 for the last stage, and
+/// each stage passes `demand · quantity` to its inputs. Edges that point
+/// forward are ignored (see [`Env`]'s validation).
+pub fn demand(env: &Env) -> Vec<f64> {
+    let mut d = vec![0.0; env.stages.len()];
+    if let Some(last) = d.last_mut() {
+        *last = 1.0;
+    }
+    for i in (0..env.stages.len()).rev() {
+        for &(j, q) in &env.stages[i].inputs {
+            if j < i {
+                d[j] += d[i] * q;
+            }
+        }
+    }
+    d
+}
+
+/// The steady-state pipeline math described in the module docs; hooks'
+/// `income_multiplier` scales the crafting income. Implement [`SimModel`] for
+/// parallel stations, batch crafting or transport delays between stages.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        _tgt: &Targets,
+        mechs: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let income = compose_income(env.income_per_sec, mechs, th, env).max(0.0);
+        let demand = demand(env);
+        let load = |i: usize| demand[i] * th.craft_seconds.get(i).copied().unwrap_or(0.0).max(0.0);
+
+        let cost_per_unit: f64 = demand.iter().zip(&th.craft_cost).map(|(d, c)| d * c.max(0.0)).sum();
+        let slowest = (0..demand.len()).max_by(|&a, &b| load(a).total_cmp(&load(b)));
+        let time_limit = slowest.map_or(f64::INFINITY, |i| 1.0 / load(i).max(1e-12));
+        let budget_limit = if cost_per_unit > 0.0 { income / cost_per_unit } else { f64::INFINITY };
+        let per_sec = time_limit.min(budget_limit).min(1e9);
+
+        Obs {
+            income_per_sec: income,
+            cost_per_unit,
+            throughput_per_hour: per_sec * 3600.0,
+            dead_time: (0..demand.len()).map(|i| (1.0 - per_sec * load(i)).clamp(0.0, 1.0)).collect(),
+            bottleneck: if time_limit <= budget_limit { slowest } else { None },
+            demand,
+        }
+    }
+}
+
+/// Unitless error vs targets: throughput error plus the mean absolute
+/// dead-time miss per stage.
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    let idle = if o.dead_time.is_empty() {
+        0.0
+    } else {
+        o.dead_time.iter().enumerate().map(|(i, &d)| (d - tgt.dead_time_at(i)).abs()).sum::<f64>() / o.dead_time.len() as f64
+    };
+    control::pct_error(o.throughput_per_hour, tgt.throughput_per_hour) + idle
+}
+
+/// Named residuals (`throughput_per_hour`, then `stage0_dead_time`, …) for
+/// [`Outcome::residuals`].
+pub fn residuals(o: &Obs, tgt: &Targets) -> Vec<Residual> {
+    let mut out = vec![Residual::new("throughput_per_hour", o.throughput_per_hour, tgt.throughput_per_hour)];
+    out.extend(o.dead_time.iter().enumerate().map(|(i, &d)| Residual::new(format!("stage{i}_dead_time"), d, tgt.dead_time_at(i))));
+    out
+}
+
+pub fn balance_ext(
+    theta0: Params,
+    env: Env,
+    tgt: Targets,
+    opts: Options<impl SimModel>,
+) -> Outcome<Params, Obs> {
+    let Options { bounds: b, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut seconds_state = vec![ControllerState::default(); theta0.craft_seconds.len()];
+    let mut cost_state = vec![ControllerState::default(); theta0.craft_cost.len()];
+    // Per-stage targets depend on the DAG in env; the step only sees θ, so
+    // it keeps the demand and the targets.
+    let (step_demand, step_tgt) = (demand(&env), tgt.clone());
+    let res_tgt = tgt.clone();
+    balance_with_hooks(
+        theta0,
+        env,
+        tgt,
+        b,
+        g,
+        mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+        max_iters,
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, mechs),
+        // nominal: x = throughput per second, y = budget per final unit
+        |_th, _env, tgt, o| {
+            let per_sec = tgt.throughput_per_hour / 3600.0;
+            NominalTargets { x: per_sec, y: o.income_per_sec / per_sec.max(1e-12), z: 0.0 }
+        },
+        // step: seconds_i → (1 − dead_i) / (throughput · demand_i); costs
+        //       scale together until a final unit costs the budget
+        move |th, b, g, nom, adj| {
+            let (per_sec, budget) = (nom.x * adj.a, nom.y * adj.b);
+            let spend: f64 = step_demand.iter().zip(&th.craft_cost).map(|(d, c)| d * c).sum();
+            let scale = budget / spend.max(1e-12);
+
+            let st = &mut seconds_state;
+            st.resize(th.craft_seconds.len(), ControllerState::default());
+            let craft_seconds = th
+                .craft_seconds
+                .iter()
+                .enumerate()
+                .map(|(i, &s)| {
+                    let d = step_demand.get(i).copied().unwrap_or(1.0);
+                    let target = (1.0 - step_tgt.dead_time_at(i)) / (per_sec * d).max(1e-12);
+                    let target = match reg.as_ref() {
+                        Some(r) => r.pull(target, |base| base.craft_seconds.get(i).copied().unwrap_or(target)),
+                        None => target,
+                    };
+                    let target = target.clamp(b.seconds_min, b.seconds_max);
+                    controller.step(&mut st[i], s, target, g.k_seconds, b.seconds_min, b.seconds_max)
+                })
+                .collect();
+
+            let st = &mut cost_state;
+            st.resize(th.craft_cost.len(), ControllerState::default());
+            let craft_cost = th
+                .craft_cost
+                .iter()
+                .enumerate()
+                .map(|(i, &c)| {
+                    let target = c * scale;
+                    let target = match reg.as_ref() {
+                        Some(r) => r.pull(target, |base| base.craft_cost.get(i).copied().unwrap_or(target)),
+                        None => target,
+                    };
+                    controller.step(&mut st[i], c, target.clamp(b.cost_min, b.cost_max), g.k_cost, b.cost_min, b.cost_max)
+                })
+                .collect();
+            Params { craft_seconds, craft_cost }
+        },
+        // converged: throughput within ±5% and every stage's dead time
+        // within 2 points of its target
+        |o, tgt| {
+            within(o.throughput_per_hour, tgt.throughput_per_hour, RelTol(0.05))
+                && o.dead_time.iter().enumerate().all(|(i, &d)| within(d, tgt.dead_time_at(i), AbsTol(0.02)))
+        },
+    )
+    .with_residuals(|o| residuals(o, &res_tgt))
+}
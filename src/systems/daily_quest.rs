@@ -0,0 +1,209 @@
+//! Daily quests: tune the number of dailies, the reward per quest and the
+//! time each takes so the daily session lands in a length band and dailies
+//! stay a bounded share of income.
+//!
+//! A daily session is `base_minutes` of free play plus `count ·
+//! quest_minutes` of quests; all of it earns play income, and each quest
+//! also pays its reward. Three targets pin the three knobs: one quest pays
+//! `quest_worth_minutes` of play income (so quests feel worth doing), the
+//! dailies' share of income sits at `max_share`, and the session length
+//! sits mid-band. When dailies pay more than [`CLIFF_SHARE`] of income a
+//! player who skips them loses most of their progress; [`Obs`] flags that
+//! as an income cliff.
+
+use crate::error::{Checks, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::{self, ControllerState};
+use crate::systems::sdk::{
+    balance_with_hooks, compose_income, within, Band, Hook, NominalTargets, Outcome, Residual, RelTol,
+};
+
+/// Dailies' share of income above which skipping them more than halves a
+/// player's progress.
+pub const CLIFF_SHARE: f64 = 0.5;
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Params {
+    pub count: f64, // dailies per day; fractional counts round in game
+    pub reward: f64,
+    pub quest_minutes: f64, // time to complete one quest
+}
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        vec![self.count, self.reward, self.quest_minutes]
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { count: v[0], reward: v[1], quest_minutes: v[2] }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Env {
+    pub play_income_per_min: f64, // income from ordinary play
+    pub base_minutes: f64,        // free play per daily session besides quests
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Targets {
+    pub session_band: (f64, f64), // daily session minutes, e.g. (20, 30)
+    pub max_share: f64,           // cap on dailies' share of total income, e.g. 0.3
+    pub quest_worth_minutes: f64, // one quest pays this many minutes of play income
+}
+
+crate::define_system! {
+    bounds {
+        count: count_min..count_max = (1.0, 50.0),
+        reward: reward_min..reward_max = (1e-6, 1e12),
+        quest_minutes: minutes_min..minutes_max = (0.1, 240.0),
+    }
+    gains { k_count = 0.6, k_reward = 0.6, k_minutes = 0.6 }
+}
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .positive("play_income_per_min", self.play_income_per_min)
+            .non_negative("base_minutes", self.base_minutes)
+            .done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .range("session_band", self.session_band.0, self.session_band.1)
+            .positive("session_band.0", self.session_band.0)
+            .check("max_share", self.max_share > 0.0 && self.max_share < 1.0, "must be in (0, 1)")
+            .positive("quest_worth_minutes", self.quest_worth_minutes)
+            .done()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obs {
+    pub reward: f64, // per quest, after hooks
+    pub session_minutes: f64,
+    pub quest_income: f64, // per day
+    pub total_income: f64, // per day, play + quests
+    pub dailies_share: f64,
+    pub quest_worth_minutes: f64, // reward in minutes of play income
+    pub income_cliff: bool,       // dailies_share > CLIFF_SHARE
+}
+
+/// Daily session length: free play plus every quest.
+pub fn session_minutes(th: &Params, env: &Env) -> f64 {
+    env.base_minutes + th.count.max(0.0) * th.quest_minutes.max(0.0)
+}
+
+/// The daily income math described in the module docs; hooks'
+/// `income_multiplier` scales the quest reward (VIP or event bonuses).
+/// Implement [`SimModel`] for partial completion, weekly streak bonuses or
+/// quest rerolls.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        _tgt: &Targets,
+        mechs: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let reward = compose_income(th.reward, mechs, th, env).max(0.0);
+        let session = session_minutes(th, env);
+        let quest_income = th.count.max(0.0) * reward;
+        let total = (env.play_income_per_min * session + quest_income).max(1e-12);
+        let share = quest_income / total;
+        Obs {
+            reward,
+            session_minutes: session,
+            quest_income,
+            total_income: total,
+            dailies_share: share,
+            quest_worth_minutes: reward / env.play_income_per_min.max(1e-12),
+            income_cliff: share > CLIFF_SHARE,
+        }
+    }
+}
+
+fn mid((lo, hi): (f64, f64)) -> f64 {
+    0.5 * (lo + hi)
+}
+
+/// Unitless error vs targets: session length against the band midpoint,
+/// quest worth, and any share above the cap.
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    control::pct_error(o.session_minutes, mid(tgt.session_band))
+        + control::pct_error(o.quest_worth_minutes, tgt.quest_worth_minutes)
+        + (o.dailies_share - tgt.max_share).max(0.0)
+}
+
+/// Named residuals for [`Outcome::residuals`]; the share cap counts as met
+/// anywhere below it.
+pub fn residuals(o: &Obs, tgt: &Targets) -> Vec<Residual> {
+    vec![
+        Residual::new("session_minutes", o.session_minutes, mid(tgt.session_band)),
+        Residual::new("quest_worth_minutes", o.quest_worth_minutes, tgt.quest_worth_minutes),
+        Residual::new("dailies_share", o.dailies_share, o.dailies_share.min(tgt.max_share)),
+    ]
+}
+
+pub fn balance_ext(
+    theta0: Params,
+    env: Env,
+    tgt: Targets,
+    opts: Options<impl SimModel>,
+) -> Outcome<Params, Obs> {
+    let Options { bounds: b, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = [ControllerState::default(); 3];
+    balance_with_hooks(
+        theta0,
+        env,
+        tgt,
+        b,
+        g,
+        mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+        max_iters,
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, mechs),
+        // nominal: x = count that puts the dailies' share at the cap, y =
+        // reward worth the target minutes (scaled back by what hooks add),
+        // z = quest time that fills the session to the band midpoint
+        |th, env, tgt, o| {
+            let session = mid(tgt.session_band);
+            let worth = tgt.quest_worth_minutes.max(1e-9);
+            let count = tgt.max_share / (1.0 - tgt.max_share).max(1e-9) * session / worth;
+            let reward = worth * env.play_income_per_min * th.reward / o.reward.max(1e-12);
+            NominalTargets { x: count, y: reward, z: (session - env.base_minutes).max(0.0) / count.max(1e-9) }
+        },
+        move |th, b, g, nom, adj| {
+            let (count_t, reward_t, minutes_t) = (nom.x * adj.a, nom.y * adj.b, nom.z * adj.c);
+
+            let (count_t, reward_t, minutes_t) = match reg {
+                Some(r) => (
+                    r.pull(count_t, |p| p.count),
+                    r.pull(reward_t, |p| p.reward),
+                    r.pull(minutes_t, |p| p.quest_minutes),
+                ),
+                None => (count_t, reward_t, minutes_t),
+            };
+
+            let st = &mut ctl_state;
+            Params {
+                count: controller.step(&mut st[0], th.count, count_t, g.k_count, b.count_min, b.count_max),
+                reward: controller.step(&mut st[1], th.reward, reward_t, g.k_reward, b.reward_min, b.reward_max),
+                quest_minutes: controller.step(&mut st[2], th.quest_minutes, minutes_t, g.k_minutes, b.minutes_min, b.minutes_max),
+            }
+        },
+        // converged: session inside the band, quests worth their minutes and
+        // the share no more than 1 point above the cap
+        |o, tgt| {
+            Band::from(tgt.session_band).contains(o.session_minutes)
+                && within(o.quest_worth_minutes, tgt.quest_worth_minutes, RelTol(0.05))
+                && o.dailies_share <= tgt.max_share + 0.01
+        },
+    )
+    .with_residuals(|o| residuals(o, &tgt))
+}
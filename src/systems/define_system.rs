@@ -0,0 +1,314 @@
+// src/systems/define_system.rs
+
+//! `define_system!`: the scaffolding every system repeats (bounds, gains,
+//! the `SimModel` trait, the `Mechanic` alias, `Options` and `Runner`) from
+//! a compact spec. What is left to write is the system itself: its
+//! `StandardModel` and a four-argument `balance_ext`.
+
+/// Declare a system's scaffolding in the current module.
+///
+/// Built-in systems keep their own `Params`/`Env`/`Targets`/`Obs` and
+/// `StandardModel`, and list the rest:
+///
+/// ```ignore
+/// game_balance::define_system! {
+///     bounds {
+///         price: price_min..price_max = (0.5, 50.0),
+///         rate: rate_min..rate_max = (0.1, 10.0),
+///     }
+///     gains { k_price = 0.5, k_rate = 0.3 }
+/// }
+/// ```
+///
+/// For a system with an all-`f64` θ the data types can be declared too;
+/// each param names its bound fields and soft range:
+///
+/// ```ignore
+/// mod toll {
+///     game_balance::define_system! {
+///         params { price: price_min..price_max = (0.0, 10.0) }
+///         env { traffic: f64 }
+///         targets { revenue: f64 }
+///         obs { revenue: f64 }
+///         gains { k_price = 0.5 }
+///     }
+/// }
+/// ```
+///
+/// Either way it generates:
+/// - `Bounds` with the named `*_min`/`*_max` fields, `Bounds::soft()` from
+///   the listed ranges and a [`Validate`](crate::Validate) impl (one
+///   `range` check per pair, plus any [`Checks`](crate::error::Checks)
+///   chained after it, e.g. `knee: knee_min..knee_max = (1.0, 1e4) =>
+///   .positive(knee_min)`);
+/// - `Gains` with `Default` from the spec and `Gains::scaled`;
+/// - `SimModel`, the observation-model trait `StandardModel` implements;
+/// - `Mechanic`, the hook alias with its blanket impl;
+/// - `Options`, everything `balance_ext(theta0, env, tgt, opts)` takes
+///   besides θ₀, env and targets, with `Default`;
+/// - `Runner`, the builder over `balance_ext`, with a setter per option,
+///   `damping`, `model`, `run` and `try_run`.
+///
+/// Optional trailing sections: `inputs { ref_income: f64 }` for required
+/// upstream signals (extra `Runner::new`, `balance_ext` and `observe`
+/// arguments), `options { order: UpdateOrder = UpdateOrder::default() }`
+/// for system-specific options (an `Options` field plus a `Runner` setter),
+/// and `validate { theta0 }` to have `try_run` also check θ₀. `Env` and
+/// `Targets` must implement [`Validate`](crate::Validate) for `try_run`.
+#[macro_export]
+macro_rules! define_system {
+    (
+        params { $($(#[$pm:meta])* $p:ident : $plo:ident .. $phi:ident = ($pa:expr, $pb:expr)),+ $(,)? }
+        env { $($(#[$em:meta])* $e:ident : $ety:ty),* $(,)? }
+        targets { $($(#[$tm:meta])* $t:ident : $tty:ty),* $(,)? }
+        obs { $($(#[$om:meta])* $o:ident : $oty:ty),* $(,)? }
+        $($rest:tt)*
+    ) => {
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        pub struct Params {
+            $($(#[$pm])* pub $p: f64,)+
+        }
+
+        impl $crate::Flat for Params {
+            fn flatten(&self) -> Vec<f64> {
+                vec![$(self.$p),+]
+            }
+            fn unflatten(&self, v: &[f64]) -> Self {
+                let mut it = v.iter().copied();
+                Self { $($p: it.next().unwrap_or(self.$p),)+ }
+            }
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct Env {
+            $($(#[$em])* pub $e: $ety,)*
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct Targets {
+            $($(#[$tm])* pub $t: $tty,)*
+        }
+
+        #[derive(Clone, Debug, Default)]
+        pub struct Obs {
+            $($(#[$om])* pub $o: $oty,)*
+        }
+
+        $crate::define_system! {
+            bounds { $($p: $plo..$phi = ($pa, $pb)),+ }
+            $($rest)*
+        }
+    };
+
+    (
+        bounds {
+            $(
+                $(#[$bm:meta])* $b:ident : $blo:ident .. $bhi:ident = ($ba:expr, $bb:expr)
+                $(=> $(.$chk:ident($cf:ident $(, $carg:expr)*))+)?
+            ),+ $(,)?
+        }
+        gains { $($(#[$gm:meta])* $g:ident = $gd:expr),+ $(,)? }
+        $(inputs { $($(#[$im:meta])* $i:ident : $ity:ty),* $(,)? })?
+        $(options { $($(#[$xm:meta])* $x:ident : $xty:ty = $xd:expr),* $(,)? })?
+        $(validate { $($v:ident),* $(,)? })?
+    ) => {
+        /// Search box per parameter; [`Bounds::soft`] is the default.
+        #[derive(Clone, Copy, Debug)]
+        pub struct Bounds {
+            $($(#[$bm])* pub $blo: f64, pub $bhi: f64,)+
+        }
+
+        impl Bounds {
+            pub fn soft() -> Self {
+                Self { $($blo: $ba, $bhi: $bb,)+ }
+            }
+        }
+
+        impl $crate::error::Validate for Bounds {
+            fn problems(&self) -> Vec<$crate::error::Problem> {
+                $crate::error::Checks::default()
+                    $(
+                        .range(stringify!($b), self.$blo, self.$bhi)
+                        $($(.$chk(stringify!($cf), self.$cf $(, $carg)*))+)?
+                    )+
+                    .done()
+            }
+        }
+
+        #[derive(Clone, Copy, Debug)]
+        pub struct Gains {
+            $($(#[$gm])* pub $g: f64,)+
+        }
+
+        impl Default for Gains {
+            fn default() -> Self {
+                Self { $($g: $gd,)+ }
+            }
+        }
+
+        impl Gains {
+            /// Scale every gain by one damping factor (one-knob stability control).
+            ///
+            /// Typical values: `1.0` leaves gains untouched, `0.5` calms mild
+            /// chatter, `0.2`–`0.3` tames a loop that oscillates around the band.
+            /// Lower factors converge more slowly, so pair them with more iterations.
+            pub fn scaled(self, factor: f64) -> Self {
+                let f = factor.max(0.0);
+                Self { $($g: self.$g * f,)+ }
+            }
+        }
+
+        /// Observation model: θ (with hooks) → π.
+        ///
+        /// `balance_ext` drives any model with the same controller and
+        /// convergence band; [`StandardModel`] is the built-in math. Implement
+        /// this to swap in a different simulation without forking the system.
+        pub trait SimModel {
+            fn observe(
+                &self,
+                th: &Params,
+                env: &Env,
+                tgt: &Targets,
+                $($($i: $ity,)*)?
+                hooks: &mut [Box<dyn $crate::systems::sdk::Hook<Params, Env, Targets, Obs>>],
+            ) -> Obs;
+        }
+
+        pub trait Mechanic: $crate::systems::sdk::Hook<Params, Env, Targets, Obs> {}
+        impl<T: $crate::systems::sdk::Hook<Params, Env, Targets, Obs>> Mechanic for T {}
+
+        /// Everything `balance_ext` takes besides θ₀, env and targets. Start
+        /// from [`Options::default`] (soft bounds, default gains and
+        /// controller, no hooks, [`StandardModel`], 120 000 iterations) and
+        /// override fields, or let [`Runner`] fill it in.
+        pub struct Options<M = StandardModel> {
+            pub bounds: Bounds,
+            pub gains: Gains,
+            pub mechs: Vec<Box<dyn Mechanic>>,
+            pub model: M,
+            pub max_iters: usize,
+            /// Optional pull toward a baseline θ (live-game retunes).
+            pub reg: Option<$crate::systems::sdk::Regularization<Params>>,
+            pub controller: $crate::mechanics::control::Controller,
+            $($($(#[$xm])* pub $x: $xty,)*)?
+        }
+
+        impl Default for Options {
+            fn default() -> Self {
+                Self {
+                    bounds: Bounds::soft(),
+                    gains: Gains::default(),
+                    mechs: Vec::new(),
+                    model: StandardModel,
+                    max_iters: 120_000,
+                    reg: None,
+                    controller: $crate::mechanics::control::Controller::default(),
+                    $($($x: $xd,)*)?
+                }
+            }
+        }
+
+        /// Builder over `balance_ext`: `Runner::new` takes what every run
+        /// needs, the setters fill in [`Options`].
+        pub struct Runner<M = StandardModel> {
+            theta0: Params,
+            env: Env,
+            tgt: Targets,
+            $($($(#[$im])* $i: $ity,)*)?
+            damping: f64,
+            opts: Options<M>,
+        }
+
+        impl Runner {
+            pub fn new(theta0: Params, env: Env, tgt: Targets $($(, $i: $ity)*)?) -> Self {
+                Self { theta0, env, tgt, $($($i,)*)? damping: 1.0, opts: Options::default() }
+            }
+        }
+
+        impl<M: SimModel> Runner<M> {
+            pub fn bounds(mut self, bounds: Bounds) -> Self {
+                self.opts.bounds = bounds;
+                self
+            }
+            pub fn gains(mut self, gains: Gains) -> Self {
+                self.opts.gains = gains;
+                self
+            }
+            /// Run with the gains scaled by `factor` (see [`Gains::scaled`]),
+            /// whichever gains are set.
+            pub fn damping(mut self, factor: f64) -> Self {
+                self.damping = factor;
+                self
+            }
+            /// Add one hook to the stack.
+            pub fn hook(mut self, hook: impl Mechanic + 'static) -> Self {
+                self.opts.mechs.push(Box::new(hook));
+                self
+            }
+            /// Append already-boxed hooks.
+            pub fn hooks(mut self, hooks: impl IntoIterator<Item = Box<dyn Mechanic>>) -> Self {
+                self.opts.mechs.extend(hooks);
+                self
+            }
+            pub fn max_iters(mut self, max_iters: usize) -> Self {
+                self.opts.max_iters = max_iters;
+                self
+            }
+            pub fn regularization(mut self, reg: $crate::systems::sdk::Regularization<Params>) -> Self {
+                self.opts.reg = Some(reg);
+                self
+            }
+            pub fn controller(mut self, controller: $crate::mechanics::control::Controller) -> Self {
+                self.opts.controller = controller;
+                self
+            }
+            $($(
+                pub fn $x(mut self, $x: $xty) -> Self {
+                    self.opts.$x = $x;
+                    self
+                }
+            )*)?
+            /// Swap the simulation model, keeping every other setting.
+            pub fn model<N: SimModel>(self, model: N) -> Runner<N> {
+                let o = self.opts;
+                Runner {
+                    theta0: self.theta0,
+                    env: self.env,
+                    tgt: self.tgt,
+                    $($($i: self.$i,)*)?
+                    damping: self.damping,
+                    opts: Options {
+                        bounds: o.bounds,
+                        gains: o.gains,
+                        mechs: o.mechs,
+                        model,
+                        max_iters: o.max_iters,
+                        reg: o.reg,
+                        controller: o.controller,
+                        $($($x: o.$x,)*)?
+                    },
+                }
+            }
+
+            /// θ₀, env, targets, inputs and options, with the damping folded
+            /// into the gains: the arguments of `balance_ext`.
+            fn into_parts(self) -> (Params, Env, Targets, $($($ity,)*)? Options<M>) {
+                let Runner { theta0, env, tgt, $($($i,)*)? damping, mut opts } = self;
+                opts.gains = opts.gains.scaled(damping);
+                (theta0, env, tgt, $($($i,)*)? opts)
+            }
+
+            pub fn run(self) -> $crate::systems::sdk::Outcome<Params, Obs> {
+                let (theta0, env, tgt, $($($i,)*)? opts) = self.into_parts();
+                balance_ext(theta0, env, tgt, $($($i,)*)? opts)
+            }
+
+            /// [`Runner::run`] after validating env, targets and bounds; every
+            /// problem found comes back in one [`Error::Invalid`]($crate::Error::Invalid).
+            pub fn try_run(self) -> Result<$crate::systems::sdk::Outcome<Params, Obs>, $crate::Error> {
+                $crate::error::validate_all(&[&self.env, &self.tgt, &self.opts.bounds $($(, &self.$v)*)?])?;
+                Ok(self.run())
+            }
+        }
+    };
+}
@@ -0,0 +1,245 @@
+//! Difficulty curve: tune enemy HP and damage scaling across a level index so
+//! the predicted fail rate follows a target curve against the player's power
+//! curve.
+//!
+//! Enemies at level `L` (0-based) have `hp_base · hp_growth^L` HP and deal
+//! `dmg_base · dmg_growth^L` DPS. Against the player's DPS and HP at that
+//! level, a fight's pressure is `d = TTK / TTD` (how long the player needs
+//! to kill over how long they survive), and the fail rate is logistic in
+//! `ln d` with scale `skill_spread`. HP growth holds fights at
+//! `fight_seconds` at the first and last level; damage growth hits the fail
+//! targets there. Levels in between follow the player curve, and [`Obs`]
+//! reports every level's fail rate with the largest spike off the straight
+//! target line.
+
+use crate::error::{Checks, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::ControllerState;
+use crate::systems::sdk::{
+    balance_with_hooks, within, AbsTol, Hook, NominalTargets, Outcome, Residual, RelTol,
+};
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Params {
+    pub hp_base: f64,    // enemy HP at level 0
+    pub hp_growth: f64,  // × per level
+    pub dmg_base: f64,   // enemy DPS at level 0
+    pub dmg_growth: f64, // × per level
+}
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        vec![self.hp_base, self.hp_growth, self.dmg_base, self.dmg_growth]
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { hp_base: v[0], hp_growth: v[1], dmg_base: v[2], dmg_growth: v[3] }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Env {
+    pub player_dps: Vec<f64>, // per level; the curve's length is the level count
+    pub player_hp: Vec<f64>,  // per level, same length
+    pub skill_spread: f64,    // logistic scale of fail rate in ln(TTK/TTD), e.g. 0.3
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Targets {
+    pub fight_seconds: f64, // enemy TTK at the first and last level
+    pub fail_first: f64,    // e.g. 0.02
+    pub fail_last: f64,     // e.g. 0.15; the target is linear in between
+}
+
+crate::define_system! {
+    bounds {
+        hp_base: hp_base_min..hp_base_max = (1e-3, 1e12),
+        hp_growth: hp_growth_min..hp_growth_max = (0.5, 3.0),
+        dmg_base: dmg_base_min..dmg_base_max = (1e-6, 1e12),
+        dmg_growth: dmg_growth_min..dmg_growth_max = (0.5, 3.0),
+    }
+    gains { k_hp_base = 0.6, k_hp_growth = 0.5, k_dmg_base = 0.6, k_dmg_growth = 0.5 }
+}
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        let c = Checks::default()
+            .check("player_dps", !self.player_dps.is_empty(), "must not be empty")
+            .check("player_hp", self.player_hp.len() == self.player_dps.len(), "must have one entry per level");
+        let c = self.player_dps.iter().fold(c, |c, &x| c.positive("player_dps", x));
+        self.player_hp.iter().fold(c, |c, &x| c.positive("player_hp", x)).positive("skill_spread", self.skill_spread).done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .positive("fight_seconds", self.fight_seconds)
+            .check("fail_first", self.fail_first > 0.0 && self.fail_first < 1.0, "must be in (0, 1)")
+            .check("fail_last", self.fail_last > 0.0 && self.fail_last < 1.0, "must be in (0, 1)")
+            .done()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obs {
+    pub ttk: Vec<f64>,      // seconds to kill the level's enemy
+    pub fail: Vec<f64>,     // predicted fail rate per level
+    pub fail_first: f64,
+    pub fail_last: f64,
+    pub max_spike: f64,     // largest |fail − target line| over the levels
+    pub spike_level: usize, // where it happens
+}
+
+/// Fail rate at fight pressure `d = TTK / TTD`.
+pub fn fail_rate(pressure: f64, skill_spread: f64) -> f64 {
+    1.0 / (1.0 + (-pressure.max(1e-300).ln() / skill_spread.max(1e-9)).exp())
+}
+
+/// The target fail rate at each of `levels` levels: linear from
+/// `fail_first` to `fail_last`.
+pub fn target_curve(tgt: &Targets, levels: usize) -> Vec<f64> {
+    let span = levels.saturating_sub(1).max(1) as f64;
+    (0..levels).map(|l| tgt.fail_first + (tgt.fail_last - tgt.fail_first) * l as f64 / span).collect()
+}
+
+/// Enemy `(hp, dps)` at `level`.
+pub fn enemy_at(th: &Params, level: usize) -> (f64, f64) {
+    (th.hp_base * th.hp_growth.powi(level as i32), th.dmg_base * th.dmg_growth.powi(level as i32))
+}
+
+/// HP base and growth that hold the first and last fights at
+/// `fight_seconds`.
+fn hp_for(env: &Env, tgt: &Targets) -> (f64, f64) {
+    let (Some(&first), Some(&last)) = (env.player_dps.first(), env.player_dps.last()) else {
+        return (tgt.fight_seconds, 1.0);
+    };
+    let n = env.player_dps.len();
+    let growth = if n > 1 { (last / first).powf(1.0 / (n - 1) as f64) } else { 1.0 };
+    (tgt.fight_seconds * first, growth)
+}
+
+/// Fight pressure that fails at rate `fail`.
+fn pressure_for(fail: f64, skill_spread: f64) -> f64 {
+    let f = fail.clamp(1e-9, 1.0 - 1e-9);
+    (skill_spread * (f / (1.0 - f)).ln()).exp()
+}
+
+/// The logistic pressure model described in the module docs. Implement
+/// [`SimModel`] for hand-authored enemy tables or a fight sim per level.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        tgt: &Targets,
+        _hooks: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let levels = env.player_dps.len().min(env.player_hp.len());
+        let (mut ttk, mut fail) = (Vec::with_capacity(levels), Vec::with_capacity(levels));
+        for l in 0..levels {
+            let (hp, dps) = enemy_at(th, l);
+            let kill = hp / env.player_dps[l].max(1e-9);
+            let die = env.player_hp[l] / dps.max(1e-9);
+            ttk.push(kill);
+            fail.push(fail_rate(kill / die, env.skill_spread));
+        }
+        let (spike_level, max_spike) = fail
+            .iter()
+            .zip(target_curve(tgt, levels))
+            .map(|(f, t)| (f - t).abs())
+            .enumerate()
+            .fold((0, 0.0), |best, (l, e)| if e > best.1 { (l, e) } else { best });
+        Obs {
+            fail_first: fail.first().copied().unwrap_or(0.0),
+            fail_last: fail.last().copied().unwrap_or(0.0),
+            ttk,
+            fail,
+            max_spike,
+            spike_level,
+        }
+    }
+}
+
+/// Unitless error vs targets: the endpoint fail-rate misses.
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    (o.fail_first - tgt.fail_first).abs() + (o.fail_last - tgt.fail_last).abs()
+}
+
+/// Named residuals for [`Outcome::residuals`].
+pub fn residuals(o: &Obs, tgt: &Targets) -> Vec<Residual> {
+    vec![
+        Residual::new("fail_first", o.fail_first, tgt.fail_first),
+        Residual::new("fail_last", o.fail_last, tgt.fail_last),
+        Residual::new("fight_seconds", o.ttk.first().copied().unwrap_or(0.0), tgt.fight_seconds),
+    ]
+}
+
+pub fn balance_ext(
+    theta0: Params,
+    env: Env,
+    tgt: Targets,
+    opts: Options<impl SimModel>,
+) -> Outcome<Params, Obs> {
+    let Options { bounds: b, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = [ControllerState::default(); 4];
+    // HP scaling depends on the player curve only; solve it once for the step.
+    let (hp_base_t, hp_growth_t) = hp_for(&env, &tgt);
+    balance_with_hooks(
+        theta0,
+        env,
+        tgt,
+        b,
+        g,
+        mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+        max_iters,
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, mechs),
+        // nominal: x = damage base, y = damage growth that put the first and
+        // last levels' pressure on their fail targets at the current TTKs
+        |th, env, tgt, o| {
+            let n = o.ttk.len();
+            if n == 0 {
+                return NominalTargets { x: th.dmg_base, y: th.dmg_growth, z: 0.0 };
+            }
+            let dmg_at = |l: usize, fail: f64| {
+                pressure_for(fail, env.skill_spread) * env.player_hp[l] / o.ttk[l].max(1e-9)
+            };
+            let base = dmg_at(0, tgt.fail_first);
+            let growth = if n > 1 { (dmg_at(n - 1, tgt.fail_last) / base).powf(1.0 / (n - 1) as f64) } else { 1.0 };
+            NominalTargets { x: base, y: growth, z: 0.0 }
+        },
+        move |th, b, g, nom, adj| {
+            let (dmg_base_t, dmg_growth_t) = (nom.x * adj.a, nom.y * adj.b);
+
+            let (hp_base_t, hp_growth_t, dmg_base_t, dmg_growth_t) = match reg {
+                Some(r) => (
+                    r.pull(hp_base_t, |p| p.hp_base),
+                    r.pull(hp_growth_t, |p| p.hp_growth),
+                    r.pull(dmg_base_t, |p| p.dmg_base),
+                    r.pull(dmg_growth_t, |p| p.dmg_growth),
+                ),
+                None => (hp_base_t, hp_growth_t, dmg_base_t, dmg_growth_t),
+            };
+
+            let st = &mut ctl_state;
+            Params {
+                hp_base: controller.step(&mut st[0], th.hp_base, hp_base_t, g.k_hp_base, b.hp_base_min, b.hp_base_max),
+                hp_growth: controller.step(&mut st[1], th.hp_growth, hp_growth_t, g.k_hp_growth, b.hp_growth_min, b.hp_growth_max),
+                dmg_base: controller.step(&mut st[2], th.dmg_base, dmg_base_t, g.k_dmg_base, b.dmg_base_min, b.dmg_base_max),
+                dmg_growth: controller.step(&mut st[3], th.dmg_growth, dmg_growth_t, g.k_dmg_growth, b.dmg_growth_min, b.dmg_growth_max),
+            }
+        },
+        // converged: first and last fights on length and on their fail targets
+        |o, tgt| {
+            let fights = |t: Option<&f64>| t.is_some_and(|&t| within(t, tgt.fight_seconds, RelTol(0.05)));
+            fights(o.ttk.first())
+                && fights(o.ttk.last())
+                && within(o.fail_first, tgt.fail_first, AbsTol(0.0025))
+                && within(o.fail_last, tgt.fail_last, AbsTol(0.005))
+        },
+    )
+    .with_residuals(|o| residuals(o, &tgt))
+}
@@ -10,6 +10,7 @@ use bevy_prng::WyRand;
 use rand_core::SeedableRng;
 
 use crate::mechanics::{control, stoch};
+use crate::mechanics::stoch::CountingRng;
 use crate::systems::sdk::Hook;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -36,22 +37,75 @@ pub struct DraftConfig {
     pub prioritize_tier: bool,
 }
 
+/// Called with every produced offer and a snapshot of the state behind it.
+pub type OfferObserver = Box<dyn Fn(&[OfferedCard], &DraftSnapshot)>;
+
+/// Read-only view of a `DraftState`, as passed to an [`OfferObserver`].
+#[derive(Clone, Debug)]
+pub struct DraftSnapshot {
+    pub rerolls_left: usize,
+    pub total_rerolls: usize,
+    pub pity_acc: Vec<f64>,
+    /// RNG outputs consumed (see [`DraftState::position`]).
+    pub position: u64,
+    /// `true` for [`preview_offer`], which leaves the state untouched.
+    pub preview: bool,
+}
+
 pub struct DraftState {
-    rng: Rc<RefCell<WyRand>>,
+    rng: Rc<RefCell<CountingRng<WyRand>>>,
     pub rerolls_left: usize,
+    total_rerolls: usize,
     pity_acc: Vec<f64>,
     last_offered_pool_idxs: Vec<usize>,
+    observer: Option<OfferObserver>,
 }
 
 impl DraftState {
     pub fn new(cfg: DraftConfig, pool_len: usize, seed: u64) -> Self {
         Self {
-            rng: Rc::new(RefCell::new(WyRand::from_seed(seed.to_le_bytes()))),
+            rng: Rc::new(RefCell::new(CountingRng::new(WyRand::from_seed(seed.to_le_bytes())))),
             rerolls_left: cfg.rerolls_per_draft,
+            total_rerolls: cfg.rerolls_per_draft,
             pity_acc: vec![0.0; pool_len],
             last_offered_pool_idxs: Vec::new(),
+            observer: None,
         }
     }
+    /// Install an observer fired by every `make_offer`, `reroll_offer` and
+    /// `preview_offer` (e.g. to refresh a tuning UI live).
+    pub fn set_offer_observer(&mut self, observer: impl Fn(&[OfferedCard], &DraftSnapshot) + 'static) {
+        self.observer = Some(Box::new(observer));
+    }
+    pub fn clear_offer_observer(&mut self) {
+        self.observer = None;
+    }
+    pub fn snapshot(&self) -> DraftSnapshot {
+        DraftSnapshot {
+            rerolls_left: self.rerolls_left,
+            total_rerolls: self.total_rerolls,
+            pity_acc: self.pity_acc.clone(),
+            position: self.position(),
+            preview: false,
+        }
+    }
+    /// Rerolls configured for the current draft (the "3" in "rerolls 1/3").
+    pub fn total_rerolls(&self) -> usize {
+        self.total_rerolls
+    }
+    /// Refill rerolls for a new draft; pity accumulation and RNG are kept.
+    pub fn reset_rerolls(&mut self, cfg: DraftConfig) {
+        self.total_rerolls = cfg.rerolls_per_draft;
+        self.rerolls_left = cfg.rerolls_per_draft;
+    }
+    /// Skip `n` RNG outputs (align independent draft sequences).
+    pub fn advance(&mut self, n: u64) {
+        self.rng.borrow_mut().skip(n);
+    }
+    /// RNG outputs consumed since `new` (including `advance`).
+    pub fn position(&self) -> u64 {
+        self.rng.borrow().draws()
+    }
     pub fn resize_pool(&mut self, new_len: usize) {
         if new_len > self.pity_acc.len() {
             self.pity_acc.resize(new_len, 0.0);
@@ -72,6 +126,41 @@ pub fn make_offer<TParams, Env, Tgt, Obs>(
     pool: &[EffectCard<TParams, Env, Tgt, Obs>],
     cfg: DraftConfig,
     st: &mut DraftState,
+) -> Vec<OfferedCard> {
+    let offer = roll_offer(pool, cfg, st);
+    if let Some(obs) = &st.observer {
+        obs(&offer, &st.snapshot());
+    }
+    offer
+}
+
+/// The offer `make_offer` would produce next, without consuming RNG or
+/// touching pity. Fires the observer with `preview: true`, so a tool can
+/// re-preview after every pool edit.
+pub fn preview_offer<TParams, Env, Tgt, Obs>(
+    pool: &[EffectCard<TParams, Env, Tgt, Obs>],
+    cfg: DraftConfig,
+    st: &DraftState,
+) -> Vec<OfferedCard> {
+    let mut scratch = DraftState {
+        rng: Rc::new(RefCell::new(st.rng.borrow().clone())),
+        rerolls_left: st.rerolls_left,
+        total_rerolls: st.total_rerolls,
+        pity_acc: st.pity_acc.clone(),
+        last_offered_pool_idxs: st.last_offered_pool_idxs.clone(),
+        observer: None,
+    };
+    let offer = roll_offer(pool, cfg, &mut scratch);
+    if let Some(obs) = &st.observer {
+        obs(&offer, &DraftSnapshot { preview: true, ..st.snapshot() });
+    }
+    offer
+}
+
+fn roll_offer<TParams, Env, Tgt, Obs>(
+    pool: &[EffectCard<TParams, Env, Tgt, Obs>],
+    cfg: DraftConfig,
+    st: &mut DraftState,
 ) -> Vec<OfferedCard> {
     let mut candidates: Vec<(usize, &EffectCard<TParams, Env, Tgt, Obs>)> = Vec::new();
     for (i, e) in pool.iter().enumerate() {
@@ -165,6 +254,41 @@ pub fn notify_picked<TParams, Env, Tgt, Obs>(
     }
 }
 
+/// Expected number of offers until `card` is shown, from its base roll
+/// probability and pity ramp (analytic; no sampling).
+///
+/// Each offer rolls the card with `p = base_p + pity`; while unseen, pity
+/// drifts toward `pity_cap` at rate `k`. Assumes the card is not crowded out
+/// of the `options_per_roll` slots once rolled. Returns `f64::INFINITY` if
+/// the card can never be rolled.
+pub fn expected_offers_to_see<TParams, Env, Tgt, Obs>(
+    card: &EffectCard<TParams, Env, Tgt, Obs>,
+) -> f64 {
+    let base = card.base_p.clamp(0.0, 1.0);
+    let (cap, k) = match card.pity {
+        Some(spec) => (spec.pity_cap.max(0.0), spec.k.clamp(0.0, 1.0)),
+        None => (0.0, 0.0),
+    };
+    if base <= 0.0 && (cap <= 0.0 || k <= 0.0) {
+        return f64::INFINITY;
+    }
+
+    // E[N] = Σ_{n≥0} P(not seen in the first n offers).
+    let mut acc: f64 = 0.0;
+    let mut survive = 1.0;
+    let mut expected = 0.0;
+    for _ in 0..1_000_000 {
+        expected += survive;
+        let p = (base + acc.clamp(0.0, 1.0)).clamp(0.0, 1.0);
+        survive *= 1.0 - p;
+        if survive < 1e-12 {
+            break;
+        }
+        acc = control::approach(acc, cap, k, 0.0, cap);
+    }
+    expected
+}
+
 /* --- internal pity update --- */
 
 fn apply_pity_after_offer<TParams, Env, Tgt, Obs>(
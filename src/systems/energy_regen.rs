@@ -0,0 +1,230 @@
+//! Energy regen: tune max energy, regen per minute and action cost toward
+//! session pacing targets (session length, natural sessions per day, energy
+//! wasted at cap).
+//!
+//! A session spends a full bar, regenerating while the player acts, so it
+//! lasts `max / (cost − regen · minutes_per_action)` actions. The player
+//! comes back when the bar has refilled (`max / regen` minutes), which gives
+//! the natural sessions per waking day. Real check-ins are irregular: gaps
+//! are exponential with mean `mean_gap_minutes` during the day and the bar
+//! also caps overnight, and whatever regenerates while full is wasted.
+//!
+//! Only ratios reach the player, so `Targets::bar_size` pins the scale.
+
+use crate::error::{Checks, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::{self, ControllerState};
+use crate::systems::sdk::{
+    balance_with_hooks, compose_income, within, AbsTol, Hook, NominalTargets, Outcome, Residual,
+    RelTol,
+};
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Params {
+    pub max_energy: f64,
+    pub regen_per_min: f64,
+    pub action_cost: f64,
+}
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        vec![self.max_energy, self.regen_per_min, self.action_cost]
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { max_energy: v[0], regen_per_min: v[1], action_cost: v[2] }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Env {
+    pub minutes_per_action: f64, // time to play one action
+    pub waking_minutes: f64,     // e.g. 960; the rest of the day is one overnight gap
+    pub mean_gap_minutes: f64,   // mean daytime gap between check-ins
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Targets {
+    pub session_minutes: f64,  // e.g. 20
+    pub sessions_per_day: f64, // natural sessions per waking day, e.g. 3
+    pub max_waste: f64,        // max share of daily regen lost at cap, e.g. 0.10
+    pub bar_size: f64,         // max energy the design wants, e.g. 100
+}
+
+crate::define_system! {
+    bounds {
+        max_energy: max_min..max_max = (1.0, 1e6),
+        regen_per_min: regen_min..regen_max = (1e-4, 1e4),
+        action_cost: cost_min..cost_max = (1e-3, 1e6),
+    }
+    gains { k_max = 0.6, k_regen = 0.5, k_cost = 0.5 }
+}
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .positive("minutes_per_action", self.minutes_per_action)
+            .check("waking_minutes", self.waking_minutes > 0.0 && self.waking_minutes <= 1440.0, "must be in (0, 1440]")
+            .positive("mean_gap_minutes", self.mean_gap_minutes)
+            .done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .positive("session_minutes", self.session_minutes)
+            .positive("sessions_per_day", self.sessions_per_day)
+            .within("max_waste", self.max_waste, 0.0, 1.0)
+            .positive("bar_size", self.bar_size)
+            .done()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obs {
+    pub regen_per_min: f64, // after hooks
+    pub actions_per_session: f64,
+    pub session_minutes: f64,
+    pub refill_minutes: f64, // empty → full
+    pub sessions_per_day: f64,
+    pub waste: f64, // share of daily regen lost at cap
+}
+
+/// Actions one full bar buys, counting regen during play (capped at a
+/// waking day's worth when regen outpaces spending).
+pub fn actions_per_session(max_energy: f64, regen_per_min: f64, action_cost: f64, env: &Env) -> f64 {
+    let cap = env.waking_minutes / env.minutes_per_action.max(1e-9);
+    let net = action_cost - regen_per_min * env.minutes_per_action;
+    if net <= 0.0 { cap } else { (max_energy / net).min(cap) }
+}
+
+/// Share of a day's regen lost while the bar is full, for a bar that takes
+/// `refill_minutes` to fill: exponential daytime gaps waste `e^(−refill/gap)`
+/// of the waking regen, and the night wastes whatever outlasts the refill.
+pub fn waste_share(refill_minutes: f64, env: &Env) -> f64 {
+    let night = (1440.0 - env.waking_minutes).max(0.0);
+    let day = env.waking_minutes * (-refill_minutes / env.mean_gap_minutes.max(1e-9)).exp();
+    (day + (night - refill_minutes).max(0.0)) / 1440.0
+}
+
+/// Shortest refill time that keeps [`waste_share`] at or under `max_waste`.
+pub fn min_refill_minutes(max_waste: f64, env: &Env) -> f64 {
+    let (mut lo, mut hi) = (0.0, 1440.0 + 50.0 * env.mean_gap_minutes);
+    for _ in 0..60 {
+        let mid = 0.5 * (lo + hi);
+        if waste_share(mid, env) > max_waste { lo = mid } else { hi = mid }
+    }
+    hi
+}
+
+/// Refill time the targets ask for: the natural-session spacing, stretched
+/// if that would waste more than `max_waste`.
+pub fn target_refill_minutes(env: &Env, tgt: &Targets) -> f64 {
+    let spacing = env.waking_minutes / tgt.sessions_per_day.max(1e-9) - tgt.session_minutes;
+    spacing.max(0.0).max(min_refill_minutes(tgt.max_waste, env))
+}
+
+/// The pacing math described in the module docs; hooks'
+/// `income_multiplier` scales regen (VIP bonuses, regen boosts). Implement
+/// [`SimModel`] for overflow storage, ad refills or schedules fitted to real
+/// check-in data.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        _tgt: &Targets,
+        mechs: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let regen = compose_income(th.regen_per_min, mechs, th, env).max(1e-12);
+        let actions = actions_per_session(th.max_energy, regen, th.action_cost, env);
+        let session = actions * env.minutes_per_action;
+        let refill = th.max_energy / regen;
+        Obs {
+            regen_per_min: regen,
+            actions_per_session: actions,
+            session_minutes: session,
+            refill_minutes: refill,
+            sessions_per_day: env.waking_minutes / (refill + session).max(1e-9),
+            waste: waste_share(refill, env),
+        }
+    }
+}
+
+/// Unitless error vs targets: session and cadence errors plus any waste
+/// excess.
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    control::pct_error(o.session_minutes, tgt.session_minutes)
+        + control::pct_error(o.sessions_per_day, tgt.sessions_per_day)
+        + (o.waste - tgt.max_waste).max(0.0)
+}
+
+/// Named residuals for [`Outcome::residuals`].
+pub fn residuals(o: &Obs, tgt: &Targets) -> Vec<Residual> {
+    vec![
+        Residual::new("session_minutes", o.session_minutes, tgt.session_minutes),
+        Residual::new("sessions_per_day", o.sessions_per_day, tgt.sessions_per_day),
+        Residual::new("waste", o.waste, o.waste.min(tgt.max_waste)),
+    ]
+}
+
+pub fn balance_ext(
+    theta0: Params,
+    env: Env,
+    tgt: Targets,
+    opts: Options<impl SimModel>,
+) -> Outcome<Params, Obs> {
+    let Options { bounds: b, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = [ControllerState::default(); 3];
+    balance_with_hooks(
+        theta0,
+        env,
+        tgt,
+        b,
+        g,
+        mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+        max_iters,
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, mechs),
+        // nominal: x = bar size, y = regen for the target refill time (scaled
+        // by what hooks add), z = cost for the target session length
+        |th, env, tgt, o| {
+            let refill = target_refill_minutes(env, tgt).max(1e-9);
+            let regen = th.regen_per_min * o.refill_minutes / refill;
+            let actions = tgt.session_minutes / env.minutes_per_action.max(1e-9);
+            let cost = th.max_energy / actions.max(1e-9) + o.regen_per_min * env.minutes_per_action;
+            NominalTargets { x: tgt.bar_size, y: regen, z: cost }
+        },
+        move |th, b, g, nom, adj| {
+            let (max_t, regen_t, cost_t) = (nom.x * adj.a, nom.y * adj.b, nom.z * adj.c);
+
+            let (max_t, regen_t, cost_t) = match reg {
+                Some(r) => (
+                    r.pull(max_t, |p| p.max_energy),
+                    r.pull(regen_t, |p| p.regen_per_min),
+                    r.pull(cost_t, |p| p.action_cost),
+                ),
+                None => (max_t, regen_t, cost_t),
+            };
+
+            let st = &mut ctl_state;
+            Params {
+                max_energy: controller.step(&mut st[0], th.max_energy, max_t, g.k_max, b.max_min, b.max_max),
+                regen_per_min: controller.step(&mut st[1], th.regen_per_min, regen_t, g.k_regen, b.regen_min, b.regen_max),
+                action_cost: controller.step(&mut st[2], th.action_cost, cost_t, g.k_cost, b.cost_min, b.cost_max),
+            }
+        },
+        // converged: session length on target, waste under its cap, and the
+        // session count on target unless the waste cap is what holds it back
+        |o, tgt| {
+            let waste_bound = o.sessions_per_day < tgt.sessions_per_day && within(o.waste, tgt.max_waste, AbsTol(0.005));
+            within(o.session_minutes, tgt.session_minutes, RelTol(0.05))
+                && o.waste <= tgt.max_waste + 0.005
+                && (within(o.sessions_per_day, tgt.sessions_per_day, RelTol(0.05)) || waste_bound)
+        },
+    )
+    .with_residuals(|o| residuals(o, &tgt))
+}
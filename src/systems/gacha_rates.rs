@@ -0,0 +1,259 @@
+//! Gacha rates: tune the top-rarity base rate, soft-pity ramp, hard-pity cap
+//! and second-rarity rate toward pull-economy targets.
+//!
+//! Everything is exact pity math, no sampling: the k-th pull since the last
+//! 5★ hits with `rate_5 + ramp · max(0, k − soft_pity_start)` (clamped to 1)
+//! and always hits at the hard cap. From that survival curve come the mean
+//! pulls per 5★, the chance of a drought longer than `drought_pulls`, and
+//! the share of 5★ that arrive before soft pity starts. The 4★ tier is a
+//! flat `rate_4` with a guarantee every `four_star_pity` pulls.
+//!
+//! Hooks act as rate-ups: their income terms (`flat_income`,
+//! `income_multiplier`) scale the 5★ base rate the pity math sees.
+
+use crate::error::{Checks, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::{self, ControllerState};
+use crate::systems::sdk::{
+    balance_with_hooks, compose_income, within, AbsTol, Hook, NominalTargets, Outcome, Residual, RelTol,
+};
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Params {
+    pub rate_5: f64,   // base 5★ chance per pull
+    pub ramp: f64,     // added 5★ chance per pull past soft pity
+    pub hard_cap: f64, // pull that always gives a 5★ (rounded)
+    pub rate_4: f64,   // base 4★ chance per pull
+}
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        vec![self.rate_5, self.ramp, self.hard_cap, self.rate_4]
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { rate_5: v[0], ramp: v[1], hard_cap: v[2], rate_4: v[3] }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Env {
+    pub soft_pity_start: f64, // last pull at the base rate; the ramp starts after it
+    pub four_star_pity: f64,  // a 4★ is guaranteed within this many pulls
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Targets {
+    pub window_pulls: f64,    // e.g. 80
+    pub five_per_window: f64, // expected 5★ per `window_pulls`
+    pub drought_pulls: f64,   // e.g. 90
+    pub drought_max: f64,     // max P(no 5★ in `drought_pulls`), e.g. 0.001
+    pub base_share: f64,      // share of 5★ pulled before soft pity
+    pub four_per_ten: f64,    // expected 4★ per 10 pulls
+}
+
+crate::define_system! {
+    bounds {
+        rate_5: rate5_min..rate5_max = (1e-4, 0.5),
+        ramp: ramp_min..ramp_max = (0.0, 1.0),
+        hard_cap: cap_min..cap_max = (1.0, 1_000.0),
+        rate_4: rate4_min..rate4_max = (1e-3, 1.0),
+    }
+    gains { k_rate5 = 0.6, k_ramp = 0.5, k_cap = 0.6, k_rate4 = 0.6 }
+}
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .check("soft_pity_start", self.soft_pity_start >= 1.0, "must be at least 1")
+            .check("four_star_pity", self.four_star_pity >= 1.0, "must be at least 1")
+            .done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .positive("window_pulls", self.window_pulls)
+            .positive("five_per_window", self.five_per_window)
+            .positive("drought_pulls", self.drought_pulls)
+            .within("drought_max", self.drought_max, 0.0, 1.0)
+            .within("base_share", self.base_share, 0.0, 1.0)
+            .check("four_per_ten", self.four_per_ten > 0.0 && self.four_per_ten <= 10.0, "must be in (0, 10]")
+            .done()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obs {
+    pub rate_5: f64,          // 5★ base rate after hooks
+    pub mean_pulls: f64,      // expected pulls per 5★
+    pub five_per_window: f64, // window_pulls / mean_pulls
+    pub drought: f64,         // P(no 5★ in drought_pulls)
+    pub base_share: f64,      // P(5★ by soft_pity_start)
+    pub four_per_ten: f64,
+}
+
+/// The hard cap as a pull count (at least 1).
+pub fn cap_pulls(th: &Params) -> usize {
+    th.hard_cap.round().max(1.0) as usize
+}
+
+/// Chance that the `k`-th pull (1-based) since the last 5★ is a 5★.
+pub fn five_star_chance(th: &Params, env: &Env, k: usize) -> f64 {
+    if k >= cap_pulls(th) {
+        return 1.0;
+    }
+    (th.rate_5 + th.ramp * (k as f64 - env.soft_pity_start).max(0.0)).clamp(0.0, 1.0)
+}
+
+/// P(no 5★ in the first `n` pulls).
+pub fn survival(th: &Params, env: &Env, n: usize) -> f64 {
+    (1..=n.min(cap_pulls(th))).map(|k| 1.0 - five_star_chance(th, env, k)).product()
+}
+
+/// Expected pulls per 5★: Σₖ P(no 5★ in the first k pulls), k < hard cap.
+pub fn mean_pulls(th: &Params, env: &Env) -> f64 {
+    let mut s = 1.0;
+    let mut sum = 0.0;
+    for k in 1..=cap_pulls(th) {
+        sum += s;
+        s *= 1.0 - five_star_chance(th, env, k);
+    }
+    sum
+}
+
+/// Expected pulls per 4★ with a guarantee every `pity` pulls.
+pub fn mean_pulls_4(rate_4: f64, pity: f64) -> f64 {
+    let n = pity.round().max(1.0) as i32;
+    let q = rate_4.clamp(0.0, 1.0);
+    if q <= 0.0 { n as f64 } else { (1.0 - (1.0 - q).powi(n)) / q }
+}
+
+/// `x` in `[lo, hi]` where decreasing `f(x)` meets `want` (an end if it
+/// never does).
+fn solve_decreasing(lo: f64, hi: f64, want: f64, f: impl Fn(f64) -> f64) -> f64 {
+    let (mut lo, mut hi) = (lo, hi);
+    for _ in 0..60 {
+        let mid = 0.5 * (lo + hi);
+        if f(mid) > want { lo = mid } else { hi = mid }
+    }
+    0.5 * (lo + hi)
+}
+
+/// The default pity math described in the module docs, on the 5★ base
+/// rate after hooks. Implement [`SimModel`] for other pity rules (e.g. a
+/// 50/50 featured-unit guarantee).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        tgt: &Targets,
+        hooks: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let th = &Params { rate_5: compose_income(th.rate_5, hooks, th, env).clamp(0.0, 1.0), ..*th };
+        let mean = mean_pulls(th, env);
+        Obs {
+            rate_5: th.rate_5,
+            mean_pulls: mean,
+            five_per_window: tgt.window_pulls / mean.max(1e-9),
+            drought: survival(th, env, tgt.drought_pulls.floor().max(0.0) as usize),
+            base_share: 1.0 - survival(th, env, env.soft_pity_start.floor() as usize),
+            four_per_ten: 10.0 / mean_pulls_4(th.rate_4, env.four_star_pity),
+        }
+    }
+}
+
+/// Unitless error vs targets: rate and share errors plus any drought excess.
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    control::pct_error(o.five_per_window, tgt.five_per_window)
+        + (o.base_share - tgt.base_share).abs()
+        + control::pct_error(o.four_per_ten, tgt.four_per_ten)
+        + (o.drought - tgt.drought_max).max(0.0)
+}
+
+/// Named residuals for [`Outcome::residuals`].
+pub fn residuals(o: &Obs, tgt: &Targets) -> Vec<Residual> {
+    vec![
+        Residual::new("five_per_window", o.five_per_window, tgt.five_per_window),
+        Residual::new("drought", o.drought, o.drought.min(tgt.drought_max)),
+        Residual::new("base_share", o.base_share, tgt.base_share),
+        Residual::new("four_per_ten", o.four_per_ten, tgt.four_per_ten),
+    ]
+}
+
+pub fn balance_ext(
+    theta0: Params,
+    env: Env,
+    tgt: Targets,
+    opts: Options<impl SimModel>,
+) -> Outcome<Params, Obs> {
+    let Options { bounds: b, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = [ControllerState::default(); 4];
+    // Drought chance of the latest simulate, after hooks.
+    let drought = &std::cell::Cell::new(0.0);
+    balance_with_hooks(
+        theta0,
+        env,
+        tgt,
+        b,
+        g,
+        mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+        max_iters,
+        move |th, env, tgt, mechs| {
+            let o = model.observe(th, env, tgt, mechs);
+            drought.set(o.drought);
+            o
+        },
+        // nominal: x = rate_5 for the base share (closed form, divided by
+        // the hooks' rate-up), y = ramp for the mean pulls at the current
+        // rate and cap, z = rate_4
+        |th, env, tgt, o| {
+            let base_pulls = env.soft_pity_start.floor().max(1.0);
+            let want_rate = 1.0 - (1.0 - tgt.base_share.clamp(0.0, 1.0)).powf(1.0 / base_pulls);
+            let rate_5 = want_rate * th.rate_5 / o.rate_5.max(1e-12);
+            let want_mean = tgt.window_pulls / tgt.five_per_window.max(1e-9);
+            let eff = Params { rate_5: o.rate_5, ..*th };
+            let ramp = solve_decreasing(0.0, 1.0, want_mean, |ramp| mean_pulls(&Params { ramp, ..eff }, env));
+            let want_4 = 10.0 / tgt.four_per_ten.max(1e-9);
+            let rate_4 = solve_decreasing(0.0, 1.0, want_4, |q| mean_pulls_4(q, env.four_star_pity));
+            NominalTargets { x: rate_5, y: ramp, z: rate_4 }
+        },
+        // step: rates and ramp toward nominal; the cap only moves in to
+        // `drought_pulls` while the drought chance is over its limit
+        move |th, b, g, nom, adj| {
+            let too_dry = drought.get() > tgt.drought_max;
+            let cap_t = if too_dry { tgt.drought_pulls.min(th.hard_cap) } else { th.hard_cap };
+            let (rate5_t, ramp_t, rate4_t) = (nom.x * adj.a, nom.y * adj.b, nom.z * adj.c);
+
+            let (rate5_t, ramp_t, cap_t, rate4_t) = match reg {
+                Some(r) => (
+                    r.pull(rate5_t, |p| p.rate_5),
+                    r.pull(ramp_t, |p| p.ramp),
+                    r.pull(cap_t, |p| p.hard_cap),
+                    r.pull(rate4_t, |p| p.rate_4),
+                ),
+                None => (rate5_t, ramp_t, cap_t, rate4_t),
+            };
+
+            let st = &mut ctl_state;
+            Params {
+                rate_5: controller.step(&mut st[0], th.rate_5, rate5_t, g.k_rate5, b.rate5_min, b.rate5_max),
+                ramp: controller.step(&mut st[1], th.ramp, ramp_t, g.k_ramp, b.ramp_min, b.ramp_max),
+                hard_cap: controller.step(&mut st[2], th.hard_cap, cap_t, g.k_cap, b.cap_min, b.cap_max),
+                rate_4: controller.step(&mut st[3], th.rate_4, rate4_t, g.k_rate4, b.rate4_min, b.rate4_max),
+            }
+        },
+        |o, tgt| {
+            within(o.five_per_window, tgt.five_per_window, RelTol(0.02))
+                && o.drought <= tgt.drought_max
+                && within(o.base_share, tgt.base_share, AbsTol(0.01))
+                && within(o.four_per_ten, tgt.four_per_ten, RelTol(0.02))
+        },
+    )
+    .with_residuals(|o| residuals(o, &tgt))
+}
@@ -0,0 +1,207 @@
+//! Loot table: tune per-item drop chances so each item class takes a target
+//! time to acquire at a given kill rate.
+//!
+//! Drops are independent per kill, so the kills until an item drops are
+//! geometric: mean `1 / chance`, and the whole acquisition-time distribution
+//! follows from the chance alone. [`Obs`] reports the mean and a fixed set of
+//! [`QUANTILES`] per item, so the worst-case grind (the unlucky 1% or 0.1%)
+//! is visible next to the average.
+
+use crate::error::{Checks, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::{self, ControllerState};
+use crate::systems::sdk::{
+    balance_with_hooks, compose_income, within, Hook, NominalTargets, Outcome, Residual, RelTol,
+};
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Params {
+    pub drop_chance: Vec<f64>, // per kill, one per item
+}
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        self.drop_chance.clone()
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { drop_chance: v.to_vec() }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Env {
+    pub kills_per_hour: f64,
+    pub item_class: Vec<usize>, // class of each item, an index into `Targets::class_hours`
+}
+
+#[derive(Clone, Debug)]
+pub struct Targets {
+    pub class_hours: Vec<f64>, // expected hours to acquire one item of each class
+}
+
+crate::define_system! {
+    bounds {
+        drop_chance: chance_min..chance_max = (1e-7, 1.0) => .positive(chance_min).within(chance_max, 0.0, 1.0),
+    }
+    gains { k_chance = 0.6 }
+}
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default().positive("kills_per_hour", self.kills_per_hour).done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        self.class_hours.iter().fold(Checks::default(), |c, &h| c.positive("class_hours", h)).done()
+    }
+}
+
+/// Acquisition-time quantiles reported per item in [`Obs::quantile_hours`].
+pub const QUANTILES: [f64; 5] = [0.5, 0.75, 0.9, 0.99, 0.999];
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obs {
+    pub kills_per_hour: f64,           // after hooks
+    pub mean_hours: Vec<f64>,          // expected hours to acquire each item
+    pub quantile_hours: Vec<[f64; 5]>, // hours by which each item has dropped, at QUANTILES
+}
+
+impl Obs {
+    /// Hours by which `item` has dropped with probability `QUANTILES[q]`
+    /// (e.g. `q = 3` for the unlucky 1%).
+    pub fn worst_case(&self, item: usize, q: usize) -> Option<f64> {
+        self.quantile_hours.get(item).and_then(|qs| qs.get(q)).copied()
+    }
+}
+
+/// P(an item with per-kill `chance` has dropped within `hours`).
+pub fn acquire_cdf(chance: f64, kills_per_hour: f64, hours: f64) -> f64 {
+    let p = chance.clamp(0.0, 1.0);
+    1.0 - (1.0 - p).powf((kills_per_hour * hours).max(0.0))
+}
+
+/// Hours by which the item has dropped with probability `q`.
+pub fn hours_at_quantile(chance: f64, kills_per_hour: f64, q: f64) -> f64 {
+    let p = chance.clamp(1e-12, 1.0);
+    let kills = if p >= 1.0 { 1.0 } else { ((1.0 - q.clamp(0.0, 1.0 - 1e-12)).ln() / (1.0 - p).ln()).max(1.0) };
+    kills / kills_per_hour.max(1e-9)
+}
+
+/// The acquisition-time CDF of one item at each of `hours`.
+pub fn acquisition_curve(chance: f64, kills_per_hour: f64, hours: &[f64]) -> Vec<f64> {
+    hours.iter().map(|&h| acquire_cdf(chance, kills_per_hour, h)).collect()
+}
+
+/// Target hours for `item` (`None` if its class has no target).
+fn target_hours(env: &Env, tgt: &Targets, item: usize) -> Option<f64> {
+    env.item_class.get(item).and_then(|&c| tgt.class_hours.get(c)).copied()
+}
+
+/// Independent per-kill drops; hooks' `income_multiplier` scales the kill
+/// rate (drop-rate events, boosters). Implement [`SimModel`] for weighted
+/// single-roll tables or pity on rare drops.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        _tgt: &Targets,
+        mechs: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let kph = compose_income(env.kills_per_hour, mechs, th, env).max(1e-9);
+        let mean_hours = th.drop_chance.iter().map(|&p| 1.0 / (p.max(1e-12) * kph)).collect();
+        let quantile_hours = th
+            .drop_chance
+            .iter()
+            .map(|&p| QUANTILES.map(|q| hours_at_quantile(p, kph, q)))
+            .collect();
+        Obs { kills_per_hour: kph, mean_hours, quantile_hours }
+    }
+}
+
+/// Unitless error vs targets: mean [`pct_error`](control::pct_error) of each
+/// targeted item's mean hours (0 if none is targeted). Needs `env` for the
+/// item classes.
+pub fn normalized_error(o: &Obs, env: &Env, tgt: &Targets) -> f64 {
+    let errs: Vec<f64> = o
+        .mean_hours
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &h)| target_hours(env, tgt, i).map(|t| control::pct_error(h, t)))
+        .collect();
+    if errs.is_empty() { 0.0 } else { errs.iter().sum::<f64>() / errs.len() as f64 }
+}
+
+/// Named per-item mean-hours residuals (`item0`, `item1`, …) for
+/// [`Outcome::residuals`]; items without a class target are skipped.
+pub fn residuals(o: &Obs, env: &Env, tgt: &Targets) -> Vec<Residual> {
+    o.mean_hours
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &h)| target_hours(env, tgt, i).map(|t| Residual::new(format!("item{i}"), h, t)))
+        .collect()
+}
+
+pub fn balance_ext(
+    theta0: Params,
+    env: Env,
+    tgt: Targets,
+    opts: Options<impl SimModel>,
+) -> Outcome<Params, Obs> {
+    let Options { bounds: bnd, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = vec![ControllerState::default(); theta0.drop_chance.len()];
+    // Per-item targets depend on the class map in env; the step and the
+    // convergence check only see θ and targets, so they keep a copy.
+    let (step_env, step_tgt) = (env.clone(), tgt.clone());
+    let conv_env = env.clone();
+    let (res_env, res_tgt) = (env.clone(), tgt.clone());
+    balance_with_hooks(
+        theta0,
+        env,
+        tgt,
+        bnd,
+        g,
+        mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+        max_iters,
+        // simulate: delegate to the observation model
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, mechs),
+        // nominal: x = effective kills per hour
+        |_th, _env, _tgt, o| NominalTargets { x: o.kills_per_hour, y: 0.0, z: 0.0 },
+        // step: chance_i → 1 / (kills/h · hours target of its class)
+        move |th, b, g, nom, adj| {
+            let kph = nom.x * adj.c;
+
+            let st = &mut ctl_state;
+            st.resize(th.drop_chance.len(), ControllerState::default());
+            let drop_chance = th
+                .drop_chance
+                .iter()
+                .enumerate()
+                .map(|(i, &p)| {
+                    let Some(hours) = target_hours(&step_env, &step_tgt, i) else { return p };
+                    let target = 1.0 / (kph * hours * adj.a).max(1e-12);
+                    let target = match reg.as_ref() {
+                        Some(r) => r.pull(target, |base| base.drop_chance.get(i).copied().unwrap_or(target)),
+                        None => target,
+                    };
+                    controller.step(&mut st[i], p, target.clamp(b.chance_min, b.chance_max), g.k_chance, b.chance_min, b.chance_max)
+                })
+                .collect();
+            Params { drop_chance }
+        },
+        // converged: every targeted item within ±5% of its class's hours
+        move |o, tgt| {
+            o.mean_hours.iter().enumerate().all(|(i, &h)| match target_hours(&conv_env, tgt, i) {
+                Some(want) => within(h, want, RelTol(0.05)),
+                None => true,
+            })
+        },
+    )
+    .with_residuals(|o| residuals(o, &res_env, &res_tgt))
+}
@@ -0,0 +1,287 @@
+//! Mana curve: tune a deckbuilder's cost distribution so the odds of
+//! "curving out" (playing a card of cost `t` on each of turns 1..=T) land in
+//! a band and few cards sit dead in hand.
+//!
+//! Everything is exact hypergeometric math over the shuffled deck (see
+//! [`crate::mechanics::combinatorics`]). By turn `t` a player has seen
+//! `opening_hand + t − 1` cards, plus one when they draw on the first turn.
+//! Curving out needs a cost-`t` card among those for every `t ≤ T`; the
+//! joint probability comes from inclusion–exclusion over the turns that
+//! miss. A card is dead on turn `t` when it costs more than `t`, and the
+//! dead-card rate is that share of the deck averaged over the curve turns.
+//!
+//! The tuner keeps the deck size and the shape within each half of the
+//! curve: it scales the low costs (1..=T) by one factor and fills the rest
+//! of the deck with the high costs. The factor is the smallest that puts
+//! curve-out at the band midpoint and the dead rate under its cap.
+
+use crate::error::{Checks, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::combinatorics::{miss_prob, miss_windows};
+use crate::mechanics::control::{self, ControllerState};
+use crate::systems::sdk::{
+    balance_with_hooks, within, AbsTol, Band, Hook, NominalTargets, Outcome, Residual,
+};
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Params {
+    pub counts: Vec<f64>, // cards at cost i+1; the last bucket is "that cost and up"
+}
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        self.counts.clone()
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { counts: v.to_vec() }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Env {
+    pub deck_size: f64,
+    pub opening_hand: u32,
+    pub draw_first_turn: bool, // false when on the play without a turn-1 draw
+    pub curve_turns: u32,      // T: turns 1..=T must each play a card of that cost
+}
+impl Env {
+    /// Cards seen by the start of turn `t` (1-based).
+    pub fn seen_by(&self, t: u32) -> u32 {
+        self.opening_hand + t.saturating_sub(1) + u32::from(self.draw_first_turn)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Targets {
+    pub curve_out_band: (f64, f64), // e.g. (0.2, 0.25)
+    pub max_dead: f64,              // cap on the average dead-card rate
+}
+
+crate::define_system! {
+    bounds {
+        count: count_min..count_max = (0.0, 1e4) => .non_negative(count_min),
+    }
+    gains { k_count = 0.5 }
+}
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .positive("deck_size", self.deck_size)
+            .check("opening_hand", self.opening_hand > 0, "must be at least 1")
+            .check("curve_turns", self.curve_turns > 0, "must be at least 1")
+            .check(
+                "curve_turns",
+                f64::from(self.seen_by(self.curve_turns)) <= self.deck_size,
+                "sees more cards than the deck holds",
+            )
+            .done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .range("curve_out_band", self.curve_out_band.0, self.curve_out_band.1)
+            .within("curve_out_band.0", self.curve_out_band.0, 0.0, 1.0)
+            .within("curve_out_band.1", self.curve_out_band.1, 0.0, 1.0)
+            .within("max_dead", self.max_dead, 0.0, 1.0)
+            .done()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obs {
+    pub deck_total: f64,
+    pub on_curve: Vec<f64>, // per turn 1..=T: P(a card of exactly that cost is in hand)
+    pub whiff: Vec<f64>,    // per turn 1..=T: P(nothing in hand is castable)
+    pub curve_out: f64,     // P(on curve every turn 1..=T)
+    pub dead_rate: f64,     // share of the deck costing more than the turn, averaged over 1..=T
+}
+
+/// Cards at exactly cost `t` (1-based) within the curve; 0 past the buckets.
+fn at_cost(counts: &[f64], t: u32) -> f64 {
+    counts.get(t as usize - 1).map_or(0.0, |&c| c.max(0.0))
+}
+
+/// P(a card of cost `t` is among the cards seen by turn `t`, for every
+/// `t ≤ T`), by inclusion–exclusion over the subsets of turns that miss.
+pub fn curve_out(counts: &[f64], env: &Env) -> f64 {
+    let total: f64 = counts.iter().map(|c| c.max(0.0)).sum();
+    let turns = env.curve_turns.min(16);
+    let mut windows = Vec::with_capacity(turns as usize);
+    (0..1u32 << turns).fold(0.0, |acc, mask| {
+        windows.clear();
+        windows.extend((1..=turns).filter(|t| mask & (1 << (t - 1)) != 0).map(|t| (at_cost(counts, t), env.seen_by(t))));
+        let sign = if windows.len().is_multiple_of(2) { 1.0 } else { -1.0 };
+        acc + sign * miss_windows(total, &windows)
+    })
+}
+
+/// Average share of the deck that costs more than the turn over 1..=T.
+pub fn dead_rate(counts: &[f64], env: &Env) -> f64 {
+    let total: f64 = counts.iter().map(|c| c.max(0.0)).sum::<f64>().max(1e-12);
+    let turns = env.curve_turns.max(1);
+    let dead: f64 = (1..=turns).map(|t| 1.0 - castable_by(counts, t) / total).sum();
+    dead / f64::from(turns)
+}
+
+/// Cards costing `t` or less.
+fn castable_by(counts: &[f64], t: u32) -> f64 {
+    (1..=t).map(|c| at_cost(counts, c)).sum()
+}
+
+/// The hypergeometric draw math described in the module docs; hooks only
+/// act through `adjust_targets` (there is no income to scale). Implement
+/// [`SimModel`] for mulligans, cost reducers or ramp cards.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        _tgt: &Targets,
+        _hooks: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let total: f64 = th.counts.iter().map(|c| c.max(0.0)).sum();
+        let turns = 1..=env.curve_turns;
+        Obs {
+            deck_total: total,
+            on_curve: turns.clone().map(|t| 1.0 - miss_prob(total, at_cost(&th.counts, t), env.seen_by(t))).collect(),
+            whiff: turns.map(|t| miss_prob(total, castable_by(&th.counts, t), env.seen_by(t))).collect(),
+            curve_out: curve_out(&th.counts, env),
+            dead_rate: dead_rate(&th.counts, env),
+        }
+    }
+}
+
+fn mid((lo, hi): (f64, f64)) -> f64 {
+    0.5 * (lo + hi)
+}
+
+/// Split `counts` at the curve: cards costing 1..=T and the rest.
+fn split(counts: &[f64], turns: u32) -> (f64, f64) {
+    let low = counts.iter().take(turns as usize).map(|c| c.max(0.0)).sum();
+    let high = counts.iter().skip(turns as usize).map(|c| c.max(0.0)).sum();
+    (low, high)
+}
+
+/// The deck rescaled to `deck_size` with the low costs scaled by `f` and
+/// the high costs filling what is left.
+fn reshape(counts: &[f64], env: &Env, f: f64) -> Vec<f64> {
+    let turns = env.curve_turns as usize;
+    let (low, high) = split(counts, env.curve_turns);
+    let low_t = (f * low).min(env.deck_size);
+    let high_scale = if high > 0.0 { (env.deck_size - low_t) / high } else { 0.0 };
+    let low_scale = if high > 0.0 { low_t / low.max(1e-12) } else { env.deck_size / low.max(1e-12) };
+    counts.iter().enumerate().map(|(i, &c)| c.max(0.0) * if i < turns { low_scale } else { high_scale }).collect()
+}
+
+/// Low-cost total that puts curve-out at the band midpoint and the dead rate
+/// at or under its cap, keeping the deck at `deck_size`.
+pub fn low_cost_target(counts: &[f64], env: &Env, tgt: &Targets) -> f64 {
+    let (low, high) = split(counts, env.curve_turns);
+    if low <= 0.0 {
+        return 0.0;
+    }
+    let normal = reshape(counts, env, env.deck_size / (low + high));
+    let (low, _) = split(&normal, env.curve_turns);
+    let f_max = env.deck_size / low;
+    let want = mid(tgt.curve_out_band);
+    // curve-out rises with the low-cost share; bisect the scale
+    let (mut lo, mut hi) = (0.0, f_max);
+    if curve_out(&reshape(&normal, env, hi), env) <= want {
+        lo = hi;
+    } else {
+        for _ in 0..60 {
+            let f = 0.5 * (lo + hi);
+            if curve_out(&reshape(&normal, env, f), env) < want { lo = f } else { hi = f }
+        }
+    }
+    let turns = env.curve_turns.max(1);
+    let mean_castable = (1..=turns).map(|t| castable_by(&normal, t)).sum::<f64>() / f64::from(turns);
+    let f_dead = (1.0 - tgt.max_dead) * env.deck_size / mean_castable.max(1e-12);
+    lo.max(f_dead).min(f_max) * low
+}
+
+/// Unitless error vs targets: curve-out against the band midpoint, dead rate
+/// above its cap, and deck size.
+pub fn normalized_error(o: &Obs, env: &Env, tgt: &Targets) -> f64 {
+    control::pct_error(o.curve_out, mid(tgt.curve_out_band))
+        + (o.dead_rate - tgt.max_dead).max(0.0)
+        + control::pct_error(o.deck_total, env.deck_size)
+}
+
+/// Named residuals for [`Outcome::residuals`]; the dead cap counts as met
+/// anywhere below it.
+pub fn residuals(o: &Obs, env: &Env, tgt: &Targets) -> Vec<Residual> {
+    vec![
+        Residual::new("curve_out", o.curve_out, mid(tgt.curve_out_band)),
+        Residual::new("dead_rate", o.dead_rate, o.dead_rate.min(tgt.max_dead)),
+        Residual::new("deck_total", o.deck_total, env.deck_size),
+    ]
+}
+
+pub fn balance_ext(
+    theta0: Params,
+    env: Env,
+    tgt: Targets,
+    opts: Options<impl SimModel>,
+) -> Outcome<Params, Obs> {
+    let Options { bounds: b, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = vec![ControllerState::default(); theta0.counts.len()];
+    let turns = env.curve_turns;
+    balance_with_hooks(
+        theta0,
+        env,
+        tgt,
+        b,
+        g,
+        mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+        max_iters,
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, mechs),
+        // nominal: x = low-cost (1..=T) total, y = high-cost total
+        |th, env, tgt, _o| {
+            let low = low_cost_target(&th.counts, env, tgt);
+            NominalTargets { x: low, y: (env.deck_size - low).max(0.0), z: 0.0 }
+        },
+        // step: count_i → its share of its half's total, so the shape within
+        //       each half is kept
+        move |th, b, g, nom, adj| {
+            let (low_t, high_t) = (nom.x * adj.a, nom.y * adj.b);
+            let (low, high) = split(&th.counts, turns);
+
+            let st = &mut ctl_state;
+            st.resize(th.counts.len(), ControllerState::default());
+            let counts = th
+                .counts
+                .iter()
+                .enumerate()
+                .map(|(i, &c)| {
+                    let target = if i < turns as usize {
+                        c.max(0.0) * low_t / low.max(1e-12)
+                    } else {
+                        c.max(0.0) * high_t / high.max(1e-12)
+                    };
+                    let target = match reg.as_ref() {
+                        Some(r) => r.pull(target, |base| base.counts.get(i).copied().unwrap_or(target)),
+                        None => target,
+                    };
+                    controller.step(&mut st[i], c, target.clamp(b.count_min, b.count_max), g.k_count, b.count_min, b.count_max)
+                })
+                .collect();
+            Params { counts }
+        },
+        // converged: curve-out inside the band, dead rate no more than half
+        // a point above the cap and the deck within half a card of its size
+        move |o, tgt| {
+            Band::from(tgt.curve_out_band).contains(o.curve_out)
+                && o.dead_rate <= tgt.max_dead + 0.005
+                && within(o.deck_total, env.deck_size, AbsTol(0.5))
+        },
+    )
+    .with_residuals(|o| residuals(o, &env, &tgt))
+}
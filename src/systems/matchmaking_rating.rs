@@ -0,0 +1,283 @@
+//! Matchmaking rating: tune the Elo K-factor, the placement boost and the
+//! rating floor toward new-player settling and ladder winrate targets.
+//!
+//! Analytic logistic model, no sampling. A player rated `gap` above their
+//! true skill, matched against accurately rated opponents at their rating,
+//! wins with `1 / (1 + 10^(gap/400))`; each game moves the rating by
+//! `K · (result − 0.5)`. Tracking the mean and variance of the gap game by
+//! game gives the RMS rating error of a new player (K scaled by
+//! `placement_boost` for the first `placement_games`) and the steady-state
+//! rating noise. Net wins over a window are the window's rating change over
+//! `K`, which gives the spread of windowed winrates. Players whose skill is
+//! below the floor are pinned at it and lose more than half their games.
+
+use crate::error::{Checks, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::{self, ControllerState};
+use crate::systems::sdk::{balance_with_hooks, Hook, NominalTargets, Outcome, Residual};
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Params {
+    pub k_factor: f64,        // rating points at stake per game
+    pub placement_boost: f64, // K multiplier during placement games
+    pub rating_floor: f64,    // ratings never drop below this
+}
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        vec![self.k_factor, self.placement_boost, self.rating_floor]
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { k_factor: v[0], placement_boost: v[1], rating_floor: v[2] }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Env {
+    pub start_gap: f64,       // typical |true skill − starting rating| of a new player, e.g. 300
+    pub placement_games: f64, // games played at the boosted K
+    pub window_games: f64,    // games per ladder winrate sample, e.g. 100
+    pub skill_min: f64,       // true skill of the weakest ranked players
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Targets {
+    pub settle_rating: f64, // new players within this of true skill, e.g. 100
+    pub settle_games: f64,  // … after this many games, e.g. 20
+    pub winrate_lo: f64,    // ladder winrates at least this, e.g. 0.45
+    pub winrate_hi: f64,    // … and at most this, e.g. 0.55
+}
+
+crate::define_system! {
+    bounds {
+        k_factor: k_min..k_max = (1.0, 400.0),
+        placement_boost: boost_min..boost_max = (1.0, MAX_BOOST),
+        rating_floor: floor_min..floor_max = (-1e6, 1e6),
+    }
+    gains { k_k = 0.5, k_boost = 0.5, k_floor = 0.6 }
+}
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .non_negative("start_gap", self.start_gap)
+            .non_negative("placement_games", self.placement_games)
+            .check("window_games", self.window_games >= 1.0, "must be at least 1")
+            .check("skill_min", self.skill_min.is_finite(), "must be finite")
+            .done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .positive("settle_rating", self.settle_rating)
+            .positive("settle_games", self.settle_games)
+            .check("winrate_lo", self.winrate_lo > 0.0 && self.winrate_lo < 0.5, "must be in (0, 0.5)")
+            .check("winrate_hi", self.winrate_hi > 0.5 && self.winrate_hi < 1.0, "must be in (0.5, 1)")
+            .done()
+    }
+}
+
+/// Ladder winrates count as "within the band" at ± this many standard
+/// deviations (≈95% of players).
+pub const BAND_Z: f64 = 2.0;
+/// Largest placement boost the nominal search considers.
+pub const MAX_BOOST: f64 = 10.0;
+/// Games simulated when looking for the settling point.
+pub const MAX_GAMES: usize = 500;
+
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obs {
+    pub games_to_settle: f64, // games until RMS rating error ≤ settle_rating (MAX_GAMES if never)
+    pub rating_noise: f64,    // steady-state sd of rating − true skill
+    pub winrate_sd: f64,      // sd of winrate over window_games
+    pub winrate_lo: f64,      // 0.5 − BAND_Z · winrate_sd
+    pub winrate_hi: f64,      // 0.5 + BAND_Z · winrate_sd
+    pub floor_winrate: f64,   // winrate of a skill_min player pinned at the floor
+}
+
+/// P(win) for a player rated `gap` above their true skill.
+pub fn win_chance(gap: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(gap / 400.0))
+}
+
+/// RMS rating error after each of the first `games` games for a player who
+/// starts `env.start_gap` below their true skill.
+pub fn rms_error_path(th: &Params, env: &Env, games: usize) -> Vec<f64> {
+    let mut gap = GapMoments::new(env);
+    (0..games).map(|n| gap.play(th, env, n)).collect()
+}
+
+/// Games until the RMS error first drops to `band`, interpolated between
+/// games ([`MAX_GAMES`] if it never does).
+pub fn games_to_settle(th: &Params, env: &Env, band: f64) -> f64 {
+    let mut gap = GapMoments::new(env);
+    let mut prev = env.start_gap;
+    for n in 0..MAX_GAMES {
+        let e = gap.play(th, env, n);
+        if e <= band {
+            let frac = if prev > e { (prev - band) / (prev - e) } else { 1.0 };
+            return n as f64 + frac.clamp(0.0, 1.0);
+        }
+        prev = e;
+    }
+    MAX_GAMES as f64
+}
+
+/// Mean and variance of rating − true skill, linearized game by game.
+struct GapMoments {
+    mean: f64,
+    var: f64,
+}
+
+impl GapMoments {
+    fn new(env: &Env) -> Self {
+        Self { mean: -env.start_gap, var: 0.0 }
+    }
+
+    /// Play game `n` (0-based) and return the RMS error after it.
+    fn play(&mut self, th: &Params, env: &Env, n: usize) -> f64 {
+        let k = if (n as f64) < env.placement_games { th.k_factor * th.placement_boost } else { th.k_factor };
+        let p = win_chance(self.mean);
+        let rho = 1.0 - k * 10f64.ln() / 400.0 * p * (1.0 - p);
+        self.mean += k * (p - 0.5);
+        self.var = rho * rho * self.var + k * k * p * (1.0 - p);
+        (self.mean * self.mean + self.var).sqrt()
+    }
+}
+
+/// Steady-state sd of rating − true skill at `k` (infinite once K is too
+/// large for the rating to settle).
+pub fn rating_noise(k: f64) -> f64 {
+    let rho = 1.0 - k * 10f64.ln() / 1600.0;
+    if rho.abs() >= 1.0 { f64::INFINITY } else { (k * k / 4.0 / (1.0 - rho * rho)).sqrt() }
+}
+
+/// Sd of a settled player's winrate over `window` games at `k`.
+pub fn winrate_sd(k: f64, window: f64) -> f64 {
+    let rho = 1.0 - k * 10f64.ln() / 1600.0;
+    let noise = rating_noise(k);
+    (2.0 * noise * noise * (1.0 - rho.max(0.0).powf(window))).sqrt() / (k * window).max(1e-9)
+}
+
+/// `x` in `[lo, hi]` where decreasing `f(x)` meets `want` (an end if it
+/// never does).
+fn solve_decreasing(lo: f64, hi: f64, want: f64, f: impl Fn(f64) -> f64) -> f64 {
+    let (mut lo, mut hi) = (lo, hi);
+    for _ in 0..60 {
+        let mid = 0.5 * (lo + hi);
+        if f(mid) > want { lo = mid } else { hi = mid }
+    }
+    0.5 * (lo + hi)
+}
+
+/// The logistic model described in the module docs. Implement [`SimModel`]
+/// for Glicko-style per-player uncertainty or replayed match logs.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        tgt: &Targets,
+        _hooks: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let sd = winrate_sd(th.k_factor, env.window_games);
+        Obs {
+            games_to_settle: games_to_settle(th, env, tgt.settle_rating),
+            rating_noise: rating_noise(th.k_factor),
+            winrate_sd: sd,
+            winrate_lo: 0.5 - BAND_Z * sd,
+            winrate_hi: 0.5 + BAND_Z * sd,
+            floor_winrate: win_chance((th.rating_floor - env.skill_min).max(0.0)),
+        }
+    }
+}
+
+/// Unitless error vs targets: settling overrun plus how far the ladder and
+/// floor winrates fall outside the band.
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    control::pct_error(o.games_to_settle.max(tgt.settle_games), tgt.settle_games)
+        + (tgt.winrate_lo - o.winrate_lo).max(0.0)
+        + (o.winrate_hi - tgt.winrate_hi).max(0.0)
+        + (tgt.winrate_lo - o.floor_winrate).max(0.0)
+}
+
+/// Named residuals for [`Outcome::residuals`].
+pub fn residuals(o: &Obs, tgt: &Targets) -> Vec<Residual> {
+    vec![
+        Residual::new("games_to_settle", o.games_to_settle, o.games_to_settle.min(tgt.settle_games)),
+        Residual::new("winrate_lo", o.winrate_lo, o.winrate_lo.max(tgt.winrate_lo)),
+        Residual::new("winrate_hi", o.winrate_hi, o.winrate_hi.min(tgt.winrate_hi)),
+        Residual::new("floor_winrate", o.floor_winrate, o.floor_winrate.max(tgt.winrate_lo)),
+    ]
+}
+
+pub fn balance_ext(
+    theta0: Params,
+    env: Env,
+    tgt: Targets,
+    opts: Options<impl SimModel>,
+) -> Outcome<Params, Obs> {
+    let Options { bounds: b, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = [ControllerState::default(); 3];
+    balance_with_hooks(
+        theta0,
+        env,
+        tgt,
+        b,
+        g,
+        mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+        max_iters,
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, mechs),
+        // nominal: x = the smallest K that keeps ladder winrates in the band
+        // (least rating noise), y = the smallest boost that settles new
+        // players in time at that K, z = the highest floor the weakest
+        // players still win `winrate_lo` at
+        |th, env, tgt, _o| {
+            let half = (0.5 - tgt.winrate_lo).min(tgt.winrate_hi - 0.5);
+            let k = solve_decreasing(1.0, 400.0, half / BAND_Z, |k| winrate_sd(k, env.window_games));
+            let settle = |boost: f64| games_to_settle(&Params { k_factor: k, placement_boost: boost, ..*th }, env, tgt.settle_rating);
+            // games_to_settle falls, then rises once placement noise dominates:
+            // search the falling branch only.
+            let fastest = (0..=36).map(|i| 1.0 + i as f64 * (MAX_BOOST - 1.0) / 36.0).fold(1.0, |best, b| {
+                if settle(b) < settle(best) { b } else { best }
+            });
+            let boost = solve_decreasing(1.0, fastest, tgt.settle_games, settle);
+            let floor = env.skill_min + 400.0 * ((1.0 - tgt.winrate_lo) / tgt.winrate_lo).log10();
+            NominalTargets { x: k, y: boost, z: floor }
+        },
+        move |th, b, g, nom, adj| {
+            let (k_t, boost_t, floor_t) = (nom.x * adj.a, nom.y * adj.b, nom.z * adj.c);
+
+            let (k_t, boost_t, floor_t) = match reg {
+                Some(r) => (
+                    r.pull(k_t, |p| p.k_factor),
+                    r.pull(boost_t, |p| p.placement_boost),
+                    r.pull(floor_t, |p| p.rating_floor),
+                ),
+                None => (k_t, boost_t, floor_t),
+            };
+
+            let st = &mut ctl_state;
+            Params {
+                k_factor: controller.step(&mut st[0], th.k_factor, k_t, g.k_k, b.k_min, b.k_max),
+                placement_boost: controller.step(&mut st[1], th.placement_boost, boost_t, g.k_boost, b.boost_min, b.boost_max),
+                rating_floor: controller.step(&mut st[2], th.rating_floor, floor_t, g.k_floor, b.floor_min, b.floor_max),
+            }
+        },
+        // converged: new players settle in time and every winrate, the floor's
+        // included, sits inside the band (half a point of slack)
+        |o, tgt| {
+            o.games_to_settle <= tgt.settle_games + 0.5
+                && o.winrate_lo >= tgt.winrate_lo - 0.005
+                && o.winrate_hi <= tgt.winrate_hi + 0.005
+                && o.floor_winrate >= tgt.winrate_lo - 0.005
+        },
+    )
+    .with_residuals(|o| residuals(o, &tgt))
+}
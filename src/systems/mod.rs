@@ -1,6 +1,26 @@
 pub mod sdk;
+mod define_system;
 #[cfg(feature="system-production_spend")]   pub mod production_spend;
 #[cfg(feature="system-upgrade_cost_curve")] pub mod upgrade_cost_curve;
 #[cfg(feature="system-reset_prestige")]     pub mod reset_prestige;
 #[cfg(feature="system-offline_accumulation")] pub mod offline_accumulation;
 #[cfg(feature="system-draft_choice")] pub mod draft_choice;
+#[cfg(feature="system-shop_pricing")] pub mod shop_pricing;
+#[cfg(feature="system-gacha_rates")] pub mod gacha_rates;
+#[cfg(feature="system-loot_table")] pub mod loot_table;
+#[cfg(feature="system-energy_regen")] pub mod energy_regen;
+#[cfg(feature="system-matchmaking_rating")] pub mod matchmaking_rating;
+#[cfg(feature="system-combat_ttk")] pub mod combat_ttk;
+#[cfg(feature="system-difficulty_curve")] pub mod difficulty_curve;
+#[cfg(feature="system-xp_curve")] pub mod xp_curve;
+#[cfg(feature="system-crafting_chain")] pub mod crafting_chain;
+#[cfg(feature="system-rewarded_ad")] pub mod rewarded_ad;
+#[cfg(feature="system-battle_pass")] pub mod battle_pass;
+#[cfg(feature="system-daily_quest")] pub mod daily_quest;
+#[cfg(feature="system-wave_pressure")] pub mod wave_pressure;
+#[cfg(feature="system-mana_curve")] pub mod mana_curve;
+#[cfg(feature="system-autobattler_economy")] pub mod autobattler_economy;
+#[cfg(feature="system-relic_power_budget")] pub mod relic_power_budget;
+#[cfg(feature="system-boss_dps_check")] pub mod boss_dps_check;
+#[cfg(feature="system-skill_rotation")] pub mod skill_rotation;
+#[cfg(feature="system-armor_mitigation")] pub mod armor_mitigation;
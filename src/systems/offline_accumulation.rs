@@ -1,12 +1,25 @@
-use crate::mechanics::control;
-use crate::systems::sdk::{Hook, NominalTargets, Outcome, balance_with_hooks};
+use crate::error::{check_range, Checks, Error, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::{self, ControllerState};
+use crate::systems::sdk::{AbsTol, Hook, NominalTargets, Outcome, Residual, balance_with_hooks, within};
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Params {
     pub cap_minutes: f64,
     pub decay: f64,
     pub efficiency: f64,
 }
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        vec![self.cap_minutes, self.decay, self.efficiency]
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { cap_minutes: v[0], decay: v[1], efficiency: v[2] }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Env {
     pub typical_afk_minutes: f64,
@@ -15,60 +28,105 @@ pub struct Env {
 pub struct Targets {
     pub retain_ratio: f64, /* target offline/online income ratio for typical AFK */
 }
-#[derive(Clone, Copy, Debug)]
-pub struct Bounds {
-    pub cmin: f64,
-    cmax: f64,
-    dmin: f64,
-    dmax: f64,
-    emin: f64,
-    emax: f64,
+crate::define_system! {
+    bounds {
+        cap_minutes: cmin..cmax = (10.0, 72.0 * 60.0),
+        decay: dmin..dmax = (0.0, 0.1),
+        efficiency: emin..emax = (0.0, 1.0),
+    }
+    gains { k_c = 0.6, k_d = 0.4, k_e = 0.6 }
 }
+
 impl Bounds {
-    pub fn soft() -> Self {
-        Self {
-            cmin: 10.0,
-            cmax: 72.0 * 60.0,
-            dmin: 0.0,
-            dmax: 0.1,
-            emin: 0.0,
-            emax: 1.0,
-        }
+    /// Validated bounds: every pair must be finite with min ≤ max.
+    pub fn new(cmin: f64, cmax: f64, dmin: f64, dmax: f64, emin: f64, emax: f64) -> Result<Self, Error> {
+        check_range("cap_minutes", cmin, cmax)?;
+        check_range("decay", dmin, dmax)?;
+        check_range("efficiency", emin, emax)?;
+        Ok(Self {
+            cmin,
+            cmax,
+            dmin,
+            dmax,
+            emin,
+            emax,
+        })
     }
 }
-#[derive(Clone, Copy, Debug)]
-pub struct Gains {
-    pub k_c: f64,
-    k_d: f64,
-    k_e: f64,
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default().non_negative("typical_afk_minutes", self.typical_afk_minutes).done()
+    }
 }
-impl Default for Gains {
-    fn default() -> Self {
-        Self {
-            k_c: 0.6,
-            k_d: 0.4,
-            k_e: 0.6,
-        }
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default().within("retain_ratio", self.retain_ratio, 0.0, 1.0).done()
     }
 }
 
 #[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Obs {
     pub retain: f64,
 }
 
-pub trait Mechanic: Hook<Params, Env, Targets, Obs> {}
-impl<T: Hook<Params, Env, Targets, Obs>> Mechanic for T {}
+/// Offline/online income ratio after `afk_minutes` away.
+pub fn retain(th: &Params, afk_minutes: f64) -> f64 {
+    let effective = th.efficiency * (1.0 - th.decay).powf(afk_minutes / th.cap_minutes.max(1.0));
+    effective.clamp(0.0, 1.0)
+}
+
+/// AFK income over `afk_minutes`, in minutes of online income: [`retain`]
+/// integrated over the span, which stops accruing at `cap_minutes`.
+pub fn retained_minutes(th: &Params, afk_minutes: f64) -> f64 {
+    let t = afk_minutes.clamp(0.0, th.cap_minutes.max(0.0));
+    if t <= 0.0 {
+        return 0.0;
+    }
+    let eff = th.efficiency.clamp(0.0, 1.0);
+    // retain(t) = eff · e^(rate · t), rate ≤ 0 per minute
+    let rate = (1.0 - th.decay.clamp(0.0, 1.0)).ln() / th.cap_minutes.max(1.0);
+    if rate == 0.0 { eff * t } else { eff * (rate * t).exp_m1() / rate }
+}
+
+/// The default AFK math: [`retain`] at `typical_afk_minutes`. Implement
+/// [`SimModel`] for a different AFK curve.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        _tgt: &Targets,
+        _hooks: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        Obs {
+            retain: retain(th, env.typical_afk_minutes),
+        }
+    }
+}
+
+/// Unitless error vs targets: [`pct_error`](control::pct_error) of the retain ratio.
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    control::pct_error(o.retain, tgt.retain_ratio)
+}
+
+/// Named retain-ratio residual for [`Outcome::residuals`].
+pub fn residuals(o: &Obs, tgt: &Targets) -> Vec<Residual> {
+    vec![Residual::new("retain_ratio", o.retain, tgt.retain_ratio)]
+}
 
 pub fn balance_ext(
     theta0: Params,
     env: Env,
     tgt: Targets,
-    b: Bounds,
-    g: Gains,
-    mechs: Vec<Box<dyn Mechanic>>,
-    max_iters: usize,
+    opts: Options<impl SimModel>,
 ) -> Outcome<Params, Obs> {
+    let Options { bounds: b, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = [ControllerState::default(); 3];
     balance_with_hooks(
         theta0,
         env,
@@ -80,38 +138,45 @@ pub fn balance_ext(
             .map(|m| m as Box<dyn Hook<_, _, _, _>>)
             .collect(),
         max_iters,
-        move |th, env, _tgt, _mechs| {
-            let t = env.typical_afk_minutes;
-            let effective = th.efficiency * (1.0 - th.decay).powf(t / th.cap_minutes.max(1.0));
-            Obs {
-                retain: effective.clamp(0.0, 1.0),
-            }
-        },
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, mechs),
         |th, _env, tgt, _o| NominalTargets {
             x: tgt.retain_ratio,
             y: th.cap_minutes,
             z: th.decay,
         },
-        |th, b, g, nom, _adj| {
+        move |th, b, g, nom, _adj| {
             let efficiency_t = nom.x;
             let cap_t = th.cap_minutes;
             let decay_t = th.decay;
 
-            let cap_minutes = control::approach(
+            let (efficiency_t, cap_t, decay_t) = match reg {
+                Some(r) => (
+                    r.pull(efficiency_t, |p| p.efficiency),
+                    r.pull(cap_t, |p| p.cap_minutes),
+                    r.pull(decay_t, |p| p.decay),
+                ),
+                None => (efficiency_t, cap_t, decay_t),
+            };
+
+            let st = &mut ctl_state;
+            let cap_minutes = controller.step(
+                &mut st[0],
                 th.cap_minutes,
                 cap_t.clamp(b.cmin, b.cmax),
                 g.k_c,
                 b.cmin,
                 b.cmax,
             );
-            let decay = control::approach(
+            let decay = controller.step(
+                &mut st[1],
                 th.decay,
                 decay_t.clamp(b.dmin, b.dmax),
                 g.k_d,
                 b.dmin,
                 b.dmax,
             );
-            let efficiency = control::approach(
+            let efficiency = controller.step(
+                &mut st[2],
                 th.efficiency,
                 efficiency_t.clamp(b.emin, b.emax),
                 g.k_e,
@@ -125,6 +190,7 @@ pub fn balance_ext(
                 efficiency,
             }
         },
-        |o, tgt| (o.retain - tgt.retain_ratio).abs() <= 0.02,
+        |o, tgt| within(o.retain, tgt.retain_ratio, AbsTol(0.02)),
     )
+    .with_residuals(|o| residuals(o, &tgt))
 }
@@ -1,12 +1,31 @@
-use crate::mechanics::{actions, control};
-use crate::systems::sdk::{Hook, NominalTargets, Outcome, balance_with_hooks};
+use std::fmt;
+
+use crate::error::{check_range, Checks, Error, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::actions;
+use crate::mechanics::control::{self, Controller, ControllerState};
+use crate::systems::sdk::{
+    AbsTol, BalanceArena, ConvergenceScore, Hook, NominalTargets, Outcome, Regularization, Residual, RelTol, Tolerance,
+    UpdateOrder, compose_cost, compose_income, within,
+};
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Params {
     pub gen_per_sec: f64,
     pub spend_rate: f64,
     pub multiplier: f64,
 }
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        vec![self.gen_per_sec, self.spend_rate, self.multiplier]
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { gen_per_sec: v[0], spend_rate: v[1], multiplier: v[2] }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Env {
     pub upgrade_cost_base: f64,
@@ -20,81 +39,401 @@ pub struct Targets {
     pub ttu_target: f64,
     pub util_target: f64,
     pub growth_target: f64,
+    pub weights: TargetWeights,
 }
+/// Relative importance of each target when they cannot all be met
+/// (used by `Controller::WeightedLeastSquares`).
 #[derive(Clone, Copy, Debug)]
-pub struct Bounds {
-    pub gen_min: f64,
-    pub gen_max: f64,
-    pub spd_min: f64,
-    pub spd_max: f64,
-    pub mul_min: f64,
-    pub mul_max: f64,
+pub struct TargetWeights {
+    pub ttu: f64,
+    pub util: f64,
+    pub growth: f64,
 }
-impl Bounds {
-    pub fn soft_defaults() -> Self {
+impl Default for TargetWeights {
+    fn default() -> Self {
         Self {
-            gen_min: 0.01,
-            gen_max: 1e6,
-            spd_min: 0.0,
-            spd_max: 1e9,
-            mul_min: 0.1,
-            mul_max: 1e6,
+            ttu: 1.0,
+            util: 1.0,
+            growth: 1.0,
         }
     }
 }
-#[derive(Clone, Copy, Debug)]
-pub struct Gains {
-    pub k_ttu: f64,
-    pub k_util: f64,
-    pub k_grow: f64,
+crate::define_system! {
+    bounds {
+        gen: gen_min..gen_max = (0.01, 1e6),
+        spd: spd_min..spd_max = (0.0, 1e9),
+        mul: mul_min..mul_max = (0.1, 1e6),
+    }
+    gains { k_ttu = 0.6, k_util = 0.6, k_grow = 0.5 }
+    options {
+        /// Jacobi or Gauss–Seidel update of the multiplier and generator.
+        order: UpdateOrder = UpdateOrder::default(),
+    }
 }
 
-impl Default for Gains {
-    fn default() -> Self {
-        Self {
-            k_ttu: 0.6,
-            k_util: 0.6,
-            k_grow: 0.5,
-        }
+impl Bounds {
+    /// Validated bounds: every pair must be finite with min ≤ max.
+    pub fn new(
+        gen_min: f64,
+        gen_max: f64,
+        spd_min: f64,
+        spd_max: f64,
+        mul_min: f64,
+        mul_max: f64,
+    ) -> Result<Self, Error> {
+        check_range("gen", gen_min, gen_max)?;
+        check_range("spd", spd_min, spd_max)?;
+        check_range("mul", mul_min, mul_max)?;
+        Ok(Self {
+            gen_min,
+            gen_max,
+            spd_min,
+            spd_max,
+            mul_min,
+            mul_max,
+        })
+    }
+    /// Same as [`Bounds::soft`].
+    pub fn soft_defaults() -> Self {
+        Self::soft()
+    }
+}
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .positive("upgrade_cost_base", self.upgrade_cost_base)
+            .positive("upgrade_cost_growth", self.upgrade_cost_growth)
+            .non_negative("gain_per_level", self.gain_per_level)
+            .within("leak", self.leak, 0.0, 1.0)
+            .positive("storage_cap", self.storage_cap)
+            .done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .positive("ttu_target", self.ttu_target)
+            .check("util_target", self.util_target > 0.0 && self.util_target <= 1.0, "must be in (0, 1]: spend cannot exceed income")
+            .positive("growth_target", self.growth_target)
+            .non_negative("weights.ttu", self.weights.ttu)
+            .non_negative("weights.util", self.weights.util)
+            .non_negative("weights.growth", self.weights.growth)
+            .done()
     }
 }
 #[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Obs {
     pub ttu: f64,
     pub util: f64,
     pub growth: f64,
     pub surplus: f64,
+    /// Income after hooks; spend is `income − surplus`. Absent (0) in
+    /// payloads written before it existed.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub income: f64,
+}
+
+/// Simulated TTU saturates here (one day), so targets above it are unreachable.
+pub const TTU_CAP_SECS: f64 = 86_400.0;
+
+/// A target (or target pair) the model equations cannot satisfy at once.
+/// Found by [`diagnose_conflicts`]; more iterations will not fix these.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Conflict {
+    /// util = spend / income lies in `[0, 1]` (spend never exceeds income).
+    UtilOutOfRange { util_target: f64 },
+    /// util ≥ 1 leaves no savings, so TTU pins at [`TTU_CAP_SECS`].
+    UtilVsTtu { util_target: f64, ttu_target: f64 },
+    /// The TTU target is above what the simulation can report.
+    TtuAboveCap { ttu_target: f64 },
+    /// At util u, growth = mult · (2 − u); hitting the growth target needs a
+    /// multiplier outside the soft bounds.
+    GrowthVsUtil { growth_target: f64, util_target: f64, mult_needed: f64 },
+    /// The multiplier implied by the growth target sets the upgrade cost; the
+    /// generator rate needed to afford it within the TTU target is out of bounds.
+    GrowthVsTtu { growth_target: f64, ttu_target: f64, gen_needed: f64 },
+}
+
+impl Conflict {
+    /// Names of the targets involved (a single-target issue repeats it).
+    pub fn fields(&self) -> (&'static str, &'static str) {
+        match self {
+            Conflict::UtilOutOfRange { .. } => ("util_target", "util_target"),
+            Conflict::UtilVsTtu { .. } => ("util_target", "ttu_target"),
+            Conflict::TtuAboveCap { .. } => ("ttu_target", "ttu_target"),
+            Conflict::GrowthVsUtil { .. } => ("growth_target", "util_target"),
+            Conflict::GrowthVsTtu { .. } => ("growth_target", "ttu_target"),
+        }
+    }
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Conflict::UtilOutOfRange { util_target } => {
+                write!(f, "util_target {util_target} is outside [0, 1]")
+            }
+            Conflict::UtilVsTtu { util_target, ttu_target } => write!(
+                f,
+                "util_target {util_target} leaves no savings, so ttu_target {ttu_target} s is unreachable"
+            ),
+            Conflict::TtuAboveCap { ttu_target } => {
+                write!(f, "ttu_target {ttu_target} s exceeds the {TTU_CAP_SECS} s TTU cap")
+            }
+            Conflict::GrowthVsUtil { growth_target, util_target, mult_needed } => write!(
+                f,
+                "growth_target {growth_target} at util_target {util_target} needs multiplier {mult_needed} (out of bounds)"
+            ),
+            Conflict::GrowthVsTtu { growth_target, ttu_target, gen_needed } => write!(
+                f,
+                "growth_target {growth_target} with ttu_target {ttu_target} s needs gen_per_sec {gen_needed} (out of bounds)"
+            ),
+        }
+    }
+}
+
+/// Check the targets against the model's steady-state equations (no loop).
+///
+/// At the targets, util = u gives savings `income · (1 − u)`, growth =
+/// `mult · (2 − u)`, and TTU = `cost(mult) / savings`. Solving these for
+/// `mult` and `gen_per_sec` and comparing with [`Bounds::soft_defaults`]
+/// exposes targets that contradict each other. An empty result means no
+/// known incompatibility, not a convergence guarantee.
+pub fn diagnose_conflicts(env: &Env, tgt: &Targets) -> Vec<Conflict> {
+    let mut out = Vec::new();
+    let u = tgt.util_target;
+    if !(0.0..=1.0).contains(&u) {
+        out.push(Conflict::UtilOutOfRange { util_target: u });
+        return out;
+    }
+    if tgt.ttu_target > TTU_CAP_SECS {
+        out.push(Conflict::TtuAboveCap { ttu_target: tgt.ttu_target });
+    }
+    if u >= 1.0 {
+        out.push(Conflict::UtilVsTtu { util_target: u, ttu_target: tgt.ttu_target });
+        return out;
+    }
+
+    let bnd = Bounds::soft_defaults();
+    let (mult, income) = steady_state(env, tgt);
+    if !(bnd.mul_min..=bnd.mul_max).contains(&mult) {
+        out.push(Conflict::GrowthVsUtil { growth_target: tgt.growth_target, util_target: u, mult_needed: mult });
+        return out;
+    }
+
+    let gen_needed = income / mult;
+    if !(bnd.gen_min..=bnd.gen_max).contains(&gen_needed) {
+        out.push(Conflict::GrowthVsTtu { growth_target: tgt.growth_target, ttu_target: tgt.ttu_target, gen_needed });
+    }
+    out
+}
+
+/// Closed-form steady state at the targets: `(mult*, income*)` with
+/// `mult* = growth / (2 − util)` and `income* = cost(mult*) / (ttu · (1 − util))`.
+fn steady_state(env: &Env, tgt: &Targets) -> (f64, f64) {
+    let u = tgt.util_target.clamp(0.0, 1.0 - 1e-6);
+    let mult = tgt.growth_target / (2.0 - u);
+    let lvl = (mult / env.gain_per_level).max(0.0);
+    let cost_next = env.upgrade_cost_base * env.upgrade_cost_growth.powf(lvl);
+    let income = cost_next / (tgt.ttu_target.max(1e-6) * (1.0 - u));
+    (mult, income)
+}
+
+/// Analytic starting θ: inverts the util, growth and TTU equations at the
+/// targets (see [`diagnose_conflicts`]) and clamps into the soft bounds.
+///
+/// Much closer to the fixed point than the generic `{10, 10, 1}` seed, so
+/// `balance_ext` needs far fewer iterations; `balance_quick` starts here.
+pub fn steady_state_seed(env: &Env, tgt: &Targets) -> Params {
+    let bnd = Bounds::soft_defaults();
+    let (mult, income) = steady_state(env, tgt);
+    if !(mult.is_finite() && income.is_finite()) {
+        return Params { gen_per_sec: 10.0, spend_rate: 10.0, multiplier: 1.0 };
+    }
+    let multiplier = mult.clamp(bnd.mul_min, bnd.mul_max);
+    Params {
+        gen_per_sec: (income / multiplier).clamp(bnd.gen_min, bnd.gen_max),
+        spend_rate: (tgt.util_target.clamp(0.0, 1.0) * income).clamp(bnd.spd_min, bnd.spd_max),
+        multiplier,
+    }
+}
+
+/// The default production/spend math (income, util, TTU, growth). Implement
+/// [`SimModel`] for a more detailed income simulation; hooks'
+/// `cost_multiplier` arrives already folded into `env.upgrade_cost_base`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        tgt: &Targets,
+        hooks: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let income = compose_income((th.gen_per_sec * th.multiplier).max(0.0), hooks, th, env);
+        let cap = actions::econ_cap(income, 1.0);
+        let spend = (th.spend_rate.min(income) * cap).clamp(0.0, income);
+        let surplus = income - spend;
+
+        let lvl = (th.multiplier / env.gain_per_level).max(0.0);
+        let cost_next = env.upgrade_cost_base * env.upgrade_cost_growth.powf(lvl);
+
+        let util = if income > 0.0 {
+            (spend / income).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let save_floor: f64 = (1.0 - tgt.util_target).clamp(0.0, 1.0);
+        let eff_save = (income - spend).max(income * save_floor).max(1e-9);
+        let ttu = (cost_next / eff_save).clamp(0.0, TTU_CAP_SECS);
+
+        let growth = if income > 0.0 {
+            th.multiplier * (1.0 + (surplus.max(0.0) / income))
+        } else {
+            th.multiplier
+        };
+
+        Obs {
+            ttu,
+            util,
+            growth,
+            surplus,
+            income,
+        }
+    }
+}
+
+/// Weighted relative residuals √w·(obs/target − 1) for TTU, util, growth.
+fn weighted_residuals(o: &Obs, tgt: &Targets) -> [f64; 3] {
+    let w = tgt.weights;
+    [
+        w.ttu.max(0.0).sqrt() * (o.ttu / tgt.ttu_target.max(1e-9) - 1.0),
+        w.util.max(0.0).sqrt() * (o.util / tgt.util_target.max(1e-9) - 1.0),
+        w.growth.max(0.0).sqrt() * (o.growth / tgt.growth_target.max(1e-9) - 1.0),
+    ]
+}
+
+/// Weighted least-squares objective ½·Σ wᵢ·(obsᵢ/targetᵢ − 1)², the
+/// quantity `Controller::WeightedLeastSquares` descends.
+pub fn weighted_objective(o: &Obs, tgt: &Targets) -> f64 {
+    0.5 * weighted_residuals(o, tgt).iter().map(|r| r * r).sum::<f64>()
+}
+
+/// One gradient step on [`weighted_objective`] in log-parameter space.
+///
+/// The gradient is a forward difference of `model`; the step is halved
+/// until the objective decreases (or θ stays put), so the loop settles at
+/// a compromise instead of bouncing. Probes run without hooks (they are
+/// stateful and cannot be replayed). Regularization adds
+/// `λ/2·‖ln θ − ln baseline‖²`.
+fn least_squares_step<M: SimModel>(
+    model: &M,
+    th: &Params,
+    env: &Env,
+    tgt: &Targets,
+    bnd: &Bounds,
+    g: &Gains,
+    reg: Option<Regularization<Params>>,
+) -> Params {
+    const H: f64 = 1e-4;
+    let pack = |p: &Params| [p.gen_per_sec, p.spend_rate, p.multiplier];
+    let unpack = |v: [f64; 3]| Params { gen_per_sec: v[0], spend_rate: v[1], multiplier: v[2] };
+    let lo = [bnd.gen_min, bnd.spd_min, bnd.mul_min];
+    let hi = [bnd.gen_max, bnd.spd_max, bnd.mul_max];
+    let gains = [g.k_ttu, g.k_util, g.k_grow];
+
+    let objective = |v: [f64; 3]| {
+        let mut f = weighted_objective(&model.observe(&unpack(v), env, tgt, &mut []), tgt);
+        if let Some(reg) = reg {
+            let b = pack(&reg.baseline);
+            let d2: f64 = (0..3).map(|j| (v[j].max(1e-9) / b[j].max(1e-9)).ln().powi(2)).sum();
+            f += 0.5 * reg.lambda.max(0.0) * d2;
+        }
+        f
+    };
+
+    let p = pack(th).map(|x| x.max(1e-9));
+    let f0 = objective(p);
+    let mut step = [0.0; 3];
+    for j in 0..3 {
+        let mut q = p;
+        q[j] *= H.exp();
+        let grad = (objective(q) - f0) / H;
+        if grad.is_finite() {
+            step[j] = -(gains[j] * grad).clamp(-0.5, 0.5);
+        }
+    }
+
+    let mut scale = 1.0;
+    for _ in 0..20 {
+        let cand: [f64; 3] = std::array::from_fn(|j| (p[j] * (scale * step[j]).exp()).clamp(lo[j], hi[j]));
+        if objective(cand) < f0 {
+            return unpack(cand);
+        }
+        scale *= 0.5;
+    }
+    *th
 }
 
-pub trait Mechanic: Hook<Params, Env, Targets, Obs> {}
-impl<T: Hook<Params, Env, Targets, Obs>> Mechanic for T {}
+/// Unitless error vs targets: mean [`pct_error`](control::pct_error) of
+/// TTU, utilization and growth (0 = on target; comparable across systems).
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    (control::pct_error(o.ttu, tgt.ttu_target)
+        + control::pct_error(o.util, tgt.util_target)
+        + control::pct_error(o.growth, tgt.growth_target))
+        / 3.0
+}
+
+/// Named TTU, util and growth residuals for [`Outcome::residuals`].
+pub fn residuals(o: &Obs, tgt: &Targets) -> Vec<Residual> {
+    vec![
+        Residual::new("ttu", o.ttu, tgt.ttu_target),
+        Residual::new("util", o.util, tgt.util_target),
+        Residual::new("growth", o.growth, tgt.growth_target),
+    ]
+}
+
+/// TTU, util and growth as a weighted [`ConvergenceScore`]: residuals
+/// `obs / target − 1`, weights from `tgt.weights`. Use with
+/// `sdk::balance_scored`, or to see which target a run is missing.
+pub fn convergence_score(o: &Obs, tgt: &Targets, threshold: f64) -> ConvergenceScore {
+    let rel = |x: f64, t: f64| x / t.max(1e-9) - 1.0;
+    ConvergenceScore::new(threshold)
+        .with("ttu", rel(o.ttu, tgt.ttu_target), tgt.weights.ttu)
+        .with("util", rel(o.util, tgt.util_target), tgt.weights.util)
+        .with("growth", rel(o.growth, tgt.growth_target), tgt.weights.growth)
+}
 
 pub fn balance_quick(env: Env, tgt: Targets) -> Outcome<Params, Obs> {
-    balance_ext(
-        Params {
-            gen_per_sec: 10.0,
-            spend_rate: 10.0,
-            multiplier: 1.0,
-        },
-        env,
-        tgt,
-        Bounds::soft_defaults(),
-        Gains::default(),
-        Vec::new(),
-        120_000,
-    )
+    Runner::new(steady_state_seed(&env, &tgt), env, tgt).run()
+}
+
+pub fn balance_ext(theta0: Params, env: Env, tgt: Targets, opts: Options<impl SimModel>) -> Outcome<Params, Obs> {
+    balance_ext_in(&mut BalanceArena::new(), theta0, env, tgt, opts)
 }
 
-pub fn balance_ext(
+/// [`balance_ext`] run in `arena`: reuses its cells and honors its settings
+/// (hold phase, `with_trace` for a per-iteration `Obs` history).
+pub fn balance_ext_in(
+    arena: &mut BalanceArena<Params, Env, Targets, Obs>,
     theta0: Params,
     env: Env,
     tgt: Targets,
-    bnd: Bounds,
-    gains: Gains,
-    mechs: Vec<Box<dyn Mechanic>>,
-    max_iters: usize,
+    opts: Options<impl SimModel>,
 ) -> Outcome<Params, Obs> {
-    balance_with_hooks(
+    let Options { bounds: bnd, gains, mechs, model, max_iters, reg, controller, order } = opts;
+    let mut ctl_state = [ControllerState::default(); 3];
+    // Shared with the least-squares step, which probes the model directly.
+    let model = &model;
+    // Hooks' `cost_multiplier` from the latest simulate. The model (and the
+    // nominal/least-squares targets) see it as a scaled `upgrade_cost_base`.
+    let cost_scale = &std::cell::Cell::new(1.0);
+    let scaled = move |env: &Env| Env { upgrade_cost_base: env.upgrade_cost_base * cost_scale.get(), ..*env };
+    arena.balance_with_hooks(
         theta0,
         env,
         tgt,
@@ -106,43 +445,14 @@ pub fn balance_ext(
             .map(|m| m as Box<dyn Hook<_, _, _, _>>)
             .collect(),
         max_iters,
-        /* simulate */
-        |th, env, tgt, mechs| {
-            let mut income = (th.gen_per_sec * th.multiplier).max(0.0);
-            for m in mechs.iter_mut() {
-                income *= m.income_multiplier(income, th, env).max(0.0);
-            }
-            let cap = actions::econ_cap(income, 1.0);
-            let spend = (th.spend_rate.min(income) * cap).clamp(0.0, income);
-            let surplus = income - spend;
-
-            let lvl = (th.multiplier / env.gain_per_level).max(0.0);
-            let cost_next = env.upgrade_cost_base * env.upgrade_cost_growth.powf(lvl);
-
-            let util = if income > 0.0 {
-                (spend / income).clamp(0.0, 1.0)
-            } else {
-                0.0
-            };
-            let save_floor: f64 = (1.0 - tgt.util_target).clamp(0.0, 1.0);
-            let eff_save = (income - spend).max(income * save_floor).max(1e-9);
-            let ttu = (cost_next / eff_save).clamp(0.0, 86_400.0);
-
-            let growth = if income > 0.0 {
-                th.multiplier * (1.0 + (surplus.max(0.0) / income))
-            } else {
-                th.multiplier
-            };
-
-            Obs {
-                ttu,
-                util,
-                growth,
-                surplus,
-            }
+        /* simulate (delegated to the observation model) */
+        move |th, env, tgt, mechs| {
+            cost_scale.set(compose_cost(1.0, mechs, th, env));
+            model.observe(th, &scaled(env), tgt, mechs)
         },
         /* nominal targets */
-        |th, env, tgt, o| {
+        move |th, env, tgt, o| {
+            let env = &scaled(env);
             let save_floor: f64 = (1.0 - tgt.util_target).clamp(1e-6, 1.0);
             let lvl = (th.multiplier / env.gain_per_level).max(0.0);
             let cost_next = env.upgrade_cost_base * env.upgrade_cost_growth.powf(lvl);
@@ -157,32 +467,54 @@ pub fn balance_ext(
             }
         },
         /* step */
-        |th, bnd, g, nom, adj| {
-            let gen_target = (nom.x / th.multiplier.max(1e-9)) * adj.a;
+        move |th, bnd, g, nom, adj| {
+            if let Controller::WeightedLeastSquares { k } = controller {
+                return least_squares_step(model, th, &scaled(&env), &tgt, bnd, &g.scaled(k), reg);
+            }
             let spend_target = nom.y * adj.b;
             let mult_target = nom.z * adj.c;
 
-            let r#gen_next = control::approach(
+            // Optional pull toward a baseline θ (live-game retunes).
+            let pull = |t: f64, field: fn(&Params) -> f64| match reg {
+                Some(r) => r.pull(t, field),
+                None => t,
+            };
+            let spend_target = pull(spend_target, |b| b.spend_rate);
+            let mult_target = pull(mult_target, |b| b.multiplier);
+
+            let st = &mut ctl_state;
+            let mul_next = controller.step(
+                &mut st[2],
+                th.multiplier,
+                mult_target.clamp(bnd.mul_min, bnd.mul_max),
+                g.k_grow,
+                bnd.mul_min,
+                bnd.mul_max,
+            );
+
+            // gen* = income* / mult: Jacobi divides by the old multiplier,
+            // Gauss–Seidel by the one just updated.
+            let mult_for_gen = match order {
+                UpdateOrder::Jacobi => th.multiplier,
+                UpdateOrder::GaussSeidel => mul_next,
+            };
+            let gen_target = pull((nom.x / mult_for_gen.max(1e-9)) * adj.a, |b| b.gen_per_sec);
+            let r#gen_next = controller.step(
+                &mut st[0],
                 th.gen_per_sec,
                 gen_target.clamp(bnd.gen_min, bnd.gen_max),
                 g.k_ttu,
                 bnd.gen_min,
                 bnd.gen_max,
             );
-            let spd_next = control::approach(
+            let spd_next = controller.step(
+                &mut st[1],
                 th.spend_rate,
                 spend_target.clamp(bnd.spd_min, bnd.spd_max),
                 g.k_util,
                 bnd.spd_min,
                 bnd.spd_max,
             );
-            let mul_next = control::approach(
-                th.multiplier,
-                mult_target.clamp(bnd.mul_min, bnd.mul_max),
-                g.k_grow,
-                bnd.mul_min,
-                bnd.mul_max,
-            );
 
             Params {
                 gen_per_sec: r#gen_next,
@@ -192,9 +524,19 @@ pub fn balance_ext(
         },
         /* converged */
         |o, tgt| {
-            (o.ttu - tgt.ttu_target).abs() <= 0.02 * tgt.ttu_target.max(1.0)
-                && (o.util - tgt.util_target).abs() <= 0.01
-                && (o.growth - tgt.growth_target).abs() <= 0.02 * tgt.growth_target.max(1.0)
+            within(o.ttu, tgt.ttu_target, RelTol(0.02).or(AbsTol(0.02)))
+                // util is a relational target: spend / income.
+                && control::ratio_within(o.income - o.surplus, o.income, tgt.util_target, 0.01)
+                && within(o.growth, tgt.growth_target, RelTol(0.02).or(AbsTol(0.02)))
         },
     )
+    .with_residuals(|o| residuals(o, &tgt))
+}
+
+impl<M: SimModel> Runner<M> {
+    /// [`Runner::run`] in `arena`, via [`balance_ext_in`].
+    pub fn run_in(self, arena: &mut BalanceArena<Params, Env, Targets, Obs>) -> Outcome<Params, Obs> {
+        let (theta0, env, tgt, opts) = self.into_parts();
+        balance_ext_in(arena, theta0, env, tgt, opts)
+    }
 }
@@ -0,0 +1,249 @@
+//! Relic power budget: tune the power each rarity tier of run-modifying
+//! relics may spend so every relic's projected run win-rate delta lands in
+//! its tier's band, with higher rarities stronger and bounded overlap.
+//!
+//! A relic spends `budget[tier] · weight` power, where the weight is the
+//! designer's share of the tier budget (about 1). A run's win rate follows
+//! `wr::tanh` over the run's baseline power plus the relic's, so power has
+//! diminishing returns; a relic's delta is the lift over the baseline run.
+//! Each tier's budget is set so its weakest and strongest relics straddle
+//! the band midpoint. This complements `draft_choice`, which decides what is
+//! offered; this system decides what an offered relic may be worth.
+
+use crate::error::{Checks, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::{self, ControllerState};
+use crate::mechanics::wr;
+use crate::systems::sdk::{balance_with_hooks, compose_income, Band, Hook, NominalTargets, Outcome, Residual};
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Params {
+    pub budgets: Vec<f64>, // power per rarity tier, most common first
+}
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        self.budgets.clone()
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { budgets: v.to_vec() }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Relic {
+    pub tier: usize, // index into the budgets (`draft_choice::Tier as usize`)
+    pub weight: f64, // share of the tier budget this relic spends, e.g. 0.9
+}
+
+#[derive(Clone, Debug)]
+pub struct Env {
+    pub relics: Vec<Relic>,
+    pub base_power: f64,  // effective power of a run without the relic
+    pub defend_rate: f64, // share of that power the run's enemies blunt
+    pub alpha: f64,       // `wr::tanh` pressure scale
+    pub beta: f64,        // `wr::tanh` amplitude: win rate tops out at 0.5 + β
+}
+
+#[derive(Clone, Debug)]
+pub struct Targets {
+    pub bands: Vec<(f64, f64)>, // win-rate delta band per tier, most common first
+    pub max_overlap: f64,       // most a tier's band may reach into the next tier's, in win-rate points
+}
+
+crate::define_system! {
+    bounds {
+        budget: budget_min..budget_max = (0.0, 1e3) => .non_negative(budget_min),
+    }
+    gains { k_budget = 0.5 }
+}
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        let c = Checks::default()
+            .non_negative("base_power", self.base_power)
+            .check("defend_rate", (0.0..1.0).contains(&self.defend_rate), "must be in [0, 1)")
+            .positive("alpha", self.alpha)
+            .check("beta", self.beta > 0.0 && self.beta <= 0.5, "must be in (0, 0.5]");
+        self.relics.iter().fold(c, |c, r| c.positive("relics.weight", r.weight)).done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        let c = self.bands.iter().fold(Checks::default(), |c, &(lo, hi)| c.range("bands", lo, hi));
+        self.bands
+            .windows(2)
+            .fold(c, |c, w| {
+                let ((lo0, hi0), (lo1, hi1)) = (w[0], w[1]);
+                c.check("bands", lo1 >= lo0 && hi1 >= hi0, "must rise with rarity")
+                    .check("bands", hi0 - lo1 <= self.max_overlap, "overlap the next tier by more than max_overlap")
+            })
+            .non_negative("max_overlap", self.max_overlap)
+            .done()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obs {
+    pub power_mult: f64,             // relic power factor from hooks
+    pub baseline_wr: f64,            // run win rate without the relic
+    pub delta: Vec<f64>,             // win-rate delta per relic
+    pub tier_range: Vec<(f64, f64)>, // weakest and strongest delta per tier (0, 0 when empty)
+    pub overlap: Vec<f64>,           // strongest of tier i − weakest of tier i+1; negative = gap
+}
+
+/// Run win rate at `power` on top of the baseline.
+pub fn run_winrate(power: f64, env: &Env) -> f64 {
+    wr::tanh(env.base_power + power, env.defend_rate, env.alpha, env.beta)
+}
+
+/// Win-rate lift of a relic worth `power`.
+pub fn winrate_delta(power: f64, env: &Env) -> f64 {
+    run_winrate(power, env) - run_winrate(0.0, env)
+}
+
+/// Smallest and largest relic weight in `tier`, if it has any relics.
+fn weight_range(env: &Env, tier: usize) -> Option<(f64, f64)> {
+    env.relics.iter().filter(|r| r.tier == tier).fold(None, |acc, r| match acc {
+        None => Some((r.weight, r.weight)),
+        Some((lo, hi)) => Some((lo.min(r.weight), hi.max(r.weight))),
+    })
+}
+
+/// Tier budget whose weakest and strongest relics' deltas average `want`,
+/// searched within `[lo, hi]` (delta rises with budget).
+pub fn budget_for(env: &Env, tier: usize, want: f64, power_mult: f64, lo: f64, hi: f64) -> Option<f64> {
+    let (w_lo, w_hi) = weight_range(env, tier)?;
+    let mid_delta = |b: f64| 0.5 * (winrate_delta(b * w_lo * power_mult, env) + winrate_delta(b * w_hi * power_mult, env));
+    if mid_delta(hi) <= want {
+        return Some(hi);
+    }
+    let (mut lo, mut hi) = (lo, hi);
+    for _ in 0..60 {
+        let b = 0.5 * (lo + hi);
+        if mid_delta(b) < want { lo = b } else { hi = b }
+    }
+    Some(0.5 * (lo + hi))
+}
+
+/// The `wr::tanh` projection described in the module docs; hooks'
+/// `income_multiplier` scales every relic's power (amplifier relics,
+/// ascension modifiers). Implement [`SimModel`] for relic synergies, run-
+/// length effects or a simulated run.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        _tgt: &Targets,
+        mechs: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let power_mult = compose_income(1.0, mechs, th, env).max(1e-9);
+        let delta: Vec<f64> = env
+            .relics
+            .iter()
+            .map(|r| {
+                let budget = th.budgets.get(r.tier).copied().unwrap_or(0.0).max(0.0);
+                winrate_delta(budget * r.weight * power_mult, env)
+            })
+            .collect();
+        let tier_range: Vec<(f64, f64)> = (0..th.budgets.len())
+            .map(|t| {
+                let mut ds = env.relics.iter().zip(&delta).filter(|(r, _)| r.tier == t).map(|(_, &d)| d);
+                let first = ds.next().unwrap_or(0.0);
+                ds.fold((first, first), |(lo, hi), d| (lo.min(d), hi.max(d)))
+            })
+            .collect();
+        let overlap = tier_range.windows(2).map(|w| w[0].1 - w[1].0).collect();
+        Obs { power_mult, baseline_wr: run_winrate(0.0, env), delta, tier_range, overlap }
+    }
+}
+
+fn mid((lo, hi): (f64, f64)) -> f64 {
+    0.5 * (lo + hi)
+}
+
+/// Unitless error vs targets: each tier's delta range against its band
+/// midpoint, plus overlap beyond the cap.
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    o.tier_range.iter().zip(&tgt.bands).map(|(&r, &b)| control::pct_error(mid(r), mid(b))).sum::<f64>()
+        + o.overlap.iter().map(|&v| (v - tgt.max_overlap).max(0.0)).sum::<f64>()
+}
+
+/// Named residuals for [`Outcome::residuals`]: each relic's delta (met
+/// anywhere in its tier's band) and each overlap (met anywhere under the
+/// cap).
+pub fn residuals(o: &Obs, env: &Env, tgt: &Targets) -> Vec<Residual> {
+    let relics = env.relics.iter().zip(&o.delta).enumerate().filter_map(|(i, (r, &d))| {
+        tgt.bands.get(r.tier).map(|&(lo, hi)| Residual::new(format!("relic{i}"), d, d.clamp(lo, hi)))
+    });
+    let overlaps =
+        o.overlap.iter().enumerate().map(|(i, &v)| Residual::new(format!("overlap{i}"), v, v.min(tgt.max_overlap)));
+    relics.chain(overlaps).collect()
+}
+
+pub fn balance_ext(
+    theta0: Params,
+    env: Env,
+    tgt: Targets,
+    opts: Options<impl SimModel>,
+) -> Outcome<Params, Obs> {
+    let Options { bounds: bnd, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = vec![ControllerState::default(); theta0.budgets.len()];
+    // Per-tier targets depend on the relic weights in env; the step only
+    // sees θ, so it keeps a copy.
+    let (step_env, step_tgt) = (env.clone(), tgt.clone());
+    let (res_env, res_tgt) = (env.clone(), tgt.clone());
+    balance_with_hooks(
+        theta0,
+        env,
+        tgt,
+        bnd,
+        g,
+        mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+        max_iters,
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, mechs),
+        // nominal: x = hooks' power factor (budgets shrink as it grows)
+        |_th, _env, _tgt, o| NominalTargets { x: o.power_mult, y: 0.0, z: 0.0 },
+        // step: budget_t → the budget that centres tier t's relics on its
+        //       band midpoint; tiers without relics or a band hold still
+        move |th, b, g, nom, adj| {
+            let power_mult = (nom.x * adj.a).max(1e-9);
+
+            let st = &mut ctl_state;
+            st.resize(th.budgets.len(), ControllerState::default());
+            let budgets = th
+                .budgets
+                .iter()
+                .enumerate()
+                .map(|(t, &budget)| {
+                    let target = step_tgt
+                        .bands
+                        .get(t)
+                        .and_then(|&band| budget_for(&step_env, t, mid(band), power_mult, b.budget_min, b.budget_max))
+                        .unwrap_or(budget);
+                    let target = match reg.as_ref() {
+                        Some(r) => r.pull(target, |base| base.budgets.get(t).copied().unwrap_or(target)),
+                        None => target,
+                    };
+                    controller.step(&mut st[t], budget, target, g.k_budget, b.budget_min, b.budget_max)
+                })
+                .collect();
+            Params { budgets }
+        },
+        // converged: every tier's weakest and strongest relic inside its
+        // band and no overlap more than half a point past the cap
+        |o, tgt| {
+            o.tier_range.iter().zip(&tgt.bands).all(|(&(lo, hi), &band)| {
+                let band = Band::from(band);
+                band.contains(lo) && band.contains(hi)
+            }) && o.overlap.iter().all(|&v| v <= tgt.max_overlap + 0.005)
+        },
+    )
+    .with_residuals(move |o| residuals(o, &res_env, &res_tgt))
+}
@@ -1,13 +1,27 @@
-use crate::mechanics::control;
-use crate::systems::sdk::{balance_with_hooks, Hook, NominalTargets, Outcome};
+use crate::error::{check_range, Checks, Error, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::{self, ControllerState};
+use crate::systems::sdk::{
+    balance_with_hooks, within, AbsTol, Hook, NominalTargets, Outcome, Residual, RelTol, Tolerance,
+};
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Params {
     pub reward_mult: f64,
     pub decay: f64,
     pub req_score: f64,
 }
 
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        vec![self.reward_mult, self.decay, self.req_score]
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { reward_mult: v[0], decay: v[1], req_score: v[2] }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Env {
     pub session_goal_minutes: f64,
@@ -19,50 +33,95 @@ pub struct Targets {
     pub reward_growth: f64,
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct Bounds {
-    pub rmin: f64,
-    pub rmax: f64,
-    pub dmin: f64,
-    pub dmax: f64,
-    pub qmin: f64,
-    pub qmax: f64,
+crate::define_system! {
+    bounds {
+        reward_mult: rmin..rmax = (1.0, 1e6),
+        decay: dmin..dmax = (0.0, 0.5),
+        req_score: qmin..qmax = (1.0, 1e12),
+    }
+    gains { k_r = 0.6, k_d = 0.4, k_q = 0.6 }
+    inputs {
+        /// Upstream income per second from the core loop.
+        ref_income: f64,
+    }
 }
+
 impl Bounds {
-    pub fn soft() -> Self {
-        Self { rmin: 1.0, rmax: 1e6, dmin: 0.0, dmax: 0.5, qmin: 1.0, qmax: 1e12 }
+    /// Validated bounds: every pair must be finite with min ≤ max.
+    pub fn new(rmin: f64, rmax: f64, dmin: f64, dmax: f64, qmin: f64, qmax: f64) -> Result<Self, Error> {
+        check_range("reward_mult", rmin, rmax)?;
+        check_range("decay", dmin, dmax)?;
+        check_range("req_score", qmin, qmax)?;
+        Ok(Self { rmin, rmax, dmin, dmax, qmin, qmax })
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct Gains {
-    pub k_r: f64,
-    pub k_d: f64,
-    pub k_q: f64,
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default().positive("session_goal_minutes", self.session_goal_minutes).done()
+    }
 }
-impl Default for Gains {
-    fn default() -> Self { Self { k_r: 0.6, k_d: 0.4, k_q: 0.6 } }
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .positive("cycle_minutes", self.cycle_minutes)
+            .positive("reward_growth", self.reward_growth)
+            .done()
+    }
 }
 
 #[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Obs {
     pub cycle_mins: f64,
     pub reward_rate: f64,
 }
 
-pub trait Mechanic: Hook<Params, Env, Targets, Obs> {}
-impl<T: Hook<Params, Env, Targets, Obs>> Mechanic for T {}
+/// The default cycle math: time to `req_score` at decayed `ref_income`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        _env: &Env,
+        _tgt: &Targets,
+        ref_income: f64,
+        _hooks: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let eff = ref_income / (1.0 + th.decay * 10.0);
+        let cycle_mins = (th.req_score / eff.max(1e-6)).clamp(0.1, 1e6);
+        let reward_rate = th.reward_mult / cycle_mins.max(1e-6);
+        Obs { cycle_mins, reward_rate }
+    }
+}
+
+/// Unitless error vs targets: mean [`pct_error`](control::pct_error) of the
+/// cycle length and of the reward rate (`reward_growth / cycle_minutes`).
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    let reward_target = tgt.reward_growth / tgt.cycle_minutes.max(1e-6);
+    (control::pct_error(o.cycle_mins, tgt.cycle_minutes) + control::pct_error(o.reward_rate, reward_target)) / 2.0
+}
+
+/// Named residuals for [`Outcome::residuals`]: cycle length and reward rate
+/// (`reward_growth / cycle_minutes`).
+pub fn residuals(o: &Obs, tgt: &Targets) -> Vec<Residual> {
+    vec![
+        Residual::new("cycle_minutes", o.cycle_mins, tgt.cycle_minutes),
+        Residual::new("reward_rate", o.reward_rate, tgt.reward_growth / tgt.cycle_minutes.max(1e-6)),
+    ]
+}
 
 pub fn balance_ext(
     theta0: Params,
     env: Env,
     tgt: Targets,
-    b: Bounds,
-    g: Gains,
-    mechs: Vec<Box<dyn Mechanic>>,
-    max_iters: usize,
     ref_income: f64,
+    opts: Options<impl SimModel>,
 ) -> Outcome<Params, Obs> {
+    let Options { bounds: b, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = [ControllerState::default(); 3];
     balance_with_hooks(
         theta0,
         env,
@@ -74,20 +133,15 @@ pub fn balance_ext(
             .map(|m| m as Box<dyn Hook<_, _, _, _>>)
             .collect(),
         max_iters,
-        // simulate: time to reach req_score given income with decay; reward rate
-        move |th, _env, _tgt, _mechs| {
-            let eff = ref_income / (1.0 + th.decay * 10.0);
-            let cycle_mins = (th.req_score / eff.max(1e-6)).clamp(0.1, 1e6);
-            let reward_rate = th.reward_mult / cycle_mins.max(1e-6);
-            Obs { cycle_mins, reward_rate }
-        },
+        // simulate: delegate to the observation model
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, ref_income, mechs),
         // nominal targets
         |th, _env, tgt, _o| {
             let reward_target = tgt.reward_growth / tgt.cycle_minutes.max(1e-6);
             NominalTargets { x: tgt.cycle_minutes, y: reward_target, z: th.decay }
         },
         // step
-        |th, b, g, nom, _adj| {
+        move |th, b, g, nom, _adj| {
             let req_target = nom.x;          // cycle target (minutes)
             let rew_rate_target = nom.y;     // desired reward/min
 
@@ -95,12 +149,23 @@ pub fn balance_ext(
             let decay_t = th.decay;          // leave as-is unless you want pacing tweak
             let req_score_t = th.req_score;  // idem
 
-            let r = control::approach(th.reward_mult, reward_mult_t.clamp(b.rmin, b.rmax), g.k_r, b.rmin, b.rmax);
-            let d = control::approach(th.decay,       decay_t.clamp(b.dmin, b.dmax),       g.k_d, b.dmin, b.dmax);
-            let q = control::approach(th.req_score,   req_score_t.clamp(b.qmin, b.qmax),   g.k_q, b.qmin, b.qmax);
+            let (reward_mult_t, decay_t, req_score_t) = match reg {
+                Some(r) => (
+                    r.pull(reward_mult_t, |p| p.reward_mult),
+                    r.pull(decay_t, |p| p.decay),
+                    r.pull(req_score_t, |p| p.req_score),
+                ),
+                None => (reward_mult_t, decay_t, req_score_t),
+            };
+
+            let st = &mut ctl_state;
+            let r = controller.step(&mut st[0], th.reward_mult, reward_mult_t.clamp(b.rmin, b.rmax), g.k_r, b.rmin, b.rmax);
+            let d = controller.step(&mut st[1], th.decay,       decay_t.clamp(b.dmin, b.dmax),       g.k_d, b.dmin, b.dmax);
+            let q = controller.step(&mut st[2], th.req_score,   req_score_t.clamp(b.qmin, b.qmax),   g.k_q, b.qmin, b.qmax);
             Params { reward_mult: r, decay: d, req_score: q }
         },
         // converge if cycle within ±5%
-        |o, tgt| (o.cycle_mins - tgt.cycle_minutes).abs() <= 0.05 * tgt.cycle_minutes.max(1.0),
+        |o, tgt| within(o.cycle_mins, tgt.cycle_minutes, RelTol(0.05).or(AbsTol(0.05))),
     )
+    .with_residuals(|o| residuals(o, &tgt))
 }
@@ -0,0 +1,221 @@
+//! Rewarded ads: tune the ad reward multiplier, cooldown and daily cap so
+//! ads speed up an engaged player without ever replacing play.
+//!
+//! An ad pays `multiplier × reward_minutes` of base income. A player who
+//! plays `m` minutes a day and watches `n` ads progresses
+//! `1 + n · reward / (income · m)` times as fast as without ads (see
+//! [`progress_ratio`]). Three players are tracked against their own no-ads
+//! baseline:
+//!
+//! - **engaged**: plays `engaged_minutes` and watches every ad that comes off
+//!   cooldown while playing;
+//! - **casual**: plays `casual_minutes` and takes `casual_watch_share` of
+//!   those offers;
+//! - **max**: plays like the engaged player but checks in for every ad the
+//!   waking day allows — the ceiling the daily cap has to hold.
+
+use crate::error::{Checks, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::{self, ControllerState};
+use crate::systems::sdk::{
+    balance_with_hooks, compose_income, within, Band, Hook, NominalTargets, Outcome, Residual, RelTol,
+};
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Params {
+    pub multiplier: f64,       // × `reward_minutes` of base income per ad
+    pub cooldown_minutes: f64, // between ads
+    pub daily_cap: f64,        // ads per day; fractional caps round down in game
+}
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        vec![self.multiplier, self.cooldown_minutes, self.daily_cap]
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { multiplier: v[0], cooldown_minutes: v[1], daily_cap: v[2] }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Env {
+    pub income_per_min: f64,     // base progress without ads
+    pub reward_minutes: f64,     // an ad pays multiplier × this much income
+    pub engaged_minutes: f64,    // daily play of an engaged player
+    pub casual_minutes: f64,     // daily play of a casual player
+    pub casual_watch_share: f64, // share of offers a casual player takes
+    pub waking_minutes: f64,     // e.g. 960; bounds how many cooldowns fit in a day
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Targets {
+    pub engaged_band: (f64, f64), // engaged progress ratio, e.g. (1.2, 1.3)
+    pub max_ratio: f64,           // ceiling for any player, e.g. 2.0
+    pub engaged_ads: f64,         // ads per day an engaged player watches
+}
+
+crate::define_system! {
+    bounds {
+        multiplier: mult_min..mult_max = (1e-3, 1e4),
+        cooldown_minutes: cooldown_min..cooldown_max = (0.5, 1440.0),
+        daily_cap: cap_min..cap_max = (1.0, 1e3),
+    }
+    gains { k_mult = 0.6, k_cooldown = 0.6, k_cap = 0.6 }
+}
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .positive("income_per_min", self.income_per_min)
+            .positive("reward_minutes", self.reward_minutes)
+            .positive("engaged_minutes", self.engaged_minutes)
+            .positive("casual_minutes", self.casual_minutes)
+            .within("casual_watch_share", self.casual_watch_share, 0.0, 1.0)
+            .check("waking_minutes", self.waking_minutes > 0.0 && self.waking_minutes <= 1440.0, "must be in (0, 1440]")
+            .check("engaged_minutes", self.engaged_minutes <= self.waking_minutes, "must fit in the waking day")
+            .done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .range("engaged_band", self.engaged_band.0, self.engaged_band.1)
+            .check("engaged_band", self.engaged_band.0 > 1.0, "ads must speed progress up (lo > 1)")
+            .check("max_ratio", self.max_ratio >= self.engaged_band.1, "must be ≥ the engaged band")
+            .positive("engaged_ads", self.engaged_ads)
+            .done()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obs {
+    pub reward_per_ad: f64, // after hooks
+    pub engaged_ads: f64,   // ads per day
+    pub casual_ads: f64,
+    pub max_ads: f64,
+    pub engaged_ratio: f64, // progress vs the same player without ads
+    pub casual_ratio: f64,
+    pub max_ratio: f64,
+}
+
+/// Progress with ads over progress without: `1 + ads · reward / (income · minutes)`.
+pub fn progress_ratio(ads: f64, reward_per_ad: f64, income_per_min: f64, minutes: f64) -> f64 {
+    1.0 + ads * reward_per_ad / (income_per_min * minutes).max(1e-12)
+}
+
+/// The three-player model described in the module docs; hooks'
+/// `income_multiplier` scales the ad reward (ad-boost events, VIP bonuses).
+/// Implement [`SimModel`] for ad fill rates, per-placement rewards or watch
+/// rates fitted to telemetry.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        _tgt: &Targets,
+        mechs: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let reward = compose_income(th.multiplier * env.reward_minutes * env.income_per_min, mechs, th, env).max(0.0);
+        let cooldown = th.cooldown_minutes.max(1e-9);
+        let cap = th.daily_cap.max(0.0);
+        let engaged_ads = (env.engaged_minutes / cooldown).min(cap);
+        let casual_ads = env.casual_watch_share * (env.casual_minutes / cooldown).min(cap);
+        let max_ads = (env.waking_minutes / cooldown).min(cap);
+        Obs {
+            reward_per_ad: reward,
+            engaged_ads,
+            casual_ads,
+            max_ads,
+            engaged_ratio: progress_ratio(engaged_ads, reward, env.income_per_min, env.engaged_minutes),
+            casual_ratio: progress_ratio(casual_ads, reward, env.income_per_min, env.casual_minutes),
+            max_ratio: progress_ratio(max_ads, reward, env.income_per_min, env.engaged_minutes),
+        }
+    }
+}
+
+fn mid((lo, hi): (f64, f64)) -> f64 {
+    0.5 * (lo + hi)
+}
+
+/// Unitless error vs targets: engaged ratio against the band midpoint and
+/// ads per day, plus any excess over the ratio ceiling.
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    control::pct_error(o.engaged_ratio, mid(tgt.engaged_band))
+        + control::pct_error(o.engaged_ads, tgt.engaged_ads)
+        + (o.max_ratio - tgt.max_ratio).max(0.0)
+}
+
+/// Named residuals for [`Outcome::residuals`]; the ratio ceiling counts as
+/// met anywhere below it.
+pub fn residuals(o: &Obs, tgt: &Targets) -> Vec<Residual> {
+    vec![
+        Residual::new("engaged_ratio", o.engaged_ratio, mid(tgt.engaged_band)),
+        Residual::new("engaged_ads", o.engaged_ads, tgt.engaged_ads),
+        Residual::new("max_ratio", o.max_ratio, o.max_ratio.min(tgt.max_ratio)),
+    ]
+}
+
+pub fn balance_ext(
+    theta0: Params,
+    env: Env,
+    tgt: Targets,
+    opts: Options<impl SimModel>,
+) -> Outcome<Params, Obs> {
+    let Options { bounds: b, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = [ControllerState::default(); 3];
+    balance_with_hooks(
+        theta0,
+        env,
+        tgt,
+        b,
+        g,
+        mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+        max_iters,
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, mechs),
+        // nominal: x = cooldown that fits `engaged_ads` into a session,
+        // y = multiplier whose reward (after hooks) puts the engaged player on
+        // the band midpoint, z = cap that lets the max player reach the ceiling
+        |th, env, tgt, o| {
+            let ads = tgt.engaged_ads.max(1e-9);
+            let boost = mid(tgt.engaged_band) - 1.0;
+            let reward = boost * env.income_per_min * env.engaged_minutes / ads;
+            NominalTargets {
+                x: env.engaged_minutes / ads,
+                y: th.multiplier * reward / o.reward_per_ad.max(1e-12),
+                z: ads * (tgt.max_ratio - 1.0) / boost.max(1e-9),
+            }
+        },
+        move |th, b, g, nom, adj| {
+            let (cooldown_t, mult_t, cap_t) = (nom.x * adj.a, nom.y * adj.b, nom.z * adj.c);
+
+            let (cooldown_t, mult_t, cap_t) = match reg {
+                Some(r) => (
+                    r.pull(cooldown_t, |p| p.cooldown_minutes),
+                    r.pull(mult_t, |p| p.multiplier),
+                    r.pull(cap_t, |p| p.daily_cap),
+                ),
+                None => (cooldown_t, mult_t, cap_t),
+            };
+
+            let st = &mut ctl_state;
+            Params {
+                multiplier: controller.step(&mut st[0], th.multiplier, mult_t, g.k_mult, b.mult_min, b.mult_max),
+                cooldown_minutes: controller.step(&mut st[1], th.cooldown_minutes, cooldown_t, g.k_cooldown, b.cooldown_min, b.cooldown_max),
+                daily_cap: controller.step(&mut st[2], th.daily_cap, cap_t, g.k_cap, b.cap_min, b.cap_max),
+            }
+        },
+        // converged: engaged player inside the band at the target ad count,
+        // and no player above the ceiling
+        |o, tgt| {
+            Band::from(tgt.engaged_band).contains(o.engaged_ratio)
+                && within(o.engaged_ads, tgt.engaged_ads, RelTol(0.05))
+                && o.max_ratio <= tgt.max_ratio + 0.01
+        },
+    )
+    .with_residuals(|o| residuals(o, &tgt))
+}
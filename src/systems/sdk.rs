@@ -14,16 +14,46 @@
 //! - **upgrade_cost_curve**: target TTU band & slope across levels
 //! - **reset_prestige**: target cycle time & meta growth
 //! - **offline_accumulation**: target AFK retention
+//! - **shop_pricing**: target purchase cadence per item tier and a cap on hoarded income
+//! - **gacha_rates**: target pulls per 5★, drought odds and pity shape
+//! - **loot_table**: target hours to acquire each item class, with worst-case grind
+//! - **energy_regen**: target session length, sessions per day and waste at cap
+//! - **matchmaking_rating**: target games to settle new players and ladder winrate band
+//! - **combat_ttk**: target TTK/TTD windows against an opponent roster
+//! - **difficulty_curve**: target fail rate per level against a player power curve
+//! - **xp_curve**: target minutes per level and hours to the level cap
+//! - **crafting_chain**: target end-to-end throughput and dead time per pipeline stage
+//! - **rewarded_ad**: target progress boost from ads for engaged players, capped for everyone
+//! - **battle_pass**: target finish week of the free and premium tracks for reference players
+//! - **daily_quest**: target daily session length and a cap on dailies' share of income
+//! - **wave_pressure**: target leak rate per wave and near-failure pressure on boss waves
+//! - **mana_curve**: target curve-out odds over the first turns and a cap on dead cards in hand
+//! - **autobattler_economy**: target net worth for greedy and all-in tempo lines, with tempo ahead on board at the spike round
+//! - **relic_power_budget**: target a run win-rate delta band per relic rarity, bands rising with rarity
+//! - **boss_dps_check**: target clear rates by week across the raid DPS distribution, the median kill time and the soft-enrage loss
+//! - **skill_rotation**: target an effect uptime band, a cap on dead GCD seconds per minute and resource demand matching regen
+//! - **armor_mitigation**: target EHP multipliers at armor breakpoints
 //!
 //! Systems should be **genre-neutral** so they can be reused in multiple
 //! genres (idle, roguelike, autobattler, …).
 //!
 //! ## What this SDK gives you
-//! - A generic `balance_with_hooks` harness that wires your pure closures into
-//!   the base refinement loop (`refine_det`).
+//! - A generic `balance_with_hooks` harness that runs your pure closures as a
+//!   fixed-point loop (the same shape as `refine_det`, with θ and `Obs`
+//!   carried directly); the closures may borrow from the caller.
+//! - The same four steps as a `System` trait, run by `balance_system`, for
+//!   systems that live in registries (`Box<dyn System<…>>`) or are mocked
+//!   in tests. `balance_with_hooks` is a thin wrapper over it.
+//! - `SystemStepper` (or `stepper_with_hooks` for closures) to drive a
+//!   system one `step_once()` at a time, e.g. an editor re-simulating on
+//!   every slider change instead of running to convergence.
+//! - `define_system!` to declare a new system's `Params`/`Env`/`Targets`/
+//!   `Obs`/`Bounds`/`Gains`, its `Mechanic` alias and `balance_ext` from a
+//!   few lines of spec, leaving only the four closures below to write.
 //! - A small **hook** protocol (`Hook`) so optional sub-mechanics can
 //!   participate without changing the core system (e.g., fees, caps, auras).
-//! - A standard `Outcome<TParams, Obs>` return (θ, π, iters, converged).
+//! - A standard `Outcome<TParams, Obs>` return (θ, π, iters, converged), plus
+//!   `stable_after_converge` from an opt-in hold phase after convergence.
 //!
 //! ## Your responsibilities (per system)
 //! Implement the four closures required by `balance_with_hooks`:
@@ -31,6 +61,9 @@
 //! 1) **simulate**: `(&θ, &Env, &Tgt, &mut [Hook]) -> Obs`  
 //!    - Compute observables `π` from current params `θ` and environment `Env`.  
 //!    - You may let hooks modulate inputs (e.g., multiply income).
+//!    - Built-in systems route this through a per-system `SimModel` trait
+//!      (`StandardModel` is the shipped math) passed to `balance_ext`, so
+//!      callers can swap the model and keep the controller.
 //!
 //! 2) **nominal**: `(&θ, &Env, &Tgt, &Obs) -> NominalTargets`  
 //!    - Convert `Obs` and `Tgt` into *pre-update* controller targets (x, y, z).
@@ -38,11 +71,28 @@
 //!
 //! 3) **step**: `(&θ, &Bounds, &Gains, NominalTargets, TargetAdjust) -> θ'`  
 //!    - Move parameters toward (adjusted) targets using your controller
-//!      (commonly proportional smoothing via `mechanics::control::approach`).
+//!      (commonly proportional smoothing via `mechanics::control::approach`,
+//!      or a caller-selected `mechanics::control::Controller` backend).
+//!    - If one field's target depends on another field (as `gen_per_sec`
+//!      depends on `multiplier` in production_spend), accept an
+//!      `UpdateOrder` so callers can pick Jacobi or Gauss–Seidel updates.
 //!
 //! 4) **converged**: `(&Obs, &Tgt) -> bool`  
 //!    - Decide if `Obs` is within your acceptance band. Keep this tolerant to
 //!      avoid oscillation; it’s a **band**, not an exact equality.
+//!      `within(obs, target, RelTol(0.02))`, `AbsTol` and `Band` spell the
+//!      usual checks; `RelTol(r).or(AbsTol(a))` keeps a relative band from
+//!      collapsing on small targets.
+//!    - Name your targets for diagnostics: `System::residuals`, or
+//!      `outcome.with_residuals(…)` after a closure run, fills
+//!      `Outcome::residuals` so a failed run says “util missed by 0.08”.
+//!    - To weigh targets against each other instead, use `balance_scored`
+//!      (the other closures bundled in a `Closures`) with a
+//!      `ConvergenceScore` (Σ weight · residual² ≤ threshold); the
+//!      per-target terms come back on `Outcome::score`.
+//!    - Each iteration runs simulate → nominal/step → converged, so the `Obs`
+//!      it sees comes from the θ *before* that iteration's step. It is never
+//!      consulted before the first simulate.
 //!
 //! ## Hooks (optional sub-mechanics)
 //! Implement `Hook<TParams, Env, Tgt, Obs>` for pluggable effects:
@@ -50,14 +100,55 @@
 //! - `income_multiplier(base_income, θ, Env) -> f64`  
 //!   Multiply a key input *inside simulate* (e.g., fees, buffs). Default 1.0.
 //!
+//! - `multiplier_mode() -> MultMode`  
+//!   How that factor stacks. `Multiplicative` (default) chains factors;
+//!   `Additive` sums `factor − 1` bonuses, so +10% and +25% give ×1.35
+//!   rather than ×1.375. Systems apply
+//!   `(base + Σ flat) · (1 + Σ additive) · Π multiplicative` through
+//!   `compose_income`.
+//!
+//! - `cost_multiplier(θ, Env) -> f64`  
+//!   Scale upgrade/purchase costs (“upgrades cost 20% less” → 0.8). Factors
+//!   chain multiplicatively via `compose_cost`; systems that price things
+//!   (production_spend, upgrade_cost_curve) consult it. Default 1.0.
+//!
+//! - `flat_income(θ, Env) -> f64`  
+//!   A flat bonus (“+5 income/sec”) added to the base before any
+//!   percentage applies. Default 0.
+//!
+//! - `priority() -> i32`  
+//!   Hooks run in ascending priority (default 0); ties keep insertion order.
+//!   The harness sorts the stack once per run.
+//!
 //! - `on_observe(&Obs, &θ, &Env, &Tgt)`  
 //!   Observe/capture state post-sim (e.g., store smoothed metrics).
 //!
 //! - `adjust_targets(&θ, &Env, &Tgt, &NominalTargets) -> TargetAdjust`  
 //!   Multiply controller’s nominal targets (x,y,z) by `(a,b,c)`; defaults to
 //!   identity `(1,1,1)`. Use this for **policy**, not for re-simulating math.
+//!   `adjust_mode()` picks how it stacks, as `multiplier_mode` does for
+//!   income (`compose_adjust`).
+//!
+//! - `post_step(&mut θ_next, &Env)`  
+//!   Mutate the controller's output before it is stored: hard caps,
+//!   rounding to designer-friendly values, level-gated unlocks.
+//!
+//! - `project(&mut θ, &Env)`  
+//!   Enforce a feasibility constraint on θ after each step (coupled limits
+//!   that box bounds can't express). `ProjectHook(p)` wraps any
+//!   `crate::Project`.
+//!
+//! - `on_signals(&mut Signals)`  
+//!   Read (or publish) shared quantities before each simulate, e.g. a fee
+//!   whose strength follows the prestige cycle time another system
+//!   measured. Cache what you need for the other methods.
 //!
 //! Hooks let you extend behavior without editing the system module.
+//! A `HookRegistry` keeps hooks under string IDs across runs, so a genre can
+//! enable, disable or replace them between outer passes (`registry.active()`
+//! is the stack for one pass).
+//! To see how a stack composes, `audit_hooks` reports each hook's factors
+//! and the combined totals without running the loop.
 //!
 //! ## Determinism & purity
 //! - Keep simulate/nominal/step **pure**. Side effects should be confined to
@@ -67,8 +158,81 @@
 //! ## Bounds, Gains, Targets
 //! - **Bounds**: clamp outputs of `step` to sane domains (stability & safety).
 //! - **Gains**: choose gentle smoothing (0.4–0.7 typical). Raise only if your
-//!   converge band is wide and the model is well-conditioned.
+//!   converge band is wide and the model is well-conditioned. If a system
+//!   chatters, `Gains::scaled(factor)` lowers every gain at once. If it
+//!   ping-pongs between its bounds, cap the per-iteration move: a
+//!   `RateLimiter` hook does it for any system, and `step_limited` does it
+//!   inside a `step` closure so stateful controllers (PID, momentum) see the
+//!   clamp and stop winding up. To skip the guessing, `autotune_gains`
+//!   probes a range of `Gains::scaled` factors and returns the fastest one
+//!   safely below where the loop starts to oscillate.
 //! - **Targets**: represent **what you want**, not how to achieve it.
+//! - **Validation**: implement `crate::Validate` for `Env`, `Targets` and
+//!   `Bounds` (the built-in systems do) so `Runner::try_run` rejects
+//!   impossible inputs — an inverted band, a utilization above 1 — with
+//!   every problem listed, before any iteration runs.
+//! - **Regularization** (optional): pass `Some(Regularization { baseline, lambda })`
+//!   to `balance_ext` when retuning a live game; each step target is pulled
+//!   toward the shipped values, yielding the smallest change that still
+//!   moves observables toward the targets.
+//!
+//! ## Batch runs
+//! Sweeps that call the harness many times can run through one
+//! `BalanceArena` (`arena.balance_with_hooks(...)`, same arguments) to set
+//! the hold phase, trace and budget once and to carry the signal bus and
+//! warm-start hooks from run to run.
+//! To tabulate θ against a scanned target, `sweep(&mut sys, &θ₀, &env,
+//! grid, max_iters)` runs a `System` once per grid point (`linspace` builds
+//! the scan); `sweep_with(grid, |tgt| …)` does the same for any runner, and
+//! with `parallel` the `_par` variants spread the grid over rayon.
+//!
+//! ## Shared signals
+//! `Signals` is the bus a genre threads between its systems. Runs through a
+//! `BalanceArena` share the arena's copy: seed it with `with_signals`, and
+//! after each run `arena.signals()` holds whatever hooks wrote, ready for
+//! the next system in the pass. Plain `balance_with_hooks` runs start from
+//! `Signals::default()`.
+//!
+//! ## Chatter
+//! Parameters implement `crate::Flat` so the harness can compare θ across
+//! iterations. When θ repeats a cycle of 2–8 values (three periods in a row,
+//! to a relative 1e-6), the run stops and `Outcome::oscillation` reports the
+//! period and amplitude; lower the gains or bound the steps rather than
+//! raising `max_iters`.
+//!
+//! ## Warm starts
+//! Hooks are moved into each run. To carry their internal state into the
+//! next one (e.g. across a genre's outer passes), run in a `BalanceArena`
+//! and call `outcome.into_warm_start(&mut arena)`: the `WarmStart` holds the
+//! final θ and the same hook objects, ready to seed the next call.
+//!
+//! ## Saving hook state
+//! With `serde`, a stateful hook implements `HookState` (a serializable
+//! `State`) and forwards `Hook::save_state` / `load_state` to it.
+//! `save_hook_states(&hooks)` writes the stack as JSON alongside a save
+//! game; rebuild the same hooks in the same order in a new session and call
+//! `load_hook_states(&mut hooks, &json)` before the first run.
+//!
+//! ## Threads
+//! A run keeps all of its state (θ, controller memory, hooks) in plain
+//! locals, so `balance_with_hooks` and the systems' `balance_ext` can be
+//! called from any thread: rayon tasks, Bevy systems, `std::thread::scope`.
+//! `Outcome` is `Send + Sync` whenever θ and `Obs` are. Hooks are built per
+//! run; to keep them in a `Send` resource, store `Box<dyn Hook<…> + Send>`
+//! and pass them in as-is (they coerce to the plain trait object). Arenas
+//! and `HookRegistry` share hooks by `Rc` and stay on one thread; give each
+//! worker its own (e.g. rayon's `map_init`).
+//!
+//! ## Time budgets
+//! With the `timing` feature, `BalanceArena::with_budget(duration)` bounds each
+//! run by wall-clock time as well as `max_iters` (for live tuning inside an
+//! editor); a run cut short returns the θ reached so far with
+//! `Outcome::budget_exhausted` set.
+//!
+//! ## Convergence traces
+//! `BalanceArena::with_trace(ObsTrace::Full)` (or `Last(n)` for a ring
+//! buffer) fills `Outcome::trace` with every iteration's `Obs`. Off by
+//! default, so plain runs allocate nothing for it.
 //!
 //! ## Testing a system
 //! - Unit tests at `tests/<system>.rs` that pin simple targets and assert
@@ -92,9 +256,11 @@
 // -----------------------------------------------------------------------------
 
 use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::rc::Rc;
 
-use crate::{Data, Metrics, Params, refine_det};
+use crate::mechanics::control;
+use crate::Flat;
 
 /// Multiplicative target scalars (mechanics compose by multiplying).
 #[derive(Clone, Copy, Debug)]
@@ -115,12 +281,75 @@ pub struct NominalTargets {
     pub z: f64,
 }
 
+/// Pull controller targets toward a baseline θ (“smallest change” retunes).
+/// `lambda` trades target accuracy (0) for staying near `baseline` (∞).
+#[derive(Clone, Copy, Debug)]
+pub struct Regularization<TParams> {
+    pub baseline: TParams,
+    pub lambda: f64,
+}
+impl<TParams> Regularization<TParams> {
+    /// Blend one parameter target toward its baseline field.
+    pub fn pull(&self, target: f64, field: impl Fn(&TParams) -> f64) -> f64 {
+        control::regularize(target, field(&self.baseline), self.lambda)
+    }
+}
+
+/// Order in which `step` updates coupled parameters.
+///
+/// `Jacobi` computes every target from the old θ; `GaussSeidel` updates
+/// fields in sequence and feeds each new value into the next field's
+/// target, which often converges faster when fields interact.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UpdateOrder {
+    #[default]
+    Jacobi,
+    GaussSeidel,
+}
+
+/// How a hook's income factor (or target adjustment) stacks with the others.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MultMode {
+    /// Chain factors: ×1.10 then ×1.25 → ×1.375.
+    #[default]
+    Multiplicative,
+    /// Sum bonuses (`factor − 1`): +10% and +25% → ×1.35.
+    Additive,
+}
+
+/// Shared signals you may pass around between systems in a genre pass.
+/// Add fields only when you actually need them; `0.0` means “not measured
+/// yet”.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Signals {
+    pub ref_income: f64,
+    /// Prestige cycle length in minutes (from `reset_prestige`).
+    pub cycle_minutes: f64,
+}
+
 /// A “mechanic” that can view observables, scale pre-update targets, etc.
 pub trait Hook<TParams, Env, Tgt, Obs> {
     /// (Optional) multiply the base income inside simulate (default: 1.0).
     fn income_multiplier(&mut self, _base_income: f64, _theta: &TParams, _env: &Env) -> f64 {
         1.0
     }
+    /// (Optional) how `income_multiplier` stacks (default: multiplicative).
+    fn multiplier_mode(&self) -> MultMode {
+        MultMode::Multiplicative
+    }
+    /// (Optional) multiply costs (upgrades, purchases) (default: 1.0).
+    fn cost_multiplier(&mut self, _theta: &TParams, _env: &Env) -> f64 {
+        1.0
+    }
+    /// (Optional) flat income bonus added to the base before any factor
+    /// (default: 0.0).
+    fn flat_income(&mut self, _theta: &TParams, _env: &Env) -> f64 {
+        0.0
+    }
+    /// (Optional) run order: ascending, ties in insertion order (default: 0).
+    fn priority(&self) -> i32 {
+        0
+    }
     /// (Optional) let the hook observe/cache state after simulate.
     fn on_observe(&mut self, _obs: &Obs, _theta: &TParams, _env: &Env, _tgt: &Tgt) {}
     /// (Optional) multiplicative adjustment of controller’s nominal targets.
@@ -133,27 +362,1100 @@ pub trait Hook<TParams, Env, Tgt, Obs> {
     ) -> TargetAdjust {
         TargetAdjust::id()
     }
+    /// (Optional) how `adjust_targets` stacks (default: multiplicative).
+    fn adjust_mode(&self) -> MultMode {
+        MultMode::Multiplicative
+    }
+    /// (Optional) modify the next θ right after the controller step (caps,
+    /// rounding, unlocks). Runs in hook order, before any `project`.
+    fn post_step(&mut self, _theta_next: &mut TParams, _env: &Env) {}
+    /// (Optional) pull θ back into a feasible set after each step. Runs after
+    /// the controller's bounds clamp and every `post_step`, in hook order;
+    /// keep results inside the bounds. See [`ProjectHook`].
+    fn project(&mut self, _theta: &mut TParams, _env: &Env) {}
+    /// (Optional) read or write the shared [`Signals`] before each simulate.
+    fn on_signals(&mut self, _signals: &mut Signals) {}
+    /// (Optional) internal state to persist with a save (default: none, the
+    /// hook is stateless). Implement [`HookState`] and forward to
+    /// [`HookState::save`].
+    #[cfg(feature = "serde")]
+    fn save_state(&self) -> Option<serde_json::Value> {
+        None
+    }
+    /// (Optional) restore what `save_state` wrote (default: ignore it).
+    /// Forward to [`HookState::load`].
+    #[cfg(feature = "serde")]
+    fn load_state(&mut self, _state: serde_json::Value) -> Result<(), crate::Error> {
+        Ok(())
+    }
+}
+
+/// A boxed hook is a hook, so a stack taken back out of a run (a
+/// [`WarmStart`]) can be re-boxed as a system's own hook object type.
+impl<TParams, Env, Tgt, Obs, H> Hook<TParams, Env, Tgt, Obs> for Box<H>
+where
+    H: Hook<TParams, Env, Tgt, Obs> + ?Sized,
+{
+    fn income_multiplier(&mut self, base_income: f64, theta: &TParams, env: &Env) -> f64 {
+        (**self).income_multiplier(base_income, theta, env)
+    }
+    fn multiplier_mode(&self) -> MultMode {
+        (**self).multiplier_mode()
+    }
+    fn cost_multiplier(&mut self, theta: &TParams, env: &Env) -> f64 {
+        (**self).cost_multiplier(theta, env)
+    }
+    fn flat_income(&mut self, theta: &TParams, env: &Env) -> f64 {
+        (**self).flat_income(theta, env)
+    }
+    fn priority(&self) -> i32 {
+        (**self).priority()
+    }
+    fn on_observe(&mut self, obs: &Obs, theta: &TParams, env: &Env, tgt: &Tgt) {
+        (**self).on_observe(obs, theta, env, tgt)
+    }
+    fn adjust_targets(&mut self, theta: &TParams, env: &Env, tgt: &Tgt, nom: &NominalTargets) -> TargetAdjust {
+        (**self).adjust_targets(theta, env, tgt, nom)
+    }
+    fn adjust_mode(&self) -> MultMode {
+        (**self).adjust_mode()
+    }
+    fn post_step(&mut self, theta_next: &mut TParams, env: &Env) {
+        (**self).post_step(theta_next, env)
+    }
+    fn project(&mut self, theta: &mut TParams, env: &Env) {
+        (**self).project(theta, env)
+    }
+    fn on_signals(&mut self, signals: &mut Signals) {
+        (**self).on_signals(signals)
+    }
+    #[cfg(feature = "serde")]
+    fn save_state(&self) -> Option<serde_json::Value> {
+        (**self).save_state()
+    }
+    #[cfg(feature = "serde")]
+    fn load_state(&mut self, state: serde_json::Value) -> Result<(), crate::Error> {
+        (**self).load_state(state)
+    }
+}
+
+/// Typed state of a stateful hook (pity counters, drafted multipliers),
+/// bridged to the object-safe [`Hook::save_state`] / [`Hook::load_state`]:
+///
+/// ```ignore
+/// impl HookState for Pity {
+///     type State = u32;
+///     fn state(&self) -> u32 { self.misses }
+///     fn restore(&mut self, s: u32) { self.misses = s; }
+/// }
+/// impl Hook<…> for Pity {
+///     fn save_state(&self) -> Option<serde_json::Value> { HookState::save(self) }
+///     fn load_state(&mut self, v: serde_json::Value) -> Result<(), Error> { HookState::load(self, v) }
+/// }
+/// ```
+#[cfg(feature = "serde")]
+pub trait HookState {
+    type State: serde::Serialize + serde::de::DeserializeOwned;
+
+    fn state(&self) -> Self::State;
+    fn restore(&mut self, state: Self::State);
+
+    fn save(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(self.state()).ok()
+    }
+    fn load(&mut self, state: serde_json::Value) -> Result<(), crate::Error> {
+        let s = serde_json::from_value(state).map_err(|e| crate::Error::Json(e.to_string()))?;
+        self.restore(s);
+        Ok(())
+    }
+}
+
+/// Save a hook stack's state as a JSON array, one entry per hook in slice
+/// order (`null` for stateless hooks).
+#[cfg(feature = "serde")]
+pub fn save_hook_states<TParams, Env, Tgt, Obs, H>(hooks: &[H]) -> Result<String, crate::Error>
+where
+    H: Hook<TParams, Env, Tgt, Obs>,
+{
+    let states: Vec<_> = hooks.iter().map(|h| h.save_state().unwrap_or(serde_json::Value::Null)).collect();
+    serde_json::to_string(&states).map_err(|e| crate::Error::Json(e.to_string()))
+}
+
+/// Restore [`save_hook_states`] output into a freshly built stack of the
+/// same hooks in the same order; `null` entries are skipped.
+#[cfg(feature = "serde")]
+pub fn load_hook_states<TParams, Env, Tgt, Obs, H>(hooks: &mut [H], json: &str) -> Result<(), crate::Error>
+where
+    H: Hook<TParams, Env, Tgt, Obs>,
+{
+    let states: Vec<serde_json::Value> = serde_json::from_str(json).map_err(|e| crate::Error::Json(e.to_string()))?;
+    if states.len() != hooks.len() {
+        return Err(crate::Error::Json(format!("{} hook states for {} hooks", states.len(), hooks.len())));
+    }
+    for (h, s) in hooks.iter_mut().zip(states) {
+        if !s.is_null() {
+            h.load_state(s)?;
+        }
+    }
+    Ok(())
+}
+
+/// Hook that applies a [`crate::Project`] constraint after every step, e.g.
+/// `ProjectHook(|th: ps::Params| ps::Params { spend_rate: th.spend_rate.min(th.gen_per_sec * th.multiplier), ..th })`.
+pub struct ProjectHook<P>(pub P);
+
+impl<TParams: Clone, Env, Tgt, Obs, P: crate::Project<TParams>> Hook<TParams, Env, Tgt, Obs> for ProjectHook<P> {
+    fn project(&mut self, theta: &mut TParams, _env: &Env) {
+        *theta = self.0.project(theta.clone());
+    }
+}
+
+/// `controller.step` with the move capped at `max_step` per iteration.
+/// When the cap bites, the controller's memory is treated as it is at
+/// `bounds` (PID integral frozen, momentum reset), so a slew-limited loop
+/// does not wind up while it catches up.
+pub fn step_limited(
+    controller: &control::Controller,
+    st: &mut control::ControllerState,
+    x: f64,
+    target: f64,
+    g: f64,
+    bounds: (f64, f64),
+    max_step: f64,
+) -> f64 {
+    let (lo, hi) = bounds;
+    let integral = st.integral;
+    let next = controller.step(st, x, target, g, lo, hi);
+    let d = max_step.max(0.0);
+    let (lo_s, hi_s) = ((x - d).max(lo), (x + d).min(hi));
+    if lo_s > hi_s {
+        // x sits outside `bounds`; let the controller pull it back in.
+        return next;
+    }
+    let capped = next.clamp(lo_s, hi_s);
+    if capped != next {
+        st.integral = integral;
+        st.velocity = 0.0;
+    }
+    capped
+}
+
+/// Hook that caps how far each θ coordinate may move per iteration:
+/// `|Δ| ≤ max(max_rel · |θ|, min_step)`, a relative slew limit with an
+/// absolute floor for coordinates near zero. Applied in
+/// `post_step`, so controllers don't see it; prefer [`step_limited`] for
+/// stateful controllers.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    pub max_rel: f64,
+    pub min_step: f64,
+    last: Vec<f64>,
+}
+
+impl RateLimiter {
+    pub fn new(max_rel: f64, min_step: f64) -> Self {
+        Self { max_rel, min_step, last: Vec::new() }
+    }
+
+    /// `next` pulled to within the allowed step of `x`.
+    pub fn limit(&self, x: f64, next: f64) -> f64 {
+        let d = (self.max_rel * x.abs()).max(self.min_step).max(0.0);
+        next.clamp(x - d, x + d)
+    }
+}
+
+impl<TParams: Flat, Env, Tgt, Obs> Hook<TParams, Env, Tgt, Obs> for RateLimiter {
+    fn on_observe(&mut self, _obs: &Obs, theta: &TParams, _env: &Env, _tgt: &Tgt) {
+        self.last = theta.flatten();
+    }
+    fn post_step(&mut self, theta_next: &mut TParams, _env: &Env) {
+        let next = theta_next.flatten();
+        if next.len() != self.last.len() {
+            return;
+        }
+        let limited: Vec<f64> = self.last.iter().zip(&next).map(|(&x, &n)| self.limit(x, n)).collect();
+        *theta_next = theta_next.unflatten(&limited);
+    }
+}
+
+/// Apply every hook's income bonus and factor to `base_income`:
+/// `(base + Σ flat) · (1 + Σ additive bonuses) · Π multiplicative factors`.
+///
+/// Each hook is asked once per method, in slice order (the harness has
+/// already sorted by [`Hook::priority`]), and `income_multiplier` sees the
+/// income composed so far (so an all-multiplicative stack chains exactly as
+/// before).
+pub fn compose_income<TParams, Env, Tgt, Obs>(
+    base_income: f64,
+    hooks: &mut [Box<dyn Hook<TParams, Env, Tgt, Obs>>],
+    theta: &TParams,
+    env: &Env,
+) -> f64 {
+    let flat: f64 = hooks.iter_mut().map(|h| h.flat_income(theta, env)).sum();
+    let base = (base_income.max(0.0) + flat).max(0.0);
+    let (mut additive, mut product): (f64, f64) = (0.0, 1.0);
+    for h in hooks.iter_mut() {
+        let current = base * (1.0 + additive).max(0.0) * product;
+        let m = h.income_multiplier(current, theta, env).max(0.0);
+        match h.multiplier_mode() {
+            MultMode::Multiplicative => product *= m,
+            MultMode::Additive => additive += m - 1.0,
+        }
+    }
+    base * (1.0 + additive).max(0.0) * product
+}
+
+/// Apply every hook's cost factor to `base_cost`: `base · Π factors`
+/// (negative factors floor at 0). Each hook is asked once, in slice order.
+pub fn compose_cost<TParams, Env, Tgt, Obs>(
+    base_cost: f64,
+    hooks: &mut [Box<dyn Hook<TParams, Env, Tgt, Obs>>],
+    theta: &TParams,
+    env: &Env,
+) -> f64 {
+    hooks.iter_mut().fold(base_cost, |c, h| c * h.cost_multiplier(theta, env).max(0.0))
+}
+
+/// Running [`TargetAdjust`] composition: `(1 + Σ additive) · Π multiplicative`
+/// per component, negatives floored at 0.
+#[derive(Clone, Copy)]
+struct AdjustAcc {
+    additive: TargetAdjust,
+    product: TargetAdjust,
+}
+
+impl AdjustAcc {
+    fn new() -> Self {
+        Self { additive: TargetAdjust { a: 0.0, b: 0.0, c: 0.0 }, product: TargetAdjust::id() }
+    }
+
+    fn push(&mut self, s: TargetAdjust, mode: MultMode) {
+        let (a, b, c) = (s.a.max(0.0), s.b.max(0.0), s.c.max(0.0));
+        match mode {
+            MultMode::Multiplicative => {
+                self.product.a *= a;
+                self.product.b *= b;
+                self.product.c *= c;
+            }
+            MultMode::Additive => {
+                self.additive.a += a - 1.0;
+                self.additive.b += b - 1.0;
+                self.additive.c += c - 1.0;
+            }
+        }
+    }
+
+    fn total(&self) -> TargetAdjust {
+        TargetAdjust {
+            a: (1.0 + self.additive.a).max(0.0) * self.product.a,
+            b: (1.0 + self.additive.b).max(0.0) * self.product.b,
+            c: (1.0 + self.additive.c).max(0.0) * self.product.c,
+        }
+    }
+}
+
+/// Compose every hook's `adjust_targets` by its `adjust_mode`, in slice
+/// order: additive scales sum their `s − 1`, multiplicative ones chain.
+pub fn compose_adjust<TParams, Env, Tgt, Obs>(
+    hooks: &mut [Box<dyn Hook<TParams, Env, Tgt, Obs>>],
+    theta: &TParams,
+    env: &Env,
+    tgt: &Tgt,
+    nom: &NominalTargets,
+) -> TargetAdjust {
+    let mut acc = AdjustAcc::new();
+    for h in hooks.iter_mut() {
+        let s = h.adjust_targets(theta, env, tgt, nom);
+        acc.push(s, h.adjust_mode());
+    }
+    acc.total()
+}
+
+/// Stable sort by [`Hook::priority`], the order the harness runs hooks in.
+pub fn sort_hooks<TParams, Env, Tgt, Obs>(hooks: &mut [Box<dyn Hook<TParams, Env, Tgt, Obs>>]) {
+    hooks.sort_by_key(|h| h.priority());
+}
+
+type SharedBox<TParams, Env, Tgt, Obs> = Rc<RefCell<Box<dyn Hook<TParams, Env, Tgt, Obs>>>>;
+
+/// A handle to a hook owned by a [`HookRegistry`]. It forwards every
+/// [`Hook`] method, so the same hook (and its internal state) serves
+/// several runs without being cloned.
+pub struct SharedHook<TParams, Env, Tgt, Obs>(SharedBox<TParams, Env, Tgt, Obs>);
+
+impl<TParams, Env, Tgt, Obs> Hook<TParams, Env, Tgt, Obs> for SharedHook<TParams, Env, Tgt, Obs> {
+    fn income_multiplier(&mut self, base_income: f64, theta: &TParams, env: &Env) -> f64 {
+        self.0.borrow_mut().income_multiplier(base_income, theta, env)
+    }
+    fn multiplier_mode(&self) -> MultMode {
+        self.0.borrow().multiplier_mode()
+    }
+    fn cost_multiplier(&mut self, theta: &TParams, env: &Env) -> f64 {
+        self.0.borrow_mut().cost_multiplier(theta, env)
+    }
+    fn flat_income(&mut self, theta: &TParams, env: &Env) -> f64 {
+        self.0.borrow_mut().flat_income(theta, env)
+    }
+    fn priority(&self) -> i32 {
+        self.0.borrow().priority()
+    }
+    fn on_observe(&mut self, obs: &Obs, theta: &TParams, env: &Env, tgt: &Tgt) {
+        self.0.borrow_mut().on_observe(obs, theta, env, tgt)
+    }
+    fn adjust_targets(&mut self, theta: &TParams, env: &Env, tgt: &Tgt, nom: &NominalTargets) -> TargetAdjust {
+        self.0.borrow_mut().adjust_targets(theta, env, tgt, nom)
+    }
+    fn adjust_mode(&self) -> MultMode {
+        self.0.borrow().adjust_mode()
+    }
+    fn post_step(&mut self, theta_next: &mut TParams, env: &Env) {
+        self.0.borrow_mut().post_step(theta_next, env)
+    }
+    fn project(&mut self, theta: &mut TParams, env: &Env) {
+        self.0.borrow_mut().project(theta, env)
+    }
+    fn on_signals(&mut self, signals: &mut Signals) {
+        self.0.borrow_mut().on_signals(signals)
+    }
+    #[cfg(feature = "serde")]
+    fn save_state(&self) -> Option<serde_json::Value> {
+        self.0.borrow().save_state()
+    }
+    #[cfg(feature = "serde")]
+    fn load_state(&mut self, state: serde_json::Value) -> Result<(), crate::Error> {
+        self.0.borrow_mut().load_state(state)
+    }
+}
+
+struct RegistryEntry<TParams, Env, Tgt, Obs> {
+    id: String,
+    enabled: bool,
+    hook: SharedBox<TParams, Env, Tgt, Obs>,
+}
+
+/// Hooks under string IDs, kept across runs so they can be inspected,
+/// toggled or replaced between outer genre passes. Each pass takes
+/// [`HookRegistry::active`] (enabled hooks, registration order) as its
+/// hook stack.
+pub struct HookRegistry<TParams, Env, Tgt, Obs> {
+    entries: Vec<RegistryEntry<TParams, Env, Tgt, Obs>>,
+}
+
+impl<TParams, Env, Tgt, Obs> Default for HookRegistry<TParams, Env, Tgt, Obs> {
+    fn default() -> Self {
+        Self { entries: Vec::new() }
+    }
+}
+
+impl<TParams, Env, Tgt, Obs> HookRegistry<TParams, Env, Tgt, Obs> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `hook` under `id`, enabled. An existing hook with that ID
+    /// is replaced in place (keeping its position and enabled flag);
+    /// returns `true` in that case.
+    pub fn register(&mut self, id: impl Into<String>, hook: Box<dyn Hook<TParams, Env, Tgt, Obs>>) -> bool {
+        let id = id.into();
+        match self.entries.iter_mut().find(|e| e.id == id) {
+            Some(e) => {
+                e.hook = Rc::new(RefCell::new(hook));
+                true
+            }
+            None => {
+                self.entries.push(RegistryEntry { id, enabled: true, hook: Rc::new(RefCell::new(hook)) });
+                false
+            }
+        }
+    }
+
+    /// Remove `id`; `false` if it was not registered.
+    pub fn remove(&mut self, id: &str) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| e.id != id);
+        self.entries.len() != before
+    }
+
+    /// Enable or disable `id`; `false` if it was not registered.
+    pub fn set_enabled(&mut self, id: &str, enabled: bool) -> bool {
+        match self.entries.iter_mut().find(|e| e.id == id) {
+            Some(e) => {
+                e.enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// `None` if `id` is not registered.
+    pub fn is_enabled(&self, id: &str) -> Option<bool> {
+        self.entries.iter().find(|e| e.id == id).map(|e| e.enabled)
+    }
+
+    /// `(id, enabled)` in registration order.
+    pub fn ids(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.entries.iter().map(|e| (e.id.as_str(), e.enabled))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Handles to the enabled hooks, in registration order. Box them as
+    /// whatever hook trait object the system takes (e.g. `ps::Mechanic`).
+    pub fn handles(&self) -> impl Iterator<Item = SharedHook<TParams, Env, Tgt, Obs>> + '_ {
+        self.entries.iter().filter(|e| e.enabled).map(|e| SharedHook(Rc::clone(&e.hook)))
+    }
+}
+
+impl<TParams: 'static, Env: 'static, Tgt: 'static, Obs: 'static> HookRegistry<TParams, Env, Tgt, Obs> {
+    /// The enabled hooks as a stack for [`balance_with_hooks`].
+    pub fn active(&self) -> Vec<Box<dyn Hook<TParams, Env, Tgt, Obs>>> {
+        self.handles().map(|h| Box::new(h) as Box<dyn Hook<_, _, _, _>>).collect()
+    }
+}
+
+/// One hook's individual contribution, as reported by [`audit_hooks`].
+#[derive(Clone, Copy, Debug)]
+pub struct HookContribution {
+    pub flat_income: f64,
+    pub income_multiplier: f64,
+    pub mode: MultMode,
+    pub cost_multiplier: f64,
+    pub adjust: TargetAdjust,
+    pub adjust_mode: MultMode,
+    pub priority: i32,
+}
+
+/// Per-hook contributions plus the composed totals the harness would apply.
+#[derive(Clone, Debug)]
+pub struct HookAudit {
+    /// In the order given (not the run order).
+    pub per_hook: Vec<HookContribution>,
+    /// Sum of flat bonuses, added to the base income.
+    pub flat_income: f64,
+    pub income_multiplier: f64,
+    pub cost_multiplier: f64,
+    pub adjust: TargetAdjust,
+}
+
+/// Debug pass over a hook stack without running the loop: asks each hook
+/// for its flat bonus, income multiplier (composed from `base_income` as in
+/// [`compose_income`]), cost factor and target adjustment, and composes them the way
+/// the harness does, in priority order. Each hook method is called exactly
+/// once.
+pub fn audit_hooks<TParams, Env, Tgt, Obs>(
+    hooks: &mut [Box<dyn Hook<TParams, Env, Tgt, Obs>>],
+    base_income: f64,
+    theta: &TParams,
+    env: &Env,
+    tgt: &Tgt,
+    nom: &NominalTargets,
+) -> HookAudit {
+    let mut order: Vec<usize> = (0..hooks.len()).collect();
+    order.sort_by_key(|&i| hooks[i].priority());
+    let flats: Vec<f64> = hooks.iter_mut().map(|h| h.flat_income(theta, env)).collect();
+    let flat_income: f64 = flats.iter().sum();
+    let base = (base_income.max(0.0) + flat_income).max(0.0);
+    let (mut additive, mut product): (f64, f64) = (0.0, 1.0);
+    let mut cost_multiplier = 1.0;
+    let mut acc = AdjustAcc::new();
+    let mut per_hook: Vec<Option<HookContribution>> = vec![None; hooks.len()];
+    for i in order {
+        let h = &mut hooks[i];
+        let current = base * (1.0 + additive).max(0.0) * product;
+        let m = h.income_multiplier(current, theta, env).max(0.0);
+        let mode = h.multiplier_mode();
+        match mode {
+            MultMode::Multiplicative => product *= m,
+            MultMode::Additive => additive += m - 1.0,
+        }
+        let c = h.cost_multiplier(theta, env).max(0.0);
+        cost_multiplier *= c;
+        let s = h.adjust_targets(theta, env, tgt, nom);
+        let adjust_mode = h.adjust_mode();
+        acc.push(s, adjust_mode);
+        per_hook[i] = Some(HookContribution {
+            flat_income: flats[i],
+            income_multiplier: m,
+            mode,
+            cost_multiplier: c,
+            adjust: s,
+            adjust_mode,
+            priority: h.priority(),
+        });
+    }
+    let income_multiplier = (1.0 + additive).max(0.0) * product;
+    HookAudit {
+        per_hook: per_hook.into_iter().flatten().collect(),
+        flat_income,
+        income_multiplier,
+        cost_multiplier,
+        adjust: acc.total(),
+    }
+}
+
+/// An acceptance band around a target, for `converged` checks via [`within`].
+pub trait Tolerance {
+    fn admits(&self, obs: f64, target: f64) -> bool;
+
+    /// Admit what either band admits, e.g. `RelTol(0.02).or(AbsTol(0.02))`
+    /// is ±2% but never narrower than ±0.02.
+    fn or<T: Tolerance>(self, other: T) -> Or<Self, T>
+    where
+        Self: Sized,
+    {
+        Or(self, other)
+    }
+}
+
+/// `|obs − target| ≤ tol`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AbsTol(pub f64);
+
+/// `|obs − target| ≤ tol · |target|`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RelTol(pub f64);
+
+/// Either of two tolerances; see [`Tolerance::or`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Or<A, B>(pub A, pub B);
+
+impl Tolerance for AbsTol {
+    fn admits(&self, obs: f64, target: f64) -> bool {
+        (obs - target).abs() <= self.0
+    }
+}
+
+impl Tolerance for RelTol {
+    fn admits(&self, obs: f64, target: f64) -> bool {
+        (obs - target).abs() <= self.0 * target.abs()
+    }
+}
+
+impl<A: Tolerance, B: Tolerance> Tolerance for Or<A, B> {
+    fn admits(&self, obs: f64, target: f64) -> bool {
+        self.0.admits(obs, target) || self.1.admits(obs, target)
+    }
+}
+
+/// `obs` is on `target` up to `tol`.
+pub fn within(obs: f64, target: f64, tol: impl Tolerance) -> bool {
+    tol.admits(obs, target)
+}
+
+/// A closed range of acceptable values, such as a TTU band in seconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Band {
+    pub lo: f64,
+    pub hi: f64,
+}
+
+impl Band {
+    pub fn contains(&self, x: f64) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+}
+
+impl From<(f64, f64)> for Band {
+    fn from((lo, hi): (f64, f64)) -> Self {
+        Self { lo, hi }
+    }
+}
+
+/// One named target at the final `obs`, in the target's own units, so a
+/// failed run shows *which* target it missed and by how much.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Residual {
+    pub name: String,
+    pub obs: f64,
+    pub target: f64,
+}
+
+impl Residual {
+    pub fn new(name: impl Into<String>, obs: f64, target: f64) -> Self {
+        Self { name: name.into(), obs, target }
+    }
+
+    /// Signed miss, `obs − target`.
+    pub fn miss(&self) -> f64 {
+        self.obs - self.target
+    }
+}
+
+/// One target's term in a [`ConvergenceScore`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TargetScore {
+    pub name: String,
+    /// Unitless miss, e.g. `obs / target − 1`.
+    pub residual: f64,
+    pub weight: f64,
+}
+
+impl TargetScore {
+    /// `weight · residual²`, this target's share of the total.
+    pub fn contribution(&self) -> f64 {
+        self.weight.max(0.0) * self.residual * self.residual
+    }
+}
+
+/// Weighted aggregate of per-target residuals: converged once
+/// `Σ weight · residual² ≤ threshold`. Unlike an all-or-nothing band, a
+/// run can trade a small TTU miss against a large util one, and the terms
+/// show which target is holding it back.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConvergenceScore {
+    pub terms: Vec<TargetScore>,
+    pub threshold: f64,
+}
+
+impl ConvergenceScore {
+    pub fn new(threshold: f64) -> Self {
+        Self { terms: Vec::new(), threshold }
+    }
+
+    /// Add a named target's residual and weight.
+    pub fn with(mut self, name: impl Into<String>, residual: f64, weight: f64) -> Self {
+        self.terms.push(TargetScore { name: name.into(), residual, weight });
+        self
+    }
+
+    pub fn total(&self) -> f64 {
+        self.terms.iter().map(TargetScore::contribution).sum()
+    }
+
+    pub fn converged(&self) -> bool {
+        self.total() <= self.threshold
+    }
+
+    /// The term contributing most to the total.
+    pub fn worst(&self) -> Option<&TargetScore> {
+        self.terms.iter().max_by(|a, b| a.contribution().total_cmp(&b.contribution()))
+    }
 }
 
 /// Generic result.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Outcome<TParams, Obs> {
     pub theta: TParams,
     pub obs: Obs,
     pub iters: usize,
     pub converged: bool,
+    /// After converging, the loop kept stepping for a hold phase and `obs`
+    /// never left the band. Tells a real equilibrium from a trajectory that
+    /// merely passed through the band. The hold phase is opt-in (see
+    /// [`BalanceArena::with_hold_iters`]); without it this equals
+    /// `converged`.
+    pub stable_after_converge: bool,
+    /// `Obs` of each iteration, oldest first, if the run was traced (see
+    /// [`ObsTrace`]); empty (and unallocated) otherwise.
+    pub trace: Vec<Obs>,
+    /// Set when the loop stopped early because θ was cycling instead of
+    /// settling (`converged` is then `false`).
+    pub oscillation: Option<Oscillation>,
+    /// The run's wall-clock budget ran out before convergence (see
+    /// `BalanceArena::with_budget`); `theta` is where the loop had got to.
+    pub budget_exhausted: bool,
+    /// Per-target breakdown for `obs`, from systems that converge on a
+    /// [`ConvergenceScore`] ([`balance_scored`], [`System::score`]).
+    pub score: Option<ConvergenceScore>,
+    /// Each named target against `obs` ([`System::residuals`]; built-in
+    /// systems fill it in `balance_ext`). Empty if the system names none
+    /// or no iteration ran.
+    pub residuals: Vec<Residual>,
+}
+
+/// A limit cycle in θ: the last [`CHATTER_REPEATS`] periods repeated each
+/// other to within a relative [`CHATTER_RTOL`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Oscillation {
+    /// Iterations per cycle (2 = θ alternates between two values).
+    pub period: usize,
+    /// Half the peak-to-peak swing of the coordinate that swings most.
+    pub amplitude: f64,
+    /// That coordinate's index in `θ.flatten()`.
+    pub coord: usize,
+}
+
+/// Longest cycle the harness looks for.
+pub const CHATTER_MAX_PERIOD: usize = 8;
+/// Consecutive matching periods needed before calling it a cycle.
+pub const CHATTER_REPEATS: usize = 3;
+/// Relative tolerance for two θ to count as the same point in a cycle.
+pub const CHATTER_RTOL: f64 = 1e-6;
+
+/// `3214` → `"3,214"`.
+fn group_thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Up to four significant digits, trailing zeros dropped (`29.70` → `29.7`).
+fn short_num(x: f64) -> String {
+    if x == 0.0 || !x.is_finite() {
+        return x.to_string();
+    }
+    let decimals = (3 - x.abs().log10().floor() as i32).clamp(0, 12) as usize;
+    let s = format!("{x:.decimals$}");
+    if s.contains('.') { s.trim_end_matches('0').trim_end_matches('.').to_string() } else { s }
+}
+
+/// What the next run needs to pick up where one left off: the final θ and
+/// the hooks with whatever they cached (smoothed metrics, counters). Pass
+/// `theta` as `theta0` and `hooks` as the hook stack.
+pub struct WarmStart<TParams, Env, Tgt, Obs> {
+    pub theta: TParams,
+    pub hooks: Vec<Box<dyn Hook<TParams, Env, Tgt, Obs>>>,
+}
+
+impl<TParams, Obs> Outcome<TParams, Obs> {
+    /// Fill [`Outcome::residuals`] from the final `obs` (left empty if no
+    /// iteration ran). For closure-based systems, whose harness call has no
+    /// names for its targets.
+    pub fn with_residuals(mut self, residuals: impl FnOnce(&Obs) -> Vec<Residual>) -> Self {
+        self.residuals = if self.iters > 0 { residuals(&self.obs) } else { Vec::new() };
+        self
+    }
+
+    /// The residual named `name`, if the system reported one.
+    pub fn residual(&self, name: &str) -> Option<&Residual> {
+        self.residuals.iter().find(|r| r.name == name)
+    }
+
+    /// One-line plain-text summary for logs and CLI output, e.g.
+    /// `converged in 3,214 iters; ttu 29.7 vs target 30; util 0.89 vs 0.9`.
+    /// Lists [`Outcome::residuals`] (empty unless the system names its
+    /// targets) and, for scored runs, the score against its threshold.
+    pub fn explain(&self) -> String {
+        let iters = group_thousands(self.iters);
+        let mut out = if let Some(osc) = &self.oscillation {
+            format!(
+                "oscillating after {iters} iters (period {}, θ[{}] ±{})",
+                osc.period,
+                osc.coord,
+                short_num(osc.amplitude)
+            )
+        } else if self.budget_exhausted {
+            format!("time budget exhausted after {iters} iters")
+        } else if !self.converged {
+            format!("not converged after {iters} iters")
+        } else if self.stable_after_converge {
+            format!("converged in {iters} iters")
+        } else {
+            format!("converged in {iters} iters, drifted out during hold")
+        };
+        for r in &self.residuals {
+            out += &format!("; {} {} vs target {}", r.name, short_num(r.obs), short_num(r.target));
+        }
+        if let Some(score) = &self.score {
+            out += &format!("; score {} (threshold {})", short_num(score.total()), short_num(score.threshold));
+        }
+        out
+    }
+
+    /// θ from this outcome plus the hooks of the run that produced it,
+    /// taken from `arena` (see [`BalanceArena::take_hooks`]). Runs outside an
+    /// arena drop their hooks, so only arena runs can be warm-started with
+    /// hook state.
+    pub fn into_warm_start<Env, Tgt>(
+        self,
+        arena: &mut BalanceArena<TParams, Env, Tgt, Obs>,
+    ) -> WarmStart<TParams, Env, Tgt, Obs> {
+        WarmStart { theta: self.theta, hooks: arena.take_hooks() }
+    }
+}
+
+/// Opt-in per-iteration observation history for [`Outcome::trace`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ObsTrace {
+    #[default]
+    Off,
+    /// Every iteration.
+    Full,
+    /// Ring buffer of the last `n` iterations.
+    Last(usize),
+}
+
+/// Schema version written by [`Outcome::to_json`]. History:
+/// 1 — unversioned; no `stable_after_converge`.
+/// 2 — `schema_version` field; adds `stable_after_converge`.
+/// 3 — adds `trace`.
+/// 4 — adds `oscillation`.
+/// 5 — adds `budget_exhausted`.
+/// 6 — adds `score`.
+/// 7 — adds `residuals`.
+#[cfg(feature = "serde")]
+pub const OUTCOME_SCHEMA_VERSION: u64 = 7;
+
+#[cfg(feature = "serde")]
+impl<TParams: serde::Serialize, Obs: serde::Serialize> Outcome<TParams, Obs> {
+    /// JSON with a `schema_version` field, readable by [`Outcome::migrate`].
+    pub fn to_json(&self) -> Result<String, crate::Error> {
+        let mut v = serde_json::to_value(self).map_err(|e| crate::Error::Json(e.to_string()))?;
+        if let Some(obj) = v.as_object_mut() {
+            obj.insert("schema_version".into(), OUTCOME_SCHEMA_VERSION.into());
+        }
+        Ok(v.to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<TParams: serde::de::DeserializeOwned, Obs: serde::de::DeserializeOwned> Outcome<TParams, Obs> {
+    /// Load an `Outcome` saved by this or an older crate version, upgrading
+    /// the layout step by step. Missing `schema_version` means version 1;
+    /// newer versions are rejected rather than silently mis-read.
+    ///
+    /// v1 → v2: `stable_after_converge = false` (the hold phase never ran).
+    /// v2 → v3: `trace = []`.
+    /// v3 → v4: `oscillation = null`.
+    /// v4 → v5: `budget_exhausted = false`.
+    /// v5 → v6: `score = null`.
+    /// v6 → v7: `residuals = []`.
+    pub fn migrate(json: &str) -> Result<Self, crate::Error> {
+        use crate::Error;
+        let mut v: serde_json::Value = serde_json::from_str(json).map_err(|e| Error::Json(e.to_string()))?;
+        let obj = v.as_object_mut().ok_or_else(|| Error::Json("expected a JSON object".into()))?;
+        let found = match obj.remove("schema_version") {
+            None => 1,
+            Some(n) => n.as_u64().ok_or_else(|| Error::Json(format!("bad schema_version {n}")))?,
+        };
+        if found > OUTCOME_SCHEMA_VERSION {
+            return Err(Error::SchemaVersion { found, supported: OUTCOME_SCHEMA_VERSION });
+        }
+        if found < 2 {
+            obj.insert("stable_after_converge".into(), false.into());
+        }
+        if found < 3 {
+            obj.insert("trace".into(), serde_json::Value::Array(Vec::new()));
+        }
+        if found < 4 {
+            obj.insert("oscillation".into(), serde_json::Value::Null);
+        }
+        if found < 5 {
+            obj.insert("budget_exhausted".into(), false.into());
+        }
+        if found < 6 {
+            obj.insert("score".into(), serde_json::Value::Null);
+        }
+        if found < 7 {
+            obj.insert("residuals".into(), serde_json::Value::Array(Vec::new()));
+        }
+        serde_json::from_value(v).map_err(|e| Error::Json(e.to_string()))
+    }
+}
+
+/// Hold-phase length used by [`balance_with_hooks`]: none, so a run stops
+/// at convergence and leaves hooks and signals as they were then. Opt in
+/// with [`BalanceArena::with_hold_iters`]; see
+/// [`Outcome::stable_after_converge`].
+pub const DEFAULT_HOLD_ITERS: usize = 0;
+
+/// A hook for a [`System`] `S`.
+pub type SystemHook<S> =
+    Box<dyn Hook<<S as System>::Params, <S as System>::Env, <S as System>::Tgt, <S as System>::Obs>>;
+/// Hooks for a [`System`] `S`.
+pub type SystemHooks<S> = Vec<SystemHook<S>>;
+
+/// A balancing system as a value: the four harness steps as methods, with
+/// bounds and gains (controller settings) held by the implementor. Object
+/// safe, so registries can hold `Box<dyn System<Params = …, …>>`, and tests
+/// can substitute a mock. Run one with [`balance_system`].
+pub trait System {
+    type Params: Clone + Flat;
+    type Env;
+    type Tgt;
+    type Obs: Clone + Default;
+
+    /// Shown in registries and reports (default: the Rust type name).
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+    /// Observables for `theta`; hooks may modulate inputs.
+    fn simulate(
+        &mut self,
+        theta: &Self::Params,
+        env: &Self::Env,
+        tgt: &Self::Tgt,
+        hooks: &mut [SystemHook<Self>],
+    ) -> Self::Obs;
+    /// Pre-update controller targets.
+    fn nominal(&mut self, theta: &Self::Params, env: &Self::Env, tgt: &Self::Tgt, obs: &Self::Obs) -> NominalTargets;
+    /// Move θ toward the (adjusted) targets, within the system's bounds.
+    fn step(&mut self, theta: &Self::Params, nom: NominalTargets, adj: TargetAdjust) -> Self::Params;
+    /// Acceptance band.
+    fn converged(&mut self, obs: &Self::Obs, tgt: &Self::Tgt) -> bool;
+    /// (Optional) weighted per-target score. When `Some`, the harness
+    /// converges on [`ConvergenceScore::converged`] instead of `converged`
+    /// and reports the final score on [`Outcome::score`] (default: `None`).
+    fn score(&mut self, _obs: &Self::Obs, _tgt: &Self::Tgt) -> Option<ConvergenceScore> {
+        None
+    }
+    /// (Optional) named targets vs `obs`, reported on
+    /// [`Outcome::residuals`] for the final `obs` (default: none).
+    fn residuals(&mut self, _obs: &Self::Obs, _tgt: &Self::Tgt) -> Vec<Residual> {
+        Vec::new()
+    }
+}
+
+
+/// Run `sys` from `theta0`: the same loop, hooks and hold phase as
+/// [`balance_with_hooks`].
+pub fn balance_system<S: System + ?Sized>(
+    sys: &mut S,
+    theta0: S::Params,
+    env: S::Env,
+    tgt: S::Tgt,
+    hooks: SystemHooks<S>,
+    max_iters: usize,
+) -> Outcome<S::Params, S::Obs> {
+    let mut scratch = Scratch::new(RunConfig::default());
+    scratch.reset(hooks);
+    run(&mut scratch, sys, theta0, &env, &tgt, max_iters)
+}
+
+/// Run `sys` once per target in `grid`, each from `theta0` without hooks,
+/// and return the outcomes in grid order — a table of how θ moves as one
+/// target scans a range (see [`linspace`]). For systems driven by
+/// `balance_ext` or a `Runner`, use [`sweep_with`].
+pub fn sweep<S: System + ?Sized>(
+    sys: &mut S,
+    theta0: &S::Params,
+    env: &S::Env,
+    grid: impl IntoIterator<Item = S::Tgt>,
+    max_iters: usize,
+) -> Vec<Outcome<S::Params, S::Obs>>
+where
+    S::Env: Clone,
+{
+    grid.into_iter().map(|tgt| balance_system(sys, theta0.clone(), env.clone(), tgt, Vec::new(), max_iters)).collect()
+}
+
+/// [`sweep`] on rayon's pool (`parallel` feature); each grid point runs on
+/// its own clone of `sys`, so results match the sequential sweep.
+#[cfg(feature = "parallel")]
+pub fn sweep_par<S>(
+    sys: &S,
+    theta0: &S::Params,
+    env: &S::Env,
+    grid: Vec<S::Tgt>,
+    max_iters: usize,
+) -> Vec<Outcome<S::Params, S::Obs>>
+where
+    S: System + Clone + Sync,
+    S::Params: Send + Sync,
+    S::Env: Clone + Sync,
+    S::Tgt: Send,
+    S::Obs: Send,
+{
+    sweep_with_par(grid, |tgt| balance_system(&mut sys.clone(), theta0.clone(), env.clone(), tgt, Vec::new(), max_iters))
+}
+
+/// One `run` per grid point, in order: the sweep for any runner, e.g.
+/// `sweep_with(grid, |tgt| ps::Runner::new(theta0, env, tgt).run())`.
+pub fn sweep_with<T, O>(grid: impl IntoIterator<Item = T>, run: impl FnMut(T) -> O) -> Vec<O> {
+    grid.into_iter().map(run).collect()
+}
+
+/// [`sweep_with`] on rayon's pool (`parallel` feature), results in grid
+/// order.
+#[cfg(feature = "parallel")]
+pub fn sweep_with_par<T: Send, O: Send>(grid: Vec<T>, run: impl Fn(T) -> O + Sync) -> Vec<O> {
+    use rayon::prelude::*;
+    grid.into_par_iter().map(&run).collect()
+}
+
+/// `n` evenly spaced values from `lo` to `hi` inclusive (`[lo]` if `n == 1`).
+pub fn linspace(lo: f64, hi: f64, n: usize) -> Vec<f64> {
+    match n {
+        0 => Vec::new(),
+        1 => vec![lo],
+        _ => (0..n).map(|i| lo + (hi - lo) * i as f64 / (n - 1) as f64).collect(),
+    }
+}
+
+/// The closure API as a [`System`].
+struct FnSystem<TParams, Env, Tgt, Obs, Bnd, G, Sim, Nom, Stp, Conv, Sc> {
+    bnd: Bnd,
+    gains: G,
+    simulate: Sim,
+    nominal: Nom,
+    step: Stp,
+    converged: Conv,
+    score: Sc,
+    _types: std::marker::PhantomData<fn(&TParams, &Env, &Tgt) -> Obs>,
+}
+
+/// The bounds and gains `step` reads, plus the `simulate`, `nominal` and
+/// `step` closures of [`balance_with_hooks`], as one argument (for
+/// [`balance_scored`], [`stepper_with_hooks`] and [`autotune_gains`]).
+pub struct Closures<Bnd, G, Sim, Nom, Stp> {
+    pub bnd: Bnd,
+    pub gains: G,
+    pub simulate: Sim,
+    pub nominal: Nom,
+    pub step: Stp,
+}
+
+/// `score` for closure systems that only have a `converged` band.
+fn no_score<Obs, Tgt>(_: &Obs, _: &Tgt) -> Option<ConvergenceScore> {
+    None
+}
+
+impl<TParams, Env, Tgt, Obs, Bnd, G, Sim, Nom, Stp, Conv, Sc> System
+    for FnSystem<TParams, Env, Tgt, Obs, Bnd, G, Sim, Nom, Stp, Conv, Sc>
+where
+    TParams: Clone + Flat,
+    Obs: Clone + Default,
+    Sim: FnMut(&TParams, &Env, &Tgt, &mut [Box<dyn Hook<TParams, Env, Tgt, Obs>>]) -> Obs,
+    Nom: FnMut(&TParams, &Env, &Tgt, &Obs) -> NominalTargets,
+    Stp: FnMut(&TParams, &Bnd, &G, NominalTargets, TargetAdjust) -> TParams,
+    Conv: FnMut(&Obs, &Tgt) -> bool,
+    Sc: FnMut(&Obs, &Tgt) -> Option<ConvergenceScore>,
+{
+    type Params = TParams;
+    type Env = Env;
+    type Tgt = Tgt;
+    type Obs = Obs;
+
+    fn simulate(&mut self, th: &TParams, env: &Env, tgt: &Tgt, hooks: &mut [Box<dyn Hook<TParams, Env, Tgt, Obs>>]) -> Obs {
+        (self.simulate)(th, env, tgt, hooks)
+    }
+    fn nominal(&mut self, th: &TParams, env: &Env, tgt: &Tgt, obs: &Obs) -> NominalTargets {
+        (self.nominal)(th, env, tgt, obs)
+    }
+    fn step(&mut self, th: &TParams, nom: NominalTargets, adj: TargetAdjust) -> TParams {
+        (self.step)(th, &self.bnd, &self.gains, nom, adj)
+    }
+    fn converged(&mut self, obs: &Obs, tgt: &Tgt) -> bool {
+        (self.converged)(obs, tgt)
+    }
+    fn score(&mut self, obs: &Obs, tgt: &Tgt) -> Option<ConvergenceScore> {
+        (self.score)(obs, tgt)
+    }
 }
 
 /// Generic harness for systems with hooks.
-/// You provide 4 closures: simulate, nominal, step, converged.
-pub fn balance_with_hooks<
-    TParams: Clone,
-    Env: Clone,
-    Tgt: Clone,
-    Bnd: Clone,
-    G: Clone,
-    Obs: Clone + Default + 'static,
->(
+/// You provide 4 closures: simulate, nominal, step, converged. They may
+/// borrow from the caller (a shared simulation context, lookup tables);
+/// nothing needs to be `'static` or cloned into the harness. A thin wrapper
+/// over [`balance_system`].
+pub fn balance_with_hooks<TParams: Clone + Flat, Env, Tgt, Bnd, G, Obs: Clone + Default>(
     theta0: TParams,
     env: Env,
     tgt: Tgt,
@@ -161,87 +1463,620 @@ pub fn balance_with_hooks<
     gains: G,
     hooks: Vec<Box<dyn Hook<TParams, Env, Tgt, Obs>>>,
     max_iters: usize,
-    simulate: impl Fn(&TParams, &Env, &Tgt, &mut [Box<dyn Hook<TParams, Env, Tgt, Obs>>]) -> Obs + 'static,
-    nominal: impl Fn(&TParams, &Env, &Tgt, &Obs) -> NominalTargets + 'static,
-    step: impl Fn(&TParams, &Bnd, &G, NominalTargets, TargetAdjust) -> TParams + 'static,
-    converged: impl Fn(&Obs, &Tgt) -> bool + 'static,
+    simulate: impl FnMut(&TParams, &Env, &Tgt, &mut [Box<dyn Hook<TParams, Env, Tgt, Obs>>]) -> Obs,
+    nominal: impl FnMut(&TParams, &Env, &Tgt, &Obs) -> NominalTargets,
+    step: impl FnMut(&TParams, &Bnd, &G, NominalTargets, TargetAdjust) -> TParams,
+    converged: impl FnMut(&Obs, &Tgt) -> bool,
 ) -> Outcome<TParams, Obs> {
-    let theta = Rc::new(RefCell::new(theta0));
-    let obs   = Rc::new(RefCell::new(Obs::default()));
-    let iters = Rc::new(RefCell::new(0usize));
-    let done  = Rc::new(RefCell::new(false));
-    let hooks_cell: Rc<RefCell<Vec<Box<dyn Hook<TParams, Env, Tgt, Obs>>>>> =
-        Rc::new(RefCell::new(hooks));
-
-    let simulate_cl = {
-        let theta = Rc::clone(&theta);
-        let obs   = Rc::clone(&obs);
-        let env   = env.clone();
-        let tgt   = tgt.clone();
-        let hooks_cell = Rc::clone(&hooks_cell);
-        move |_p: &Params| -> Data {
-            let mut hs = hooks_cell.borrow_mut();
-            let o = simulate(&theta.borrow(), &env, &tgt, &mut hs);
-            *obs.borrow_mut() = o.clone();
-            for h in hs.iter_mut() {
-                h.on_observe(&o, &theta.borrow(), &env, &tgt);
+    let mut sys =
+        FnSystem { bnd, gains, simulate, nominal, step, converged, score: no_score, _types: std::marker::PhantomData };
+    balance_system(&mut sys, theta0, env, tgt, hooks, max_iters)
+}
+
+/// [`balance_with_hooks`] that converges on a weighted [`ConvergenceScore`]
+/// instead of a `converged` band; the last score lands on
+/// [`Outcome::score`].
+pub fn balance_scored<TParams: Clone + Flat, Env, Tgt, Bnd, G, Obs: Clone + Default, Sim, Nom, Stp>(
+    theta0: TParams,
+    env: Env,
+    tgt: Tgt,
+    hooks: Vec<Box<dyn Hook<TParams, Env, Tgt, Obs>>>,
+    max_iters: usize,
+    closures: Closures<Bnd, G, Sim, Nom, Stp>,
+    mut score: impl FnMut(&Obs, &Tgt) -> ConvergenceScore,
+) -> Outcome<TParams, Obs>
+where
+    Sim: FnMut(&TParams, &Env, &Tgt, &mut [Box<dyn Hook<TParams, Env, Tgt, Obs>>]) -> Obs,
+    Nom: FnMut(&TParams, &Env, &Tgt, &Obs) -> NominalTargets,
+    Stp: FnMut(&TParams, &Bnd, &G, NominalTargets, TargetAdjust) -> TParams,
+{
+    let Closures { bnd, gains, simulate, nominal, step } = closures;
+    let mut sys = FnSystem {
+        bnd,
+        gains,
+        simulate,
+        nominal,
+        step,
+        converged: |_: &Obs, _: &Tgt| false,
+        score: move |o: &Obs, t: &Tgt| Some(score(o, t)),
+        _types: std::marker::PhantomData,
+    };
+    balance_system(&mut sys, theta0, env, tgt, hooks, max_iters)
+}
+
+/// Drives a [`System`] one iteration at a time, for tools that show every
+/// step (editor sliders, live previews) instead of running to convergence.
+/// Owns θ, the last `Obs`, the hooks and the shared [`Signals`]; targets,
+/// env and θ may be changed between steps. No hold phase or chatter
+/// detection: the caller decides when to stop.
+pub struct SystemStepper<S: System> {
+    sys: S,
+    theta: S::Params,
+    obs: S::Obs,
+    env: S::Env,
+    tgt: S::Tgt,
+    hooks: SystemHooks<S>,
+    signals: Signals,
+    iters: usize,
+    converged: bool,
+    score: Option<ConvergenceScore>,
+}
+
+impl<S: System> SystemStepper<S> {
+    pub fn new(sys: S, theta0: S::Params, env: S::Env, tgt: S::Tgt, mut hooks: SystemHooks<S>) -> Self {
+        sort_hooks(&mut hooks);
+        Self {
+            sys,
+            theta: theta0,
+            obs: S::Obs::default(),
+            env,
+            tgt,
+            hooks,
+            signals: Signals::default(),
+            iters: 0,
+            converged: false,
+            score: None,
+        }
+    }
+
+    /// One harness iteration: simulate the current θ, step it, judge the
+    /// observation. Returns that observation (of θ *before* the step).
+    pub fn step_once(&mut self) -> &S::Obs {
+        self.obs = observe(&mut self.sys, &self.theta, &self.env, &self.tgt, &mut self.hooks, &mut self.signals);
+        self.theta = advance(&mut self.sys, &self.theta, &self.obs, &self.env, &self.tgt, &mut self.hooks);
+        self.iters += 1;
+        (self.converged, self.score) = accept(&mut self.sys, &self.obs, &self.tgt);
+        &self.obs
+    }
+
+    /// The next θ to be simulated.
+    pub fn theta(&self) -> &S::Params {
+        &self.theta
+    }
+    /// Replace θ (e.g. a slider moved); the next step starts from it.
+    pub fn set_theta(&mut self, theta: S::Params) {
+        self.theta = theta;
+    }
+    /// The last step's observation (`Obs::default()` before the first step).
+    pub fn obs(&self) -> &S::Obs {
+        &self.obs
+    }
+    pub fn env_mut(&mut self) -> &mut S::Env {
+        &mut self.env
+    }
+    pub fn tgt_mut(&mut self) -> &mut S::Tgt {
+        &mut self.tgt
+    }
+    pub fn hooks_mut(&mut self) -> &mut SystemHooks<S> {
+        &mut self.hooks
+    }
+    pub fn signals_mut(&mut self) -> &mut Signals {
+        &mut self.signals
+    }
+    pub fn system_mut(&mut self) -> &mut S {
+        &mut self.sys
+    }
+    /// Steps taken so far.
+    pub fn iters(&self) -> usize {
+        self.iters
+    }
+    /// Whether the last step's observation was accepted.
+    pub fn converged(&self) -> bool {
+        self.converged
+    }
+    /// The last step's score, for systems with [`System::score`].
+    pub fn score(&self) -> Option<&ConvergenceScore> {
+        self.score.as_ref()
+    }
+
+    /// Step until accepted or `max_steps` more steps; `true` if accepted.
+    pub fn step_until_converged(&mut self, max_steps: usize) -> bool {
+        for _ in 0..max_steps {
+            self.step_once();
+            if self.converged {
+                return true;
             }
-            Data {}
         }
-    };
+        false
+    }
 
-    let measure = |_d: &Data| Metrics {};
-
-    let update_cl = {
-        let theta = Rc::clone(&theta);
-        let obs   = Rc::clone(&obs);
-        let env   = env.clone();
-        let tgt   = tgt.clone();
-        let bnd   = bnd.clone();
-        let gains = gains.clone();
-        let hooks_cell = Rc::clone(&hooks_cell);
-        move |_p: &Params, _m: &Metrics| -> Params {
-            let th  = theta.borrow().clone();
-            let o   = obs.borrow().clone();
-            let nom = nominal(&th, &env, &tgt, &o);
-
-            // Compose multiplicative adjustments from all hooks.
-            let mut adj = TargetAdjust::id();
-            {
-                let mut hs = hooks_cell.borrow_mut();
-                for h in hs.iter_mut() {
-                    let s = h.adjust_targets(&th, &env, &tgt, &nom);
-                    adj.a *= s.a.max(0.0);
-                    adj.b *= s.b.max(0.0);
-                    adj.c *= s.c.max(0.0);
-                }
+    /// The state so far as an [`Outcome`] (no hold phase, so
+    /// `stable_after_converge` mirrors `converged`).
+    pub fn outcome(&mut self) -> Outcome<S::Params, S::Obs> {
+        let residuals = if self.iters > 0 { self.sys.residuals(&self.obs, &self.tgt) } else { Vec::new() };
+        Outcome {
+            theta: self.theta.clone(),
+            obs: self.obs.clone(),
+            iters: self.iters,
+            converged: self.converged,
+            stable_after_converge: self.converged,
+            trace: Vec::new(),
+            oscillation: None,
+            budget_exhausted: false,
+            score: self.score.clone(),
+            residuals,
+        }
+    }
+
+    /// θ and the hooks, to continue in [`balance_system`] or an arena.
+    pub fn into_warm_start(self) -> WarmStart<S::Params, S::Env, S::Tgt, S::Obs> {
+        WarmStart { theta: self.theta, hooks: self.hooks }
+    }
+}
+
+/// A [`SystemStepper`] over the closure API of [`balance_with_hooks`].
+pub fn stepper_with_hooks<TParams: Clone + Flat, Env, Tgt, Bnd, G, Obs: Clone + Default, Sim, Nom, Stp>(
+    theta0: TParams,
+    env: Env,
+    tgt: Tgt,
+    hooks: Vec<Box<dyn Hook<TParams, Env, Tgt, Obs>>>,
+    closures: Closures<Bnd, G, Sim, Nom, Stp>,
+    converged: impl FnMut(&Obs, &Tgt) -> bool,
+) -> SystemStepper<impl System<Params = TParams, Env = Env, Tgt = Tgt, Obs = Obs>>
+where
+    Sim: FnMut(&TParams, &Env, &Tgt, &mut [Box<dyn Hook<TParams, Env, Tgt, Obs>>]) -> Obs,
+    Nom: FnMut(&TParams, &Env, &Tgt, &Obs) -> NominalTargets,
+    Stp: FnMut(&TParams, &Bnd, &G, NominalTargets, TargetAdjust) -> TParams,
+{
+    let Closures { bnd, gains, simulate, nominal, step } = closures;
+    let sys =
+        FnSystem { bnd, gains, simulate, nominal, step, converged, score: no_score, _types: std::marker::PhantomData };
+    SystemStepper::new(sys, theta0, env, tgt, hooks)
+}
+
+/// Iterations per [`autotune_gains`] probe.
+pub const AUTOTUNE_PROBE_ITERS: usize = 200;
+/// Gain factors [`autotune_gains`] probes: `1/32, 1/16, …, 4` × the base.
+pub const AUTOTUNE_FACTORS: [f64; 8] = [1.0 / 32.0, 1.0 / 16.0, 0.125, 0.25, 0.5, 1.0, 2.0, 4.0];
+/// Fraction of the smallest oscillating factor that [`autotune_gains`]
+/// stays under (the Ziegler–Nichols ½·Kᵤ rule).
+pub const AUTOTUNE_MARGIN: f64 = 0.5;
+
+/// Pick stable gains by probing instead of guessing. Runs short,
+/// hook-free probes of the closure system at each of [`AUTOTUNE_FACTORS`] ×
+/// `closures.gains` (via `scale`, e.g. `|g, f| g.scaled(f)`), finds the smallest
+/// factor at which θ keeps reversing without settling (the *ultimate*
+/// gain, as in relay-feedback tuning), and returns the fastest-converging
+/// factor that stays within [`AUTOTUNE_MARGIN`] of it. If no probe
+/// converges, the largest stable factor wins; if none is stable, the
+/// smallest factor.
+pub fn autotune_gains<TParams: Clone + Flat, Env: Clone, Tgt: Clone, Bnd, G, Obs: Clone + Default, Sim, Nom, Stp>(
+    theta0: TParams,
+    env: &Env,
+    tgt: &Tgt,
+    closures: Closures<Bnd, G, Sim, Nom, Stp>,
+    scale: impl Fn(&G, f64) -> G,
+    mut converged: impl FnMut(&Obs, &Tgt) -> bool,
+) -> G
+where
+    Sim: FnMut(&TParams, &Env, &Tgt, &mut [Box<dyn Hook<TParams, Env, Tgt, Obs>>]) -> Obs,
+    Nom: FnMut(&TParams, &Env, &Tgt, &Obs) -> NominalTargets,
+    Stp: FnMut(&TParams, &Bnd, &G, NominalTargets, TargetAdjust) -> TParams,
+{
+    let Closures { bnd, gains: base, mut simulate, mut nominal, mut step } = closures;
+    // (factor, iterations to converge or None, stable)
+    let probes: Vec<(f64, Option<usize>, bool)> = AUTOTUNE_FACTORS
+        .iter()
+        .map(|&f| {
+            let gains = scale(&base, f);
+            let sys = FnSystem {
+                bnd: &bnd,
+                gains: &gains,
+                simulate: &mut simulate,
+                nominal: &mut nominal,
+                step: |th: &TParams, b: &&Bnd, g: &&G, nom, adj| step(th, b, g, nom, adj),
+                converged: &mut converged,
+                score: no_score,
+                _types: std::marker::PhantomData,
+            };
+            let mut stepper = SystemStepper::new(sys, theta0.clone(), env.clone(), tgt.clone(), Vec::new());
+            let mut path = vec![theta0.flatten()];
+            while stepper.iters() < AUTOTUNE_PROBE_ITERS && !stepper.converged() {
+                stepper.step_once();
+                path.push(stepper.theta().flatten());
+            }
+            if stepper.converged() {
+                (f, Some(stepper.iters()), true)
+            } else {
+                (f, None, settles(&path))
             }
+        })
+        .collect();
+
+    let ultimate = probes.iter().find(|p| !p.2).map(|p| p.0);
+    let limit = ultimate.map_or(f64::INFINITY, |u| AUTOTUNE_MARGIN * u);
+    let under: Vec<_> = probes.iter().filter(|p| p.2 && p.0 <= limit).collect();
+    let factor = under
+        .iter()
+        .filter_map(|p| p.1.map(|n| (n, p.0)))
+        .min_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)))
+        .map(|(_, f)| f)
+        .or_else(|| under.last().map(|p| p.0))
+        .unwrap_or(AUTOTUNE_FACTORS[0]);
+    scale(&base, factor)
+}
+
+/// Whether a probe's θ path is settling: finite, and its steps neither grow
+/// nor keep reversing direction over the second half.
+fn settles(path: &[Vec<f64>]) -> bool {
+    if path.iter().flatten().any(|x| !x.is_finite()) {
+        return false;
+    }
+    let deltas: Vec<Vec<f64>> = path.windows(2).map(|w| w[1].iter().zip(&w[0]).map(|(a, b)| a - b).collect()).collect();
+    let norm = |d: &Vec<f64>| d.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let half = deltas.len() / 2;
+    if half == 0 {
+        return true;
+    }
+    let mean = |ds: &[Vec<f64>]| ds.iter().map(norm).sum::<f64>() / ds.len() as f64;
+    let (early, late) = (mean(&deltas[..half]), mean(&deltas[half..]));
+    let reversals = deltas[half..]
+        .windows(2)
+        .filter(|w| w[0].iter().zip(&w[1]).map(|(a, b)| a * b).sum::<f64>() < 0.0)
+        .count();
+    !(late > 0.0 && late >= 0.5 * early && 2 * reversals >= deltas.len() - half - 1)
+}
+
+/// Per-run settings an arena carries.
+#[derive(Clone, Copy, Debug)]
+struct RunConfig {
+    hold_iters: usize,
+    trace: ObsTrace,
+    #[cfg(feature = "timing")]
+    budget: Option<std::time::Duration>,
+}
 
-            let next = step(&th, &bnd, &gains, nom, adj);
-            *theta.borrow_mut() = next;
-            Params {}
+impl Default for RunConfig {
+    fn default() -> Self {
+        Self {
+            hold_iters: DEFAULT_HOLD_ITERS,
+            trace: ObsTrace::Off,
+            #[cfg(feature = "timing")]
+            budget: None,
         }
-    };
+    }
+}
 
-    let done_cl = {
-        let obs   = Rc::clone(&obs);
-        let iters = Rc::clone(&iters);
-        let done  = Rc::clone(&done);
-        let tgt   = tgt.clone();
-        move |_a: &Params, _b: &Params| -> bool {
-            *iters.borrow_mut() += 1;
-            let ok = converged(&obs.borrow(), &tgt);
-            if ok { *done.borrow_mut() = true; }
-            ok
+/// A run's wall-clock deadline (`timing` feature; never passes without it).
+struct Deadline {
+    #[cfg(feature = "timing")]
+    at: Option<std::time::Instant>,
+}
+
+impl Deadline {
+    fn start(config: &RunConfig) -> Self {
+        #[cfg(not(feature = "timing"))]
+        let _ = config;
+        Self {
+            #[cfg(feature = "timing")]
+            at: config.budget.map(|b| std::time::Instant::now() + b),
         }
+    }
+
+    /// Read the clock every [`crate::TIME_CHECK_EVERY`] iterations.
+    fn passed(&self, iters: usize) -> bool {
+        #[cfg(feature = "timing")]
+        if let Some(at) = self.at {
+            return iters.is_multiple_of(crate::TIME_CHECK_EVERY) && std::time::Instant::now() >= at;
+        }
+        let _ = iters;
+        false
+    }
+}
+
+/// Reusable harness state for batch runs (sweeps, grid search).
+///
+/// Holds the per-run settings (hold phase, trace, budget) and the signal
+/// bus across calls; each run starts from cleared buffers, so results are
+/// identical to the free function with the same settings.
+pub struct BalanceArena<TParams, Env, Tgt, Obs> {
+    scratch: Scratch<TParams, Env, Tgt, Obs>,
+}
+
+impl<TParams, Env, Tgt, Obs> Default for BalanceArena<TParams, Env, Tgt, Obs> {
+    fn default() -> Self {
+        Self { scratch: Scratch::new(RunConfig::default()) }
+    }
+}
+
+impl<TParams, Env, Tgt, Obs> BalanceArena<TParams, Env, Tgt, Obs> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Steps to keep running after convergence to judge stability
+    /// (`0`, the default, reports any converged run as stable). The
+    /// returned θ is the converged one, but hooks and the signal bus see
+    /// the extra steps.
+    pub fn with_hold_iters(mut self, hold_iters: usize) -> Self {
+        self.scratch.config.hold_iters = hold_iters;
+        self
+    }
+    /// Record each iteration's `Obs` into [`Outcome::trace`].
+    pub fn with_trace(mut self, trace: ObsTrace) -> Self {
+        self.scratch.config.trace = trace;
+        self
+    }
+    /// Stop a run once `budget` of wall-clock time has passed, even short of
+    /// `max_iters`, and flag it in [`Outcome::budget_exhausted`]. The clock
+    /// is read every [`crate::TIME_CHECK_EVERY`] iterations; the hold phase
+    /// is not timed.
+    #[cfg(feature = "timing")]
+    pub fn with_budget(mut self, budget: std::time::Duration) -> Self {
+        self.scratch.config.budget = Some(budget);
+        self
+    }
+    /// Seed the signal bus hooks see through [`Hook::on_signals`].
+    pub fn with_signals(mut self, signals: Signals) -> Self {
+        self.scratch.signals = signals;
+        self
+    }
+    /// The signal bus as the last run left it. It carries over to the next
+    /// run on this arena.
+    pub fn signals(&self) -> &Signals {
+        &self.scratch.signals
+    }
+    pub fn signals_mut(&mut self) -> &mut Signals {
+        &mut self.scratch.signals
+    }
+
+    /// [`balance_system`], reusing this arena's buffers and settings.
+    pub fn balance_system<S>(
+        &mut self,
+        sys: &mut S,
+        theta0: TParams,
+        env: Env,
+        tgt: Tgt,
+        hooks: Vec<Box<dyn Hook<TParams, Env, Tgt, Obs>>>,
+        max_iters: usize,
+    ) -> Outcome<TParams, Obs>
+    where
+        S: System<Params = TParams, Env = Env, Tgt = Tgt, Obs = Obs> + ?Sized,
+    {
+        self.scratch.reset(hooks);
+        run(&mut self.scratch, sys, theta0, &env, &tgt, max_iters)
+    }
+
+    /// The last run's hooks, internal state intact (in run order, i.e.
+    /// sorted by priority). Until taken they stay here; the next run drops
+    /// them. See [`Outcome::into_warm_start`].
+    pub fn take_hooks(&mut self) -> Vec<Box<dyn Hook<TParams, Env, Tgt, Obs>>> {
+        self.scratch.hooks.drain(..).collect()
+    }
+}
+
+impl<TParams: Clone + Flat, Env, Tgt, Obs: Clone + Default> BalanceArena<TParams, Env, Tgt, Obs> {
+    /// Same contract as [`balance_with_hooks`], reusing this arena's buffers.
+    pub fn balance_with_hooks<Bnd, G>(
+        &mut self,
+        theta0: TParams,
+        env: Env,
+        tgt: Tgt,
+        bnd: Bnd,
+        gains: G,
+        hooks: Vec<Box<dyn Hook<TParams, Env, Tgt, Obs>>>,
+        max_iters: usize,
+        simulate: impl FnMut(&TParams, &Env, &Tgt, &mut [Box<dyn Hook<TParams, Env, Tgt, Obs>>]) -> Obs,
+        nominal: impl FnMut(&TParams, &Env, &Tgt, &Obs) -> NominalTargets,
+        step: impl FnMut(&TParams, &Bnd, &G, NominalTargets, TargetAdjust) -> TParams,
+        converged: impl FnMut(&Obs, &Tgt) -> bool,
+    ) -> Outcome<TParams, Obs> {
+        let mut sys =
+            FnSystem { bnd, gains, simulate, nominal, step, converged, score: no_score, _types: std::marker::PhantomData };
+        self.balance_system(&mut sys, theta0, env, tgt, hooks, max_iters)
+    }
+}
+
+/// Buffers the harness reuses across runs, plus the run settings and the
+/// signal bus.
+struct Scratch<TParams, Env, Tgt, Obs> {
+    hooks: Vec<Box<dyn Hook<TParams, Env, Tgt, Obs>>>,
+    trace: VecDeque<Obs>,
+    /// Recent flattened θ, newest last, for chatter detection.
+    recent: VecDeque<Vec<f64>>,
+    config: RunConfig,
+    signals: Signals,
+}
+
+impl<TParams, Env, Tgt, Obs> Scratch<TParams, Env, Tgt, Obs> {
+    fn new(config: RunConfig) -> Self {
+        Self {
+            hooks: Vec::new(),
+            trace: VecDeque::new(),
+            recent: VecDeque::new(),
+            config,
+            signals: Signals::default(),
+        }
+    }
+
+    fn reset(&mut self, hooks: Vec<Box<dyn Hook<TParams, Env, Tgt, Obs>>>) {
+        self.trace.clear();
+        self.recent.clear();
+        self.hooks.clear();
+        self.hooks.extend(hooks);
+        sort_hooks(&mut self.hooks);
+    }
+}
+
+/// The hooks' `on_signals`, one simulate, then the hooks' `on_observe`.
+fn observe<S: System + ?Sized>(
+    sys: &mut S,
+    th: &S::Params,
+    env: &S::Env,
+    tgt: &S::Tgt,
+    hooks: &mut [SystemHook<S>],
+    signals: &mut Signals,
+) -> S::Obs {
+    for h in hooks.iter_mut() {
+        h.on_signals(signals);
+    }
+    let o = sys.simulate(th, env, tgt, hooks);
+    for h in hooks.iter_mut() {
+        h.on_observe(&o, th, env, tgt);
+    }
+    o
+}
+
+/// nominal → hook adjustments → step → `post_step` → `project`.
+fn advance<S: System + ?Sized>(
+    sys: &mut S,
+    th: &S::Params,
+    obs: &S::Obs,
+    env: &S::Env,
+    tgt: &S::Tgt,
+    hooks: &mut [SystemHook<S>],
+) -> S::Params {
+    let nom = sys.nominal(th, env, tgt, obs);
+    let adj = compose_adjust(hooks, th, env, tgt, &nom);
+    let mut next = sys.step(th, nom, adj);
+    for h in hooks.iter_mut() {
+        h.post_step(&mut next, env);
+    }
+    for h in hooks.iter_mut() {
+        h.project(&mut next, env);
+    }
+    next
+}
+
+/// A cycle of period 2..=[`CHATTER_MAX_PERIOD`] at the end of `recent`, if
+/// the last [`CHATTER_REPEATS`] periods match. A θ that stopped moving is a
+/// stall, not a cycle.
+fn detect_cycle(recent: &VecDeque<Vec<f64>>) -> Option<Oscillation> {
+    let close = |a: &[f64], b: &[f64]| {
+        a.iter().zip(b).all(|(x, y)| (x - y).abs() <= CHATTER_RTOL * x.abs().max(y.abs()).max(1e-12))
     };
+    let n = recent.len();
+    (2..=CHATTER_MAX_PERIOD).filter(|p| p * CHATTER_REPEATS <= n).find_map(|p| {
+        let window = n - p * CHATTER_REPEATS;
+        if !(window + p..n).all(|t| close(&recent[t], &recent[t - p])) {
+            return None;
+        }
+        let last = recent.range(n - p..);
+        if last.clone().skip(1).all(|th| close(th, &recent[n - p])) {
+            return None;
+        }
+        let (coord, amplitude) = (0..recent[n - 1].len())
+            .map(|j| {
+                let (lo, hi) = last.clone().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), th| {
+                    (lo.min(th[j]), hi.max(th[j]))
+                });
+                (j, 0.5 * (hi - lo))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))?;
+        Some(Oscillation { period: p, amplitude, coord })
+    })
+}
+
+/// `System::score` if the system has one, else its `converged` band.
+fn accept<S: System + ?Sized>(sys: &mut S, obs: &S::Obs, tgt: &S::Tgt) -> (bool, Option<ConvergenceScore>) {
+    match sys.score(obs, tgt) {
+        Some(score) => (score.converged(), Some(score)),
+        None => (sys.converged(obs, tgt), None),
+    }
+}
+
+/// The refinement loop: simulate → nominal/step → converged, then the hold
+/// phase. θ and `Obs` are plain locals carried between iterations. A run
+/// whose θ settles into a cycle stops early with [`Outcome::oscillation`]
+/// set instead of spending the rest of `max_iters`.
+fn run<S: System + ?Sized>(
+    scratch: &mut Scratch<S::Params, S::Env, S::Tgt, S::Obs>,
+    sys: &mut S,
+    theta0: S::Params,
+    env: &S::Env,
+    tgt: &S::Tgt,
+    max_iters: usize,
+) -> Outcome<S::Params, S::Obs> {
+    let Scratch { hooks, trace, recent, config, signals } = scratch;
 
-    let _ = refine_det(Params {}, simulate_cl, measure, update_cl, done_cl, max_iters);
+    // `obs` stays `Obs::default()` only if no iteration runs; `converged`
+    // is never asked about that placeholder.
+    let mut theta = theta0;
+    let mut obs = S::Obs::default();
+    let mut iters = 0;
+    let mut converged_ok = false;
+    let mut oscillation = None;
+    let mut budget_exhausted = false;
+    let mut score = None;
+    let deadline = Deadline::start(config);
+    while iters < max_iters {
+        obs = observe(sys, &theta, env, tgt, hooks, signals);
+        theta = advance(sys, &theta, &obs, env, tgt, hooks);
+        iters += 1;
+        match config.trace {
+            ObsTrace::Off | ObsTrace::Last(0) => {}
+            ObsTrace::Full => trace.push_back(obs.clone()),
+            ObsTrace::Last(n) => {
+                if trace.len() == n {
+                    trace.pop_front();
+                }
+                trace.push_back(obs.clone());
+            }
+        }
+        let (ok, s) = accept(sys, &obs, tgt);
+        score = s;
+        if ok {
+            converged_ok = true;
+            break;
+        }
+        if recent.len() == CHATTER_MAX_PERIOD * CHATTER_REPEATS {
+            recent.pop_front();
+        }
+        recent.push_back(theta.flatten());
+        oscillation = detect_cycle(recent);
+        if oscillation.is_some() {
+            break;
+        }
+        if deadline.passed(iters) {
+            budget_exhausted = true;
+            break;
+        }
+    }
+
+    // Snapshot the converged state; the hold phase only judges stability.
+    let out_theta = theta.clone();
+    let mut stable = converged_ok;
+    if converged_ok {
+        for _ in 0..config.hold_iters {
+            let o = observe(sys, &theta, env, tgt, hooks, signals);
+            if !accept(sys, &o, tgt).0 {
+                stable = false;
+                break;
+            }
+            theta = advance(sys, &theta, &o, env, tgt, hooks);
+        }
+    }
 
+    let residuals = if iters > 0 { sys.residuals(&obs, tgt) } else { Vec::new() };
     Outcome {
-        theta: theta.borrow().clone(),
-        obs:   obs.borrow().clone(),
-        iters: *iters.borrow(),
-        converged: *done.borrow(),
+        theta: out_theta,
+        obs,
+        iters,
+        converged: converged_ok,
+        stable_after_converge: stable,
+        trace: trace.drain(..).collect(),
+        oscillation,
+        budget_exhausted,
+        score,
+        residuals,
     }
-}
\ No newline at end of file
+}
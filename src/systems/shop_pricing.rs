@@ -0,0 +1,255 @@
+//! Shop pricing: tune item prices so purchases land on a target cadence.
+//!
+//! Uses the TTU math from `production_spend` (price / saving rate) and the
+//! per-level pacing idea from `upgrade_cost_curve` (each item tier takes
+//! `cadence_slope`× longer to afford than the previous one).
+//!
+//! Restock intervals cap how often each item can be bought: item `i` absorbs
+//! at most `cadence_i / restock_i` of the shop budget, and whatever the stock
+//! cannot absorb is hoarded. Restocks are stretched until the hoarded share
+//! sits at `max_hoard`, so items stay scarce without currency piling up.
+
+use crate::error::{check_range, Checks, Error, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::{self, ControllerState};
+#[cfg(feature = "system-production_spend")]
+use crate::systems::production_spend;
+use crate::systems::sdk::{
+    balance_with_hooks, compose_income, within, Hook, NominalTargets, Outcome, Residual, RelTol,
+};
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Params {
+    pub prices: Vec<f64>,          // one price per item, cheapest tier first
+    pub restock_minutes: Vec<f64>, // minutes between restocks per item; empty = unlimited stock
+}
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        self.prices.iter().chain(&self.restock_minutes).copied().collect()
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        let (prices, restock) = v.split_at(self.prices.len().min(v.len()));
+        Self { prices: prices.to_vec(), restock_minutes: restock.to_vec() }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Env {
+    pub income_per_sec: f64, // reference currency income
+    pub shop_share: f64,     // fraction of income players put toward the shop
+}
+impl Env {
+    /// Shop env fed by a `production_spend` economy: base income
+    /// (`gen_per_sec · multiplier`) with the unspent surplus as the shop share.
+    #[cfg(feature = "system-production_spend")]
+    pub fn from_production(th: &production_spend::Params, o: &production_spend::Obs) -> Self {
+        let income_per_sec = (th.gen_per_sec * th.multiplier).max(1e-9);
+        Self { income_per_sec, shop_share: (o.surplus / income_per_sec).clamp(1e-6, 1.0) }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Targets {
+    pub cadence_minutes: f64, // minutes between purchases of the first item
+    pub cadence_slope: f64,   // cadence_{i+1} / cadence_i, e.g. 1.5
+    pub max_hoard: f64,       // largest share of the shop budget left unspent, e.g. 0.1
+}
+
+crate::define_system! {
+    bounds {
+        price: price_min..price_max = (1.0, 1e12),
+        restock: restock_min..restock_max = (0.1, 1e6),
+    }
+    gains { k_price = 0.6, k_restock = 0.6 }
+    validate { theta0 }
+}
+
+impl Bounds {
+    /// Validated bounds: each range must be finite with min ≤ max.
+    pub fn new(price_min: f64, price_max: f64, restock_min: f64, restock_max: f64) -> Result<Self, Error> {
+        check_range("price", price_min, price_max)?;
+        check_range("restock", restock_min, restock_max)?;
+        Ok(Self { price_min, price_max, restock_min, restock_max })
+    }
+}
+
+impl Validate for Params {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .check(
+                "restock_minutes",
+                self.restock_minutes.is_empty() || self.restock_minutes.len() == self.prices.len(),
+                "needs one entry per item, or none for unlimited stock",
+            )
+            .done()
+    }
+}
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .positive("income_per_sec", self.income_per_sec)
+            .check("shop_share", self.shop_share > 0.0 && self.shop_share <= 1.0, "must be in (0, 1]")
+            .done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .positive("cadence_minutes", self.cadence_minutes)
+            .positive("cadence_slope", self.cadence_slope)
+            .check("max_hoard", (0.0..1.0).contains(&self.max_hoard), "must be in [0, 1)")
+            .done()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obs {
+    pub minutes_between: Vec<f64>, // time to afford each item
+    pub save_per_min: f64,         // currency/min available to the shop
+    pub spend_per_min: f64,        // currency/min the stock can absorb
+    pub hoarded_share: f64,        // 1 − spend / save: budget left unspent
+}
+
+/// Share of the shop budget the stock cannot absorb: `1 − Σ cadence_i / restock_i`
+/// floored at 0. Items past the end of `restock_minutes` have unlimited stock
+/// and absorb whatever is left, so any such item means nothing is hoarded
+/// ([`Runner::try_run`] rejects a partial list as a likely mistake).
+pub fn hoarded_share(minutes_between: &[f64], restock_minutes: &[f64]) -> f64 {
+    if minutes_between.is_empty() || restock_minutes.len() < minutes_between.len() {
+        return 0.0;
+    }
+    let absorbed: f64 = minutes_between.iter().zip(restock_minutes).map(|(m, r)| m / r.max(1e-9)).sum();
+    (1.0 - absorbed).max(0.0)
+}
+
+/// The default cadence math: price / (income share per minute), with the
+/// hoarded share from [`hoarded_share`]. Implement [`SimModel`] for a richer
+/// purchase model (sales, bundles).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        _tgt: &Targets,
+        mechs: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let income = compose_income(env.income_per_sec, mechs, th, env);
+        let save_per_min = (income * env.shop_share.clamp(0.0, 1.0) * 60.0).max(1e-9);
+        let minutes_between: Vec<f64> = th.prices.iter().map(|p| (p / save_per_min).clamp(0.0, 1e6)).collect();
+        let hoarded_share = hoarded_share(&minutes_between, &th.restock_minutes);
+        Obs { minutes_between, save_per_min, spend_per_min: save_per_min * (1.0 - hoarded_share), hoarded_share }
+    }
+}
+
+/// Unitless error vs targets: mean [`pct_error`](control::pct_error) of each
+/// item's cadence against `cadence_minutes * cadence_slope^i` (0 if no items),
+/// plus any hoarded share above `max_hoard`.
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    if o.minutes_between.is_empty() {
+        return 0.0;
+    }
+    let sum: f64 = o
+        .minutes_between
+        .iter()
+        .enumerate()
+        .map(|(i, &m)| control::pct_error(m, tgt.cadence_minutes * tgt.cadence_slope.powi(i as i32)))
+        .sum();
+    sum / o.minutes_between.len() as f64 + (o.hoarded_share - tgt.max_hoard).max(0.0)
+}
+
+/// Named per-item cadence residuals (`item0`, `item1`, …) and the hoard
+/// ceiling (`hoarded_share`) for [`Outcome::residuals`].
+pub fn residuals(o: &Obs, tgt: &Targets) -> Vec<Residual> {
+    o.minutes_between
+        .iter()
+        .enumerate()
+        .map(|(i, &m)| Residual::new(format!("item{i}"), m, tgt.cadence_minutes * tgt.cadence_slope.powi(i as i32)))
+        .chain([Residual::new("hoarded_share", o.hoarded_share, o.hoarded_share.min(tgt.max_hoard))])
+        .collect()
+}
+
+pub fn balance_ext(
+    theta0: Params,
+    env: Env,
+    tgt: Targets,
+    opts: Options<impl SimModel>,
+) -> Outcome<Params, Obs> {
+    let Options { bounds: bnd, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = vec![ControllerState::default(); theta0.prices.len()];
+    let mut restock_state = vec![ControllerState::default(); theta0.restock_minutes.len()];
+    let stretch = theta0.prices.len() as f64 / (1.0 - tgt.max_hoard).max(1e-6);
+    balance_with_hooks(
+        theta0,
+        env,
+        tgt,
+        bnd,
+        g,
+        mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+        max_iters,
+        // simulate: delegate to the observation model
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, mechs),
+        // nominal: x = first-item cadence, y = slope, z = saving rate
+        |_th, _env, tgt, o| NominalTargets {
+            x: tgt.cadence_minutes,
+            y: tgt.cadence_slope.max(1e-6),
+            z: o.save_per_min,
+        },
+        // step: price_i → save_rate · cadence · slope^i;
+        //       restock_i → cadence_i · n / (1 − max_hoard), which puts the
+        //       hoarded share exactly at the cap
+        move |th, b, g, nom, adj| {
+            let cadence = nom.x * adj.a;
+            let slope = nom.y * adj.b;
+            let save = nom.z * adj.c;
+
+            let st = &mut ctl_state;
+            st.resize(th.prices.len(), ControllerState::default());
+            let prices = th
+                .prices
+                .iter()
+                .enumerate()
+                .map(|(i, &p)| {
+                    let target = save * cadence * slope.powi(i as i32);
+                    let target = match reg.as_ref() {
+                        Some(r) => r.pull(target, |base| base.prices.get(i).copied().unwrap_or(target)),
+                        None => target,
+                    };
+                    controller.step(&mut st[i], p, target.clamp(b.price_min, b.price_max), g.k_price, b.price_min, b.price_max)
+                })
+                .collect();
+
+            let st = &mut restock_state;
+            st.resize(th.restock_minutes.len(), ControllerState::default());
+            let restock_minutes = th
+                .restock_minutes
+                .iter()
+                .enumerate()
+                .map(|(i, &r)| {
+                    let target = cadence * slope.powi(i as i32) * stretch;
+                    let target = match reg.as_ref() {
+                        Some(rg) => rg.pull(target, |base| base.restock_minutes.get(i).copied().unwrap_or(target)),
+                        None => target,
+                    };
+                    let target = target.clamp(b.restock_min, b.restock_max);
+                    controller.step(&mut st[i], r, target, g.k_restock, b.restock_min, b.restock_max)
+                })
+                .collect();
+            Params { prices, restock_minutes }
+        },
+        // converged: every item within ±5% of its cadence target and the
+        // hoarded share no more than 1 point above the cap
+        |o, tgt| {
+            o.minutes_between.iter().enumerate().all(|(i, &m)| {
+                let want = tgt.cadence_minutes * tgt.cadence_slope.powi(i as i32);
+                within(m, want, RelTol(0.05))
+            }) && o.hoarded_share <= tgt.max_hoard + 0.01
+        },
+    )
+    .with_residuals(|o| residuals(o, &tgt))
+}
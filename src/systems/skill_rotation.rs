@@ -0,0 +1,261 @@
+//! Skill rotation: tune ability cooldowns, effect durations and resource
+//! costs so a fixed priority rotation keeps its effects up within a band and
+//! wastes no more than a set number of seconds per minute on dead GCDs.
+//!
+//! The rotation is simulated exactly, event by event: whenever the global
+//! cooldown is free the player casts the highest-priority ability (θ order)
+//! that is off cooldown and affordable; resource regenerates continuously up
+//! to a cap. Time with nothing to press is dead, and dead time spent waiting
+//! on resource for a ready ability is starvation. Cooldowns scale together
+//! until the dead time sits at the cap, durations scale together until the
+//! mean uptime sits mid-band, and costs scale together until casting on
+//! cooldown spends exactly what regenerates.
+
+use crate::error::{Checks, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::{self, ControllerState};
+use crate::systems::sdk::{
+    balance_with_hooks, compose_income, Band, Hook, NominalTargets, Outcome, Residual,
+};
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Params {
+    pub cooldowns: Vec<f64>, // seconds, one per ability in priority order
+    pub durations: Vec<f64>, // seconds each cast keeps the ability's effect up; 0 = none
+    pub costs: Vec<f64>,     // resource per cast
+}
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        self.cooldowns.iter().chain(&self.durations).chain(&self.costs).copied().collect()
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        let (cooldowns, rest) = v.split_at(self.cooldowns.len().min(v.len()));
+        let (durations, costs) = rest.split_at(self.durations.len().min(rest.len()));
+        Self { cooldowns: cooldowns.to_vec(), durations: durations.to_vec(), costs: costs.to_vec() }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Env {
+    pub gcd_seconds: f64,
+    pub regen_per_sec: f64,
+    pub resource_max: f64,    // the rotation starts full
+    pub horizon_seconds: f64, // simulated fight length
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Targets {
+    pub uptime_band: (f64, f64), // mean effect uptime, e.g. (0.55, 0.65)
+    pub max_dead_per_min: f64,   // dead GCD seconds per minute, e.g. 6
+}
+
+crate::define_system! {
+    bounds {
+        cooldown: cooldown_min..cooldown_max = (0.0, 600.0),
+        duration: duration_min..duration_max = (0.0, 600.0),
+        cost: cost_min..cost_max = (0.0, 1e9),
+    }
+    gains { k_cooldown = 0.4, k_duration = 0.5, k_cost = 0.5 }
+}
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .positive("gcd_seconds", self.gcd_seconds)
+            .non_negative("regen_per_sec", self.regen_per_sec)
+            .non_negative("resource_max", self.resource_max)
+            .positive("horizon_seconds", self.horizon_seconds)
+            .done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .range("uptime_band", self.uptime_band.0, self.uptime_band.1)
+            .within("uptime_band.0", self.uptime_band.0, 0.0, 1.0)
+            .within("uptime_band.1", self.uptime_band.1, 0.0, 1.0)
+            .check("max_dead_per_min", (0.0..60.0).contains(&self.max_dead_per_min), "must be in [0, 60)")
+            .done()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obs {
+    pub regen_per_sec: f64,   // after hooks
+    pub casts: Vec<usize>,    // per ability over the horizon
+    pub uptime: Vec<f64>,     // per ability: share of the fight its effect is up
+    pub rotation_uptime: f64, // mean uptime over abilities with an effect
+    pub busy: f64,            // share of the fight spent on GCDs
+    pub dead_per_min: f64,    // seconds per minute with nothing to press
+    pub starvation: f64,      // share of the fight waiting on resource for a ready ability
+    pub apm: f64,             // casts per minute
+    pub demand_ratio: f64,    // resource spent casting everything on cooldown / regenerated
+}
+
+/// Run the priority list for `env.horizon_seconds` at `regen_per_sec`.
+pub fn simulate(th: &Params, env: &Env, regen_per_sec: f64) -> Obs {
+    let n = th.cooldowns.len();
+    let cost = |i: usize| th.costs.get(i).copied().unwrap_or(0.0).max(0.0);
+    let horizon = env.horizon_seconds.max(0.0);
+    let (mut t, mut resource) = (0.0_f64, env.resource_max.max(0.0));
+    let (mut ready_at, mut up_until, mut up) = (vec![0.0_f64; n], vec![0.0_f64; n], vec![0.0_f64; n]);
+    let mut casts = vec![0_usize; n];
+    let (mut dead, mut starved) = (0.0_f64, 0.0_f64);
+    let advance = |t: &mut f64, resource: &mut f64, dt: f64| {
+        *resource = (*resource + regen_per_sec * dt).min(env.resource_max.max(0.0));
+        *t += dt;
+    };
+    while t < horizon {
+        let ready = |i: &usize| ready_at[*i] <= t + 1e-9;
+        match (0..n).filter(ready).find(|&i| cost(i) <= resource + 1e-9) {
+            Some(i) => {
+                resource -= cost(i);
+                ready_at[i] = t + th.cooldowns[i].max(0.0);
+                let until = (t + th.durations.get(i).copied().unwrap_or(0.0).max(0.0)).min(horizon);
+                up[i] += (until - t.max(up_until[i])).max(0.0);
+                up_until[i] = up_until[i].max(until);
+                casts[i] += 1;
+                advance(&mut t, &mut resource, env.gcd_seconds.max(1e-9));
+            }
+            None => {
+                // wait for the next cooldown or for resource for a ready ability
+                let next_ready = ready_at.iter().copied().filter(|&r| r > t + 1e-9).fold(f64::INFINITY, f64::min);
+                let afford = (0..n)
+                    .filter(ready)
+                    .map(|i| if regen_per_sec > 0.0 { (cost(i) - resource) / regen_per_sec } else { f64::INFINITY })
+                    .fold(f64::INFINITY, f64::min);
+                let dt = (next_ready - t).min(afford).min(horizon - t).max(1e-9);
+                dead += dt;
+                if (0..n).any(|i| ready(&i)) {
+                    starved += dt;
+                }
+                advance(&mut t, &mut resource, dt);
+            }
+        }
+    }
+    let span = horizon.max(1e-12);
+    let uptime: Vec<f64> = up.iter().map(|u| u / span).collect();
+    let with_effect: Vec<f64> =
+        uptime.iter().zip(&th.durations).filter(|&(_, &d)| d > 0.0).map(|(&u, _)| u).collect();
+    let total_casts: usize = casts.iter().sum();
+    let demand: f64 = (0..n).map(|i| cost(i) / th.cooldowns[i].max(env.gcd_seconds).max(1e-9)).sum();
+    Obs {
+        regen_per_sec,
+        rotation_uptime: if with_effect.is_empty() { 0.0 } else { with_effect.iter().sum::<f64>() / with_effect.len() as f64 },
+        busy: (span - dead).max(0.0) / span,
+        dead_per_min: dead * 60.0 / span,
+        starvation: starved / span,
+        apm: total_casts as f64 * 60.0 / span,
+        demand_ratio: demand / regen_per_sec.max(1e-12),
+        casts,
+        uptime,
+    }
+}
+
+/// The priority simulation described in the module docs; hooks'
+/// `income_multiplier` scales resource regeneration. Implement [`SimModel`]
+/// for haste, procs or charges.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        _tgt: &Targets,
+        mechs: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        simulate(th, env, compose_income(env.regen_per_sec, mechs, th, env))
+    }
+}
+
+fn mid((lo, hi): (f64, f64)) -> f64 {
+    0.5 * (lo + hi)
+}
+
+/// Unitless error vs targets: uptime against the band midpoint, dead time
+/// over its cap (per minute) and resource demand against regeneration.
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    control::pct_error(o.rotation_uptime, mid(tgt.uptime_band))
+        + (o.dead_per_min - tgt.max_dead_per_min).max(0.0) / 60.0
+        + (o.demand_ratio - 1.0).abs()
+}
+
+/// Named residuals for [`Outcome::residuals`]; the dead-time cap counts as
+/// met anywhere below it.
+pub fn residuals(o: &Obs, tgt: &Targets) -> Vec<Residual> {
+    vec![
+        Residual::new("rotation_uptime", o.rotation_uptime, mid(tgt.uptime_band)),
+        Residual::new("dead_per_min", o.dead_per_min, o.dead_per_min.min(tgt.max_dead_per_min)),
+        Residual::new("demand_ratio", o.demand_ratio, 1.0),
+    ]
+}
+
+pub fn balance_ext(
+    theta0: Params,
+    env: Env,
+    tgt: Targets,
+    opts: Options<impl SimModel>,
+) -> Outcome<Params, Obs> {
+    let Options { bounds: b, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = vec![ControllerState::default(); theta0.flatten().len()];
+    balance_with_hooks(
+        theta0,
+        env,
+        tgt,
+        b,
+        g,
+        mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+        max_iters,
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, mechs),
+        // nominal: scale factors for x = cooldowns (busy share onto
+        // 1 − dead cap), y = durations (uptime onto the band midpoint) and
+        // z = costs (demand onto regeneration)
+        |_th, _env, tgt, o| {
+            let busy_t = 1.0 - tgt.max_dead_per_min / 60.0;
+            NominalTargets {
+                x: o.busy / busy_t.max(1e-9),
+                y: mid(tgt.uptime_band) / o.rotation_uptime.max(1e-9),
+                z: 1.0 / o.demand_ratio.max(1e-9),
+            }
+        },
+        // step: every value → its current value times its group's factor
+        move |th, b, g, nom, adj| {
+            let groups = [
+                (nom.x * adj.a, g.k_cooldown, b.cooldown_min, b.cooldown_max),
+                (nom.y * adj.b, g.k_duration, b.duration_min, b.duration_max),
+                (nom.z * adj.c, g.k_cost, b.cost_min, b.cost_max),
+            ];
+            let n = th.cooldowns.len().max(1);
+
+            let flat = th.flatten();
+            let st = &mut ctl_state;
+            st.resize(flat.len(), ControllerState::default());
+            let next: Vec<f64> = flat
+                .iter()
+                .enumerate()
+                .map(|(j, &v)| {
+                    let (f, k, lo, hi) = groups[(j / n).min(2)];
+                    let target = match reg.as_ref() {
+                        Some(r) => r.pull(v * f, |base| base.flatten().get(j).copied().unwrap_or(v * f)),
+                        None => v * f,
+                    };
+                    controller.step(&mut st[j], v, target.clamp(lo, hi), k, lo, hi)
+                })
+                .collect();
+            th.unflatten(&next)
+        },
+        // converged: uptime inside the band, dead time no more than half a
+        // second per minute over the cap and demand within ±5% of regen
+        |o, tgt| {
+            Band::from(tgt.uptime_band).contains(o.rotation_uptime)
+                && o.dead_per_min <= tgt.max_dead_per_min + 0.5
+                && (o.demand_ratio - 1.0).abs() <= 0.05
+        },
+    )
+    .with_residuals(|o| residuals(o, &tgt))
+}
@@ -1,13 +1,27 @@
-use crate::mechanics::control;
-use crate::systems::sdk::{balance_with_hooks, Hook, NominalTargets, Outcome};
+use crate::error::{check_range, Checks, Error, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::{self, ControllerState};
+use crate::systems::sdk::{
+    balance_with_hooks, compose_cost, within, AbsTol, Band, Hook, NominalTargets, Outcome, Residual,
+};
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Params {
     pub base: f64,       // C0
     pub growth: f64,     // g > 1
     pub track_mult: f64, // per-track scaling
 }
 
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        vec![self.base, self.growth, self.track_mult]
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { base: v[0], growth: v[1], track_mult: v[2] }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Env {
     pub levels: u32,        // upgrades in this “chapter”
@@ -20,45 +34,125 @@ pub struct Targets {
     pub slope_pref: f64,      // TTU_{L+1}/TTU_L preference, e.g. 1.10
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct Bounds {
-    pub base_min: f64,  pub base_max: f64,
-    pub growth_min: f64, pub growth_max: f64,
-    pub mult_min: f64,  pub mult_max: f64,
+crate::define_system! {
+    bounds {
+        base: base_min..base_max = (1.0, 1e9),
+        growth: growth_min..growth_max = (1.01, 2.5),
+        track_mult: mult_min..mult_max = (0.1, 100.0),
+    }
+    gains { k_base = 0.6, k_growth = 0.4, k_mult = 0.5 }
+    inputs {
+        /// Upstream income per second from the core loop.
+        ref_income: f64,
+    }
 }
+
 impl Bounds {
-    pub fn soft() -> Self {
-        Self {
-            base_min: 1.0, base_max: 1e9,
-            growth_min: 1.01, growth_max: 2.5,
-            mult_min: 0.1, mult_max: 100.0,
-        }
+    /// Validated bounds: every pair must be finite with min ≤ max.
+    pub fn new(
+        base_min: f64, base_max: f64,
+        growth_min: f64, growth_max: f64,
+        mult_min: f64, mult_max: f64,
+    ) -> Result<Self, Error> {
+        check_range("base", base_min, base_max)?;
+        check_range("growth", growth_min, growth_max)?;
+        check_range("track_mult", mult_min, mult_max)?;
+        Ok(Self { base_min, base_max, growth_min, growth_max, mult_min, mult_max })
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct Gains { pub k_base: f64, pub k_growth: f64, pub k_mult: f64 }
-impl Default for Gains { fn default() -> Self { Self { k_base: 0.6, k_growth: 0.4, k_mult: 0.5 } } }
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .check("levels", self.levels > 0, "must be at least 1")
+            .non_negative("gain_per_level", self.gain_per_level)
+            .done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        let (lo, hi) = self.ttu_band;
+        Checks::default()
+            .range("ttu_band", lo, hi)
+            .positive("ttu_band.0", lo)
+            .positive("slope_pref", self.slope_pref)
+            .done()
+    }
+}
 
 #[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Obs {
     pub ttu_mean: f64,  // mean TTU over levels under a reference income
     pub ttu_slope: f64, // average TTU_{L+1}/TTU_L
 }
 
-pub trait Mechanic: Hook<Params, Env, Targets, Obs> {}
-impl<T: Hook<Params, Env, Targets, Obs>> Mechanic for T {}
+/// The default per-level TTU proxy (~10% of `ref_income` saved).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        _tgt: &Targets,
+        ref_income: f64,
+        hooks: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let n = env.levels as usize;
+        let cost_scale = compose_cost(1.0, hooks, th, env);
+        let mut sum = 0.0;
+        let mut slope_acc = 0.0;
+        let mut prev_ttu: Option<f64> = None;
+
+        for l in 0..n {
+            let lvl = l as f64;
+            let cost = th.base * th.growth.powf(lvl) * th.track_mult * cost_scale;
+            // Proxy: assume ~90% utilization → 10% savings
+            let save_rate = (1.0_f64 - 0.9_f64).max(0.1) * ref_income;
+            let ttu = (cost / save_rate.max(1e-9)).clamp(0.0, 86_400.0);
+
+            sum += ttu;
+            if let Some(p) = prev_ttu {
+                slope_acc += (ttu / p).clamp(0.1, 10.0);
+            }
+            prev_ttu = Some(ttu);
+        }
+
+        let ttu_mean = sum / (n.max(1) as f64);
+        let ttu_slope = if n > 1 { slope_acc / ((n - 1) as f64) } else { 1.0 };
+        Obs { ttu_mean, ttu_slope }
+    }
+}
+
+/// Unitless error vs targets: mean of the TTU distance outside the band
+/// (relative to the nearest edge; 0 inside) and the slope's `pct_error`.
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    let (lo, hi) = tgt.ttu_band;
+    let edge = o.ttu_mean.clamp(lo.min(hi), hi.max(lo));
+    (control::pct_error(o.ttu_mean, edge) + control::pct_error(o.ttu_slope, tgt.slope_pref)) / 2.0
+}
+
+/// Named residuals for [`Outcome::residuals`]: the mean TTU against the
+/// nearest band edge (on target inside the band) and the slope.
+pub fn residuals(o: &Obs, tgt: &Targets) -> Vec<Residual> {
+    let (lo, hi) = tgt.ttu_band;
+    vec![
+        Residual::new("ttu_mean", o.ttu_mean, o.ttu_mean.clamp(lo.min(hi), hi.max(lo))),
+        Residual::new("ttu_slope", o.ttu_slope, tgt.slope_pref),
+    ]
+}
 
 pub fn balance_ext(
     theta0: Params,
     env: Env,
     tgt: Targets,
-    bnd: Bounds,
-    g: Gains,
-    mechs: Vec<Box<dyn Mechanic>>,
-    max_iters: usize,
     ref_income: f64,
+    opts: Options<impl SimModel>,
 ) -> Outcome<Params, Obs> {
+    let Options { bounds: bnd, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = [ControllerState::default(); 3];
     balance_with_hooks(
         theta0,
         env,
@@ -67,31 +161,8 @@ pub fn balance_ext(
         g,
         mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
         max_iters,
-        // simulate: approximate TTU given cost_k and ref income
-        move |th, env, _tgt, _mechs| {
-            let n = env.levels as usize;
-            let mut sum = 0.0;
-            let mut slope_acc = 0.0;
-            let mut prev_ttu: Option<f64> = None;
-
-            for l in 0..n {
-                let lvl = l as f64;
-                let cost = th.base * th.growth.powf(lvl) * th.track_mult;
-                // Proxy: assume ~90% utilization → 10% savings
-                let save_rate = (1.0_f64 - 0.9_f64).max(0.1) * ref_income;
-                let ttu = (cost / save_rate.max(1e-9)).clamp(0.0, 86_400.0);
-
-                sum += ttu;
-                if let Some(p) = prev_ttu {
-                    slope_acc += (ttu / p).clamp(0.1, 10.0);
-                }
-                prev_ttu = Some(ttu);
-            }
-
-            let ttu_mean = sum / (n.max(1) as f64);
-            let ttu_slope = if n > 1 { slope_acc / ((n - 1) as f64) } else { 1.0 };
-            Obs { ttu_mean, ttu_slope }
-        },
+        // simulate: delegate to the observation model
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, ref_income, mechs),
         // nominal: target mean & slope from band
         |th, _env, tgt, _o| {
             let target_mean  = 0.5 * (tgt.ttu_band.0 + tgt.ttu_band.1);
@@ -99,7 +170,7 @@ pub fn balance_ext(
             NominalTargets { x: target_mean, y: target_slope, z: th.track_mult } // z unused here
         },
         // step: base→mean, growth→slope, mult as buffer
-        |th, b, g, nom, _adj| {
+        move |th, b, g, nom, _adj| {
             let desired_mean  = nom.x;
             let desired_slope = nom.y;
 
@@ -109,17 +180,28 @@ pub fn balance_ext(
                 .clamp(b.growth_min, b.growth_max);
             let mult_target   = th.track_mult;
 
-            let base       = control::approach(th.base,       base_target.clamp(b.base_min, b.base_max),   g.k_base,  b.base_min,  b.base_max);
-            let growth     = control::approach(th.growth,     growth_target,                               g.k_growth,b.growth_min,b.growth_max);
-            let track_mult = control::approach(th.track_mult, mult_target.clamp(b.mult_min, b.mult_max),   g.k_mult,  b.mult_min,  b.mult_max);
+            let (base_target, growth_target, mult_target) = match reg {
+                Some(r) => (
+                    r.pull(base_target, |p| p.base),
+                    r.pull(growth_target, |p| p.growth),
+                    r.pull(mult_target, |p| p.track_mult),
+                ),
+                None => (base_target, growth_target, mult_target),
+            };
+
+            let st = &mut ctl_state;
+            let base       = controller.step(&mut st[0], th.base,       base_target.clamp(b.base_min, b.base_max),   g.k_base,  b.base_min,  b.base_max);
+            let growth     = controller.step(&mut st[1], th.growth,     growth_target,                               g.k_growth,b.growth_min,b.growth_max);
+            let track_mult = controller.step(&mut st[2], th.track_mult, mult_target.clamp(b.mult_min, b.mult_max),   g.k_mult,  b.mult_min,  b.mult_max);
 
             Params { base, growth, track_mult }
         },
         // converged: mean TTU within band & slope near target
         |o, tgt| {
-            let mean_ok  = o.ttu_mean >= tgt.ttu_band.0 && o.ttu_mean <= tgt.ttu_band.1;
-            let slope_ok = (o.ttu_slope - tgt.slope_pref).abs() <= 0.05;
+            let mean_ok  = Band::from(tgt.ttu_band).contains(o.ttu_mean);
+            let slope_ok = within(o.ttu_slope, tgt.slope_pref, AbsTol(0.05));
             mean_ok && slope_ok
         },
     )
+    .with_residuals(|o| residuals(o, &tgt))
 }
@@ -0,0 +1,338 @@
+//! Wave pressure: tune tower-defense wave scaling (enemy count and HP growth,
+//! spawn spacing, boss HP) against a reference tower DPS curve, toward a
+//! leak-rate band on ordinary waves and a near-failure window on boss waves.
+//!
+//! Wave `w` (0-based) sends `first_count · count_growth^w` enemies of
+//! `first_hp · hp_growth^w` HP (× `boss_hp_mult` on every `boss_every`-th
+//! wave), `spacing` seconds apart. Towers engage from the first spawn until
+//! the last enemy has walked the path, so a wave's pressure is the tower
+//! time it needs over the time it gets:
+//! `count · hp / dps / ((count − 1) · spacing + path_seconds)`.
+//!
+//! Players' towers spread uniformly within ±`dps_spread` of the reference
+//! curve, and a player at pressure `p` leaks `1 − 1/p` of the wave once
+//! `p > 1`; [`leak_share`] averages that over the spread. Spacing puts the
+//! first wave on the leak band, HP growth the last ordinary wave, count
+//! growth stretches the last wave's spawn to `last_wave_seconds`, and the
+//! boss multiplier centres boss pressure in its window. Waves in between
+//! follow the tower curve; [`Obs`] reports every wave.
+
+use crate::error::{Checks, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::{self, ControllerState};
+use crate::systems::sdk::{
+    balance_with_hooks, compose_income, within, Band, Hook, NominalTargets, Outcome, Residual, RelTol,
+};
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Params {
+    pub spacing: f64,      // seconds between spawns
+    pub hp_growth: f64,    // × enemy HP per wave
+    pub count_growth: f64, // × enemy count per wave
+    pub boss_hp_mult: f64, // × HP on boss waves
+}
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        vec![self.spacing, self.hp_growth, self.count_growth, self.boss_hp_mult]
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { spacing: v[0], hp_growth: v[1], count_growth: v[2], boss_hp_mult: v[3] }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Env {
+    pub tower_dps: Vec<f64>, // reference tower DPS per wave; its length is the wave count
+    pub first_count: f64,    // enemies in wave 0
+    pub first_hp: f64,       // enemy HP in wave 0
+    pub path_seconds: f64,   // time an enemy spends in tower range
+    pub dps_spread: f64,     // players' towers span ±this share of the curve, e.g. 0.3
+    pub boss_every: usize,   // every n-th wave is a boss wave; 0 = none
+}
+impl Env {
+    /// Whether wave `w` (0-based) is a boss wave.
+    pub fn is_boss(&self, w: usize) -> bool {
+        self.boss_every > 0 && (w + 1).is_multiple_of(self.boss_every)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Targets {
+    pub leak_band: (f64, f64),   // leaked share on ordinary waves, e.g. (0.02, 0.06)
+    pub boss_window: (f64, f64), // reference-player pressure on boss waves, e.g. (0.95, 1.05)
+    pub last_wave_seconds: f64,  // spawn duration of the last wave
+}
+
+crate::define_system! {
+    bounds {
+        spacing: spacing_min..spacing_max = (0.05, 60.0),
+        hp_growth: hp_growth_min..hp_growth_max = (0.5, 3.0),
+        count_growth: count_growth_min..count_growth_max = (0.5, 3.0),
+        boss_hp_mult: boss_min..boss_max = (1.0, 1e3),
+    }
+    gains { k_spacing = 0.5, k_hp_growth = 0.5, k_count_growth = 0.5, k_boss = 0.6 }
+}
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        let ordinary = (0..self.tower_dps.len()).filter(|&w| !self.is_boss(w)).count();
+        let c = Checks::default()
+            .check("tower_dps", ordinary >= 2, "needs at least two ordinary waves")
+            .check("boss_every", self.boss_every != 1, "must leave ordinary waves (0 or ≥ 2)");
+        self.tower_dps
+            .iter()
+            .fold(c, |c, &d| c.positive("tower_dps", d))
+            .check("first_count", self.first_count >= 1.0, "must be ≥ 1")
+            .positive("first_hp", self.first_hp)
+            .positive("path_seconds", self.path_seconds)
+            .check("dps_spread", (0.0..1.0).contains(&self.dps_spread), "must be in [0, 1)")
+            .done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .range("leak_band", self.leak_band.0, self.leak_band.1)
+            .check("leak_band", self.leak_band.0 >= 0.0 && self.leak_band.1 < 1.0, "must lie in [0, 1)")
+            .range("boss_window", self.boss_window.0, self.boss_window.1)
+            .positive("boss_window.0", self.boss_window.0)
+            .positive("last_wave_seconds", self.last_wave_seconds)
+            .done()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obs {
+    pub dps_mult: f64,           // tower DPS factor from hooks
+    pub pressure: Vec<f64>,      // reference-player pressure per wave
+    pub leak: Vec<f64>,          // leaked share per wave, averaged over the spread
+    pub spawn_seconds: Vec<f64>, // first spawn → last spawn per wave
+    pub leak_first: f64,         // first ordinary wave
+    pub leak_last: f64,          // last ordinary wave
+    pub boss_pressure: f64,      // mean over boss waves (0 without any)
+    pub worst_leak: f64,         // highest leak on an ordinary wave
+    pub worst_wave: usize,       // where it happens
+}
+
+/// Enemy `(count, hp)` of wave `w`, boss multiplier included.
+pub fn wave_at(th: &Params, env: &Env, w: usize) -> (f64, f64) {
+    let count = (env.first_count * th.count_growth.powi(w as i32)).max(1.0);
+    let boss = if env.is_boss(w) { th.boss_hp_mult } else { 1.0 };
+    (count, env.first_hp * th.hp_growth.powi(w as i32) * boss)
+}
+
+/// Tower time a wave needs over the time it gets.
+pub fn pressure(count: f64, hp: f64, dps: f64, spacing: f64, path_seconds: f64) -> f64 {
+    count * hp / dps.max(1e-12) / ((count - 1.0).max(0.0) * spacing + path_seconds).max(1e-12)
+}
+
+/// Leaked share at reference pressure `p`, averaged over towers spread
+/// uniformly within ±`spread`: a player with DPS factor `x` leaks
+/// `max(0, 1 − x / p)`.
+pub fn leak_share(p: f64, spread: f64) -> f64 {
+    if spread <= 0.0 {
+        return (1.0 - 1.0 / p.max(1e-12)).max(0.0);
+    }
+    let (a, b) = (1.0 - spread, 1.0 + spread);
+    let u = b.min(p);
+    if u <= a {
+        return 0.0;
+    }
+    ((u - a) - (u * u - a * a) / (2.0 * p)) / (b - a)
+}
+
+/// Reference pressure that leaks `leak` (the weakest towers' break-even
+/// `1 − spread` for a zero leak).
+pub fn pressure_for_leak(leak: f64, spread: f64) -> f64 {
+    let (mut lo, mut hi) = ((1.0 - spread).max(1e-9), 1e6);
+    if leak <= 0.0 {
+        return lo;
+    }
+    for _ in 0..100 {
+        let mid = (lo * hi).sqrt();
+        if leak_share(mid, spread) < leak { lo = mid } else { hi = mid }
+    }
+    hi
+}
+
+/// The fluid pressure model described in the module docs; hooks'
+/// `income_multiplier` scales tower DPS (global tower buffs). Implement
+/// [`SimModel`] for a tick-level wave sim with tower ranges and targeting
+/// rules.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        _tgt: &Targets,
+        mechs: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let dps_mult = compose_income(1.0, mechs, th, env).max(1e-9);
+        let waves = env.tower_dps.len();
+        let (mut p, mut leak, mut spawn) = (Vec::with_capacity(waves), Vec::with_capacity(waves), Vec::with_capacity(waves));
+        for (w, &dps) in env.tower_dps.iter().enumerate() {
+            let (count, hp) = wave_at(th, env, w);
+            let pw = pressure(count, hp, dps * dps_mult, th.spacing, env.path_seconds);
+            p.push(pw);
+            leak.push(leak_share(pw, env.dps_spread));
+            spawn.push((count - 1.0) * th.spacing);
+        }
+        let ordinary: Vec<usize> = (0..waves).filter(|&w| !env.is_boss(w)).collect();
+        let bosses: Vec<f64> = (0..waves).filter(|&w| env.is_boss(w)).map(|w| p[w]).collect();
+        let (worst_wave, worst_leak) =
+            ordinary.iter().map(|&w| (w, leak[w])).fold((0, 0.0), |best, (w, l)| if l > best.1 { (w, l) } else { best });
+        Obs {
+            dps_mult,
+            leak_first: ordinary.first().map_or(0.0, |&w| leak[w]),
+            leak_last: ordinary.last().map_or(0.0, |&w| leak[w]),
+            boss_pressure: if bosses.is_empty() { 0.0 } else { bosses.iter().sum::<f64>() / bosses.len() as f64 },
+            worst_leak,
+            worst_wave,
+            pressure: p,
+            leak,
+            spawn_seconds: spawn,
+        }
+    }
+}
+
+fn mid((lo, hi): (f64, f64)) -> f64 {
+    0.5 * (lo + hi)
+}
+
+/// Unitless error vs targets: endpoint leak misses against the band
+/// midpoint, boss pressure against the window midpoint and the last
+/// wave's spawn length.
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    let leak = mid(tgt.leak_band);
+    let boss = if o.boss_pressure > 0.0 { control::pct_error(o.boss_pressure, mid(tgt.boss_window)) } else { 0.0 };
+    (o.leak_first - leak).abs()
+        + (o.leak_last - leak).abs()
+        + boss
+        + control::pct_error(o.spawn_seconds.last().copied().unwrap_or(0.0), tgt.last_wave_seconds)
+}
+
+/// Named residuals for [`Outcome::residuals`]; `boss_pressure` only when
+/// there are boss waves.
+pub fn residuals(o: &Obs, tgt: &Targets) -> Vec<Residual> {
+    let mut out = vec![
+        Residual::new("leak_first", o.leak_first, mid(tgt.leak_band)),
+        Residual::new("leak_last", o.leak_last, mid(tgt.leak_band)),
+        Residual::new("last_wave_seconds", o.spawn_seconds.last().copied().unwrap_or(0.0), tgt.last_wave_seconds),
+    ];
+    if o.boss_pressure > 0.0 {
+        out.push(Residual::new("boss_pressure", o.boss_pressure, mid(tgt.boss_window)));
+    }
+    out
+}
+
+pub fn balance_ext(
+    theta0: Params,
+    env: Env,
+    tgt: Targets,
+    opts: Options<impl SimModel>,
+) -> Outcome<Params, Obs> {
+    let Options { bounds: b, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = [ControllerState::default(); 4];
+    // Per-wave targets depend on the tower curve; the step only sees θ, so
+    // it keeps a copy.
+    let step_env = env.clone();
+    let ordinary: Vec<usize> = (0..env.tower_dps.len()).filter(|&w| !env.is_boss(w)).collect();
+    let (first, last) = (ordinary.first().copied().unwrap_or(0), ordinary.last().copied().unwrap_or(0));
+    balance_with_hooks(
+        theta0,
+        env,
+        tgt,
+        b,
+        g,
+        mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+        max_iters,
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, mechs),
+        // nominal: x = ordinary-wave pressure and y = boss pressure, both
+        // scaled by the hooks' DPS factor (HP has to rise with it); z = last
+        // wave's spawn length
+        |_th, env, tgt, o| NominalTargets {
+            x: pressure_for_leak(mid(tgt.leak_band), env.dps_spread) * o.dps_mult,
+            y: mid(tgt.boss_window) * o.dps_mult,
+            z: tgt.last_wave_seconds,
+        },
+        // step: spacing → first ordinary wave on target pressure; count
+        //       growth → last wave's spawn length at that spacing; HP growth
+        //       → last ordinary wave on target pressure; boss multiplier →
+        //       geometric mean of what each boss wave needs
+        move |th, b, g, nom, adj| {
+            let env = &step_env;
+            let (p_t, boss_t, seconds_t) = (nom.x * adj.a, nom.y * adj.b, nom.z * adj.c);
+            // HP that puts wave `w` at pressure `p` with `count` enemies.
+            let hp_for = |w: usize, count: f64, spacing: f64, p: f64| {
+                p * env.tower_dps[w] * ((count - 1.0).max(0.0) * spacing + env.path_seconds) / count
+            };
+
+            let (count0, hp0) = wave_at(th, env, first);
+            let spacing_t = if count0 > 1.0 + 1e-9 {
+                (count0 * hp0 / (env.tower_dps[first] * p_t) - env.path_seconds) / (count0 - 1.0)
+            } else {
+                th.spacing
+            };
+            let spacing = spacing_t.clamp(b.spacing_min, b.spacing_max);
+
+            let waves = env.tower_dps.len().saturating_sub(1).max(1) as f64;
+            let count_last = seconds_t / spacing + 1.0;
+            let count_growth_t = (count_last / env.first_count).powf(1.0 / waves);
+
+            let count_at = |w: usize| (env.first_count * count_growth_t.powi(w as i32)).max(1.0);
+            let hp_growth_t = if last > 0 {
+                (hp_for(last, count_at(last), spacing, p_t) / env.first_hp).powf(1.0 / last as f64)
+            } else {
+                th.hp_growth
+            };
+
+            let bosses: Vec<f64> = (0..env.tower_dps.len())
+                .filter(|&w| env.is_boss(w))
+                .map(|w| (hp_for(w, count_at(w), spacing, boss_t) / (env.first_hp * hp_growth_t.powi(w as i32))).ln())
+                .collect();
+            let boss_mult_t =
+                if bosses.is_empty() { th.boss_hp_mult } else { (bosses.iter().sum::<f64>() / bosses.len() as f64).exp() };
+
+            let (spacing_t, hp_growth_t, count_growth_t, boss_mult_t) = match reg {
+                Some(r) => (
+                    r.pull(spacing_t, |p| p.spacing),
+                    r.pull(hp_growth_t, |p| p.hp_growth),
+                    r.pull(count_growth_t, |p| p.count_growth),
+                    r.pull(boss_mult_t, |p| p.boss_hp_mult),
+                ),
+                None => (spacing_t, hp_growth_t, count_growth_t, boss_mult_t),
+            };
+
+            let st = &mut ctl_state;
+            Params {
+                spacing: controller.step(&mut st[0], th.spacing, spacing_t, g.k_spacing, b.spacing_min, b.spacing_max),
+                hp_growth: controller.step(&mut st[1], th.hp_growth, hp_growth_t, g.k_hp_growth, b.hp_growth_min, b.hp_growth_max),
+                count_growth: controller.step(
+                    &mut st[2],
+                    th.count_growth,
+                    count_growth_t,
+                    g.k_count_growth,
+                    b.count_growth_min,
+                    b.count_growth_max,
+                ),
+                boss_hp_mult: controller.step(&mut st[3], th.boss_hp_mult, boss_mult_t, g.k_boss, b.boss_min, b.boss_max),
+            }
+        },
+        // converged: first and last ordinary waves inside the leak band,
+        // boss pressure inside its window and the last wave on length
+        |o, tgt| {
+            let leaks = Band::from(tgt.leak_band);
+            leaks.contains(o.leak_first)
+                && leaks.contains(o.leak_last)
+                && (o.boss_pressure == 0.0 || Band::from(tgt.boss_window).contains(o.boss_pressure))
+                && o.spawn_seconds.last().is_some_and(|&s| within(s, tgt.last_wave_seconds, RelTol(0.05)))
+        },
+    )
+    .with_residuals(|o| residuals(o, &tgt))
+}
@@ -0,0 +1,250 @@
+//! XP curve: tune the XP requirement (base and growth per level) and the XP
+//! per activity so levels take a target number of minutes and the whole
+//! climb a target number of hours.
+//!
+//! Level `L` (0-based) needs `xp_base · xp_growth^L` XP. XP arrives from
+//! activities (`xp_per_activity` every `activity_minutes`) plus `ref_income`,
+//! the upstream XP per minute from everything else (quests, passive trickle),
+//! passed in the same way `upgrade_cost_curve` takes its reference income.
+//! Hooks' `income_multiplier` scales the total (XP boosts).
+//!
+//! Growth is the steepest that keeps the fastest and slowest levels inside
+//! `minutes_band` at the target total, so later levels slow down as much as
+//! the band allows; XP per activity sets activities' share of the XP, since
+//! only the XP/requirement ratio reaches the player otherwise.
+
+use crate::error::{Checks, Problem, Validate};
+use crate::Flat;
+use crate::mechanics::control::{self, ControllerState};
+use crate::systems::sdk::{
+    balance_with_hooks, compose_income, within, AbsTol, Band, Hook, NominalTargets, Outcome, Residual, RelTol,
+};
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Params {
+    pub xp_base: f64,         // XP for the first level-up
+    pub xp_growth: f64,       // × per level
+    pub xp_per_activity: f64, // XP per completed activity
+}
+
+impl Flat for Params {
+    fn flatten(&self) -> Vec<f64> {
+        vec![self.xp_base, self.xp_growth, self.xp_per_activity]
+    }
+    fn unflatten(&self, v: &[f64]) -> Self {
+        Self { xp_base: v[0], xp_growth: v[1], xp_per_activity: v[2] }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Env {
+    pub levels: u32,           // level-ups from 1 to the cap
+    pub activity_minutes: f64, // minutes per activity
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Targets {
+    pub minutes_band: (f64, f64), // every level takes this long
+    pub hours_to_cap: f64,        // total climb
+    pub activity_share: f64,      // share of XP from activities (1 when ref_income is 0)
+}
+
+crate::define_system! {
+    bounds {
+        xp_base: base_min..base_max = (1.0, 1e12),
+        xp_growth: growth_min..growth_max = (1.0, 2.0),
+        xp_per_activity: xpa_min..xpa_max = (1e-3, 1e9),
+    }
+    gains { k_base = 0.6, k_growth = 0.4, k_xpa = 0.6 }
+    inputs {
+        /// Upstream XP per minute from everything but activities.
+        ref_income: f64,
+    }
+}
+
+impl Validate for Env {
+    fn problems(&self) -> Vec<Problem> {
+        Checks::default()
+            .check("levels", self.levels > 0, "must be at least 1")
+            .positive("activity_minutes", self.activity_minutes)
+            .done()
+    }
+}
+impl Validate for Targets {
+    fn problems(&self) -> Vec<Problem> {
+        let (lo, hi) = self.minutes_band;
+        Checks::default()
+            .range("minutes_band", lo, hi)
+            .positive("minutes_band.0", lo)
+            .positive("hours_to_cap", self.hours_to_cap)
+            .check("activity_share", self.activity_share > 0.0 && self.activity_share <= 1.0, "must be in (0, 1]")
+            .done()
+    }
+}
+
+/// Relative room [`steepest_growth`] leaves at the binding band edge.
+pub const BAND_MARGIN: f64 = 0.05;
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obs {
+    pub xp_per_min: f64,             // after hooks
+    pub activity_share: f64,         // activities' share of XP, before hooks
+    pub minutes_per_level: Vec<f64>, // per level-up
+    pub fastest_level: f64,          // minutes
+    pub slowest_level: f64,          // minutes
+    pub hours_to_cap: f64,
+}
+
+/// Minutes of the first level-up when `levels` level-ups growing by
+/// `growth` take `total_minutes` in all.
+pub fn first_level_minutes(total_minutes: f64, growth: f64, levels: u32) -> f64 {
+    let n = levels.max(1) as i32;
+    if (growth - 1.0).abs() < 1e-12 {
+        total_minutes / n as f64
+    } else {
+        total_minutes * (growth - 1.0) / (growth.powi(n) - 1.0)
+    }
+}
+
+/// Steepest growth that keeps every level inside the band (with
+/// [`BAND_MARGIN`] to spare) at the target total; 1 (a flat curve) when
+/// even that misses the band.
+pub fn steepest_growth(env: &Env, tgt: &Targets, growth_max: f64) -> f64 {
+    let total = tgt.hours_to_cap * 60.0;
+    let (lo, hi) = (tgt.minutes_band.0 * (1.0 + BAND_MARGIN), tgt.minutes_band.1 / (1.0 + BAND_MARGIN));
+    // Steeper growth makes the first level faster and the last slower, so
+    // the growths that fit form an interval starting at 1.
+    let fits = |g: f64| {
+        let first = first_level_minutes(total, g, env.levels);
+        first >= lo && first * g.powi(env.levels.saturating_sub(1) as i32) <= hi
+    };
+    let (mut ok, mut bad) = (1.0, growth_max.max(1.0));
+    if fits(bad) {
+        return bad;
+    }
+    for _ in 0..60 {
+        let mid = 0.5 * (ok + bad);
+        if fits(mid) { ok = mid } else { bad = mid }
+    }
+    ok
+}
+
+/// Steady XP income; the requirement grows geometrically. `ref_income` is the
+/// upstream XP signal.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardModel;
+
+impl SimModel for StandardModel {
+    fn observe(
+        &self,
+        th: &Params,
+        env: &Env,
+        _tgt: &Targets,
+        ref_income: f64,
+        hooks: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>],
+    ) -> Obs {
+        let activity = th.xp_per_activity / env.activity_minutes.max(1e-9);
+        let raw = activity + ref_income.max(0.0);
+        let rate = compose_income(raw, hooks, th, env).max(1e-12);
+        let minutes: Vec<f64> =
+            (0..env.levels as i32).map(|l| th.xp_base * th.xp_growth.powi(l) / rate).collect();
+        Obs {
+            xp_per_min: rate,
+            activity_share: activity / raw.max(1e-12),
+            fastest_level: minutes.iter().copied().fold(f64::INFINITY, f64::min),
+            slowest_level: minutes.iter().copied().fold(0.0, f64::max),
+            hours_to_cap: minutes.iter().sum::<f64>() / 60.0,
+            minutes_per_level: minutes,
+        }
+    }
+}
+
+/// Unitless error vs targets: the total's `pct_error` plus how far the
+/// fastest and slowest levels sit outside the band (relative to its edges).
+pub fn normalized_error(o: &Obs, tgt: &Targets) -> f64 {
+    let (lo, hi) = tgt.minutes_band;
+    control::pct_error(o.hours_to_cap, tgt.hours_to_cap)
+        + control::pct_error(o.fastest_level, o.fastest_level.max(lo))
+        + control::pct_error(o.slowest_level, o.slowest_level.min(hi))
+}
+
+/// Named residuals for [`Outcome::residuals`]: the total, the fastest and
+/// slowest levels against the nearest band edge (on target inside), and
+/// the activity share.
+pub fn residuals(o: &Obs, tgt: &Targets) -> Vec<Residual> {
+    let (lo, hi) = tgt.minutes_band;
+    vec![
+        Residual::new("hours_to_cap", o.hours_to_cap, tgt.hours_to_cap),
+        Residual::new("fastest_level", o.fastest_level, o.fastest_level.clamp(lo, hi)),
+        Residual::new("slowest_level", o.slowest_level, o.slowest_level.clamp(lo, hi)),
+        Residual::new("activity_share", o.activity_share, tgt.activity_share),
+    ]
+}
+
+pub fn balance_ext(
+    theta0: Params,
+    env: Env,
+    tgt: Targets,
+    ref_income: f64,
+    opts: Options<impl SimModel>,
+) -> Outcome<Params, Obs> {
+    let Options { bounds: b, gains: g, mechs, model, max_iters, reg, controller } = opts;
+    let mut ctl_state = [ControllerState::default(); 3];
+    let growth_max = b.growth_max;
+    balance_with_hooks(
+        theta0,
+        env,
+        tgt,
+        b,
+        g,
+        mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+        max_iters,
+        // simulate: delegate to the observation model
+        move |th, env, tgt, mechs| model.observe(th, env, tgt, ref_income, mechs),
+        // nominal: x = base for the first level's minutes at the current XP
+        // rate, y = steepest growth, z = XP per activity for the share (held
+        // when there is no upstream XP to share with)
+        move |th, env, tgt, o| {
+            let growth = steepest_growth(env, tgt, growth_max);
+            let first = first_level_minutes(tgt.hours_to_cap * 60.0, growth, env.levels);
+            let xpa = if ref_income > 0.0 && tgt.activity_share < 1.0 {
+                ref_income * tgt.activity_share / (1.0 - tgt.activity_share) * env.activity_minutes
+            } else {
+                th.xp_per_activity
+            };
+            NominalTargets { x: first * o.xp_per_min, y: growth, z: xpa }
+        },
+        move |th, b, g, nom, adj| {
+            let (base_t, growth_t, xpa_t) = (nom.x * adj.a, nom.y * adj.b, nom.z * adj.c);
+
+            let (base_t, growth_t, xpa_t) = match reg {
+                Some(r) => (
+                    r.pull(base_t, |p| p.xp_base),
+                    r.pull(growth_t, |p| p.xp_growth),
+                    r.pull(xpa_t, |p| p.xp_per_activity),
+                ),
+                None => (base_t, growth_t, xpa_t),
+            };
+
+            let st = &mut ctl_state;
+            Params {
+                xp_base: controller.step(&mut st[0], th.xp_base, base_t, g.k_base, b.base_min, b.base_max),
+                xp_growth: controller.step(&mut st[1], th.xp_growth, growth_t, g.k_growth, b.growth_min, b.growth_max),
+                xp_per_activity: controller.step(&mut st[2], th.xp_per_activity, xpa_t, g.k_xpa, b.xpa_min, b.xpa_max),
+            }
+        },
+        // converged: total on target, every level inside the band, and the
+        // activity share on target when there is upstream XP
+        move |o, tgt| {
+            let band = Band::from(tgt.minutes_band);
+            let share_ok = ref_income <= 0.0 || within(o.activity_share, tgt.activity_share, AbsTol(0.02));
+            within(o.hours_to_cap, tgt.hours_to_cap, RelTol(0.05))
+                && band.contains(o.fastest_level)
+                && band.contains(o.slowest_level)
+                && share_ok
+        },
+    )
+    .with_residuals(|o| residuals(o, &tgt))
+}
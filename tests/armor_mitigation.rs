@@ -0,0 +1,80 @@
+// tests/armor_mitigation.rs
+use game_balance::systems::armor_mitigation as armor;
+use game_balance::systems::sdk::Hook;
+
+fn env() -> armor::Env {
+    armor::Env { samples: vec![0.0, 500.0, 1000.0, 2000.0, 3000.0, 5000.0] }
+}
+
+fn targets() -> armor::Targets {
+    armor::Targets { breakpoints: vec![(1000.0, 2.0), (3000.0, 3.0)] }
+}
+
+fn theta0() -> armor::Params {
+    armor::Params { armor_constant: 100.0, knee: 10_000.0 }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Curve math — effective armor, EHP, slope
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn a_far_knee_gives_the_linear_ehp_curve() {
+    let th = armor::Params { armor_constant: 100.0, knee: 1e12 };
+    assert!((armor::ehp_multiplier(100.0, &th) - 2.0).abs() < 1e-6);
+    assert!((armor::ehp_multiplier(1000.0, &th) - 11.0).abs() < 1e-6);
+    assert!((armor::ehp_slope(1000.0, &th) - 0.01).abs() < 1e-9);
+    // at the knee a_eff = knee · ln 2
+    assert!((armor::effective_armor(500.0, 500.0) - 500.0 * 2f64.ln()).abs() < 1e-9);
+}
+
+#[test]
+fn two_breakpoints_fit_exactly() {
+    // ln(1 + 3r) = 2 ln(1 + r) → r = 1: knee 1000, K = 1000 · ln 2
+    let (k, knee) = armor::fit(&targets().breakpoints, 1.0, 1.0, 1e6).unwrap();
+    assert!((knee / 1000.0 - 1.0).abs() < 1e-6, "{knee}");
+    assert!((k / (1000.0 * 2f64.ln()) - 1.0).abs() < 1e-6, "{k}");
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Balance — breakpoints, sampled curve
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn breakpoints_land_and_the_curve_flattens() {
+    let out = armor::Runner::new(theta0(), env(), targets()).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!((out.theta.knee / 1000.0 - 1.0).abs() < 0.03, "{:?}", out.theta);
+    let o = &out.obs;
+    assert!((o.ehp[2] - 2.0).abs() < 0.04 && (o.ehp[4] - 3.0).abs() < 0.06, "{o:?}");
+    assert_eq!(o.ehp[0], 1.0);
+    assert!((o.mitigation[2] - 0.5).abs() < 0.01, "{o:?}");
+    assert!(o.ehp.windows(2).all(|w| w[1] > w[0]), "{o:?}");
+    assert!(o.slope.windows(2).all(|w| w[1] < w[0]), "{o:?}");
+    assert_eq!(out.residuals.len(), 2);
+}
+
+struct ArmorBuff;
+impl Hook<armor::Params, armor::Env, armor::Targets, armor::Obs> for ArmorBuff {
+    fn income_multiplier(&mut self, _base: f64, _th: &armor::Params, _env: &armor::Env) -> f64 {
+        2.0
+    }
+}
+
+#[test]
+fn armor_hooks_scale_the_constant_and_the_knee() {
+    let out = armor::Runner::new(theta0(), env(), targets()).hook(ArmorBuff).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!((out.theta.knee / 2000.0 - 1.0).abs() < 0.03, "{:?}", out.theta);
+    assert!((out.theta.armor_constant / (2000.0 * 2f64.ln()) - 1.0).abs() < 0.03, "{:?}", out.theta);
+}
+
+#[test]
+fn try_run_rejects_falling_breakpoints() {
+    let env = armor::Env { samples: vec![-1.0] };
+    let tgt = armor::Targets { breakpoints: vec![(1000.0, 2.0), (500.0, 0.9)] };
+    let err = armor::Runner::new(theta0(), env, tgt).try_run().unwrap_err();
+    let game_balance::Error::Invalid(problems) = err else { panic!("expected Invalid") };
+    let fields: Vec<_> = problems.iter().map(|p| p.field).collect();
+    assert_eq!(fields, ["samples", "breakpoints.ehp", "breakpoints"]);
+}
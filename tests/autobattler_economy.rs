@@ -0,0 +1,91 @@
+// tests/autobattler_economy.rs
+use game_balance::systems::autobattler_economy as auto;
+use game_balance::systems::sdk::Hook;
+
+fn env() -> auto::Env {
+    auto::Env {
+        rounds: 30,
+        spike_round: 10,
+        streak_cap: 3,
+        greedy: auto::Strategy { win_rate: 0.4, bank: 1.0 },
+        tempo: auto::Strategy { win_rate: 0.6, bank: 0.0 },
+    }
+}
+
+fn targets() -> auto::Targets {
+    auto::Targets { tempo_worth: 300.0, streak_share: 0.15, greedy_edge: 1.2, tempo_lead: 1.3 }
+}
+
+fn theta0() -> auto::Params {
+    auto::Params { base_income: 5.0, streak_gold: 1.0, interest_step: 10.0, interest_cap: 5.0 }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Round math — streak expectations, banking
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn streak_rounds_count_agreeing_rounds_up_to_the_cap() {
+    assert_eq!(auto::streak_rounds(0.5, 1, 3), 0.0);
+    assert_eq!(auto::streak_rounds(0.5, 2, 3), 0.5);
+    // past the cap: 2·(1/4 + 1/8 + 1/16)
+    assert_eq!(auto::streak_rounds(0.5, 10, 3), 0.875);
+    assert_eq!(auto::streak_rounds(1.0, 10, 3), 3.0);
+}
+
+#[test]
+fn greedy_banks_to_the_breakpoint_and_spends_the_rest() {
+    let th = auto::Params { base_income: 5.0, streak_gold: 0.0, interest_step: 10.0, interest_cap: 1.0 };
+    let env = auto::Env { rounds: 3, spike_round: 2, ..env() };
+    let greedy = auto::trajectory(&th, &env, &env.greedy, 5.0);
+    // 5 banked; 10.5 → bank 10, spend 0.5; +5 +1 interest → spend 6
+    assert_eq!(greedy.board, [0.0, 0.5, 6.5]);
+    assert_eq!(greedy.final_worth(), 16.5);
+    assert_eq!(greedy.interest, 1.5);
+    let tempo = auto::trajectory(&th, &env, &env.tempo, 5.0);
+    assert_eq!(tempo.worth, tempo.board);
+    assert_eq!(tempo.final_worth(), 15.0);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Balance — greedy viable, tempo ahead at the spike
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn greedy_and_tempo_follow_their_trajectories() {
+    let out = auto::Runner::new(theta0(), env(), targets()).run();
+    assert!(out.converged, "{}", out.explain());
+    let o = &out.obs;
+    // 85% of tempo's 300 is base income over 30 rounds
+    assert!((out.theta.base_income / 8.5 - 1.0).abs() < 0.02, "{:?}", out.theta);
+    assert!((o.streak_share - 0.15).abs() < 0.01, "{:?}", o);
+    assert!(o.greedy.final_worth() > o.tempo.final_worth(), "{:?}", o);
+    assert!(o.tempo.board[9] > o.greedy.board[9], "{:?}", o);
+    assert_eq!(o.greedy.worth.len(), 30);
+    assert_eq!(out.residuals.len(), 4);
+}
+
+struct DoubleIncome;
+impl Hook<auto::Params, auto::Env, auto::Targets, auto::Obs> for DoubleIncome {
+    fn income_multiplier(&mut self, _base: f64, _th: &auto::Params, _env: &auto::Env) -> f64 {
+        2.0
+    }
+}
+
+#[test]
+fn income_hooks_are_compensated() {
+    let out = auto::Runner::new(theta0(), env(), targets()).hook(DoubleIncome).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!((out.theta.base_income / 4.25 - 1.0).abs() < 0.03, "{:?}", out.theta);
+    assert!((out.obs.base_income / 8.5 - 1.0).abs() < 0.03, "{:?}", out.obs);
+}
+
+#[test]
+fn try_run_rejects_a_spike_outside_the_game() {
+    let env = auto::Env { spike_round: 0, ..env() };
+    let tgt = auto::Targets { streak_share: 1.0, ..targets() };
+    let err = auto::Runner::new(theta0(), env, tgt).try_run().unwrap_err();
+    let game_balance::Error::Invalid(problems) = err else { panic!("expected Invalid") };
+    let fields: Vec<_> = problems.iter().map(|p| p.field).collect();
+    assert_eq!(fields, ["spike_round", "streak_share"]);
+}
@@ -0,0 +1,85 @@
+// tests/battle_pass.rs
+use game_balance::systems::battle_pass::{self as pass, Player};
+use game_balance::systems::sdk::Hook;
+
+/// A 10-week season: 50 free tiers, 100 premium tiers.
+fn env() -> pass::Env {
+    pass::Env {
+        season_weeks: 10.0,
+        free_tiers: 50.0,
+        premium_tiers: 100.0,
+        average: Player { sessions_per_week: 4.0, minutes_per_session: 30.0, weekly_share: 1.0 },
+        dedicated: Player { sessions_per_week: 10.0, minutes_per_session: 60.0, weekly_share: 1.0 },
+    }
+}
+
+fn targets() -> pass::Targets {
+    pass::Targets { free_finish_share: 0.8, spare_weeks: 1.0, tier_xp: 1000.0 }
+}
+
+fn theta0() -> pass::Params {
+    pass::Params { xp_per_tier: 500.0, xp_per_minute: 20.0, weekly_xp: 1000.0 }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Source mix — play XP separates the players, challenges lift both
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn source_mix_solves_both_finish_times() {
+    // Average: 50 000 XP in 8 weeks; dedicated: 100 000 XP in 9 weeks.
+    let (per_minute, weekly) = pass::source_mix(&env(), &targets()).unwrap();
+    let env = env();
+    assert!((pass::weekly_xp(&env.average, per_minute, weekly) - 6250.0).abs() < 1e-6);
+    assert!((pass::weekly_xp(&env.dedicated, per_minute, weekly) - 100_000.0 / 9.0).abs() < 1e-6);
+
+    // A "dedicated" player who barely plays more can't also finish a track twice as long.
+    let mut close = self::env();
+    close.dedicated.sessions_per_week = 5.0;
+    close.dedicated.minutes_per_session = 30.0;
+    assert!(pass::source_mix(&close, &targets()).is_none());
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Balance — free track at 80% of the season, premium with a week spare
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn tracks_finish_on_schedule() {
+    let out = pass::Runner::new(theta0(), env(), targets()).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!((out.obs.free_weeks - 8.0).abs() <= 0.2, "{:?}", out.obs);
+    assert!((out.obs.premium_weeks - 9.0).abs() <= 0.2, "{:?}", out.obs);
+    assert!((out.theta.xp_per_tier / 1000.0 - 1.0).abs() < 0.05, "{:?}", out.theta);
+    // Most of the average player's XP comes from challenges; they see about 62% of premium.
+    assert!((out.obs.challenge_share - 0.8).abs() < 0.05, "{:?}", out.obs);
+    assert!((out.obs.average_premium - 0.625).abs() < 0.03, "{:?}", out.obs);
+    assert_eq!(out.residuals.len(), 2);
+}
+
+struct DoubleXpWeekend;
+impl Hook<pass::Params, pass::Env, pass::Targets, pass::Obs> for DoubleXpWeekend {
+    fn income_multiplier(&mut self, _base: f64, _th: &pass::Params, _env: &pass::Env) -> f64 {
+        1.25
+    }
+}
+
+#[test]
+fn play_xp_boosts_are_compensated() {
+    let base = pass::Runner::new(theta0(), env(), targets()).run();
+    let out = pass::Runner::new(theta0(), env(), targets()).hook(DoubleXpWeekend).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!((out.theta.xp_per_minute * 1.25 / base.theta.xp_per_minute - 1.0).abs() < 0.05, "{:?} vs {:?}", out.theta, base.theta);
+    assert!((out.theta.weekly_xp / base.theta.weekly_xp - 1.0).abs() < 0.05);
+}
+
+#[test]
+fn try_run_rejects_idle_players() {
+    let mut env = env();
+    env.average.sessions_per_week = 0.0;
+    let tgt = pass::Targets { free_finish_share: 1.2, ..targets() };
+    let err = pass::Runner::new(theta0(), env, tgt).try_run().unwrap_err();
+    let game_balance::Error::Invalid(problems) = err else { panic!("expected Invalid") };
+    let fields: Vec<_> = problems.iter().map(|p| p.field).collect();
+    assert_eq!(fields, ["average", "free_finish_share"]);
+}
@@ -0,0 +1,91 @@
+// tests/boss_dps_check.rs
+use game_balance::systems::boss_dps_check as boss;
+use game_balance::systems::sdk::Hook;
+
+fn env() -> boss::Env {
+    boss::Env { median_dps: 100.0, dps_spread: 0.05, weekly_growth: 0.04, weeks: 6 }
+}
+
+fn targets() -> boss::Targets {
+    boss::Targets {
+        clears: vec![boss::ClearTarget { week: 1, rate: 0.1 }, boss::ClearTarget { week: 4, rate: 0.5 }],
+        kill_seconds: 300.0,
+        soft_loss: 0.1,
+    }
+}
+
+fn theta0() -> boss::Params {
+    boss::Params { hp: 20_000.0, enrage_seconds: 240.0, soft_ramp: 0.0 }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Fight math — soft enrage, kill time, DPS distribution
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn soft_enrage_bends_damage_and_kill_time() {
+    // 0.6 of DPS lost per minute = 1% per second: 100 − 100²/200 = 50 by 100s
+    assert_eq!(boss::damage_by(100.0, 0.6), 50.0);
+    assert_eq!(boss::damage_by(100.0, 0.0), 100.0);
+    let th = boss::Params { hp: 40.0, enrage_seconds: 100.0, soft_ramp: 0.6 };
+    let t = boss::kill_seconds(40.0, 1.0, &th).unwrap();
+    assert!((boss::damage_by(t, 0.6) - 40.0).abs() < 1e-9, "{t}");
+    assert!(boss::kill_seconds(60.0, 1.0, &th).is_none());
+}
+
+#[test]
+fn dps_distribution_is_log_logistic_and_grows_weekly() {
+    let env = env();
+    assert!((boss::share_above(100.0, &env, 1) - 0.5).abs() < 1e-12);
+    assert!((boss::share_above(104.0, &env, 2) - 0.5).abs() < 1e-12);
+    let top = boss::dps_at_share(0.1, &env, 1);
+    assert!((top / (100.0 * 9f64.powf(0.05)) - 1.0).abs() < 1e-12, "{top}");
+    assert!((boss::share_above(top, &env, 1) - 0.1).abs() < 1e-9);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Balance — clear weeks, kill time, wipe stats
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn top_groups_clear_week_one_and_the_median_by_week_four() {
+    let out = boss::Runner::new(theta0(), env(), targets()).run();
+    assert!(out.converged, "{}", out.explain());
+    let o = &out.obs;
+    assert!((o.clears[0] - 0.1).abs() < 0.03 && (o.clears[1] - 0.5).abs() < 0.03, "{:?}", o.clears);
+    assert_eq!(o.median_clear_week, 4);
+    assert!((o.median_kill_seconds / 300.0 - 1.0).abs() < 0.02, "{:?}", o);
+    assert!((o.soft_loss - 0.1).abs() < 0.01, "{:?}", o);
+    assert!(out.theta.enrage_seconds >= o.median_kill_seconds, "{:?}", out.theta);
+
+    assert_eq!(o.weeks.len(), 6);
+    assert!(o.weeks.windows(2).all(|w| w[1].clear_rate > w[0].clear_rate), "{:?}", o.weeks);
+    assert!(o.weeks.windows(2).all(|w| w[1].mean_wipe_at < w[0].mean_wipe_at), "{:?}", o.weeks);
+    assert!(o.weeks[0].median_wipe_at > 0.0 && o.weeks[3].median_wipe_at == 0.0, "{:?}", o.weeks);
+    assert_eq!(out.residuals.len(), 4);
+}
+
+struct RaidBuff;
+impl Hook<boss::Params, boss::Env, boss::Targets, boss::Obs> for RaidBuff {
+    fn income_multiplier(&mut self, _base: f64, _th: &boss::Params, _env: &boss::Env) -> f64 {
+        1.5
+    }
+}
+
+#[test]
+fn raid_buffs_raise_boss_hp_not_the_timer() {
+    let plain = boss::Runner::new(theta0(), env(), targets()).run();
+    let buffed = boss::Runner::new(theta0(), env(), targets()).hook(RaidBuff).run();
+    assert!(buffed.converged, "{}", buffed.explain());
+    assert!((buffed.theta.hp / plain.theta.hp - 1.5).abs() < 0.03, "{:?} vs {:?}", buffed.theta, plain.theta);
+    assert!((buffed.theta.enrage_seconds / plain.theta.enrage_seconds - 1.0).abs() < 0.02);
+}
+
+#[test]
+fn try_run_rejects_certain_clears_and_a_runaway_soft_enrage() {
+    let tgt = boss::Targets { clears: vec![boss::ClearTarget { week: 2, rate: 1.0 }], soft_loss: 0.7, ..targets() };
+    let err = boss::Runner::new(theta0(), env(), tgt).try_run().unwrap_err();
+    let game_balance::Error::Invalid(problems) = err else { panic!("expected Invalid") };
+    let fields: Vec<_> = problems.iter().map(|p| p.field).collect();
+    assert_eq!(fields, ["clears.rate", "soft_loss"]);
+}
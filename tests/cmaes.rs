@@ -0,0 +1,77 @@
+// tests/cmaes.rs
+use game_balance::optim::{CmaesConfig, EsConfig, refine_cmaes, refine_es};
+
+/* ──────────────────────────────────────────────────────────────────────────
+1) Hero kit — 12 coupled, badly scaled tunables
+────────────────────────────────────────────────────────────────────────── */
+
+const N: usize = 12;
+
+fn bounds() -> Vec<(f64, f64)> {
+    // Damage-like stats in the hundreds, ratios in [0, 1].
+    (0..N).map(|i| if i % 2 == 0 { (10.0, 1_000.0) } else { (0.0, 1.0) }).collect()
+}
+
+fn truth() -> Vec<f64> {
+    bounds().iter().enumerate().map(|(i, (lo, hi))| lo + (hi - lo) * (0.2 + 0.05 * i as f64)).collect()
+}
+
+/// Each metric mixes a stat with its neighbour (DPS = dmg × (1 + crit) …).
+fn metrics(th: &[f64]) -> Vec<f64> {
+    (0..N).map(|i| th[i] * (1.0 + th[(i + 1) % N])).collect()
+}
+
+fn residuals(pi: &[f64]) -> Vec<f64> {
+    let t = metrics(&truth());
+    pi.iter().zip(&t).map(|(p, t)| (p - t) / t).collect()
+}
+
+#[test]
+fn cmaes_fits_twelve_coupled_tunables() {
+    let theta0: Vec<f64> = bounds().iter().map(|(lo, hi)| 0.5 * (lo + hi)).collect();
+    let cfg = CmaesConfig { seed: 9, generations: 2_000, tol: 1e-4, ..CmaesConfig::default() };
+
+    let out = refine_cmaes(&theta0, &bounds(), metrics, |d: &Vec<f64>| d.clone(), |pi: &Vec<f64>| residuals(pi), &cfg);
+    assert!(out.done, "no fit after {} generations, loss {}", out.generations, out.loss);
+    for (th, (lo, hi)) in out.theta.iter().zip(bounds()) {
+        assert!((lo..=hi).contains(th));
+    }
+
+    // Isotropic ES with the same evaluation budget stays well short.
+    let evals = out.generations * (4 + (3.0 * (N as f64).ln()) as usize);
+    let es_cfg = EsConfig { seed: 9, generations: evals / 17, ..EsConfig::default() };
+    let sq = |d: &Vec<f64>| residuals(d).iter().map(|r| r * r).sum::<f64>();
+    let es = refine_es(&theta0, metrics, |d: &Vec<f64>| d.clone(), sq, |_d: &Vec<f64>| false, &es_cfg);
+    assert!(es.loss > out.loss, "es {} vs cmaes {}", es.loss, out.loss);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+2) Bounds — unreachable targets pin θ to the box edge
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn cmaes_respects_bounds() {
+    let bounds = [(0.0, 2.0), (1.0, 3.0)];
+    let cfg = CmaesConfig { seed: 2, generations: 300, ..CmaesConfig::default() };
+    // Wants θ = (5, −4): both outside the box.
+    let out = refine_cmaes(
+        &[1.0, 2.0],
+        &bounds,
+        |th: &[f64]| th.to_vec(),
+        |d: &Vec<f64>| d.clone(),
+        |pi: &Vec<f64>| vec![pi[0] - 5.0, pi[1] + 4.0],
+        &cfg,
+    );
+    assert!(!out.done);
+    assert!((out.theta[0] - 2.0).abs() < 1e-3 && (out.theta[1] - 1.0).abs() < 1e-3, "{:?}", out.theta);
+
+    let again = refine_cmaes(
+        &[1.0, 2.0],
+        &bounds,
+        |th: &[f64]| th.to_vec(),
+        |d: &Vec<f64>| d.clone(),
+        |pi: &Vec<f64>| vec![pi[0] - 5.0, pi[1] + 4.0],
+        &cfg,
+    );
+    assert_eq!(again, out, "seeded replay");
+}
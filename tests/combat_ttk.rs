@@ -0,0 +1,69 @@
+// tests/combat_ttk.rs
+use game_balance::systems::combat_ttk::{self as ttk, Crit, Mitigation, Opponent};
+
+/// The roster from the TTK window test in tests/core.rs.
+fn env() -> ttk::Env {
+    ttk::Env { opponents: vec![Opponent::new(500.0, 50.0), Opponent::new(800.0, 40.0), Opponent::new(1200.0, 80.0)] }
+}
+
+fn targets() -> ttk::Targets {
+    ttk::Targets { ttk_band: (7.8, 8.2), ttd_band: (7.8, 8.2) }
+}
+
+fn theta0() -> ttk::Params {
+    ttk::Params { hp: 600.0, dps: 60.0 }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Roster averages — the same window the core refine test reaches
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn hp_and_dps_land_in_ttk_and_ttd_windows() {
+    let out = ttk::Runner::new(theta0(), env(), targets()).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!((7.8..=8.2).contains(&out.obs.avg_ttk), "{:?}", out.obs);
+    assert!((7.8..=8.2).contains(&out.obs.avg_ttd), "{:?}", out.obs);
+    // Closed form: dps = mean(hp_i) / 8, hp = 8 / mean(1 / dps_i).
+    assert!((out.theta.dps / (2500.0 / 3.0 / 8.0) - 1.0).abs() < 0.03, "{:?}", out.theta);
+    let inv = (1.0 / 50.0 + 1.0 / 40.0 + 1.0 / 80.0) / 3.0;
+    assert!((out.theta.hp / (8.0 / inv) - 1.0).abs() < 0.03, "{:?}", out.theta);
+    assert_eq!(out.obs.ttk.len(), 3);
+}
+
+#[test]
+fn weights_shift_the_average_toward_common_opponents() {
+    let mut env = env();
+    env.opponents[2].weight = 4.0; // the 1200 HP brute shows up most
+    let out = ttk::Runner::new(theta0(), env, targets()).run();
+    let even = ttk::Runner::new(theta0(), self::env(), targets()).run();
+    assert!(out.converged);
+    assert!(out.theta.dps > even.theta.dps, "{:?} vs {:?}", out.theta, even.theta);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Hooks — crit raises effective DPS, mitigation stretches TTD
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn crit_and_mitigation_hooks_are_compensated() {
+    let base = ttk::Runner::new(theta0(), env(), targets()).run();
+    let out = ttk::Runner::new(theta0(), env(), targets())
+        .hook(Crit { chance: 0.25, mult: 2.0 })
+        .hook(Mitigation { reduction: 0.2 })
+        .run();
+    assert!(out.converged, "{}", out.explain());
+    assert!((out.obs.dps / out.theta.dps - 1.25).abs() < 0.02, "{:?} vs {:?}", out.obs, out.theta);
+    assert!((out.obs.damage_taken - 0.8).abs() < 1e-12);
+    // Base stats shrink so the fights still take as long.
+    assert!((out.theta.dps * 1.25 / base.theta.dps - 1.0).abs() < 0.06, "{:?} vs {:?}", out.theta, base.theta);
+    assert!((out.theta.hp / 0.8 / base.theta.hp - 1.0).abs() < 0.06, "{:?} vs {:?}", out.theta, base.theta);
+}
+
+#[test]
+fn try_run_rejects_an_empty_roster() {
+    let err = ttk::Runner::new(theta0(), ttk::Env { opponents: Vec::new() }, targets()).try_run().unwrap_err();
+    let game_balance::Error::Invalid(problems) = err else { panic!("expected Invalid") };
+    let fields: Vec<_> = problems.iter().map(|p| p.field).collect();
+    assert_eq!(fields, ["opponents", "opponents.weight"]);
+}
@@ -0,0 +1,34 @@
+// tests/combinatorics.rs
+use game_balance::mechanics::combinatorics::{choose, hypergeom_pmf, miss_prob, miss_windows};
+
+/* ──────────────────────────────────────────────────────────────────────────
+Binomials and hypergeometric draws
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn choose_matches_known_values() {
+    assert_eq!(choose(5, 2), 10.0);
+    assert_eq!(choose(5, 0), 1.0);
+    assert_eq!(choose(3, 4), 0.0);
+    assert!((choose(52, 5) - 2_598_960.0).abs() < 1e-6);
+}
+
+#[test]
+fn hypergeom_pmf_sums_to_one_and_matches_the_miss_probability() {
+    let total: f64 = (0..=4).map(|k| hypergeom_pmf(60, 4, 7, k)).sum();
+    assert!((total - 1.0).abs() < 1e-12, "{total}");
+    let miss = hypergeom_pmf(60, 4, 7, 0);
+    assert!((miss - choose(56, 7) / choose(60, 7)).abs() < 1e-12);
+    assert!((miss_prob(60.0, 4.0, 7) - miss).abs() < 1e-12);
+}
+
+#[test]
+fn miss_windows_is_the_exact_joint_miss() {
+    // 6 cards: two A that must avoid the first 2, one B that must avoid the
+    // first 3. B sits in 3 slots, then the As take 2 of the 3 slots left
+    // past position 2: 9 of the 60 arrangements.
+    let p = miss_windows(6.0, &[(2.0, 2), (1.0, 3)]);
+    assert!((p - 9.0 / 60.0).abs() < 1e-12, "{p}");
+    assert_eq!(miss_windows(6.0, &[]), 1.0);
+    assert_eq!(miss_prob(6.0, 6.0, 1), 0.0);
+}
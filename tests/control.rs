@@ -0,0 +1,136 @@
+// tests/control.rs
+use game_balance::mechanics::control;
+
+/* ──────────────────────────────────────────────────────────────────────────
+Hysteresis — a parameter pinned at its bound stops flip-flopping
+────────────────────────────────────────────────────────────────────────── */
+
+/// Target nudges just across the upper bound depending on where x sits.
+fn bound_nudge(x: f64) -> f64 {
+    if x > 0.95 { 0.9 } else { 1.05 }
+}
+
+#[test]
+fn plain_approach_flip_flops_at_bound() {
+    let mut x = 1.0;
+    let mut flips = 0;
+    for _ in 0..100 {
+        let next = control::approach(x, bound_nudge(x), 1.0, 0.0, 1.0);
+        if (next - x).abs() > 1e-9 {
+            flips += 1;
+        }
+        x = next;
+    }
+    assert!(flips >= 99, "expected chattering, got {flips} moves");
+}
+
+#[test]
+fn hysteresis_approach_settles_at_bound() {
+    let (mut x, mut dir) = (1.0, 1i8);
+    let mut last_move = 0;
+    for i in 0..100 {
+        let (next, d) = control::approach_hysteresis(x, bound_nudge(x), 1.0, 0.0, 1.0, 0.2, dir);
+        if (next - x).abs() > 1e-9 {
+            last_move = i;
+        }
+        x = next;
+        dir = d;
+    }
+    assert_eq!(last_move, 0, "still moving at iteration {last_move}");
+    assert!((x - 1.0).abs() < 1e-9, "did not hold at bound: {x}");
+}
+
+#[test]
+fn hysteresis_reverses_on_large_error() {
+    let (x, dir) = control::approach_hysteresis(1.0, 0.5, 0.5, 0.0, 1.0, 0.2, 1);
+    assert!((x - 0.75).abs() < 1e-9, "x = {x}");
+    assert_eq!(dir, -1);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Ratio targets — offline income converges to 30% of online income
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn ratio_target_converges_to_point_three() {
+    let online = 120.0;
+    let mut efficiency: f64 = 0.9;
+    let mut iters = 0;
+    while !control::ratio_within(efficiency * online, online, 0.3, 1e-3) && iters < 1_000 {
+        let offline = efficiency * online;
+        // Proportional step on the ratio error.
+        let ratio_err = offline / online - 0.3;
+        efficiency = control::p_against_error(efficiency, ratio_err, 0.5, 0.0, 1.0);
+        iters += 1;
+    }
+    assert!(control::ratio_within(efficiency * online, online, 0.3, 1e-3), "ratio {}", efficiency);
+    assert!(iters < 1_000);
+    assert!(!control::ratio_within(1.0, 0.0, 0.3, 1.0));
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Monotone projections — no cheaper higher level after projection
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn isotonic_projection_is_monotone_and_least_squares() {
+    let mut v = vec![10.0, 14.0, 12.0, 20.0, 18.0, 17.0, 30.0];
+    control::isotonic_nondecreasing(&mut v);
+    assert!(v.windows(2).all(|w| w[1] >= w[0]), "{v:?}");
+    // Violating pairs are pooled to their means.
+    assert_eq!(v, vec![10.0, 13.0, 13.0, 55.0 / 3.0, 55.0 / 3.0, 55.0 / 3.0, 30.0]);
+
+    let mut sorted = vec![1.0, 2.0, 3.0];
+    control::isotonic_nondecreasing(&mut sorted);
+    assert_eq!(sorted, vec![1.0, 2.0, 3.0]);
+}
+
+#[test]
+fn forward_fill_projection_never_lowers_costs() {
+    let mut v = vec![10.0, 14.0, 12.0, 20.0];
+    control::forward_fill_nondecreasing(&mut v);
+    assert_eq!(v, vec![10.0, 14.0, 14.0, 20.0]);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Clamp modes — saturate, wrap and reflect at the bounds
+────────────────────────────────────────────────────────────────────────── */
+
+use control::ClampMode;
+
+#[test]
+fn saturate_matches_approach() {
+    for (x, t, k) in [(0.5, 2.0, 0.8), (0.5, -1.0, 1.0), (0.2, 0.9, 0.5)] {
+        assert_eq!(
+            control::approach_mode(x, t, k, 0.0, 1.0, ClampMode::Saturate),
+            control::approach(x, t, k, 0.0, 1.0)
+        );
+    }
+    assert_eq!(ClampMode::Saturate.apply(1.3, 0.0, 1.0), 1.0);
+}
+
+#[test]
+fn wrap_takes_the_short_way_round() {
+    // Angle in [0, 360): from 350° toward 10° goes up through 0°, not down.
+    let next = control::approach_mode(350.0, 10.0, 0.5, 0.0, 360.0, ClampMode::Wrap);
+    assert!((next - 0.0).abs() < 1e-9, "{next}");
+    let next = control::approach_mode(350.0, 10.0, 0.75, 0.0, 360.0, ClampMode::Wrap);
+    assert!((next - 5.0).abs() < 1e-9, "{next}");
+
+    assert_eq!(ClampMode::Wrap.apply(360.0, 0.0, 360.0), 0.0);
+    assert!((ClampMode::Wrap.apply(-30.0, 0.0, 360.0) - 330.0).abs() < 1e-9);
+    assert!((ClampMode::Wrap.apply(725.0, 0.0, 360.0) - 5.0).abs() < 1e-9);
+}
+
+#[test]
+fn reflect_bounces_by_the_overshoot() {
+    assert!((ClampMode::Reflect.apply(1.2, 0.0, 1.0) - 0.8).abs() < 1e-9);
+    assert!((ClampMode::Reflect.apply(-0.3, 0.0, 1.0) - 0.3).abs() < 1e-9);
+    assert!((ClampMode::Reflect.apply(2.25, 0.0, 1.0) - 0.25).abs() < 1e-9);
+    assert_eq!(ClampMode::Reflect.apply(1.0, 0.0, 1.0), 1.0);
+    assert_eq!(ClampMode::Reflect.apply(0.0, 0.0, 1.0), 0.0);
+
+    // Overshooting gain: 0.9 → 0.9 + 1.5·(1.0 − 0.9) = 1.05 → 0.95.
+    let next = control::approach_mode(0.9, 1.0, 1.5, 0.0, 1.0, ClampMode::Reflect);
+    assert!((next - 0.95).abs() < 1e-9, "{next}");
+}
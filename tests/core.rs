@@ -330,7 +330,7 @@ fn rpsls_converges_to_uniform() {
 }
 
 /* ──────────────────────────────────────────────────────────────────────────
-4) TTK window — constant DPS vs opponent set
+4) TTK window — constant DPS vs opponent set (packaged as systems::combat_ttk)
 ────────────────────────────────────────────────────────────────────────── */
 
 #[derive(Clone, Debug)]
@@ -456,3 +456,753 @@ fn ttk_converges_to_target_window() {
         s
     );
 }
+
+/* ──────────────────────────────────────────────────────────────────────────
+5) Time budget — refine_timed stops on the clock, not on max_iters
+────────────────────────────────────────────────────────────────────────── */
+
+#[cfg(feature = "timing")]
+#[test]
+fn refine_timed_respects_budget() {
+    use game_balance::{RefineStatus, refine_timed};
+    use std::time::{Duration, Instant};
+
+    let steps = Rc::new(RefCell::new(0usize));
+    let update = {
+        let steps = Rc::clone(&steps);
+        move |_t: &Params, _m: &Metrics| -> Params {
+            *steps.borrow_mut() += 1;
+            std::thread::sleep(Duration::from_micros(200));
+            Params {}
+        }
+    };
+
+    let start = Instant::now();
+    let report = refine_timed(
+        Params {},
+        |_t: &Params| Data {},
+        |_d: &Data| Metrics {},
+        update,
+        |_a: &Params, _b: &Params| false,
+        usize::MAX,
+        Duration::from_millis(20),
+    );
+
+    assert_eq!(report.status, RefineStatus::Time);
+    assert!(start.elapsed() < Duration::from_secs(2), "budget ignored");
+    assert_eq!(*steps.borrow(), report.iters);
+
+    let report = refine_timed(
+        Params {},
+        |_t: &Params| Data {},
+        |_d: &Data| Metrics {},
+        |_t: &Params, _m: &Metrics| Params {},
+        |_a: &Params, _b: &Params| true,
+        10,
+        Duration::from_secs(1),
+    );
+    assert!(report.converged() && report.iters == 1);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+6) RefineIter — host-driven stepping matches refine_det
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn refine_iter_steps_one_update_per_next() {
+    use game_balance::RefineIter;
+
+    let p_state = Rc::new(RefCell::new(Prob3 { r: 0.8, p: 0.15, s: 0.05 }));
+    let update = {
+        let p_state = Rc::clone(&p_state);
+        move |_t: &Params, _m: &Metrics| -> Params {
+            let old = p_state.borrow().clone();
+            *p_state.borrow_mut() = step_toward_uniform(&old, 0.2);
+            Params {}
+        }
+    };
+    let mut it = RefineIter::new(Params {}, |_t: &Params| Data {}, |_d: &Data| Metrics {}, update);
+
+    let u = Prob3 { r: 1.0 / 3.0, p: 1.0 / 3.0, s: 1.0 / 3.0 };
+    let before = p_state.borrow().l1(&u);
+    let _ = it.next();
+    let after_one = p_state.borrow().l1(&u);
+    assert!((after_one - 0.8 * before).abs() < 1e-9, "one next() should be one update");
+
+    // One "frame" at a time until converged.
+    let mut frames = 1;
+    while p_state.borrow().l1(&u) >= 1e-6 && frames < 10_000 {
+        let _ = it.next();
+        frames += 1;
+    }
+    assert!(p_state.borrow().l1(&u) < 1e-6, "did not converge in {frames} frames");
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+7) Caller-supplied types — θ/D/Π threaded directly, no cells
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn refine_det_threads_caller_types() {
+    struct Sample {
+        mean: f64,
+    }
+    struct Gap(f64);
+
+    let target = 5.0;
+    let theta = refine_det(
+        Prob3 { r: 0.8, p: 0.15, s: 0.05 },
+        |t: &Prob3| Sample { mean: 10.0 * t.r },
+        |d: &Sample| Gap(target - d.mean),
+        |t: &Prob3, g: &Gap| {
+            let r = (t.r + 0.05 * g.0).clamp(0.0, 1.0);
+            Prob3 { r, p: (1.0 - r) / 2.0, s: (1.0 - r) / 2.0 }
+        },
+        |a: &Prob3, b: &Prob3| (a.r - b.r).abs() < 1e-12,
+        10_000,
+    )
+    .theta;
+    assert!((theta.r - 0.5).abs() < 1e-9, "r = {}", theta.r);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+8) refine_stoch — seeded Monte Carlo loop replays exactly
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn refine_stoch_replays_by_seed() {
+    use game_balance::{refine_stoch, stream_rng};
+    use rand_core::RngCore;
+
+    // θ = scale; simulate = mean of 64 draws of U(0, 2·scale); drive mean → 3.
+    let sample_mean = |scale: f64, rng: &mut dyn RngCore| -> f64 {
+        (0..64).map(|_| 2.0 * scale * (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64).sum::<f64>() / 64.0
+    };
+    let run = |seed: u64| {
+        refine_stoch(
+            1.0_f64,
+            seed,
+            |s: &f64, rng: &mut _| sample_mean(*s, rng),
+            |m: &f64| 3.0 - *m,
+            |s: &f64, e: &f64| s + 0.2 * e,
+            |_a: &f64, _b: &f64| false,
+            200,
+        )
+        .theta
+    };
+
+    let a = run(7);
+    assert_eq!(a.to_bits(), run(7).to_bits(), "same seed must replay bit-for-bit");
+    assert_ne!(a.to_bits(), run(8).to_bits());
+    assert!((a - 3.0).abs() < 0.5, "scale = {a}");
+
+    // Streams depend only on (seed, iter).
+    assert_eq!(stream_rng(7, 42).next_u64(), stream_rng(7, 42).next_u64());
+    assert_ne!(stream_rng(7, 42).next_u64(), stream_rng(7, 43).next_u64());
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+9) try_refine_det — first failing step aborts with its iteration
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn try_refine_det_reports_failing_iteration() {
+    use game_balance::{RefineError, try_refine_det};
+
+    // Doubling θ each step; the "simulation" rejects anything above 100.
+    let sim = |t: &f64| if *t > 100.0 { Err(format!("overflow at {t}")) } else { Ok(*t) };
+    let out = try_refine_det(
+        1.0_f64,
+        sim,
+        |d: &f64| Ok(*d),
+        |_t: &f64, m: &f64| Ok(m * 2.0),
+        |_a: &f64, _b: &f64| false,
+        1_000,
+    );
+    assert_eq!(out, Err(RefineError { iter: 7, error: "overflow at 128".to_string() }));
+
+    // No failure: behaves like refine_det.
+    let ok: Result<_, RefineError<String>> = try_refine_det(
+        1.0_f64,
+        |t: &f64| Ok(*t),
+        |d: &f64| Ok(*d),
+        |t: &f64, m: &f64| Ok(0.5 * (t + m / 2.0)),
+        |a: &f64, b: &f64| (a - b).abs() < 1e-12,
+        1_000,
+    );
+    let report = ok.unwrap();
+    assert!(report.converged() && report.theta.abs() < 1e-9);
+    assert!(report.residual.is_some_and(|m| (m - report.theta).abs() < 1e-9));
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+10) refine_traced — per-iteration (θ, π) path
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn refine_traced_records_path() {
+    use game_balance::refine_traced;
+
+    // θ halves its gap to 8 each step; π = gap.
+    let (last, trace) = refine_traced(
+        0.0_f64,
+        |t: &f64| *t,
+        |d: &f64| 8.0 - d,
+        |t: &f64, gap: &f64| t + 0.5 * gap,
+        |a: &f64, b: &f64| (a - b).abs() < 1e-3,
+        1_000,
+    );
+    let plain = refine_det(
+        0.0_f64,
+        |t: &f64| *t,
+        |d: &f64| 8.0 - d,
+        |t: &f64, gap: &f64| t + 0.5 * gap,
+        |a: &f64, b: &f64| (a - b).abs() < 1e-3,
+        1_000,
+    )
+    .theta;
+    assert_eq!(last, plain);
+    assert_eq!(trace[0], (0.0, 8.0));
+    assert_eq!(trace[1], (4.0, 4.0));
+    for w in trace.windows(2) {
+        assert_eq!(w[1].0, w[0].0 + 0.5 * w[0].1, "θ_(t+1) follows from (θ_t, π_t)");
+    }
+    assert_eq!(trace.last().unwrap().0 + 0.5 * trace.last().unwrap().1, last);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+11) refine_until — stop rules as data, with the reason reported
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn refine_until_reports_stop_reason() {
+    use game_balance::{RefineStatus, StopCondition, refine_until};
+
+    let count = |stop: &StopCondition, conv_at: u32| {
+        let report =
+            refine_until(0u32, |t: &u32| *t, |d: &u32| *d, |t: &u32, _m: &u32| t + 1, |_a: &u32, b: &u32| *b == conv_at, stop);
+        (report.theta, report.status)
+    };
+    assert_eq!(count(&StopCondition::Iters(10), 1_000), (10, RefineStatus::MaxIters));
+    assert_eq!(count(&StopCondition::Iters(10), 4), (4, RefineStatus::Converged { iter: 3 }));
+    let any = StopCondition::Any(vec![StopCondition::Iters(50), StopCondition::Iters(7)]);
+    assert_eq!(count(&any, 1_000), (7, RefineStatus::MaxIters));
+}
+
+#[cfg(feature = "timing")]
+#[test]
+fn refine_until_stops_on_time() {
+    use game_balance::{RefineStatus, StopCondition, refine_until};
+    use std::time::Duration;
+
+    let stop = StopCondition::Any(vec![StopCondition::Iters(usize::MAX), StopCondition::Time(Duration::from_millis(20))]);
+    let report = refine_until(
+        0usize,
+        |t: &usize| *t,
+        |d: &usize| *d,
+        |t: &usize, _m: &usize| {
+            std::thread::sleep(Duration::from_micros(200));
+            t + 1
+        },
+        |_a: &usize, _b: &usize| false,
+        &stop,
+    );
+    assert_eq!(report.status, RefineStatus::Time);
+    assert_eq!(report.theta, report.iters);
+    assert!(report.iters > 0 && report.iters.is_multiple_of(game_balance::TIME_CHECK_EVERY));
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+12) refine_guarded — NaN/∞ stops the loop early
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn refine_guarded_stops_on_divergence() {
+    use game_balance::{RefineStatus, refine_guarded};
+
+    // Cost curve that squares itself each step: 2, 4, 16, …, overflows to ∞.
+    let report = refine_guarded(
+        [2.0_f64, 1.0],
+        |t: &[f64; 2]| *t,
+        |d: &[f64; 2]| d[0] * d[0],
+        |t: &[f64; 2], sq: &f64| [*sq, t[1]],
+        |_a: &[f64; 2], _b: &[f64; 2]| false,
+        120_000,
+    );
+    assert_eq!(report.status, RefineStatus::Diverged { iter: 9 });
+    assert_eq!(report.theta[0], 2f64.powi(512));
+    assert!(!report.converged());
+
+    // π goes NaN: θ that produced it comes back.
+    let report = refine_guarded(
+        3.0_f64,
+        |t: &f64| *t,
+        |d: &f64| if *d < 1.0 { f64::NAN } else { *d },
+        |t: &f64, _m: &f64| t - 1.0,
+        |_a: &f64, _b: &f64| false,
+        100,
+    );
+    assert_eq!((report.theta, report.status), (0.0, RefineStatus::Diverged { iter: 3 }));
+    assert!(report.residual.unwrap().is_nan());
+
+    let report =
+        refine_guarded(1.0_f64, |t: &f64| *t, |d: &f64| *d, |t: &f64, _m: &f64| *t, |_a: &f64, _b: &f64| true, 5);
+    assert_eq!(report.status, RefineStatus::Converged { iter: 0 });
+    assert!(report.converged() && report.iters == 1);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+13) Patience — plateaued residual ends the run early
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn plateau_stops_stalled_loop() {
+    use game_balance::{RefineStatus, StopCondition, refine_until, refine_until_residual};
+
+    // θ approaches 3 (not the target 5), so |π| stalls at 2 forever.
+    let stop = StopCondition::Any(vec![
+        StopCondition::Iters(120_000),
+        StopCondition::Plateau { patience: 10, min_delta: 1e-6 },
+    ]);
+    let run = |with_residual: bool| {
+        let sim = |t: &f64| *t;
+        let meas = |d: &f64| 5.0 - d;
+        let upd = |t: &f64, _e: &f64| t + 0.5 * (3.0 - t);
+        let conv = |_a: &f64, _b: &f64| false;
+        if with_residual {
+            refine_until_residual(0.0_f64, sim, meas, upd, conv, |_t: &f64, e: &f64| e.abs(), &stop)
+        } else {
+            refine_until(0.0_f64, sim, meas, upd, conv, &stop)
+        }
+    };
+
+    let report = run(true);
+    assert_eq!(report.status, RefineStatus::Plateau);
+    assert!((report.theta - 3.0).abs() < 1e-4, "θ = {}", report.theta);
+
+    // Without a residual, Plateau never fires and the iteration cap does.
+    assert_eq!(run(false).status, RefineStatus::MaxIters);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+14) refine_anneal — escapes the shallow basin of a double well
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn anneal_escapes_local_basin() {
+    use game_balance::{Anneal, geometric_schedule, refine_anneal};
+    use rand_core::RngCore;
+
+    // E(x) = (x² − 1)² + 0.3x: shallow well near +1, deep well near −1.
+    let energy = |x: &f64| (x * x - 1.0).powi(2) + 0.3 * x;
+    let propose = |x: &f64, _e: &f64, rng: &mut bevy_prng::WyRand| {
+        let u = (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        x + (u - 0.5)
+    };
+    let run = |temperature: &dyn Fn(usize) -> f64, seed: u64| {
+        let cfg = Anneal { seed, temperature, max_iters: 4_000 };
+        refine_anneal(1.0_f64, |t: &f64| *t, |d: &f64| *d, propose, energy, &cfg)
+    };
+
+    // Greedy (T = 0) never climbs the barrier at x = 0.
+    let (greedy, _) = run(&|_| 0.0, 3);
+    assert!(greedy > 0.5, "greedy x = {greedy}");
+
+    let schedule = geometric_schedule(2.0, 0.998);
+    let (x, e) = run(&schedule, 3);
+    assert!((x + 1.0).abs() < 0.1, "annealed x = {x}");
+    assert!(e < energy(&greedy));
+    assert_eq!(run(&schedule, 3).0.to_bits(), x.to_bits(), "seeded replay");
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+15) with_momentum — heavy ball speeds up a slow proportional loop
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn momentum_converges_in_fewer_iterations() {
+    use game_balance::{RefineStatus, refine_guarded, with_momentum};
+
+    // TTU-style loop: π is the gap to [30, 0.9]; θ closes 2% of it per step.
+    let sim = |t: &[f64; 2]| *t;
+    let gap = |d: &[f64; 2]| [30.0 - d[0], 0.9 - d[1]];
+    let slow = |t: &[f64; 2], g: &[f64; 2]| [t[0] + 0.02 * g[0], t[1] + 0.02 * g[1]];
+    let conv = |a: &[f64; 2], b: &[f64; 2]| (a[0] - b[0]).abs() < 1e-6 && (a[1] - b[1]).abs() < 1e-8;
+    let iters = |status| match status {
+        RefineStatus::Converged { iter } => iter,
+        s => panic!("{s:?}"),
+    };
+
+    let plain = refine_guarded([10.0, 0.5], sim, gap, slow, conv, 100_000);
+    let heavy = refine_guarded([10.0, 0.5], sim, gap, with_momentum(slow, 0.8), conv, 100_000);
+    let (s_plain, s_heavy) = (plain.status, heavy.status);
+    let (plain, heavy) = (plain.theta, heavy.theta);
+
+    assert!((plain[0] - 30.0).abs() < 1e-3 && (heavy[0] - 30.0).abs() < 1e-3);
+    assert!((heavy[1] - 0.9).abs() < 1e-5);
+    assert!(iters(s_heavy) * 3 < iters(s_plain), "heavy {s_heavy:?} vs plain {s_plain:?}");
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+16) refine_multistart — restarts find the deeper basin
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn multistart_keeps_best_restart() {
+    use game_balance::refine_multistart;
+    use rand_core::RngCore;
+
+    // Gradient descent on E(x) = (x² − 1)² + 0.3x from a guess in the shallow well.
+    let energy = |x: f64| (x * x - 1.0).powi(2) + 0.3 * x;
+    let descend = |x0: f64| {
+        refine_det(
+            x0,
+            |x: &f64| *x,
+            |d: &f64| 4.0 * d * (d * d - 1.0) + 0.3,
+            |x: &f64, grad: &f64| x - 0.05 * grad,
+            |a: &f64, b: &f64| (a - b).abs() < 1e-10,
+            10_000,
+        )
+        .theta
+    };
+    let perturb = |x: &f64, rng: &mut bevy_prng::WyRand| {
+        let u = (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        x + 4.0 * (u - 0.5)
+    };
+
+    let (single, _, _) = refine_multistart(&0.9, 11, 1, perturb, descend, |x: &f64| energy(*x));
+    assert!(single > 0.0, "one start stays in the shallow well");
+
+    let (x, e, start) = refine_multistart(&0.9, 11, 8, perturb, descend, |x: &f64| energy(*x));
+    assert!(x < -0.9, "x = {x}");
+    assert!(start > 0 && e < energy(single));
+    assert_eq!(refine_multistart(&0.9, 11, 8, perturb, descend, |x: &f64| energy(*x)).2, start);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+17) refine_replicated — averaged metrics, confident convergence
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn replicated_convergence_does_not_flap() {
+    use game_balance::{ReplicateConfig, Replicated, refine_replicated};
+    use rand_core::RngCore;
+
+    // Combat sim: mean TTK = θ, but every fight adds U(−3, 3) noise.
+    let fight = |th: &f64, rng: &mut bevy_prng::WyRand| {
+        let u = (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        th + 6.0 * (u - 0.5)
+    };
+    let update = |th: &f64, pi: &Replicated| th + 0.5 * (10.0 - pi.mean[0]);
+    let run = |count: usize| {
+        let report = refine_replicated(
+            4.0_f64,
+            ReplicateConfig { count, seed: 21 },
+            fight,
+            |d: &f64| vec![*d],
+            update,
+            |pi: &Replicated| pi.within(&[10.0], &[0.5], 2.0),
+            200,
+        );
+        (report.theta, report.converged())
+    };
+
+    let (th, ok) = run(64);
+    assert!(ok && (th - 10.0).abs() < 0.6, "θ = {th}");
+    // One replicate has no error estimate, so the loop runs two.
+    assert_eq!(run(1), run(2));
+
+    let r = Replicated::from_samples(&[vec![1.0, 4.0], vec![3.0, 4.0]]);
+    assert_eq!(r.mean, vec![2.0, 4.0]);
+    assert_eq!(r.stderr, vec![1.0, 0.0]);
+
+    let one = Replicated::from_samples(&[vec![10.2]]);
+    assert_eq!(one.stderr, vec![f64::INFINITY]);
+    assert!(!one.within(&[10.0], &[0.5], 2.0));
+    assert!(one.within(&[10.0], &[0.5], 0.0), "z = 0 checks the mean alone");
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+18) Anderson mixing — opt-in acceleration via RefineOptions
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn anderson_accelerates_slow_fixed_point() {
+    use game_balance::{RefineOptions, refine_with};
+    use std::cell::Cell;
+
+    // Coupled cost-curve-like map with contraction factor ~0.98.
+    let steps = Cell::new(0usize);
+    let update = |x: &[f64; 3], _m: &()| {
+        steps.set(steps.get() + 1);
+        [
+            0.97 * x[0] + 0.01 * x[1] + 1.0,
+            0.01 * x[0] + 0.96 * x[1] + 0.01 * x[2] + 2.0,
+            0.01 * x[1] + 0.98 * x[2] + 0.5,
+        ]
+    };
+    let conv = |a: &[f64; 3], b: &[f64; 3]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-10);
+    let run = |depth: usize| {
+        steps.set(0);
+        let report = refine_with([0.0; 3], |_t: &[f64; 3]| (), |_d: &()| (), update, conv, 100_000, &RefineOptions {
+            anderson_depth: depth,
+            ..RefineOptions::default()
+        });
+        assert!(report.converged() && report.iters == steps.get());
+        (report.theta, steps.get())
+    };
+
+    let (plain, n_plain) = run(0);
+    let (fast, n_fast) = run(3);
+    assert!(plain.iter().zip(&fast).all(|(a, b)| (a - b).abs() < 1e-6), "{plain:?} vs {fast:?}");
+    assert!(n_fast * 20 < n_plain, "anderson {n_fast} vs plain {n_plain} steps");
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+19) Checkpoints — a killed run resumes to the same result
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn resumed_run_matches_uninterrupted() {
+    use game_balance::{Checkpoint, Checkpointer, refine_resumable};
+    use rand_core::RngCore;
+
+    let sim = |th: &f64, rng: &mut bevy_prng::WyRand| th + ((rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64 - 0.5);
+    let meas = |d: &f64| 20.0 - d;
+    let upd = |th: &f64, gap: &f64| th + 0.1 * gap;
+    let never = |_a: &f64, _b: &f64| false;
+
+    let full = refine_resumable(Checkpoint::new(1.0, 99), sim, meas, upd, never, 100, Checkpointer {
+        every: 0,
+        save: |_c: &Checkpoint<f64>| {},
+    });
+
+    // "CI timeout" at iteration 55; last checkpoint written at 50.
+    let mut saved = Vec::new();
+    let _ = refine_resumable(Checkpoint::new(1.0, 99), sim, meas, upd, never, 55, Checkpointer {
+        every: 10,
+        save: |c: &Checkpoint<f64>| saved.push(c.clone()),
+    });
+    assert_eq!(saved.iter().map(|c| c.iter).collect::<Vec<_>>(), vec![10, 20, 30, 40, 50]);
+
+    let resumed = refine_resumable(saved.pop().unwrap(), sim, meas, upd, never, 100, Checkpointer {
+        every: 0,
+        save: |_c: &Checkpoint<f64>| {},
+    });
+    assert_eq!(resumed.theta.to_bits(), full.theta.to_bits());
+    assert_eq!((resumed.iters, resumed.residual), (full.iters, full.residual));
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+20) refine_det_async — awaited simulate, same path as refine_det
+────────────────────────────────────────────────────────────────────────── */
+
+/// Resolves after being polled `n` times (a stand-in for a server round trip).
+struct Remote {
+    polls_left: u32,
+    value: f64,
+}
+
+impl std::future::Future for Remote {
+    type Output = f64;
+    fn poll(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<f64> {
+        if self.polls_left == 0 {
+            return std::task::Poll::Ready(self.value);
+        }
+        self.polls_left -= 1;
+        cx.waker().wake_by_ref();
+        std::task::Poll::Pending
+    }
+}
+
+fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+    let mut fut = std::pin::pin!(fut);
+    let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+    loop {
+        if let std::task::Poll::Ready(v) = fut.as_mut().poll(&mut cx) {
+            return v;
+        }
+    }
+}
+
+#[test]
+fn async_driver_matches_sync() {
+    use game_balance::refine_det_async;
+
+    let meas = |d: &f64| 12.0 - d;
+    let upd = |t: &f64, gap: &f64| t + 0.3 * gap;
+    let conv = |a: &f64, b: &f64| (a - b).abs() < 1e-9;
+
+    let sync = refine_det(0.0, |t: &f64| t * 0.9, meas, upd, conv, 10_000);
+    let calls = std::cell::Cell::new(0);
+    let remote = |t: &f64| {
+        calls.set(calls.get() + 1);
+        Remote { polls_left: 3, value: t * 0.9 }
+    };
+    let asynced = block_on(refine_det_async(0.0, remote, meas, upd, conv, 10_000));
+    assert_eq!(asynced, sync);
+    assert!(asynced.converged());
+    assert_eq!(calls.get(), asynced.iters);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+21) with_projection — θ stays in the feasible set
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn projection_applies_after_each_update() {
+    use game_balance::{RefineIter, with_projection};
+
+    // Update pushes (a, b) toward (8, 8); constraint a + b ≤ 10.
+    let upd = |t: &[f64; 2], _m: &()| [t[0] + 0.5 * (8.0 - t[0]), t[1] + 0.5 * (8.0 - t[1])];
+    let proj = |t: [f64; 2]| {
+        let excess = (t[0] + t[1] - 10.0).max(0.0) / 2.0;
+        [t[0] - excess, t[1] - excess]
+    };
+    let it = RefineIter::new([0.0, 2.0], |_t: &[f64; 2]| (), |_d: &()| (), with_projection(upd, proj));
+    let path: Vec<[f64; 2]> = it.take(50).collect();
+    assert!(path.iter().all(|t| t[0] + t[1] <= 10.0 + 1e-12));
+    let last = path.last().unwrap();
+    assert!((last[0] - 5.0).abs() < 1e-6 && (last[1] - 5.0).abs() < 1e-6, "{last:?}");
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+22) Trust region — one bad update can't fling θ
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn step_cap_limits_each_move() {
+    use game_balance::{RefineOptions, StepCap, refine_with};
+    use std::cell::RefCell;
+
+    // Proportional loop toward (50, 0.5) with one glitched measurement.
+    let calls = RefCell::new(0);
+    let path = RefCell::new(Vec::new());
+    let update = |t: &[f64; 2], _m: &()| {
+        *calls.borrow_mut() += 1;
+        path.borrow_mut().push(*t);
+        let glitch = if *calls.borrow() == 5 { 1e4 } else { 0.0 };
+        [t[0] + 0.5 * (50.0 - t[0]) + glitch, t[1] + 0.5 * (0.5 - t[1])]
+    };
+    let opts = RefineOptions { max_step: Some(StepCap::Relative(0.25)), ..RefineOptions::default() };
+    let conv = |a: &[f64; 2], b: &[f64; 2]| (a[0] - b[0]).abs() < 1e-9 && (a[1] - b[1]).abs() < 1e-12;
+    let th = refine_with([40.0, 1.0], |_t: &[f64; 2]| (), |_d: &()| (), update, conv, 10_000, &opts).theta;
+
+    assert!((th[0] - 50.0).abs() < 1e-6 && (th[1] - 0.5).abs() < 1e-9, "{th:?}");
+    let path = path.borrow();
+    for w in path.windows(2) {
+        for i in 0..2 {
+            assert!((w[1][i] - w[0][i]).abs() <= 0.25 * w[0][i].abs() + 1e-12, "{:?} → {:?}", w[0], w[1]);
+        }
+    }
+    assert!(path.iter().all(|t| t[0] < 100.0), "glitch leaked through");
+
+    // Absolute cap: a 1e4 jump becomes a 2-unit move.
+    let one = refine_with(0.0_f64, |t: &f64| *t, |d: &f64| *d, |t: &f64, _m: &f64| t + 1e4, |_a: &f64, _b: &f64| false, 3, &RefineOptions {
+        max_step: Some(StepCap::Absolute(2.0)),
+        ..RefineOptions::default()
+    });
+    assert_eq!((one.theta, one.status), (6.0, game_balance::RefineStatus::MaxIters));
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+23) refine_scalar — monotone one-knob solve in few evaluations
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn scalar_solver_hits_target_ttu() {
+    use game_balance::refine_scalar;
+
+    // Mean TTU over 20 levels as a function of base cost (income 3/s, growth 1.15).
+    let mean_ttu = |base: f64| (0..20).map(|l| base * 1.15f64.powi(l) / 3.0).sum::<f64>() / 20.0;
+    let out = refine_scalar((0.1, 1_000.0), 45.0, mean_ttu, |t: &f64| *t, 1e-9, 60);
+    assert!(out.converged, "{out:?}");
+    assert!(out.evals <= 40, "{} evals", out.evals);
+    assert!((mean_ttu(out.x) - 45.0).abs() <= 1e-9);
+
+    // Decreasing metric (drop chance → expected kills per drop) works too.
+    let out = refine_scalar((0.001, 1.0), 25.0, |p: f64| 1.0 / p, |k: &f64| *k, 1e-9, 60);
+    assert!(out.converged && (out.x - 0.04).abs() < 1e-9, "{out:?}");
+
+    // Target outside the bracket: closest endpoint, not converged.
+    let out = refine_scalar((1.0, 2.0), 100.0, |x: f64| x, |v: &f64| *v, 1e-9, 60);
+    assert!(!out.converged && out.x == 2.0 && out.evals == 2);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+24) RefineReport — success vs exhaustion is visible to the caller
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn refine_det_reports_how_it_ended() {
+    use game_balance::RefineStatus;
+
+    let sim = |t: &f64| *t;
+    let gap = |d: &f64| 8.0 - d;
+    let upd = |t: &f64, g: &f64| t + 0.5 * g;
+
+    let ok = refine_det(0.0, sim, gap, upd, |a: &f64, b: &f64| (a - b).abs() < 1e-6, 1_000);
+    assert!(ok.converged());
+    assert_eq!(ok.status, RefineStatus::Converged { iter: ok.iters - 1 });
+    assert!((ok.theta - 8.0).abs() < 1e-5);
+    assert!(ok.residual.unwrap().abs() < 1e-5);
+
+    let short = refine_det(0.0, sim, gap, upd, |a: &f64, b: &f64| (a - b).abs() < 1e-6, 3);
+    assert!(!short.converged());
+    assert_eq!((short.iters, short.status), (3, RefineStatus::MaxIters));
+    assert_eq!((short.theta, short.residual), (7.0, Some(2.0)));
+
+    let none = refine_det(1.0, sim, gap, upd, |_a: &f64, _b: &f64| true, 0);
+    assert_eq!((none.theta, none.iters, none.residual), (1.0, 0, None));
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+25) refine_observed — per-iteration callback sees (t, θ_t, π_t)
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn observer_sees_every_iteration() {
+    use game_balance::refine_observed;
+
+    let mut log: Vec<(usize, f64, f64)> = Vec::new();
+    let report = refine_observed(
+        0.0_f64,
+        |t: &f64| *t,
+        |d: &f64| 8.0 - d,
+        |t: &f64, gap: &f64| t + 0.5 * gap,
+        |a: &f64, b: &f64| (a - b).abs() < 1e-3,
+        1_000,
+        |t, theta: &f64, gap: &f64| log.push((t, *theta, *gap)),
+    );
+    assert_eq!(log.len(), report.iters);
+    assert_eq!(&log[..2], &[(0, 0.0, 8.0), (1, 4.0, 4.0)]);
+    assert_eq!(log.last().map(|e| e.2), report.residual);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+26) Damping — relaxation tames an overshooting controller
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn damping_stabilizes_chattery_loop() {
+    use game_balance::{RefineOptions, refine_with};
+
+    // Gain 1.9 overshoots the target 10 each step: error × (−0.9) per iteration.
+    let update = |t: &f64, m: &f64| t + 1.9 * (10.0 - m);
+    let conv = |a: &f64, b: &f64| (a - b).abs() < 1e-9;
+    let steps = |damping| {
+        let mut n = 0;
+        let th = refine_with(0.0_f64, |t: &f64| *t, |d: &f64| { n += 1; *d }, update, conv, 100_000, &RefineOptions {
+            damping,
+            ..RefineOptions::default()
+        })
+        .theta;
+        (th, n)
+    };
+    let (plain, n_plain) = steps(None);
+    // λ = 0.5: effective gain 0.95 → error × 0.05 per step.
+    let (damped, n_damped) = steps(Some(0.5));
+    assert!((plain - 10.0).abs() < 1e-6 && (damped - 10.0).abs() < 1e-6);
+    assert!(n_damped * 10 < n_plain, "damped {n_damped} vs plain {n_plain}");
+    assert_eq!(steps(Some(1.0)), (plain, n_plain));
+}
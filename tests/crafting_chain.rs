@@ -0,0 +1,98 @@
+// tests/crafting_chain.rs
+use game_balance::systems::crafting_chain::{self as craft, SimModel as _, Stage};
+use game_balance::systems::sdk::Hook;
+
+/// Ore → ingot (2 ore) → sword (3 ingots + 1 ore for the pommel).
+fn env() -> craft::Env {
+    craft::Env {
+        stages: vec![Stage::source(), Stage::new(vec![(0, 2.0)]), Stage::new(vec![(1, 3.0), (0, 1.0)])],
+        income_per_sec: 10.0,
+    }
+}
+
+fn targets() -> craft::Targets {
+    craft::Targets { throughput_per_hour: 60.0, dead_time: vec![0.2] }
+}
+
+fn theta0() -> craft::Params {
+    craft::Params { craft_seconds: vec![5.0, 5.0, 5.0], craft_cost: vec![1.0, 4.0, 20.0] }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Pipeline math — demand through the DAG, bottleneck vs budget
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn demand_and_dead_time_follow_the_dag() {
+    // 1 sword = 3 ingots = 6 ore, plus 1 ore.
+    assert_eq!(craft::demand(&env()), [7.0, 3.0, 1.0]);
+
+    // Loads 35 s, 15 s, 5 s per sword: ore is the bottleneck at 1/35 per second.
+    let th = craft::Params { craft_seconds: vec![5.0; 3], craft_cost: vec![0.0; 3] };
+    let o = craft::StandardModel.observe(&th, &env(), &targets(), &mut []);
+    assert_eq!(o.bottleneck, Some(0));
+    assert!((o.throughput_per_hour - 3600.0 / 35.0).abs() < 1e-9);
+    assert!((o.dead_time[1] - 4.0 / 7.0).abs() < 1e-12 && o.dead_time[0] == 0.0);
+
+    // Fast stations but 7 + 12 + 20 = 39 per sword at 10/s: income is the limit.
+    let th = craft::Params { craft_seconds: vec![0.5; 3], ..theta0() };
+    let o = craft::StandardModel.observe(&th, &env(), &targets(), &mut []);
+    assert_eq!(o.bottleneck, None);
+    assert_eq!(o.cost_per_unit, 39.0);
+    assert!((o.throughput_per_hour - 36000.0 / 39.0).abs() < 1e-9);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Balance — 60 swords an hour with 20% slack at every station
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn times_and_costs_hit_throughput_and_dead_time() {
+    let out = craft::Runner::new(theta0(), env(), targets()).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!((out.obs.throughput_per_hour / 60.0 - 1.0).abs() <= 0.05, "{:?}", out.obs);
+    assert!(out.obs.dead_time.iter().all(|&d| (d - 0.2).abs() <= 0.02), "{:?}", out.obs.dead_time);
+    // 48 s per sword at 80% use: 48 / demand_i seconds per unit.
+    for (s, d) in out.theta.craft_seconds.iter().zip([7.0, 3.0, 1.0]) {
+        assert!((s * d / 48.0 - 1.0).abs() < 0.06, "{:?}", out.theta);
+    }
+    // A sword costs one minute of income; the 1 : 4 : 20 shape is kept.
+    assert!((out.obs.cost_per_unit / 600.0 - 1.0).abs() < 0.06, "{:?}", out.obs);
+    assert!((out.theta.craft_cost[2] / out.theta.craft_cost[1] - 5.0).abs() < 1e-6, "{:?}", out.theta);
+    assert_eq!(out.residuals.len(), 4);
+}
+
+#[test]
+fn a_zero_dead_time_stage_becomes_the_bottleneck() {
+    let tgt = craft::Targets { dead_time: vec![0.3, 0.3, 0.0], ..targets() };
+    let out = craft::Runner::new(theta0(), env(), tgt).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!(out.obs.dead_time[2] <= 0.02 && (out.obs.dead_time[0] - 0.3).abs() <= 0.02, "{:?}", out.obs);
+}
+
+struct GuildDiscount;
+impl Hook<craft::Params, craft::Env, craft::Targets, craft::Obs> for GuildDiscount {
+    fn income_multiplier(&mut self, _base: f64, _th: &craft::Params, _env: &craft::Env) -> f64 {
+        2.0
+    }
+}
+
+#[test]
+fn income_hooks_raise_costs_not_times() {
+    let base = craft::Runner::new(theta0(), env(), targets()).run();
+    let out = craft::Runner::new(theta0(), env(), targets()).hook(GuildDiscount).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!((out.obs.cost_per_unit / base.obs.cost_per_unit - 2.0).abs() < 0.15, "{:?} vs {:?}", out.obs, base.obs);
+    assert!((out.theta.craft_seconds[0] / base.theta.craft_seconds[0] - 1.0).abs() < 0.06);
+}
+
+#[test]
+fn try_run_rejects_forward_edges_and_full_idle() {
+    let mut env = env();
+    env.stages[0].inputs.push((2, 1.0));
+    let tgt = craft::Targets { dead_time: vec![1.0], ..targets() };
+    let err = craft::Runner::new(theta0(), env, tgt).try_run().unwrap_err();
+    let game_balance::Error::Invalid(problems) = err else { panic!("expected Invalid") };
+    let fields: Vec<_> = problems.iter().map(|p| p.field).collect();
+    assert_eq!(fields, ["stages.inputs", "dead_time"]);
+}
@@ -0,0 +1,67 @@
+// tests/daily_quest.rs
+use game_balance::systems::daily_quest as daily;
+use game_balance::systems::sdk::Hook;
+
+fn env() -> daily::Env {
+    daily::Env { play_income_per_min: 10.0, base_minutes: 10.0 }
+}
+
+fn targets() -> daily::Targets {
+    daily::Targets { session_band: (20.0, 30.0), max_share: 0.3, quest_worth_minutes: 5.0 }
+}
+
+fn theta0() -> daily::Params {
+    daily::Params { count: 5.0, reward: 20.0, quest_minutes: 3.0 }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Balance — 25-minute sessions, dailies at 30% of income
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn dailies_fill_the_session_under_the_share_cap() {
+    let out = daily::Runner::new(theta0(), env(), targets()).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!((20.0..=30.0).contains(&out.obs.session_minutes), "{:?}", out.obs);
+    assert!(out.obs.dailies_share <= 0.31 && !out.obs.income_cliff, "{:?}", out.obs);
+    // 50 per quest; 0.3/0.7 · 25 / 5 ≈ 2.14 quests of 7 minutes each.
+    assert!((out.theta.reward / 50.0 - 1.0).abs() < 0.05, "{:?}", out.theta);
+    assert!((out.theta.count / (0.3 / 0.7 * 5.0) - 1.0).abs() < 0.06, "{:?}", out.theta);
+    assert!((out.theta.quest_minutes / 7.0 - 1.0).abs() < 0.06, "{:?}", out.theta);
+    assert_eq!(out.residuals.len(), 3);
+}
+
+#[test]
+fn dominant_dailies_report_an_income_cliff() {
+    let th = daily::Params { count: 6.0, reward: 100.0, quest_minutes: 2.5 };
+    assert_eq!(daily::session_minutes(&th, &env()), 25.0);
+
+    let tgt = daily::Targets { max_share: 0.6, ..targets() };
+    let out = daily::Runner::new(theta0(), env(), tgt).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!(out.obs.income_cliff && out.obs.dailies_share > daily::CLIFF_SHARE, "{:?}", out.obs);
+}
+
+struct VipRewards;
+impl Hook<daily::Params, daily::Env, daily::Targets, daily::Obs> for VipRewards {
+    fn income_multiplier(&mut self, _base: f64, _th: &daily::Params, _env: &daily::Env) -> f64 {
+        2.0
+    }
+}
+
+#[test]
+fn reward_hooks_are_compensated() {
+    let out = daily::Runner::new(theta0(), env(), targets()).hook(VipRewards).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!((out.theta.reward / 25.0 - 1.0).abs() < 0.06, "{:?}", out.theta);
+    assert!((out.obs.reward / 50.0 - 1.0).abs() < 0.06, "{:?}", out.obs);
+}
+
+#[test]
+fn try_run_rejects_a_full_share_cap() {
+    let tgt = daily::Targets { max_share: 1.0, session_band: (30.0, 20.0), ..targets() };
+    let err = daily::Runner::new(theta0(), env(), tgt).try_run().unwrap_err();
+    let game_balance::Error::Invalid(problems) = err else { panic!("expected Invalid") };
+    let fields: Vec<_> = problems.iter().map(|p| p.field).collect();
+    assert_eq!(fields, ["session_band", "max_share"]);
+}
@@ -0,0 +1,72 @@
+// tests/difficulty_curve.rs
+use game_balance::systems::difficulty_curve as diff;
+
+/// 50 levels of player power growing 6% per level, with a gear spike at 25.
+fn env() -> diff::Env {
+    let dps = (0..50).map(|l| 20.0 * 1.06f64.powi(l) * if l >= 25 { 1.3 } else { 1.0 }).collect();
+    let hp = (0..50).map(|l| 200.0 * 1.06f64.powi(l)).collect();
+    diff::Env { player_dps: dps, player_hp: hp, skill_spread: 0.3 }
+}
+
+fn targets() -> diff::Targets {
+    diff::Targets { fight_seconds: 10.0, fail_first: 0.02, fail_last: 0.15 }
+}
+
+fn theta0() -> diff::Params {
+    diff::Params { hp_base: 100.0, hp_growth: 1.05, dmg_base: 5.0, dmg_growth: 1.05 }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Fail model — logistic in ln(TTK / TTD)
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn fail_rate_is_logistic_in_pressure() {
+    assert_eq!(diff::fail_rate(1.0, 0.3), 0.5, "an even fight fails half the time");
+    assert!(diff::fail_rate(0.5, 0.3) < 0.1 && diff::fail_rate(2.0, 0.3) > 0.9);
+    assert!((diff::fail_rate(0.5, 0.3) + diff::fail_rate(2.0, 0.3) - 1.0).abs() < 1e-12);
+
+    let line = diff::target_curve(&targets(), 50);
+    assert_eq!((line[0], line[49]), (0.02, 0.15));
+    assert!((line[1] - line[0] - 0.13 / 49.0).abs() < 1e-12);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Balance — 2% → 15% over 50 levels, spikes reported per level
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn scaling_hits_the_fail_curve_endpoints() {
+    let out = diff::Runner::new(theta0(), env(), targets()).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!((out.obs.fail_first - 0.02).abs() <= 0.0025, "{:?}", out.obs.fail_first);
+    assert!((out.obs.fail_last - 0.15).abs() <= 0.005, "{:?}", out.obs.fail_last);
+    assert!((out.obs.ttk[0] - 10.0).abs() < 0.5 && (out.obs.ttk[49] - 10.0).abs() < 0.5);
+    assert_eq!(out.obs.fail.len(), 50);
+}
+
+#[test]
+fn player_power_spikes_show_up_per_level() {
+    let out = diff::Runner::new(theta0(), env(), targets()).run();
+    // The gear jump at level 25 makes the levels after it easier than the line.
+    let line = diff::target_curve(&targets(), 50);
+    assert!(out.obs.fail[25] < line[25] && out.obs.fail[24] > out.obs.fail[25], "{:?}", &out.obs.fail[20..30]);
+    assert!((20..=30).contains(&out.obs.spike_level), "spike at {}", out.obs.spike_level);
+    assert!(out.obs.max_spike > 0.02);
+
+    // A smooth player curve tracks the line far more closely.
+    let mut smooth = env();
+    smooth.player_dps = (0..50).map(|l| 20.0 * 1.06f64.powi(l)).collect();
+    let even = diff::Runner::new(theta0(), smooth, targets()).run();
+    assert!(even.converged);
+    assert!(even.obs.max_spike < out.obs.max_spike, "{} vs {}", even.obs.max_spike, out.obs.max_spike);
+}
+
+#[test]
+fn try_run_rejects_mismatched_curves() {
+    let mut env = env();
+    env.player_hp.pop();
+    let err = diff::Runner::new(theta0(), env, targets()).try_run().unwrap_err();
+    let game_balance::Error::Invalid(problems) = err else { panic!("expected Invalid") };
+    assert_eq!(problems.iter().map(|p| p.field).collect::<Vec<_>>(), ["player_hp"]);
+}
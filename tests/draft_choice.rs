@@ -0,0 +1,183 @@
+// tests/draft_choice.rs
+use game_balance::systems::draft_choice as draft;
+use game_balance::systems::sdk::Hook;
+
+struct Noop;
+impl Hook<(), (), (), ()> for Noop {}
+
+fn card(name: &str, tier: draft::Tier, base_p: f64, pity: Option<draft::PitySpec>) -> draft::EffectCard<(), (), (), ()> {
+    draft::EffectCard {
+        name: name.into(),
+        tier,
+        base_p,
+        pity,
+        mk: Box::new(|| Box::new(Noop)),
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Expected offers to see a card — analytic vs simulated
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn expected_offers_matches_simulation() {
+    let pity = draft::PitySpec { pity_cap: 0.3, k: 0.2 };
+    let pool = vec![
+        card("Filler", draft::Tier::Common, 1.0, None),
+        card("Epic", draft::Tier::Epic, 0.05, Some(pity)),
+    ];
+    let cfg = draft::DraftConfig {
+        options_per_roll: 2,
+        rerolls_per_draft: 0,
+        prioritize_tier: true,
+    };
+
+    let analytic = draft::expected_offers_to_see(&pool[1]);
+
+    let mut st = draft::DraftState::new(cfg, pool.len(), 7);
+    let offers = 40_000;
+    let mut seen = 0;
+    for _ in 0..offers {
+        let offer = draft::make_offer(&pool, cfg, &mut st);
+        if offer.iter().any(|c| c.pool_idx == 1) {
+            seen += 1;
+        }
+    }
+    let simulated = offers as f64 / seen as f64;
+
+    assert!(
+        (analytic - simulated).abs() <= 0.05 * simulated,
+        "analytic {analytic} vs simulated {simulated}"
+    );
+}
+
+#[test]
+fn expected_offers_without_pity_is_geometric() {
+    let c = card("Rare", draft::Tier::Rare, 0.125, None);
+    let e = draft::expected_offers_to_see(&c);
+    assert!((e - 8.0).abs() < 1e-6, "expected 8 offers, got {e}");
+
+    let never = card("Never", draft::Tier::Epic, 0.0, None);
+    assert!(draft::expected_offers_to_see(&never).is_infinite());
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+RNG stream position — advance() reproduces a consumed prefix
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn advance_matches_consumed_stream() {
+    let pool = vec![
+        card("A", draft::Tier::Common, 0.6, None),
+        card("B", draft::Tier::Rare, 0.4, None),
+        card("C", draft::Tier::Epic, 0.2, None),
+    ];
+    let cfg = draft::DraftConfig { options_per_roll: 2, rerolls_per_draft: 1, prioritize_tier: false };
+
+    let mut a = draft::DraftState::new(cfg, pool.len(), 99);
+    assert_eq!(a.position(), 0);
+    let _ = draft::make_offer(&pool, cfg, &mut a);
+    let consumed = a.position();
+    assert!(consumed >= pool.len() as u64, "one roll per card at least");
+    let next_a: Vec<usize> = draft::make_offer(&pool, cfg, &mut a).iter().map(|c| c.pool_idx).collect();
+
+    // Skipping the same number of outputs lands on the same offer.
+    let mut b = draft::DraftState::new(cfg, pool.len(), 99);
+    b.advance(consumed);
+    assert_eq!(b.position(), consumed);
+    let next_b: Vec<usize> = draft::make_offer(&pool, cfg, &mut b).iter().map(|c| c.pool_idx).collect();
+    assert_eq!(next_a, next_b);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Offer observer — live notifications and non-mutating previews
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn observer_sees_offers_and_previews_do_not_mutate() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let mut pool = vec![
+        card("A", draft::Tier::Common, 0.5, None),
+        card("B", draft::Tier::Rare, 0.5, Some(draft::PitySpec { pity_cap: 0.4, k: 0.5 })),
+    ];
+    let cfg = draft::DraftConfig { options_per_roll: 1, rerolls_per_draft: 1, prioritize_tier: true };
+    let mut st = draft::DraftState::new(cfg, pool.len(), 3);
+
+    let log: Rc<RefCell<Vec<(Vec<usize>, bool, u64)>>> = Rc::new(RefCell::new(Vec::new()));
+    let sink = Rc::clone(&log);
+    st.set_offer_observer(move |offer, snap| {
+        sink.borrow_mut().push((offer.iter().map(|c| c.pool_idx).collect(), snap.preview, snap.position));
+    });
+
+    // Preview is exactly the next real offer and leaves the state alone.
+    let preview: Vec<usize> = draft::preview_offer(&pool, cfg, &st).iter().map(|c| c.pool_idx).collect();
+    assert_eq!(st.position(), 0);
+    let real: Vec<usize> = draft::make_offer(&pool, cfg, &mut st).iter().map(|c| c.pool_idx).collect();
+    assert_eq!(preview, real);
+
+    // Editing the pool and previewing again notifies without advancing.
+    let before = st.position();
+    pool[0].base_p = 0.0;
+    pool[1].base_p = 1.0;
+    let edited = draft::preview_offer(&pool, cfg, &st);
+    assert_eq!(edited[0].pool_idx, 1);
+    assert_eq!(st.position(), before);
+
+    let _ = draft::reroll_offer(&pool, cfg, &mut st);
+    st.clear_offer_observer();
+    let _ = draft::make_offer(&pool, cfg, &mut st);
+
+    let log = log.borrow();
+    assert_eq!(log.len(), 4, "preview, offer, preview, reroll");
+    assert_eq!(log.iter().map(|e| e.1).collect::<Vec<_>>(), vec![true, false, true, false]);
+    assert_eq!(log[0].0, log[1].0);
+    assert_eq!(log[2].2, before, "preview snapshot shows the live position");
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Reroll budget — spent rerolls accumulate, a reset refills the budget
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn rerolls_accumulate_against_configured_total() {
+    let pool = vec![card("A", draft::Tier::Common, 0.5, None), card("B", draft::Tier::Rare, 0.5, None)];
+    let cfg = draft::DraftConfig { options_per_roll: 1, rerolls_per_draft: 3, prioritize_tier: false };
+    let mut st = draft::DraftState::new(cfg, pool.len(), 11);
+    let _ = draft::make_offer(&pool, cfg, &mut st);
+
+    for used in 1..=3 {
+        assert!(draft::reroll_offer(&pool, cfg, &mut st).is_some());
+        assert_eq!(st.total_rerolls() - st.rerolls_left, used);
+        assert_eq!(st.total_rerolls(), 3, "spending rerolls leaves the total alone");
+    }
+    assert!(draft::reroll_offer(&pool, cfg, &mut st).is_none(), "budget exhausted");
+    assert_eq!(st.rerolls_left, 0);
+
+    let snap = st.snapshot();
+    assert_eq!((snap.rerolls_left, snap.total_rerolls), (0, 3));
+}
+
+#[test]
+fn reset_rerolls_restores_configured_budget() {
+    let pool = vec![
+        card("A", draft::Tier::Common, 0.5, None),
+        card("B", draft::Tier::Rare, 0.2, Some(draft::PitySpec { pity_cap: 0.4, k: 0.5 })),
+    ];
+    let cfg = draft::DraftConfig { options_per_roll: 1, rerolls_per_draft: 2, prioritize_tier: false };
+    let mut st = draft::DraftState::new(cfg, pool.len(), 5);
+    let _ = draft::make_offer(&pool, cfg, &mut st);
+    while draft::reroll_offer(&pool, cfg, &mut st).is_some() {}
+    assert_eq!(st.rerolls_left, 0);
+
+    let (position, pity) = (st.position(), st.snapshot().pity_acc);
+    st.reset_rerolls(cfg);
+    assert_eq!((st.rerolls_left, st.total_rerolls()), (2, 2));
+    assert_eq!(st.position(), position, "reset keeps the RNG stream");
+    assert_eq!(st.snapshot().pity_acc, pity, "reset keeps pity");
+
+    // A new draft may configure a different budget.
+    st.reset_rerolls(draft::DraftConfig { rerolls_per_draft: 4, ..cfg });
+    assert_eq!((st.rerolls_left, st.total_rerolls()), (4, 4));
+}
@@ -0,0 +1,89 @@
+// tests/energy_regen.rs
+use game_balance::systems::energy_regen as energy;
+use game_balance::systems::sdk::Hook;
+
+fn env() -> energy::Env {
+    energy::Env { minutes_per_action: 0.5, waking_minutes: 1080.0, mean_gap_minutes: 60.0 }
+}
+
+fn targets() -> energy::Targets {
+    energy::Targets { session_minutes: 20.0, sessions_per_day: 3.0, max_waste: 0.10, bar_size: 100.0 }
+}
+
+fn theta0() -> energy::Params {
+    energy::Params { max_energy: 60.0, regen_per_min: 1.0, action_cost: 5.0 }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Pacing math — sessions, refill and waste
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn pacing_math_matches_closed_forms() {
+    // 100 energy, 5 per action, 1/min regen at 0.5 min/action: 100 / 4.5 actions.
+    let n = energy::actions_per_session(100.0, 1.0, 5.0, &env());
+    assert!((n - 100.0 / 4.5).abs() < 1e-12);
+    assert_eq!(energy::actions_per_session(100.0, 20.0, 5.0, &env()), 2160.0, "regen outpaces spending");
+
+    // Waste: e^(−refill/gap) of the waking day plus the night past the refill.
+    let w = energy::waste_share(300.0, &env());
+    assert!((w - (1080.0 * (-5.0f64).exp() + 60.0) / 1440.0).abs() < 1e-12);
+    let r = energy::min_refill_minutes(0.05, &env());
+    assert!((energy::waste_share(r, &env()) - 0.05).abs() < 1e-6);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Balance — three sessions a day, or as close as the waste cap allows
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn bar_regen_and_cost_hit_session_targets() {
+    let out = energy::Runner::new(theta0(), env(), targets()).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!((out.obs.session_minutes - 20.0).abs() <= 1.0, "{:?}", out.obs);
+    assert!((out.obs.sessions_per_day - 3.0).abs() <= 0.15, "{:?}", out.obs);
+    assert!(out.obs.waste <= 0.10, "{:?}", out.obs);
+    // 340 min to refill a bar of 100; 40 actions per session.
+    assert!((out.theta.max_energy - 100.0).abs() < 5.0, "{:?}", out.theta);
+    assert!((out.obs.refill_minutes / 340.0 - 1.0).abs() < 0.06, "{:?}", out.obs);
+    assert_eq!(out.residuals.len(), 3);
+}
+
+#[test]
+fn waste_cap_stretches_the_refill() {
+    // Longer nights and longer daytime gaps: 3 sessions would waste ~18%.
+    let env = energy::Env { waking_minutes: 960.0, mean_gap_minutes: 120.0, ..env() };
+    assert!(energy::waste_share(300.0, &env) > 0.15);
+
+    let out = energy::Runner::new(theta0(), env, targets()).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!(out.obs.waste <= 0.105, "{:?}", out.obs);
+    assert!(out.obs.sessions_per_day < 2.7, "fewer, longer-spaced sessions: {:?}", out.obs);
+    assert!(out.obs.refill_minutes >= 0.95 * energy::min_refill_minutes(0.10, &env));
+}
+
+struct VipRegen;
+impl Hook<energy::Params, energy::Env, energy::Targets, energy::Obs> for VipRegen {
+    fn income_multiplier(&mut self, _base: f64, _th: &energy::Params, _env: &energy::Env) -> f64 {
+        1.5
+    }
+}
+
+#[test]
+fn regen_hooks_are_compensated() {
+    let base = energy::Runner::new(theta0(), env(), targets()).run();
+    let vip = energy::Runner::new(theta0(), env(), targets()).hook(VipRegen).run();
+    assert!(vip.converged, "{}", vip.explain());
+    assert!((vip.theta.regen_per_min * 1.5 / base.theta.regen_per_min - 1.0).abs() < 0.08, "{:?} vs {:?}", vip.theta, base.theta);
+    assert!((vip.obs.refill_minutes / base.obs.refill_minutes - 1.0).abs() < 0.08);
+}
+
+#[test]
+fn try_run_rejects_a_day_longer_than_a_day() {
+    let env = energy::Env { waking_minutes: 2000.0, ..env() };
+    let tgt = energy::Targets { max_waste: -0.1, ..targets() };
+    let err = energy::Runner::new(theta0(), env, tgt).try_run().unwrap_err();
+    let game_balance::Error::Invalid(problems) = err else { panic!("expected Invalid") };
+    let fields: Vec<_> = problems.iter().map(|p| p.field).collect();
+    assert_eq!(fields, ["waking_minutes", "max_waste"]);
+}
@@ -0,0 +1,45 @@
+// tests/ensemble.rs
+use game_balance::ensemble::Summary;
+use game_balance::{refine_ensemble, refine_stoch};
+use rand_core::RngCore;
+
+/* ──────────────────────────────────────────────────────────────────────────
+1) Seed stability — spread of a noisy balance point
+────────────────────────────────────────────────────────────────────────── */
+
+/// Noisy loot loop: drop value = θ·(1 ± 20%); drive mean value per hour to 600.
+fn run(seed: u64) -> (f64, f64) {
+    let sim = |th: &f64, rng: &mut bevy_prng::WyRand| {
+        (0..60).map(|_| th * (0.8 + 0.4 * (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64)).sum::<f64>()
+    };
+    let th = refine_stoch(1.0, seed, sim, |d: &f64| *d, |t: &f64, v: &f64| t * (600.0 / v).powf(0.5), |_a: &f64, _b: &f64| false, 40).theta;
+    let final_rate = sim(&th, &mut game_balance::stream_rng(seed, u64::MAX));
+    (th, final_rate)
+}
+
+#[test]
+fn ensemble_summarizes_final_theta_and_pi() {
+    let e = refine_ensemble(64, 3, run);
+    assert_eq!(e.runs, 64);
+    assert_eq!((e.theta.len(), e.pi.len()), (1, 1));
+
+    let th = &e.theta[0];
+    assert!((th.mean - 10.0).abs() < 0.5, "θ mean {}", th.mean);
+    assert!(th.stddev > 0.0 && th.stddev < 1.0, "θ sd {}", th.stddev);
+    assert!(th.quantile(0.05) <= th.quantile(0.5) && th.quantile(0.5) <= th.quantile(0.95));
+    assert!((e.pi[0].mean - 600.0).abs() < 30.0, "π mean {}", e.pi[0].mean);
+
+    // Independent of thread scheduling.
+    assert_eq!(refine_ensemble(64, 3, run), e);
+}
+
+#[test]
+fn summary_quantiles_interpolate() {
+    let s = Summary::from_values(vec![4.0, 1.0, 3.0, 2.0, 5.0]);
+    assert_eq!(s.mean, 3.0);
+    assert_eq!(s.quantile(0.0), 1.0);
+    assert_eq!(s.quantile(0.5), 3.0);
+    assert_eq!(s.quantile(0.875), 4.5);
+    assert_eq!(s.quantile(1.0), 5.0);
+    assert!((s.stddev - 2.5f64.sqrt()).abs() < 1e-12);
+}
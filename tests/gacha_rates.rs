@@ -0,0 +1,134 @@
+// tests/gacha_rates.rs
+use game_balance::systems::gacha_rates as gacha;
+use gacha::SimModel;
+
+fn env() -> gacha::Env {
+    gacha::Env { soft_pity_start: 73.0, four_star_pity: 10.0 }
+}
+
+fn targets() -> gacha::Targets {
+    gacha::Targets {
+        window_pulls: 70.0,
+        five_per_window: 1.0,
+        drought_pulls: 90.0,
+        drought_max: 0.001,
+        base_share: 0.3,
+        four_per_ten: 1.3,
+    }
+}
+
+fn theta0() -> gacha::Params {
+    gacha::Params { rate_5: 0.006, ramp: 0.06, hard_cap: 100.0, rate_4: 0.05 }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Pity math — closed-form checks on the survival curve
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn pity_math_matches_closed_forms() {
+    // No ramp: geometric up to the cap.
+    let flat = gacha::Params { rate_5: 0.01, ramp: 0.0, hard_cap: 50.0, rate_4: 0.1 };
+    let want = (1.0 - 0.99_f64.powi(49)) / 0.01 + 0.99_f64.powi(49);
+    assert!((gacha::mean_pulls(&flat, &env()) - want).abs() < 1e-9);
+    assert!((gacha::survival(&flat, &env(), 30) - 0.99_f64.powi(30)).abs() < 1e-12);
+    assert_eq!(gacha::survival(&flat, &env(), 50), 0.0, "the hard cap always hits");
+    assert_eq!(gacha::five_star_chance(&flat, &env(), 50), 1.0);
+
+    // The ramp adds `ramp` per pull past soft pity.
+    let ramped = gacha::Params { ramp: 0.1, hard_cap: 100.0, ..flat };
+    assert_eq!(gacha::five_star_chance(&ramped, &env(), 73), 0.01);
+    assert!((gacha::five_star_chance(&ramped, &env(), 75) - 0.21).abs() < 1e-12);
+
+    // 4★ with a 10-pull guarantee: 10 / E = 10q / (1 − (1−q)¹⁰).
+    assert!((gacha::mean_pulls_4(0.1, 10.0) - (1.0 - 0.9_f64.powi(10)) / 0.1).abs() < 1e-12);
+    assert_eq!(gacha::mean_pulls_4(1.0, 10.0), 1.0);
+}
+
+#[test]
+fn survival_hits_zero_exactly_at_the_hard_cap() {
+    // Ramp still short of 1 on the last pull before the cap.
+    let th = gacha::Params { rate_5: 0.006, ramp: 0.03, hard_cap: 90.4, rate_4: 0.05 };
+    let cap = gacha::cap_pulls(&th);
+    assert_eq!(cap, 90, "the cap rounds to a pull count");
+    assert!(gacha::five_star_chance(&th, &env(), cap - 1) < 1.0);
+    assert_eq!(gacha::five_star_chance(&th, &env(), cap), 1.0);
+    assert!(gacha::survival(&th, &env(), cap - 1) > 0.0);
+    assert_eq!(gacha::survival(&th, &env(), cap), 0.0);
+    assert_eq!(gacha::survival(&th, &env(), cap + 50), 0.0, "nothing survives past the cap");
+    let mean = gacha::mean_pulls(&th, &env());
+    assert!(mean > 1.0 && mean <= cap as f64, "mean = {mean}");
+
+    // A cap under one pull still guarantees the first.
+    let instant = gacha::Params { hard_cap: 0.2, ..th };
+    assert_eq!(gacha::cap_pulls(&instant), 1);
+    assert_eq!(gacha::survival(&instant, &env(), 1), 0.0);
+    assert_eq!(gacha::mean_pulls(&instant, &env()), 1.0);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Balance — rates, ramp and cap settle on every target at once
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn rates_hit_pull_economy_targets() {
+    let out = gacha::balance_ext(theta0(), env(), targets(), gacha::Options { max_iters: 10_000, ..Default::default() });
+    assert!(out.converged, "{}", out.explain());
+    assert!((out.obs.mean_pulls - 70.0).abs() <= 0.02 * 70.0, "{:?}", out.obs);
+    assert!(out.obs.drought <= 0.001, "{:?}", out.obs);
+    assert!((out.obs.base_share - 0.3).abs() <= 0.01, "{:?}", out.obs);
+    // Base rate from the share: 1 − 0.7^(1/73).
+    assert!((out.theta.rate_5 - (1.0 - 0.7_f64.powf(1.0 / 73.0))).abs() < 2e-4, "{:?}", out.theta);
+    assert_eq!(out.residuals.len(), 4);
+}
+
+#[test]
+fn hard_cap_moves_in_when_droughts_are_too_likely() {
+    // No ramp budget: only the cap can end long droughts.
+    let bounds = gacha::Bounds { ramp_max: 0.0, ..gacha::Bounds::soft() };
+    let tgt = gacha::Targets { window_pulls: 80.0, base_share: 0.5, ..targets() };
+    let out = gacha::Runner::new(gacha::Params { ramp: 0.0, hard_cap: 200.0, ..theta0() }, env(), tgt)
+        .bounds(bounds)
+        .max_iters(10_000)
+        .run();
+    assert_eq!(out.obs.drought, 0.0, "{}", out.explain());
+    assert!(gacha::cap_pulls(&out.theta) <= 90, "{:?}", out.theta);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Hooks — a rate-up scales the 5★ base rate the pity math sees
+────────────────────────────────────────────────────────────────────────── */
+
+struct RateUp(f64);
+impl game_balance::systems::sdk::Hook<gacha::Params, gacha::Env, gacha::Targets, gacha::Obs> for RateUp {
+    fn income_multiplier(&mut self, _base: f64, _th: &gacha::Params, _env: &gacha::Env) -> f64 {
+        self.0
+    }
+}
+
+#[test]
+fn rate_up_hook_halves_the_tuned_base_rate() {
+    let plain = gacha::Runner::new(theta0(), env(), targets()).max_iters(10_000).run();
+    let boosted = gacha::Runner::new(theta0(), env(), targets()).hook(RateUp(2.0)).max_iters(10_000).run();
+    assert!(plain.converged && boosted.converged, "{}", boosted.explain());
+
+    // Same observed odds; the hook supplies half of the base rate.
+    let hooks: &mut [Box<dyn game_balance::systems::sdk::Hook<_, _, _, _>>] = &mut [Box::new(RateUp(2.0))];
+    let seen = gacha::StandardModel.observe(&boosted.theta, &env(), &targets(), hooks);
+    assert!((seen.rate_5 - 2.0 * boosted.theta.rate_5).abs() < 1e-12, "{seen:?} vs {:?}", boosted.theta);
+    assert!((boosted.obs.rate_5 - plain.obs.rate_5).abs() < 2e-4, "{:?} vs {:?}", boosted.obs, plain.obs);
+    assert!((boosted.theta.rate_5 / plain.theta.rate_5 - 0.5).abs() < 0.05, "{:?}", boosted.theta);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Validation — impossible odds are rejected up front
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn impossible_targets_are_rejected() {
+    let tgt = gacha::Targets { drought_max: 1.5, four_per_ten: 12.0, ..targets() };
+    let err = gacha::Runner::new(theta0(), env(), tgt).try_run().unwrap_err();
+    let game_balance::Error::Invalid(problems) = err else { panic!("expected Invalid") };
+    let fields: Vec<_> = problems.iter().map(|p| p.field).collect();
+    assert_eq!(fields, ["drought_max", "four_per_ten"]);
+}
@@ -0,0 +1,51 @@
+// tests/game.rs
+use game_balance::mechanics::game::solve_zero_sum;
+
+const A_MP: [[f64; 2]; 2] = [[1.0, -1.0], [-1.0, 1.0]];
+
+const A_RPS: [[f64; 3]; 3] = [
+    [0.0, -1.0, 1.0],
+    [1.0, 0.0, -1.0],
+    [-1.0, 1.0, 0.0],
+];
+
+fn l1(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b.iter()).map(|(x, y)| (x - y).abs()).sum()
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Zero-sum solver — tolerance, iteration count and convergence flag
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn matching_pennies_converges_at_several_tolerances() {
+    let mut prev_iters = 0;
+    for tol in [1e-2, 1e-3, 1e-4] {
+        let sol = solve_zero_sum(&A_MP, [0.9, 0.1], [0.1, 0.9], 0.2, 0.01, tol, 200_000);
+        assert!(sol.converged, "tol {tol}: not converged ({:?})", sol);
+        assert!(sol.l1 < tol, "tol {tol}: l1 {}", sol.l1);
+        assert!(sol.iters >= prev_iters, "tighter tol took fewer iters");
+        prev_iters = sol.iters;
+        assert!(l1(&sol.row, &[0.5, 0.5]) < 50.0 * tol, "row {:?}", sol.row);
+        assert!(l1(&sol.col, &[0.5, 0.5]) < 50.0 * tol, "col {:?}", sol.col);
+    }
+}
+
+#[test]
+fn rps_converges_to_uniform() {
+    let u = [1.0 / 3.0; 3];
+    for tol in [1e-3, 1e-5] {
+        let sol = solve_zero_sum(&A_RPS, [0.8, 0.15, 0.05], [0.2, 0.3, 0.5], 0.2, 0.02, tol, 200_000);
+        assert!(sol.converged, "tol {tol}: not converged ({:?})", sol);
+        assert!(l1(&sol.row, &u) < 1e-2, "row {:?}", sol.row);
+        assert!(l1(&sol.col, &u) < 1e-2, "col {:?}", sol.col);
+    }
+}
+
+#[test]
+fn reports_non_convergence_when_budget_is_too_small() {
+    let sol = solve_zero_sum(&A_MP, [0.9, 0.1], [0.1, 0.9], 0.2, 0.01, 1e-9, 10);
+    assert!(!sol.converged);
+    assert_eq!(sol.iters, 10);
+    assert!(sol.l1.is_finite() && sol.l1 >= 1e-9);
+}
@@ -0,0 +1,151 @@
+// tests/idle.rs
+use game_balance::genres::idle::*;
+use game_balance::systems::{offline_accumulation as off, production_spend as ps};
+
+/* ──────────────────────────────────────────────────────────────────────────
+Daily income — online rate plus retained AFK income
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn daily_income_combines_online_and_afk() {
+    let online = ps::Params { gen_per_sec: 10.0, spend_rate: 9.0, multiplier: 2.0 };
+    let offline = off::Params { cap_minutes: 600.0, decay: 0.0, efficiency: 0.5 };
+
+    // 20/s → 1_200/min; 60 min online + 600 min AFK at 50% retain.
+    let total = daily_income(&online, &offline, 60.0, 600.0);
+    assert!((total - (72_000.0 + 360_000.0)).abs() < 1e-6, "total = {total}");
+
+    let no_afk = daily_income(&online, &offline, 60.0, 0.0);
+    assert!((no_afk - 72_000.0).abs() < 1e-6, "no_afk = {no_afk}");
+}
+
+#[test]
+fn daily_income_stops_at_offline_cap() {
+    let online = ps::Params { gen_per_sec: 10.0, spend_rate: 9.0, multiplier: 2.0 };
+    let offline = off::Params { cap_minutes: 120.0, decay: 0.0, efficiency: 0.5 };
+
+    // 600 min away, but only the first 120 min accrue at 50%.
+    let capped = daily_income(&online, &offline, 0.0, 600.0);
+    assert!((capped - 1_200.0 * 60.0).abs() < 1e-6, "capped = {capped}");
+    assert_eq!(capped, daily_income(&online, &offline, 0.0, 120.0));
+}
+
+#[test]
+fn daily_income_integrates_decaying_retain() {
+    let online = ps::Params { gen_per_sec: 1.0, spend_rate: 1.0, multiplier: 1.0 };
+    let offline = off::Params { cap_minutes: 480.0, decay: 0.5, efficiency: 0.8 };
+
+    // Midpoint-rule integral of retain(t) over the span.
+    let n = 10_000;
+    let dt = 300.0 / n as f64;
+    let riemann: f64 = (0..n).map(|i| off::retain(&offline, (i as f64 + 0.5) * dt) * dt).sum();
+    let total = daily_income(&online, &offline, 0.0, 300.0);
+    assert!((total - 60.0 * riemann).abs() < 1e-3, "total = {total}, riemann = {}", 60.0 * riemann);
+
+    // Between span · retain(end) (the old shortcut) and span · retain(0).
+    assert!(total > 60.0 * 300.0 * off::retain(&offline, 300.0));
+    assert!(total < 60.0 * 300.0 * off::retain(&offline, 0.0));
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Difficulty dial — endpoints and monotone interpolation
+────────────────────────────────────────────────────────────────────────── */
+
+fn target_fields(t: &IdleGenreTargets) -> [f64; 10] {
+    [
+        t.ttu_target_secs,
+        t.util_target,
+        t.growth_target,
+        t.ttu_band_per_level.0,
+        t.ttu_band_per_level.1,
+        t.ttu_slope_pref,
+        t.prestige_cycle_minutes,
+        t.prestige_growth,
+        t.offline_retain_ratio,
+        t.typical_afk_minutes,
+    ]
+}
+
+#[test]
+fn from_difficulty_matches_endpoints() {
+    assert_eq!(target_fields(&IdleGenreTargets::from_difficulty(0.0)), target_fields(&IdleGenreTargets::easy()));
+    assert_eq!(target_fields(&IdleGenreTargets::from_difficulty(1.0)), target_fields(&IdleGenreTargets::hard()));
+}
+
+#[test]
+fn from_difficulty_interpolates_monotonically() {
+    let (easy, hard) = (target_fields(&IdleGenreTargets::easy()), target_fields(&IdleGenreTargets::hard()));
+    let mut prev = easy;
+    for i in 1..=10 {
+        let cur = target_fields(&IdleGenreTargets::from_difficulty(i as f64 / 10.0));
+        for f in 0..cur.len() {
+            // every field moves from its easy value toward its hard value
+            let dir = (hard[f] - easy[f]).signum();
+            assert!((cur[f] - prev[f]) * dir >= 0.0, "field {f} went backwards at d = {}", i as f64 / 10.0);
+            assert!(cur[f].min(prev[f]) >= easy[f].min(hard[f]) - 1e-12);
+            assert!(cur[f].max(prev[f]) <= easy[f].max(hard[f]) + 1e-12);
+        }
+        prev = cur;
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+DOT export — every system node and signal edge is present
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn to_dot_lists_systems_and_signal_edges() {
+    let out = balance_idle_genre(
+        ps::Env {
+            upgrade_cost_base: 10.0,
+            upgrade_cost_growth: 1.15,
+            gain_per_level: 0.05,
+            leak: 0.02,
+            storage_cap: 100_000.0,
+        },
+        game_balance::systems::upgrade_cost_curve::Env { levels: 10, gain_per_level: 0.05 },
+        game_balance::systems::reset_prestige::Env { session_goal_minutes: 20.0 },
+        (),
+        IdleGenreTargets::from_difficulty(0.5),
+        IdleGenreConfig { max_iters_per_system: 2_000, outer_iters: 1 },
+        IdleGenreHooks::default(),
+    );
+    let dot = out.to_dot();
+
+    assert!(dot.starts_with("digraph idle_genre {"));
+    for id in ["core", "curve", "prestige", "offline"] {
+        assert!(dot.contains(&format!("  {id} [label=")), "missing node {id}:\n{dot}");
+    }
+    assert!(dot.contains("core -> curve"));
+    assert!(dot.contains("core -> prestige"));
+    assert!(dot.trim_end().ends_with('}'));
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Genre score — sum of per-system normalized errors
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn genre_normalized_error_sums_systems() {
+    let tgt = IdleGenreTargets::from_difficulty(0.5);
+    let out = balance_idle_genre(
+        ps::Env {
+            upgrade_cost_base: 10.0,
+            upgrade_cost_growth: 1.15,
+            gain_per_level: 0.05,
+            leak: 0.02,
+            storage_cap: 100_000.0,
+        },
+        game_balance::systems::upgrade_cost_curve::Env { levels: 10, gain_per_level: 0.05 },
+        game_balance::systems::reset_prestige::Env { session_goal_minutes: 20.0 },
+        (),
+        tgt,
+        IdleGenreConfig { max_iters_per_system: 2_000, outer_iters: 1 },
+        IdleGenreHooks::default(),
+    );
+    let score = out.normalized_error(&tgt);
+    let offline = off::normalized_error(&out.offline.obs, &off::Targets { retain_ratio: tgt.offline_retain_ratio });
+
+    assert!(score.is_finite() && score >= 0.0);
+    assert!(score >= offline);
+}
@@ -0,0 +1,100 @@
+// tests/loot_table.rs
+use game_balance::systems::loot_table as loot;
+use game_balance::systems::sdk::Hook;
+
+fn env() -> loot::Env {
+    // items 0–1 common, 2 rare, 3 legendary
+    loot::Env { kills_per_hour: 120.0, item_class: vec![0, 0, 1, 2] }
+}
+
+fn targets() -> loot::Targets {
+    loot::Targets { class_hours: vec![0.5, 4.0, 20.0] }
+}
+
+fn theta0() -> loot::Params {
+    loot::Params { drop_chance: vec![0.1; 4] }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Acquisition math — geometric kills-to-drop
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn acquisition_distribution_is_geometric() {
+    // 1% per kill at 100 kills/h: the median is ln 2 / −ln 0.99 kills.
+    let median = loot::hours_at_quantile(0.01, 100.0, 0.5);
+    assert!((median - 2f64.ln() / -(0.99f64.ln()) / 100.0).abs() < 1e-12);
+    assert!((loot::acquire_cdf(0.01, 100.0, median) - 0.5).abs() < 1e-12);
+    assert_eq!(loot::hours_at_quantile(1.0, 100.0, 0.999), 0.01, "a guaranteed drop takes one kill");
+
+    let curve = loot::acquisition_curve(0.01, 100.0, &[0.0, 1.0, 5.0, 50.0]);
+    assert_eq!(curve[0], 0.0);
+    assert!(curve.windows(2).all(|w| w[0] < w[1]));
+    assert!(curve[3] > 0.99);
+}
+
+#[test]
+fn quantile_hours_stay_finite_as_q_approaches_one() {
+    let qs = [0.9, 0.99, 0.999, 0.999_999, 1.0 - 1e-12, 1.0];
+    let hours: Vec<f64> = qs.iter().map(|&q| loot::hours_at_quantile(0.01, 100.0, q)).collect();
+    assert!(hours.iter().all(|h| h.is_finite()), "{hours:?}");
+    assert!(hours.windows(2).all(|w| w[0] <= w[1]), "{hours:?}");
+    // q = 1 clamps to the last representable tail rather than running off.
+    assert_eq!(hours[4], hours[5]);
+    assert!(hours[5] > 20.0, "{hours:?}");
+    for (&q, &h) in qs.iter().zip(&hours).take(4) {
+        assert!((loot::acquire_cdf(0.01, 100.0, h) - q).abs() < 1e-9, "q = {q}");
+    }
+    // Never less than one kill, however low the quantile.
+    assert_eq!(loot::hours_at_quantile(0.5, 100.0, 0.0), 0.01);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Balancing — class targets and the worst-case tail
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn drop_chances_hit_class_hours() {
+    let out = loot::Runner::new(theta0(), env(), targets()).run();
+    assert!(out.converged, "{}", out.explain());
+
+    let hours = [0.5, 0.5, 4.0, 20.0];
+    for (i, (&h, &want)) in out.obs.mean_hours.iter().zip(&hours).enumerate() {
+        assert!((h / want - 1.0).abs() < 0.05, "item{i}: {h:.3}h vs {want}h");
+    }
+    assert!((out.theta.drop_chance[3] - 1.0 / (120.0 * 20.0)).abs() / out.theta.drop_chance[3] < 0.05);
+    assert_eq!(out.residuals.len(), 4);
+    assert!(out.residual("item3").is_some());
+
+    // The unlucky 1% of players grind ~4.6× the mean for the legendary.
+    let p99 = out.obs.worst_case(3, 3).unwrap();
+    assert_eq!(loot::QUANTILES[3], 0.99);
+    assert!(p99 > 4.0 * out.obs.mean_hours[3] && p99 < 5.0 * out.obs.mean_hours[3], "p99 = {p99}");
+    assert!(out.obs.quantile_hours[3].windows(2).all(|w| w[0] < w[1]));
+}
+
+struct DoubleDrops;
+impl Hook<loot::Params, loot::Env, loot::Targets, loot::Obs> for DoubleDrops {
+    fn income_multiplier(&mut self, _base: f64, _th: &loot::Params, _env: &loot::Env) -> f64 {
+        2.0
+    }
+}
+
+#[test]
+fn kill_rate_hooks_halve_the_chances() {
+    let base = loot::Runner::new(theta0(), env(), targets()).run();
+    let boosted = loot::Runner::new(theta0(), env(), targets()).hook(DoubleDrops).run();
+    assert!(boosted.converged);
+    assert_eq!(boosted.obs.kills_per_hour, 240.0);
+    for (a, b) in base.theta.drop_chance.iter().zip(&boosted.theta.drop_chance) {
+        assert!((b / a - 0.5).abs() < 0.06, "{b} vs {a}");
+    }
+}
+
+#[test]
+fn try_run_rejects_nonpositive_inputs() {
+    let bad_env = loot::Env { kills_per_hour: 0.0, ..env() };
+    let bad_tgt = loot::Targets { class_hours: vec![0.5, -1.0, 20.0] };
+    let err = loot::Runner::new(theta0(), bad_env, bad_tgt).try_run().unwrap_err().to_string();
+    assert!(err.contains("kills_per_hour") && err.contains("class_hours"), "{err}");
+}
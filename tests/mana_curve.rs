@@ -0,0 +1,105 @@
+// tests/mana_curve.rs
+use game_balance::mechanics::combinatorics::choose;
+use game_balance::systems::mana_curve::{self as mana, SimModel};
+
+fn env() -> mana::Env {
+    mana::Env { deck_size: 30.0, opening_hand: 3, draw_first_turn: true, curve_turns: 4 }
+}
+
+fn targets() -> mana::Targets {
+    mana::Targets { curve_out_band: (0.2, 0.25), max_dead: 0.6 }
+}
+
+fn theta0() -> mana::Params {
+    mana::Params { counts: vec![4.0, 5.0, 5.0, 4.0, 4.0, 4.0, 4.0] }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Draw math — exact odds on small decks
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn curve_out_matches_enumeration_on_a_small_deck() {
+    // 8 cards (2×1, 1×2, 2×3, 3 dead), one-card hand plus a turn-1 draw:
+    // 1/12 of the orderings put a 1, 2 and 3 in the first 2, 3 and 4 cards.
+    let env = mana::Env { deck_size: 8.0, opening_hand: 1, draw_first_turn: true, curve_turns: 3 };
+    assert_eq!(env.seen_by(3), 4);
+    let p = mana::curve_out(&[2.0, 1.0, 2.0, 3.0], &env);
+    assert!((p - 1.0 / 12.0).abs() < 1e-12, "{p}");
+}
+
+#[test]
+fn draw_math_handles_empty_costs_and_whole_deck_draws() {
+    let env = mana::Env { deck_size: 8.0, opening_hand: 1, draw_first_turn: true, curve_turns: 3 };
+
+    // No card at cost 2: curving out is impossible, and half the deck is
+    // dead until turn 3.
+    assert!(mana::curve_out(&[4.0, 0.0, 4.0], &env).abs() < 1e-12);
+    assert!(mana::curve_out(&[0.0, 0.0, 0.0], &env).abs() < 1e-12, "an empty deck never curves out");
+    assert!((mana::dead_rate(&[4.0, 0.0, 4.0], &env) - 1.0 / 3.0).abs() < 1e-12);
+
+    // Seeing the whole deck by the last turn: every present cost is found.
+    let whole = mana::Env { deck_size: 4.0, opening_hand: 2, draw_first_turn: false, curve_turns: 3 };
+    assert_eq!(whole.seen_by(3), 4);
+    let p = mana::curve_out(&[1.0, 1.0, 1.0, 1.0], &whole);
+    // Only turn 1 (two cards of four) and turn 2 (three) can miss; both
+    // miss when the 2 is last and the 1 third.
+    let miss1 = choose(3, 2) / choose(4, 2);
+    let miss2 = choose(3, 3) / choose(4, 3);
+    let both = 1.0 / 12.0;
+    assert!((p - (1.0 - miss1 - miss2 + both)).abs() < 1e-12, "{p}");
+
+    // All cards at cost 1 with one curve turn: always on curve, never dead.
+    let one = mana::Env { curve_turns: 1, ..whole };
+    assert_eq!(mana::curve_out(&[4.0], &one), 1.0);
+    assert_eq!(mana::dead_rate(&[4.0], &one), 0.0);
+}
+
+#[test]
+fn observation_reports_per_turn_odds_and_dead_cards() {
+    let o = mana::StandardModel.observe(&theta0(), &env(), &targets(), &mut []);
+    assert_eq!(o.on_curve.len(), 4);
+    let turn1 = 1.0 - choose(26, 4) / choose(30, 4);
+    assert!((o.on_curve[0] - turn1).abs() < 1e-12, "{o:?}");
+    assert!((o.whiff[0] - (1.0 - turn1)).abs() < 1e-12, "{o:?}");
+    assert!(o.whiff.windows(2).all(|w| w[1] < w[0]), "{o:?}");
+    // 26, 21, 16 and 12 of 30 cards are uncastable on turns 1..=4.
+    assert!((o.dead_rate - 75.0 / 120.0).abs() < 1e-12, "{o:?}");
+    assert!((o.curve_out - 0.0844).abs() < 0.001, "{o:?}");
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Balance — curve-out band, dead-card cap
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn low_costs_grow_until_the_deck_curves_out() {
+    let out = mana::Runner::new(theta0(), env(), targets()).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!((0.2..=0.25).contains(&out.obs.curve_out), "{:?}", out.obs);
+    assert!((out.obs.deck_total - 30.0).abs() < 0.5, "{:?}", out.obs);
+    let c = &out.theta.counts;
+    // shape within each half of the curve is kept
+    assert!((c[1] / c[0] - 1.25).abs() < 0.02, "{c:?}");
+    assert!((c[6] / c[4] - 1.0).abs() < 0.02, "{c:?}");
+    assert!(c[..4].iter().sum::<f64>() > 18.0, "{c:?}");
+    assert_eq!(out.residuals.len(), 3);
+}
+
+#[test]
+fn dead_card_cap_pushes_past_the_band_midpoint() {
+    let tgt = mana::Targets { curve_out_band: (0.2, 0.35), max_dead: 0.4 };
+    let out = mana::Runner::new(theta0(), env(), tgt).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!(out.obs.dead_rate <= 0.405, "{:?}", out.obs);
+    assert!(out.obs.curve_out > 0.3, "{:?}", out.obs);
+}
+
+#[test]
+fn try_run_rejects_an_inverted_band() {
+    let tgt = mana::Targets { curve_out_band: (0.3, 0.2), max_dead: 1.5 };
+    let err = mana::Runner::new(theta0(), env(), tgt).try_run().unwrap_err();
+    let game_balance::Error::Invalid(problems) = err else { panic!("expected Invalid") };
+    let fields: Vec<_> = problems.iter().map(|p| p.field).collect();
+    assert_eq!(fields, ["curve_out_band", "max_dead"]);
+}
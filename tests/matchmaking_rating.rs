@@ -0,0 +1,66 @@
+// tests/matchmaking_rating.rs
+use game_balance::systems::matchmaking_rating as mmr;
+
+fn env() -> mmr::Env {
+    mmr::Env { start_gap: 300.0, placement_games: 10.0, window_games: 100.0, skill_min: 600.0 }
+}
+
+fn targets() -> mmr::Targets {
+    mmr::Targets { settle_rating: 100.0, settle_games: 20.0, winrate_lo: 0.45, winrate_hi: 0.55 }
+}
+
+fn theta0() -> mmr::Params {
+    mmr::Params { k_factor: 32.0, placement_boost: 1.0, rating_floor: 0.0 }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Logistic model — Elo closed forms
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn logistic_model_matches_elo() {
+    assert_eq!(mmr::win_chance(0.0), 0.5);
+    assert!((mmr::win_chance(-400.0) - 10.0 / 11.0).abs() < 1e-12, "400 under-rated wins 10:1");
+
+    // Higher K: noisier ratings but tighter windowed winrates.
+    assert!(mmr::rating_noise(64.0) > mmr::rating_noise(16.0));
+    assert!(mmr::winrate_sd(64.0, 100.0) < mmr::winrate_sd(16.0, 100.0));
+
+    // The RMS error shrinks from the starting gap, then levels off at the noise.
+    let th = mmr::Params { k_factor: 32.0, placement_boost: 1.0, rating_floor: 0.0 };
+    let path = mmr::rms_error_path(&th, &env(), 400);
+    assert!(path[0] < 300.0 && path[50] < path[10]);
+    assert!((path[399] - mmr::rating_noise(32.0)).abs() < 5.0, "{} vs {}", path[399], mmr::rating_noise(32.0));
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Balance — settle in 20 games, ladder winrates within 45–55%
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn k_boost_and_floor_hit_ladder_targets() {
+    let out = mmr::Runner::new(theta0(), env(), targets()).max_iters(5_000).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!(out.obs.games_to_settle <= 20.5, "{:?}", out.obs);
+    assert!(out.obs.winrate_lo >= 0.445 && out.obs.winrate_hi <= 0.555, "{:?}", out.obs);
+    assert!(out.theta.placement_boost > 1.0, "placement needs a boost: {:?}", out.theta);
+    // The floor sits where a 600-skill player still wins 45%: 600 + 400·log10(0.55/0.45).
+    assert!((out.theta.rating_floor - (600.0 + 400.0 * (0.55f64 / 0.45).log10())).abs() < 10.0, "{:?}", out.theta);
+    assert_eq!(out.residuals.len(), 4);
+}
+
+#[test]
+fn short_windows_need_a_larger_k() {
+    let long = mmr::Runner::new(theta0(), env(), targets()).max_iters(5_000).run();
+    let short = mmr::Runner::new(theta0(), mmr::Env { window_games: 50.0, ..env() }, targets()).max_iters(5_000).run();
+    assert!(short.theta.k_factor > long.theta.k_factor, "{:?} vs {:?}", short.theta, long.theta);
+}
+
+#[test]
+fn try_run_rejects_an_inverted_band() {
+    let tgt = mmr::Targets { winrate_lo: 0.55, winrate_hi: 0.45, ..targets() };
+    let err = mmr::Runner::new(theta0(), env(), tgt).try_run().unwrap_err();
+    let game_balance::Error::Invalid(problems) = err else { panic!("expected Invalid") };
+    let fields: Vec<_> = problems.iter().map(|p| p.field).collect();
+    assert_eq!(fields, ["winrate_lo", "winrate_hi"]);
+}
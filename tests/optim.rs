@@ -0,0 +1,134 @@
+// tests/optim.rs
+use game_balance::optim::{EsConfig, refine_es};
+
+/* ──────────────────────────────────────────────────────────────────────────
+1) Evolution strategy — step-function loss, band predicate
+────────────────────────────────────────────────────────────────────────── */
+
+/// Prices snap to 0.25 steps in the shop, so π is piecewise constant in θ:
+/// zero gradient almost everywhere.
+fn snapped(th: &[f64]) -> Vec<f64> {
+    th.iter().map(|x| (x * 4.0).round() / 4.0).collect()
+}
+
+#[test]
+fn es_reaches_band_on_non_smooth_loss() {
+    let target = [3.0, -1.5, 7.25, 0.5];
+    let loss = |pi: &Vec<f64>| pi.iter().zip(&target).map(|(p, t)| (p - t).abs()).sum::<f64>();
+    let in_band = |pi: &Vec<f64>| pi.iter().zip(&target).all(|(p, t)| (p - t).abs() < 0.3);
+    let cfg = EsConfig { seed: 5, ..EsConfig::default() };
+
+    let out = refine_es(&[0.0; 4], snapped, |d: &Vec<f64>| d.clone(), loss, in_band, &cfg);
+    assert!(out.done, "not in band after {} generations: {out:?}", out.generations);
+    assert!(out.generations < cfg.generations);
+    assert!(in_band(&snapped(&out.theta)));
+
+    let again = refine_es(&[0.0; 4], snapped, |d: &Vec<f64>| d.clone(), loss, in_band, &cfg);
+    assert_eq!(again, out, "seeded replay");
+}
+
+#[test]
+fn es_minimizes_without_done() {
+    // Shifted sphere; `done` never fires, so the full budget runs.
+    let cfg = EsConfig { generations: 150, seed: 1, ..EsConfig::default() };
+    let out = refine_es(
+        &[5.0, 5.0, 5.0],
+        |th: &[f64]| th.to_vec(),
+        |d: &Vec<f64>| d.iter().map(|x| (x - 1.0).powi(2)).sum::<f64>(),
+        |l: &f64| *l,
+        |_l: &f64| false,
+        &cfg,
+    );
+    assert!(!out.done);
+    assert_eq!(out.generations, 150);
+    assert!(out.loss < 1e-6, "loss {}", out.loss);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+2) Nelder–Mead — simulate + residual only, inside bounds
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn nelder_mead_fits_production_rates() {
+    use game_balance::optim::{NelderMeadConfig, refine_nelder_mead};
+
+    // θ = (gen/s, spend/s); simulate an hour of play → (net banked, upgrades bought).
+    let simulate = |th: &[f64]| {
+        let banked = 3_600.0 * (th[0] - th[1]);
+        let bought = 3_600.0 * th[1] / 50.0;
+        (banked, bought)
+    };
+    let residual = |o: &(f64, f64)| ((o.0 - 7_200.0) / 7_200.0).powi(2) + ((o.1 - 576.0) / 576.0).powi(2);
+    let bounds = [(0.1, 50.0), (0.1, 50.0)];
+
+    let out = refine_nelder_mead(&[5.0, 1.0], &bounds, simulate, residual, &NelderMeadConfig::default());
+    assert!(out.done);
+    assert!(out.loss < 1e-9, "loss {}", out.loss);
+    assert!((out.theta[0] - 10.0).abs() < 1e-3 && (out.theta[1] - 8.0).abs() < 1e-3, "{:?}", out.theta);
+
+    // Target needs spend above the cap: the answer sits on the bound.
+    let tight = [(0.1, 50.0), (0.1, 5.0)];
+    let out = refine_nelder_mead(&[5.0, 1.0], &tight, simulate, residual, &NelderMeadConfig::default());
+    assert!((out.theta[1] - 5.0).abs() < 1e-4, "{:?}", out.theta);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+3) Bayesian optimization — strict simulation budget
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn bayes_finds_optimum_within_budget() {
+    use game_balance::optim::{BayesConfig, refine_bayes};
+    use std::cell::Cell;
+
+    // "Headless run": win rate as a smooth bump in (hp, dmg); target 0.5.
+    let sims = Cell::new(0usize);
+    let simulate = |th: &[f64]| {
+        sims.set(sims.get() + 1);
+        let (hp, dmg) = (th[0], th[1]);
+        1.0 / (1.0 + (-(0.02 * (hp - 400.0) + 0.1 * (dmg - 40.0))).exp())
+    };
+    let loss = |win: &f64| (win - 0.5).powi(2);
+    let bounds = [(100.0, 1_000.0), (10.0, 100.0)];
+    let cfg = BayesConfig { budget: 40, seed: 4, ..BayesConfig::default() };
+
+    let out = refine_bayes(&[900.0, 90.0], &bounds, simulate, |w: &f64| *w, loss, &cfg);
+    assert!(sims.get() <= 40, "{} sims", sims.get());
+    assert_eq!(out.generations, sims.get());
+    assert!(out.loss < 1e-4, "loss {} at {:?}", out.loss, out.theta);
+    assert!(bounds.iter().zip(&out.theta).all(|((lo, hi), t)| (lo..=hi).contains(&t)));
+}
+
+#[test]
+fn bayes_skips_non_finite_losses() {
+    use game_balance::optim::{BayesConfig, refine_bayes};
+
+    // θ₀ "crashes" (NaN) and a strip overflows (inf); neither may poison
+    // the surrogate. The optimum sits in the finite part at x = 0.7.
+    let loss = |x: &f64| {
+        if *x < 0.15 {
+            f64::NAN
+        } else if *x > 0.9 {
+            f64::INFINITY
+        } else {
+            (x - 0.7).powi(2)
+        }
+    };
+    let cfg = BayesConfig { budget: 30, seed: 2, ..BayesConfig::default() };
+    let out = refine_bayes(&[0.1], &[(0.0, 1.0)], |th: &[f64]| th[0], |x: &f64| *x, loss, &cfg);
+    assert!(out.loss.is_finite(), "best loss {}", out.loss);
+    assert!(out.loss < 1e-3, "loss {} at {:?}", out.loss, out.theta);
+}
+
+#[test]
+fn bayes_handles_clamped_duplicates() {
+    use game_balance::optim::{BayesConfig, refine_bayes};
+
+    // Optimum at the corner: local candidates clamp onto the same boundary
+    // point over and over, and the plateau makes many losses identical.
+    let loss = |th: &Vec<f64>| 2.0 - th[0].min(0.95) - th[1].min(0.95);
+    let cfg = BayesConfig { budget: 60, candidates: 200, seed: 9, ..BayesConfig::default() };
+    let out = refine_bayes(&[1.0, 1.0], &[(0.0, 1.0), (0.0, 1.0)], |th: &[f64]| th.to_vec(), |v: &Vec<f64>| v.clone(), loss, &cfg);
+    assert_eq!(out.generations, 60);
+    assert!((out.loss - 0.1).abs() < 1e-9, "loss {}", out.loss);
+}
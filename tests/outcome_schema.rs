@@ -0,0 +1,74 @@
+// tests/outcome_schema.rs
+use game_balance::systems::production_spend as ps;
+use game_balance::systems::sdk::{Oscillation, Outcome, Residual, OUTCOME_SCHEMA_VERSION};
+use game_balance::Error;
+
+type PsOutcome = Outcome<ps::Params, ps::Obs>;
+
+/// Written by the crate before `schema_version` / `stable_after_converge`. Do not edit.
+const PINNED_V1: &str = r#"{
+    "theta": { "gen_per_sec": 12.5, "spend_rate": 11.25, "multiplier": 1.8 },
+    "obs": { "ttu": 30.2, "util": 0.9, "growth": 5.01, "surplus": 1.25 },
+    "iters": 418,
+    "converged": true
+}"#;
+
+/* ──────────────────────────────────────────────────────────────────────────
+Migration — pinned v1 payload loads with defaults for new fields
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn pinned_v1_outcome_migrates() {
+    let out = PsOutcome::migrate(PINNED_V1).expect("v1 migrates");
+    assert_eq!(out.theta.gen_per_sec, 12.5);
+    assert_eq!(out.theta.spend_rate, 11.25);
+    assert_eq!(out.obs.ttu, 30.2);
+    assert_eq!(out.iters, 418);
+    assert!(out.converged);
+    assert!(!out.stable_after_converge, "v1 never ran a hold phase");
+    assert!(out.trace.is_empty());
+    assert!(out.oscillation.is_none());
+    assert!(!out.budget_exhausted);
+    assert!(out.score.is_none());
+    assert!(out.residuals.is_empty());
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Round trip — current version survives to_json → migrate
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn current_outcome_round_trips() {
+    let out = PsOutcome::migrate(PINNED_V1).unwrap();
+    let trace = vec![ps::Obs { ttu: 31.0, ..out.obs }, out.obs];
+    let oscillation = Some(Oscillation { period: 2, amplitude: 0.5, coord: 1 });
+    let residuals = vec![Residual::new("util", 0.85, 0.9)];
+    let out = PsOutcome { stable_after_converge: true, trace, oscillation, budget_exhausted: true, residuals, ..out };
+    let json = out.to_json().unwrap();
+    assert!(json.contains(&format!("\"schema_version\":{OUTCOME_SCHEMA_VERSION}")));
+
+    let back = PsOutcome::migrate(&json).unwrap();
+    assert_eq!(back.theta.multiplier, out.theta.multiplier);
+    assert_eq!(back.obs.surplus, out.obs.surplus);
+    assert!(back.stable_after_converge);
+    assert_eq!(back.trace.len(), 2);
+    assert_eq!(back.trace[0].ttu, 31.0);
+    assert_eq!(back.oscillation, oscillation);
+    assert!(back.budget_exhausted);
+    assert!(back.score.is_none());
+    assert_eq!(back.residuals, out.residuals);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Rejection — unknown future versions and malformed input
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn newer_schema_version_is_rejected() {
+    let json = PINNED_V1.replacen('{', "{ \"schema_version\": 99, \"stable_after_converge\": false, \"trace\": [], \"oscillation\": null, \"budget_exhausted\": false, \"score\": null, \"residuals\": [],", 1);
+    match PsOutcome::migrate(&json) {
+        Err(Error::SchemaVersion { found: 99, supported }) => assert_eq!(supported, OUTCOME_SCHEMA_VERSION),
+        other => panic!("expected SchemaVersion error, got {other:?}"),
+    }
+    assert!(matches!(PsOutcome::migrate("[1, 2]"), Err(Error::Json(_))));
+}
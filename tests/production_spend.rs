@@ -0,0 +1,652 @@
+// tests/production_spend.rs
+use game_balance::systems::production_spend as ps;
+use game_balance::mechanics::control::Controller;
+use game_balance::systems::sdk::{Regularization, UpdateOrder};
+
+fn env() -> ps::Env {
+    ps::Env {
+        upgrade_cost_base: 10.0,
+        upgrade_cost_growth: 1.15,
+        gain_per_level: 0.05,
+        leak: 0.02,
+        storage_cap: 100_000.0,
+    }
+}
+
+fn targets() -> ps::Targets {
+    ps::Targets {
+        ttu_target: 30.0,
+        util_target: 0.90,
+        growth_target: 5.0,
+        weights: ps::TargetWeights::default(),
+    }
+}
+
+fn dist(a: &ps::Params, b: &ps::Params) -> f64 {
+    let rel = |x: f64, y: f64| (x - y).abs() / y.abs().max(1e-9);
+    rel(a.gen_per_sec, b.gen_per_sec) + rel(a.spend_rate, b.spend_rate) + rel(a.multiplier, b.multiplier)
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Regularization — retunes stay closer to the shipped baseline
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn regularized_retune_stays_closer_to_baseline() {
+    let baseline = ps::Params {
+        gen_per_sec: 10.0,
+        spend_rate: 10.0,
+        multiplier: 1.0,
+    };
+    let run = |reg| {
+        ps::balance_ext(baseline, env(), targets(), ps::Options { max_iters: 2_000, reg, ..Default::default() })
+    };
+
+    let free = run(None);
+    let regd = run(Some(Regularization { baseline, lambda: 2.0 }));
+
+    assert!(
+        dist(&regd.theta, &baseline) < dist(&free.theta, &baseline),
+        "regularized {:?} not closer than free {:?}",
+        regd.theta,
+        free.theta
+    );
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Bounds::new — rejects inverted and non-finite ranges
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn bounds_new_validates_ranges() {
+    assert!(ps::Bounds::new(0.01, 1e6, 0.0, 1e9, 0.1, 1e6).is_ok());
+
+    let err = ps::Bounds::new(5.0, 1.0, 0.0, 1e9, 0.1, 1e6).unwrap_err();
+    assert_eq!(
+        err,
+        game_balance::Error::InvalidBounds { field: "gen", min: 5.0, max: 1.0 }
+    );
+
+    assert!(ps::Bounds::new(0.01, 1e6, 0.0, f64::NAN, 0.1, 1e6).is_err());
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Controller backends — each one drives production_spend into its band
+────────────────────────────────────────────────────────────────────────── */
+
+fn run_with(controller: Controller) -> game_balance::systems::sdk::Outcome<ps::Params, ps::Obs> {
+    ps::balance_ext(
+        ps::Params { gen_per_sec: 10.0, spend_rate: 10.0, multiplier: 1.0 },
+        env(),
+        targets(),
+        ps::Options { max_iters: 20_000, controller, ..Default::default() },
+    )
+}
+
+fn assert_in_band(name: &str, out: &game_balance::systems::sdk::Outcome<ps::Params, ps::Obs>) {
+    assert!(out.converged, "{name}: not converged after {} iters: {:?}", out.iters, out.obs);
+    assert!((out.obs.util - 0.90).abs() <= 0.01, "{name}: util {}", out.obs.util);
+}
+
+#[test]
+fn proportional_backend_converges() {
+    assert_in_band("proportional", &run_with(Controller::Proportional { k: 1.0 }));
+}
+
+#[test]
+fn pid_backend_converges() {
+    assert_in_band("pid", &run_with(Controller::Pid { kp: 1.0, ki: 0.05, kd: 0.0 }));
+}
+
+#[test]
+fn momentum_backend_converges() {
+    assert_in_band("momentum", &run_with(Controller::Momentum { k: 0.5, beta: 0.3 }));
+}
+
+#[test]
+fn adaptive_gain_backend_converges() {
+    assert_in_band(
+        "adaptive",
+        &run_with(Controller::AdaptiveGain { k: 0.5, up: 1.1, down: 0.5, k_max: 1.5 }),
+    );
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Update ordering — Gauss–Seidel converges too, in no more iterations
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn gauss_seidel_order_converges() {
+    let run = |order| {
+        ps::balance_ext(
+            ps::Params { gen_per_sec: 10.0, spend_rate: 10.0, multiplier: 1.0 },
+            env(),
+            targets(),
+            ps::Options { max_iters: 20_000, order, ..Default::default() },
+        )
+    };
+    let jacobi = run(UpdateOrder::Jacobi);
+    let gs = run(UpdateOrder::GaussSeidel);
+    assert!(jacobi.converged && gs.converged, "jacobi {:?} / gs {:?}", jacobi.obs, gs.obs);
+    assert!(gs.iters <= jacobi.iters, "gs {} vs jacobi {}", gs.iters, jacobi.iters);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Normalized error — unitless, zero on target, small once converged
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn normalized_error_is_unitless() {
+    let tgt = targets();
+    let on = ps::Obs { ttu: 30.0, util: 0.90, growth: 5.0, surplus: 0.0, ..Default::default() };
+    assert_eq!(ps::normalized_error(&on, &tgt), 0.0);
+
+    // 10% off on every axis → 0.10, regardless of seconds vs ratio vs multiplier.
+    let off = ps::Obs { ttu: 33.0, util: 0.99, growth: 5.5, surplus: 0.0, ..Default::default() };
+    assert!((ps::normalized_error(&off, &tgt) - 0.10).abs() < 1e-12);
+
+    let out = run_with(Controller::default());
+    assert!(ps::normalized_error(&out.obs, &tgt) < 0.05);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Conflict diagnosis — contradictory targets are named, sane ones are not
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn consistent_targets_have_no_conflicts() {
+    assert!(ps::diagnose_conflicts(&env(), &targets()).is_empty());
+}
+
+#[test]
+fn full_utilization_conflicts_with_ttu() {
+    let tgt = ps::Targets { util_target: 1.0, ..targets() };
+    let found = ps::diagnose_conflicts(&env(), &tgt);
+    assert_eq!(found, vec![ps::Conflict::UtilVsTtu { util_target: 1.0, ttu_target: 30.0 }]);
+    assert_eq!(found[0].fields(), ("util_target", "ttu_target"));
+
+    let out = ps::balance_quick(env(), tgt);
+    assert!(!out.converged, "the loop cannot resolve it either");
+}
+
+#[test]
+fn runaway_growth_conflicts_with_ttu() {
+    // ×500 growth implies ~10k upgrade levels at 1.15× cost each.
+    let tgt = ps::Targets { growth_target: 500.0, ..targets() };
+    let found = ps::diagnose_conflicts(&env(), &tgt);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].fields(), ("growth_target", "ttu_target"));
+    assert!(found[0].to_string().contains("gen_per_sec"));
+
+    let tgt = ps::Targets { util_target: 1.5, ..targets() };
+    assert_eq!(ps::diagnose_conflicts(&env(), &tgt), vec![ps::Conflict::UtilOutOfRange { util_target: 1.5 }]);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Analytic seed — iteration counts vs the fixed {10, 10, 1} seed
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn steady_state_seed_needs_fewer_iterations() {
+    let fixed = ps::Params { gen_per_sec: 10.0, spend_rate: 10.0, multiplier: 1.0 };
+    for tgt in [
+        targets(),
+        ps::Targets { ttu_target: 10.0, util_target: 0.80, growth_target: 2.0, weights: ps::TargetWeights::default() },
+        ps::Targets { ttu_target: 60.0, util_target: 0.95, growth_target: 3.0, weights: ps::TargetWeights::default() },
+    ] {
+        let run = |theta0| {
+            ps::balance_ext(theta0, env(), tgt, ps::Options { max_iters: 120_000, ..Default::default() })
+        };
+        let from_fixed = run(fixed);
+        let from_seed = run(ps::steady_state_seed(&env(), &tgt));
+        println!("{tgt:?}: fixed seed {} iters, analytic seed {} iters", from_fixed.iters, from_seed.iters);
+
+        assert!(from_seed.converged, "{tgt:?}");
+        assert!(from_seed.iters < from_fixed.iters, "{tgt:?}: {} ≥ {}", from_seed.iters, from_fixed.iters);
+    }
+    assert!(ps::balance_quick(env(), targets()).converged);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Custom SimModel — same controller, user-supplied observation math
+────────────────────────────────────────────────────────────────────────── */
+
+/// A world with 2% growth friction (observed growth × 0.98).
+struct DampedGrowth;
+impl ps::SimModel for DampedGrowth {
+    fn observe(
+        &self,
+        th: &ps::Params,
+        env: &ps::Env,
+        tgt: &ps::Targets,
+        hooks: &mut [Box<dyn game_balance::systems::sdk::Hook<ps::Params, ps::Env, ps::Targets, ps::Obs>>],
+    ) -> ps::Obs {
+        let o = ps::StandardModel.observe(th, env, tgt, hooks);
+        ps::Obs { growth: 0.98 * o.growth, ..o }
+    }
+}
+
+#[test]
+fn custom_model_drives_the_same_controller() {
+    use ps::SimModel;
+
+    let out = ps::Runner::new(ps::steady_state_seed(&env(), &targets()), env(), targets()).model(DampedGrowth).run();
+    assert!(out.converged, "{:?}", out.obs);
+    assert!((out.obs.growth - 5.0).abs() <= 0.1, "custom model's growth on target: {}", out.obs.growth);
+
+    // The standard math on the tuned θ reports the unfrictioned (higher) growth.
+    let std_growth = ps::StandardModel.observe(&out.theta, &env(), &targets(), &mut []).growth;
+    assert!(std_growth > out.obs.growth, "{std_growth} vs {}", out.obs.growth);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Weighted least squares — conflicting targets settle at a compromise
+────────────────────────────────────────────────────────────────────────── */
+
+fn run_lsq(tgt: ps::Targets, controller: Controller, iters: usize) -> game_balance::systems::sdk::Outcome<ps::Params, ps::Obs> {
+    ps::balance_ext(
+        ps::Params { gen_per_sec: 10.0, spend_rate: 10.0, multiplier: 1.0 },
+        env(),
+        tgt,
+        ps::Options { max_iters: iters, controller, ..Default::default() },
+    )
+}
+
+#[test]
+fn least_squares_compromises_on_conflicting_targets() {
+    // util = 1 leaves no savings, so TTU 30 s is unreachable (see diagnose_conflicts).
+    let tgt = ps::Targets { util_target: 1.0, ..targets() };
+    let lsq = Controller::WeightedLeastSquares { k: 0.5 };
+
+    let independent = run_lsq(tgt, Controller::default(), 2_000);
+    let joint = run_lsq(tgt, lsq, 2_000);
+    assert!(!independent.converged && !joint.converged);
+    assert!(
+        ps::weighted_objective(&joint.obs, &tgt) < 0.01 * ps::weighted_objective(&independent.obs, &tgt),
+        "joint {:?} vs independent {:?}",
+        joint.obs,
+        independent.obs
+    );
+
+    // Settled: one more iteration barely moves θ.
+    let next = run_lsq(tgt, lsq, 2_001);
+    assert!(dist(&joint.theta, &next.theta) < 0.01, "{:?} → {:?}", joint.theta, next.theta);
+
+    // Weights pick the trade-off: favoring TTU gives up utilization, and vice versa.
+    let favor = |ttu: f64, util: f64| ps::Targets { weights: ps::TargetWeights { ttu, util, growth: 1.0 }, ..tgt };
+    let ttu_first = run_lsq(favor(10.0, 1.0), lsq, 2_000).obs;
+    let util_first = run_lsq(favor(1.0, 10.0), lsq, 2_000).obs;
+    assert!((ttu_first.ttu - 30.0).abs() < (util_first.ttu - 30.0).abs(), "{ttu_first:?} vs {util_first:?}");
+    assert!(util_first.util > ttu_first.util, "{ttu_first:?} vs {util_first:?}");
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Projection — coupled constraint holds on every step
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn project_hook_keeps_spend_within_income() {
+    use game_balance::systems::sdk::{Hook, ProjectHook};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    // spend_rate ≤ 0.5 · gen_per_sec · multiplier, checked after every step.
+    let feasible = |th: &ps::Params| th.spend_rate <= 0.5 * th.gen_per_sec * th.multiplier + 1e-12;
+    let cap = |th: ps::Params| ps::Params { spend_rate: th.spend_rate.min(0.5 * th.gen_per_sec * th.multiplier), ..th };
+
+    struct Witness(Rc<Cell<usize>>);
+    impl Hook<ps::Params, ps::Env, ps::Targets, ps::Obs> for Witness {
+        fn project(&mut self, th: &mut ps::Params, _env: &ps::Env) {
+            assert!(th.spend_rate <= 0.5 * th.gen_per_sec * th.multiplier + 1e-12, "{th:?}");
+            self.0.set(self.0.get() + 1);
+        }
+    }
+    let seen = Rc::new(Cell::new(0));
+
+    let out = ps::balance_ext(
+        ps::Params { gen_per_sec: 10.0, spend_rate: 10.0, multiplier: 1.0 },
+        env(),
+        targets(),
+        ps::Options {
+            mechs: vec![Box::new(ProjectHook(cap)), Box::new(Witness(Rc::clone(&seen)))],
+            max_iters: 2_000,
+            ..Default::default()
+        },
+    );
+    assert!(seen.get() > 0);
+    assert!(feasible(&out.theta), "{:?}", out.theta);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+post_step — hooks round and cap θ after the controller step
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn post_step_hook_rounds_and_caps_theta() {
+    use game_balance::systems::sdk::Hook;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // Designer-friendly spend (multiples of 0.25), multiplier capped at 2.
+    struct Tidy(Rc<RefCell<Vec<ps::Params>>>);
+    impl Hook<ps::Params, ps::Env, ps::Targets, ps::Obs> for Tidy {
+        fn post_step(&mut self, th: &mut ps::Params, _env: &ps::Env) {
+            th.spend_rate = (th.spend_rate * 4.0).round() / 4.0;
+            th.multiplier = th.multiplier.min(2.0);
+            self.0.borrow_mut().push(*th);
+        }
+    }
+    let seen = Rc::new(RefCell::new(Vec::new()));
+
+    let out = ps::balance_ext(
+        ps::Params { gen_per_sec: 10.0, spend_rate: 10.0, multiplier: 1.0 },
+        env(),
+        targets(),
+        ps::Options { mechs: vec![Box::new(Tidy(Rc::clone(&seen)))], max_iters: 500, ..Default::default() },
+    );
+    let seen = seen.borrow();
+    assert!(!seen.is_empty());
+    for th in seen.iter() {
+        assert_eq!(th.spend_rate * 4.0, (th.spend_rate * 4.0).round(), "{th:?}");
+        assert!(th.multiplier <= 2.0, "{th:?}");
+    }
+    assert_eq!(&out.theta.spend_rate, &seen.last().unwrap().spend_rate);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Trace — opt-in per-iteration Obs history in the Outcome
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn traced_run_records_every_iteration() {
+    use game_balance::systems::sdk::{BalanceArena, ObsTrace};
+
+    let run = |trace: ObsTrace| {
+        ps::balance_ext_in(
+            &mut BalanceArena::new().with_trace(trace),
+            ps::Params { gen_per_sec: 10.0, spend_rate: 10.0, multiplier: 1.0 },
+            env(),
+            targets(),
+            ps::Options { max_iters: 2_000, ..Default::default() },
+        )
+    };
+    let off = run(ObsTrace::Off);
+    assert!(off.trace.is_empty() && off.trace.capacity() == 0);
+
+    let full = run(ObsTrace::Full);
+    assert_eq!(full.trace.len(), full.iters);
+    assert_eq!((full.iters, full.converged), (off.iters, off.converged));
+    assert_eq!(full.trace.last().map(|o| o.ttu), Some(full.obs.ttu));
+
+    let tail = run(ObsTrace::Last(5));
+    assert_eq!(tail.trace.len(), 5.min(full.iters));
+    let from = full.trace.len() - tail.trace.len();
+    for (a, b) in tail.trace.iter().zip(&full.trace[from..]) {
+        assert_eq!((a.ttu, a.util, a.growth), (b.ttu, b.util, b.growth));
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Cost multiplier — cheaper upgrades need proportionally less income
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn cost_multiplier_hook_scales_required_income() {
+    use game_balance::systems::sdk::Hook;
+
+    // "Upgrades cost 20% less" card.
+    struct Discount;
+    impl Hook<ps::Params, ps::Env, ps::Targets, ps::Obs> for Discount {
+        fn cost_multiplier(&mut self, _th: &ps::Params, _env: &ps::Env) -> f64 {
+            0.8
+        }
+    }
+
+    let run = |hooks: Vec<Box<dyn ps::Mechanic>>| {
+        ps::balance_ext(
+            ps::Params { gen_per_sec: 10.0, spend_rate: 10.0, multiplier: 1.0 },
+            env(),
+            targets(),
+            ps::Options { mechs: hooks, max_iters: 2_000, ..Default::default() },
+        )
+    };
+    let plain = run(Vec::new());
+    let cheap = run(vec![Box::new(Discount)]);
+    assert!(plain.converged && cheap.converged, "{plain:?} / {cheap:?}");
+    assert!((cheap.obs.ttu - plain.obs.ttu).abs() < 0.05 * plain.obs.ttu, "{:?} vs {:?}", cheap.obs, plain.obs);
+
+    let income = |th: &ps::Params| th.gen_per_sec * th.multiplier;
+    let ratio = income(&cheap.theta) / income(&plain.theta);
+    assert!((ratio - 0.8).abs() < 0.05, "ratio = {ratio}");
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Signals — a fee hook scaled by the prestige cycle on the shared bus
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn fee_hook_reads_cycle_minutes_from_signals() {
+    use game_balance::systems::sdk::{BalanceArena, Hook, Signals};
+
+    // Longer prestige cycles → heavier fee: 1% of income per cycle minute.
+    struct CycleFee(f64);
+    impl Hook<ps::Params, ps::Env, ps::Targets, ps::Obs> for CycleFee {
+        fn on_signals(&mut self, signals: &mut Signals) {
+            self.0 = signals.cycle_minutes;
+        }
+        fn income_multiplier(&mut self, _base: f64, _th: &ps::Params, _env: &ps::Env) -> f64 {
+            1.0 - 0.01 * self.0
+        }
+    }
+
+    let run = |cycle_minutes: f64| {
+        let mut arena = BalanceArena::new().with_signals(Signals { ref_income: 0.0, cycle_minutes });
+        let out = ps::balance_ext_in(
+            &mut arena,
+            ps::Params { gen_per_sec: 10.0, spend_rate: 10.0, multiplier: 1.0 },
+            env(),
+            targets(),
+            ps::Options { mechs: vec![Box::new(CycleFee(0.0))], max_iters: 1, ..Default::default() },
+        );
+        assert_eq!(arena.signals().cycle_minutes, cycle_minutes);
+        out
+    };
+    let short = run(10.0);
+    let long = run(40.0);
+
+    // One iteration observes θ₀ under each fee: net income 0.9× vs 0.6× the
+    // gross, so TTU stretches by 1.5×.
+    let ratio = long.obs.ttu / short.obs.ttu;
+    assert!((ratio - 1.5).abs() < 1e-9, "ratio = {ratio}");
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Convergence score — which of TTU / util / growth a run is missing
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn convergence_score_names_the_missed_target() {
+    let tgt = targets();
+    let on = ps::Obs {
+        ttu: tgt.ttu_target,
+        util: tgt.util_target,
+        growth: tgt.growth_target,
+        ..Default::default()
+    };
+    assert_eq!(ps::convergence_score(&on, &tgt, 1e-6).total(), 0.0);
+
+    let off_util = ps::Obs { util: tgt.util_target * 0.9, ..on };
+    let s = ps::convergence_score(&off_util, &tgt, 1e-6);
+    assert!(!s.converged());
+    let worst = s.worst().unwrap();
+    assert_eq!(worst.name, "util");
+    assert!((worst.residual + 0.1).abs() < 1e-12);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Residuals — a run that stops short says which target it missed
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn unconverged_run_reports_named_residuals() {
+    let tgt = targets();
+    let out = ps::balance_ext(
+        ps::Params { gen_per_sec: 10.0, spend_rate: 5.0, multiplier: 1.0 },
+        env(),
+        tgt,
+        ps::Options { max_iters: 1, ..Default::default() },
+    );
+    assert!(!out.converged);
+    let names: Vec<_> = out.residuals.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["ttu", "util", "growth"]);
+
+    let util = out.residual("util").unwrap();
+    assert_eq!((util.obs, util.target), (out.obs.util, tgt.util_target));
+    assert!(util.miss().abs() > 0.02, "util miss {}", util.miss());
+    assert!(out.residual("retain_ratio").is_none());
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Threads — runs are independent and match the sequential results
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn systems_balance_on_worker_threads() {
+    use game_balance::systems::sdk::{Hook, Outcome};
+
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Outcome<ps::Params, ps::Obs>>();
+
+    // A Send hook stack, built on the caller's thread, runs on a worker.
+    struct Fee;
+    impl Hook<ps::Params, ps::Env, ps::Targets, ps::Obs> for Fee {
+        fn cost_multiplier(&mut self, _th: &ps::Params, _env: &ps::Env) -> f64 {
+            0.9
+        }
+    }
+    let run = |ttu: f64, mechs: Vec<Box<dyn ps::Mechanic + Send>>| {
+        ps::balance_ext(
+            ps::Params { gen_per_sec: 10.0, spend_rate: 10.0, multiplier: 1.0 },
+            env(),
+            ps::Targets { ttu_target: ttu, ..targets() },
+            ps::Options {
+                mechs: mechs.into_iter().map(|m| m as Box<dyn ps::Mechanic>).collect(),
+                max_iters: 2_000,
+                ..Default::default()
+            },
+        )
+    };
+    let ttus = [20.0, 30.0, 45.0, 60.0];
+    let parallel: Vec<_> = std::thread::scope(|s| {
+        let handles: Vec<_> = ttus
+            .iter()
+            .map(|&ttu| {
+                let mechs: Vec<Box<dyn ps::Mechanic + Send>> = vec![Box::new(Fee)];
+                s.spawn(move || run(ttu, mechs))
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    for (&ttu, par) in ttus.iter().zip(&parallel) {
+        let seq = run(ttu, vec![Box::new(Fee)]);
+        assert_eq!((par.iters, par.converged), (seq.iters, seq.converged), "ttu {ttu}");
+        assert_eq!(par.theta.gen_per_sec, seq.theta.gen_per_sec, "ttu {ttu}");
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Runner — named settings, same run as Options
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn runner_matches_options_balance_ext() {
+    use game_balance::systems::sdk::Hook;
+
+    struct Fee;
+    impl Hook<ps::Params, ps::Env, ps::Targets, ps::Obs> for Fee {
+        fn cost_multiplier(&mut self, _th: &ps::Params, _env: &ps::Env) -> f64 {
+            1.2
+        }
+    }
+    let theta0 = ps::Params { gen_per_sec: 10.0, spend_rate: 10.0, multiplier: 1.0 };
+    let gains = ps::Gains { k_ttu: 0.3, ..ps::Gains::default() };
+    let direct = ps::balance_ext(
+        theta0,
+        env(),
+        targets(),
+        ps::Options {
+            gains,
+            mechs: vec![Box::new(Fee)],
+            max_iters: 5_000,
+            order: UpdateOrder::GaussSeidel,
+            ..Default::default()
+        },
+    );
+    let built = ps::Runner::new(theta0, env(), targets())
+        .gains(gains)
+        .hook(Fee)
+        .max_iters(5_000)
+        .order(UpdateOrder::GaussSeidel)
+        .run();
+    assert_eq!(
+        (built.iters, built.converged, built.theta.gen_per_sec),
+        (direct.iters, direct.converged, direct.theta.gen_per_sec)
+    );
+
+    // Unset options are balance_quick's.
+    let quick = ps::balance_quick(env(), targets());
+    let seed = ps::steady_state_seed(&env(), &targets());
+    assert_eq!(ps::Runner::new(seed, env(), targets()).run().iters, quick.iters);
+}
+
+#[test]
+fn runner_converges_on_a_spend_to_income_ratio() {
+    use game_balance::mechanics::control;
+
+    // 30% of income spent, the rest saved toward upgrades.
+    let tgt = ps::Targets { util_target: 0.3, ..targets() };
+    let out = ps::Runner::new(ps::steady_state_seed(&env(), &tgt), env(), tgt).try_run().unwrap();
+    assert!(out.converged, "{:?}", out.obs);
+    let spend = out.obs.income - out.obs.surplus;
+    assert!(control::ratio_within(spend, out.obs.income, 0.3, 0.01), "spend {spend} of {}", out.obs.income);
+    assert!((out.obs.util - spend / out.obs.income).abs() < 1e-12);
+}
+
+#[test]
+fn runner_damping_slows_but_still_converges() {
+    let theta0 = ps::Params { gen_per_sec: 10.0, spend_rate: 10.0, multiplier: 1.0 };
+    let full = ps::Runner::new(theta0, env(), targets()).max_iters(20_000).run();
+    let half = ps::Runner::new(theta0, env(), targets()).max_iters(20_000).damping(0.5).run();
+    assert!(full.converged && half.converged, "full: {:?}, half: {:?}", full.obs, half.obs);
+    assert!(half.iters > full.iters, "damped {} vs undamped {} iterations", half.iters, full.iters);
+
+    // Same run as halving the gains by hand.
+    let by_hand = ps::Runner::new(theta0, env(), targets())
+        .max_iters(20_000)
+        .gains(ps::Gains::default().scaled(0.5))
+        .run();
+    assert_eq!(half.iters, by_hand.iters);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Validation — impossible inputs are rejected before the loop, all at once
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn try_run_lists_every_invalid_input() {
+    use game_balance::{Error, Validate};
+
+    assert!(env().validate().is_ok() && targets().validate().is_ok());
+    assert!(ps::Bounds::soft_defaults().validate().is_ok());
+
+    let tgt = ps::Targets { util_target: 1.2, ..targets() };
+    let bounds = ps::Bounds { mul_min: 5.0, mul_max: 1.0, ..ps::Bounds::soft_defaults() };
+    let theta0 = ps::Params { gen_per_sec: 10.0, spend_rate: 10.0, multiplier: 1.0 };
+    let err = ps::Runner::new(theta0, env(), tgt).bounds(bounds).try_run().unwrap_err();
+    let Error::Invalid(problems) = &err else { panic!("expected Invalid, got {err:?}") };
+    let fields: Vec<_> = problems.iter().map(|p| p.field).collect();
+    assert_eq!(fields, ["util_target", "mul"]);
+    assert!(err.to_string().contains("`util_target` must be in (0, 1]"), "{err}");
+
+    assert!(ps::Runner::new(theta0, env(), targets()).try_run().unwrap().converged);
+}
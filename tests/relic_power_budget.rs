@@ -0,0 +1,76 @@
+// tests/relic_power_budget.rs
+use game_balance::systems::relic_power_budget as relic;
+use game_balance::systems::sdk::Hook;
+
+fn env() -> relic::Env {
+    let relics = (0..3)
+        .flat_map(|tier| [0.85, 1.0, 1.15].map(|weight| relic::Relic { tier, weight }))
+        .collect();
+    relic::Env { relics, base_power: 1.0, defend_rate: 0.5, alpha: 0.5, beta: 0.4 }
+}
+
+fn targets() -> relic::Targets {
+    relic::Targets { bands: vec![(0.01, 0.03), (0.025, 0.05), (0.045, 0.08)], max_overlap: 0.01 }
+}
+
+fn theta0() -> relic::Params {
+    relic::Params { budgets: vec![1.0, 1.0, 1.0] }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Win-rate model — `wr::tanh` lift
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn delta_is_the_tanh_lift_with_diminishing_returns() {
+    let env = env();
+    assert_eq!(relic::winrate_delta(0.0, &env), 0.0);
+    let one = 0.4 * ((0.25_f64 * 2.0).tanh() - 0.25_f64.tanh());
+    assert!((relic::winrate_delta(1.0, &env) - one).abs() < 1e-12);
+    assert!(relic::winrate_delta(2.0, &env) < 2.0 * one);
+    assert!((relic::run_winrate(0.0, &env) - (0.5 + 0.4 * 0.25_f64.tanh())).abs() < 1e-12);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Balance — rarity-ordered bands
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn every_relic_lands_in_its_rarity_band() {
+    let out = relic::Runner::new(theta0(), env(), targets()).run();
+    assert!(out.converged, "{}", out.explain());
+    for (&(lo, hi), &(band_lo, band_hi)) in out.obs.tier_range.iter().zip(&targets().bands) {
+        assert!(band_lo <= lo && hi <= band_hi, "{:?}", out.obs);
+    }
+    let b = &out.theta.budgets;
+    assert!(b[0] < b[1] && b[1] < b[2], "{b:?}");
+    assert!(out.obs.overlap.iter().all(|&v| v <= 0.01), "{:?}", out.obs);
+    assert_eq!(out.residuals.len(), 9 + 2);
+}
+
+struct Amplifier;
+impl Hook<relic::Params, relic::Env, relic::Targets, relic::Obs> for Amplifier {
+    fn income_multiplier(&mut self, _base: f64, _th: &relic::Params, _env: &relic::Env) -> f64 {
+        2.0
+    }
+}
+
+#[test]
+fn power_hooks_shrink_the_budgets() {
+    let plain = relic::Runner::new(theta0(), env(), targets()).run();
+    let amped = relic::Runner::new(theta0(), env(), targets()).hook(Amplifier).run();
+    assert!(amped.converged, "{}", amped.explain());
+    assert_eq!(amped.obs.power_mult, 2.0);
+    for (a, p) in amped.theta.budgets.iter().zip(&plain.theta.budgets) {
+        assert!((a / p - 0.5).abs() < 0.02, "{:?} vs {:?}", amped.theta, plain.theta);
+    }
+}
+
+#[test]
+fn try_run_rejects_bands_out_of_rarity_order() {
+    let tgt = relic::Targets { bands: vec![(0.03, 0.05), (0.01, 0.02)], max_overlap: -0.1 };
+    let err = relic::Runner::new(theta0(), env(), tgt).try_run().unwrap_err();
+    let game_balance::Error::Invalid(problems) = err else { panic!("expected Invalid") };
+    let fields: Vec<_> = problems.iter().map(|p| p.field).collect();
+    assert_eq!(fields, ["bands", "bands", "max_overlap"]);
+}
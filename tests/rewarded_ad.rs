@@ -0,0 +1,86 @@
+// tests/rewarded_ad.rs
+use game_balance::systems::rewarded_ad as ad;
+use game_balance::systems::sdk::Hook;
+
+fn env() -> ad::Env {
+    ad::Env {
+        income_per_min: 100.0,
+        reward_minutes: 5.0,
+        engaged_minutes: 120.0,
+        casual_minutes: 30.0,
+        casual_watch_share: 0.3,
+        waking_minutes: 960.0,
+    }
+}
+
+fn targets() -> ad::Targets {
+    ad::Targets { engaged_band: (1.2, 1.3), max_ratio: 2.0, engaged_ads: 6.0 }
+}
+
+fn theta0() -> ad::Params {
+    ad::Params { multiplier: 2.0, cooldown_minutes: 10.0, daily_cap: 10.0 }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Progress ratio — ad income over play income
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn progress_ratio_adds_ad_income_to_play_income() {
+    assert_eq!(ad::progress_ratio(0.0, 500.0, 100.0, 120.0), 1.0);
+    // 6 ads of 500 on top of 120 minutes at 100/min: +25%.
+    assert!((ad::progress_ratio(6.0, 500.0, 100.0, 120.0) - 1.25).abs() < 1e-12);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Balance — engaged +20–30%, nobody above 2×
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn ads_boost_engaged_players_inside_the_band() {
+    let out = ad::Runner::new(theta0(), env(), targets()).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!((1.2..=1.3).contains(&out.obs.engaged_ratio), "{:?}", out.obs);
+    assert!(out.obs.max_ratio <= 2.01, "{:?}", out.obs);
+    // 120 min / 6 ads = 20 min cooldown; 500 per ad = 1× five minutes; cap 24.
+    assert!((out.theta.cooldown_minutes / 20.0 - 1.0).abs() < 0.06, "{:?}", out.theta);
+    assert!((out.theta.multiplier - 1.0).abs() < 0.06, "{:?}", out.theta);
+    assert!((out.theta.daily_cap / 24.0 - 1.0).abs() < 0.06, "{:?}", out.theta);
+    // Casual players see a smaller boost: 0.3 · 1.5 ads over 30 minutes.
+    assert!(out.obs.casual_ratio > 1.0 && out.obs.casual_ratio < out.obs.engaged_ratio, "{:?}", out.obs);
+    assert_eq!(out.residuals.len(), 3);
+}
+
+#[test]
+fn short_days_leave_the_cap_slack() {
+    // Only 8 hours awake: 24 cooldowns of 20 minutes, so the cap never binds.
+    let env = ad::Env { waking_minutes: 480.0, ..env() };
+    let out = ad::Runner::new(theta0(), env, targets()).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!(out.obs.max_ratio <= 2.01 && out.obs.max_ads <= out.theta.daily_cap + 1e-9, "{:?}", out.obs);
+}
+
+struct AdBoostWeekend;
+impl Hook<ad::Params, ad::Env, ad::Targets, ad::Obs> for AdBoostWeekend {
+    fn income_multiplier(&mut self, _base: f64, _th: &ad::Params, _env: &ad::Env) -> f64 {
+        1.5
+    }
+}
+
+#[test]
+fn reward_hooks_shrink_the_multiplier() {
+    let out = ad::Runner::new(theta0(), env(), targets()).hook(AdBoostWeekend).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!((out.theta.multiplier * 1.5 - 1.0).abs() < 0.08, "{:?}", out.theta);
+    assert!((out.obs.reward_per_ad / 500.0 - 1.0).abs() < 0.08, "{:?}", out.obs);
+}
+
+#[test]
+fn try_run_rejects_a_ceiling_below_the_band() {
+    let tgt = ad::Targets { max_ratio: 1.1, ..targets() };
+    let env = ad::Env { casual_watch_share: 1.5, ..env() };
+    let err = ad::Runner::new(theta0(), env, tgt).try_run().unwrap_err();
+    let game_balance::Error::Invalid(problems) = err else { panic!("expected Invalid") };
+    let fields: Vec<_> = problems.iter().map(|p| p.field).collect();
+    assert_eq!(fields, ["casual_watch_share", "max_ratio"]);
+}
@@ -0,0 +1,1091 @@
+// tests/sdk.rs
+use game_balance::mechanics::control;
+use game_balance::systems::sdk::{
+    audit_hooks, balance_with_hooks, compose_income, BalanceArena, Hook, MultMode, NominalTargets, Outcome, TargetAdjust,
+    Signals,
+};
+
+struct Income(f64);
+impl<T, E, G, O> Hook<T, E, G, O> for Income {
+    fn income_multiplier(&mut self, _base: f64, _th: &T, _env: &E) -> f64 {
+        self.0
+    }
+}
+
+struct AddIncome(f64);
+impl<T, E, G, O> Hook<T, E, G, O> for AddIncome {
+    fn income_multiplier(&mut self, _base: f64, _th: &T, _env: &E) -> f64 {
+        self.0
+    }
+    fn multiplier_mode(&self) -> MultMode {
+        MultMode::Additive
+    }
+}
+
+struct Nudge(TargetAdjust);
+impl<T, E, G, O> Hook<T, E, G, O> for Nudge {
+    fn adjust_targets(&mut self, _th: &T, _env: &E, _tgt: &G, _nom: &NominalTargets) -> TargetAdjust {
+        self.0
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Hook audit — per-hook factors and composed totals
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn audit_reports_each_hook_and_totals() {
+    let mut hooks: Vec<Box<dyn Hook<(), (), (), ()>>> = vec![
+        Box::new(Income(1.10)),
+        Box::new(Income(1.25)),
+        Box::new(Nudge(TargetAdjust { a: 1.0, b: 1.05, c: 1.5 })),
+    ];
+    let nom = NominalTargets { x: 1.0, y: 1.0, z: 1.0 };
+    let audit = audit_hooks(&mut hooks, 10.0, &(), &(), &(), &nom);
+
+    assert_eq!(audit.per_hook.len(), 3);
+    assert!((audit.per_hook[1].income_multiplier - 1.25).abs() < 1e-12);
+    assert!((audit.per_hook[2].adjust.c - 1.5).abs() < 1e-12);
+    assert!((audit.income_multiplier - 1.375).abs() < 1e-12);
+    assert!((audit.adjust.b - 1.05).abs() < 1e-12);
+    assert!((audit.adjust.c - 1.5).abs() < 1e-12);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Balance arena — reused cells give the same outcomes as fresh ones
+────────────────────────────────────────────────────────────────────────── */
+
+type ToyHooks = Vec<Box<dyn Hook<f64, (), f64, f64>>>;
+
+fn toy_fresh(target: f64, hooks: ToyHooks) -> Outcome<f64, f64> {
+    balance_with_hooks(
+        1.0,
+        (),
+        target,
+        (),
+        0.5,
+        hooks,
+        1_000,
+        |th, env, _tgt, hs| hs.iter_mut().fold(*th, |x, h| x * h.income_multiplier(x, th, env)),
+        |_th, _env, tgt, _o| NominalTargets { x: *tgt, y: 0.0, z: 0.0 },
+        |th, _b, k, nom, adj| control::approach(*th, nom.x * adj.a, *k, 0.0, 1e6),
+        |o, tgt| (o - tgt).abs() <= 1e-3,
+    )
+}
+
+#[test]
+fn arena_matches_fresh_harness() {
+    let mut arena = BalanceArena::new();
+    for (i, target) in [5.0, 12.0, 3.0].into_iter().enumerate() {
+        // The middle run doubles income and halves θ's target (same obs target).
+        let hooks = || -> ToyHooks {
+            if i == 1 {
+                vec![Box::new(Income(2.0)), Box::new(Nudge(TargetAdjust { a: 0.5, b: 1.0, c: 1.0 }))]
+            } else {
+                Vec::new()
+            }
+        };
+        let fresh = toy_fresh(target, hooks());
+        let reused = arena.balance_with_hooks(
+            1.0,
+            (),
+            target,
+            (),
+            0.5,
+            hooks(),
+            1_000,
+            |th, env, _tgt, hs| hs.iter_mut().fold(*th, |x, h| x * h.income_multiplier(x, th, env)),
+            |_th, _env, tgt, _o| NominalTargets { x: *tgt, y: 0.0, z: 0.0 },
+            |th, _b, k, nom, adj| control::approach(*th, nom.x * adj.a, *k, 0.0, 1e6),
+            |o, tgt| (o - tgt).abs() <= 1e-3,
+        );
+        assert!(fresh.converged && reused.converged);
+        assert_eq!(fresh.iters, reused.iters, "run {i}");
+        assert_eq!(fresh.theta, reused.theta, "run {i}");
+        assert_eq!(fresh.obs, reused.obs, "run {i}");
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Ordering — converged never reads the placeholder Obs::default()
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn converged_waits_for_first_simulate() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // Obs::default() == 0.0 sits inside the band for target 0, but θ₀ = 10
+    // is far off: an early "converged" would return θ almost untouched.
+    let sims = Rc::new(RefCell::new(0usize));
+    let first_seen = Rc::new(RefCell::new(None));
+    let (s, f) = (Rc::clone(&sims), Rc::clone(&first_seen));
+    let out = balance_with_hooks(
+        10.0,
+        (),
+        0.0,
+        (),
+        0.5,
+        Vec::new(),
+        1_000,
+        move |th: &f64, _env: &(), _tgt: &f64, _hs| {
+            *s.borrow_mut() += 1;
+            *th
+        },
+        |_th, _env, tgt, _o| NominalTargets { x: *tgt, y: 0.0, z: 0.0 },
+        |th, _b, k: &f64, nom, _adj| control::approach(*th, nom.x, *k, 0.0, 1e6),
+        move |o: &f64, tgt: &f64| {
+            f.borrow_mut().get_or_insert(*o);
+            (o - tgt).abs() <= 1e-3
+        },
+    );
+
+    assert_eq!(*first_seen.borrow(), Some(10.0), "first converged() call saw simulated obs");
+    assert!(out.converged && out.stable_after_converge && out.iters > 1);
+    // One simulate per iteration; the hold phase is opt-in.
+    assert_eq!(*sims.borrow(), out.iters);
+    assert!(out.theta.abs() <= 1e-3);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Multiplier modes — additive bonuses sum, multiplicative factors chain
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn additive_and_multiplicative_stacking() {
+    let mut chained: Vec<Box<dyn Hook<(), (), (), ()>>> = vec![Box::new(Income(1.10)), Box::new(Income(1.25))];
+    assert!((compose_income(100.0, &mut chained, &(), &()) - 137.5).abs() < 1e-9);
+
+    let mut summed: Vec<Box<dyn Hook<(), (), (), ()>>> = vec![Box::new(AddIncome(1.10)), Box::new(AddIncome(1.25))];
+    assert!((compose_income(100.0, &mut summed, &(), &()) - 135.0).abs() < 1e-9);
+
+    // (1 + 0.10 + 0.25) · 2.0
+    let mut mixed: Vec<Box<dyn Hook<(), (), (), ()>>> =
+        vec![Box::new(AddIncome(1.10)), Box::new(Income(2.0)), Box::new(AddIncome(1.25))];
+    assert!((compose_income(100.0, &mut mixed, &(), &()) - 270.0).abs() < 1e-9);
+
+    let nom = NominalTargets { x: 1.0, y: 1.0, z: 1.0 };
+    let audit = audit_hooks(&mut mixed, 100.0, &(), &(), &(), &nom);
+    assert_eq!(audit.per_hook[0].mode, MultMode::Additive);
+    assert_eq!(audit.per_hook[1].mode, MultMode::Multiplicative);
+    assert!((audit.income_multiplier - 2.7).abs() < 1e-12);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Hold phase — a pass through the band is not a stable equilibrium
+────────────────────────────────────────────────────────────────────────── */
+
+fn overshoot(k: f64, hold: usize) -> Outcome<f64, f64> {
+    // Gain k > 1 overshoots: the error is multiplied by (1 − k) per step.
+    // k = 1.9 shrinks it (stable); k = 2.1 grows it, so θ₀ starts inside the
+    // band and then spirals out.
+    BalanceArena::new().with_hold_iters(hold).balance_with_hooks(
+        4.0,
+        (),
+        5.0,
+        (),
+        k,
+        Vec::new(),
+        10_000,
+        |th: &f64, _env: &(), _tgt: &f64, _hs| *th,
+        |_th, _env, tgt, _o| NominalTargets { x: *tgt, y: 0.0, z: 0.0 },
+        |th, _b, k: &f64, nom, _adj| th + k * (nom.x - th),
+        |o: &f64, tgt: &f64| (o - tgt).abs() <= 1.0,
+    )
+}
+
+#[test]
+fn hold_phase_reports_stability() {
+    let damped = overshoot(1.9, 32);
+    assert!(damped.converged && damped.stable_after_converge);
+
+    let diverging = overshoot(2.1, 32);
+    assert!(diverging.converged, "passes through the band");
+    assert!(!diverging.stable_after_converge, "but does not stay there");
+
+    // Zero hold: any converged run counts as stable.
+    assert!(overshoot(2.1, 0).stable_after_converge);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Priorities, flat bonuses, additive target adjustments
+────────────────────────────────────────────────────────────────────────── */
+
+struct Flat(f64);
+impl<T, E, G, O> Hook<T, E, G, O> for Flat {
+    fn flat_income(&mut self, _th: &T, _env: &E) -> f64 {
+        self.0
+    }
+}
+
+struct AddNudge(TargetAdjust);
+impl<T, E, G, O> Hook<T, E, G, O> for AddNudge {
+    fn adjust_targets(&mut self, _th: &T, _env: &E, _tgt: &G, _nom: &NominalTargets) -> TargetAdjust {
+        self.0
+    }
+    fn adjust_mode(&self) -> MultMode {
+        MultMode::Additive
+    }
+}
+
+/// Records when it ran; runs at `.1`.
+struct Ordered(&'static str, i32, std::rc::Rc<std::cell::RefCell<Vec<&'static str>>>);
+impl<T, E, G, O> Hook<T, E, G, O> for Ordered {
+    fn income_multiplier(&mut self, _base: f64, _th: &T, _env: &E) -> f64 {
+        self.2.borrow_mut().push(self.0);
+        1.0
+    }
+    fn priority(&self) -> i32 {
+        self.1
+    }
+}
+
+#[test]
+fn flat_bonus_applies_before_percentages() {
+    // (100 + 5) · (1 + 0.10) · 2
+    let mut hooks: Vec<Box<dyn Hook<(), (), (), ()>>> =
+        vec![Box::new(AddIncome(1.10)), Box::new(Flat(5.0)), Box::new(Income(2.0))];
+    assert!((compose_income(100.0, &mut hooks, &(), &()) - 231.0).abs() < 1e-9);
+
+    let nom = NominalTargets { x: 1.0, y: 1.0, z: 1.0 };
+    let audit = audit_hooks(&mut hooks, 100.0, &(), &(), &(), &nom);
+    assert_eq!(audit.flat_income, 5.0);
+    assert_eq!(audit.per_hook[1].flat_income, 5.0);
+    assert!((audit.income_multiplier - 2.2).abs() < 1e-12);
+}
+
+#[test]
+fn cost_multipliers_chain_and_leave_income_alone() {
+    use game_balance::systems::sdk::compose_cost;
+
+    struct Cost(f64);
+    impl<T, E, G, O> Hook<T, E, G, O> for Cost {
+        fn cost_multiplier(&mut self, _th: &T, _env: &E) -> f64 {
+            self.0
+        }
+    }
+    // 0.8 · 0.5, income untouched; negative factors clamp to free.
+    let mut hooks: Vec<Box<dyn Hook<(), (), (), ()>>> = vec![Box::new(Cost(0.8)), Box::new(Cost(0.5))];
+    assert!((compose_cost(10.0, &mut hooks, &(), &()) - 4.0).abs() < 1e-12);
+    assert_eq!(compose_income(100.0, &mut hooks, &(), &()), 100.0);
+
+    let nom = NominalTargets { x: 1.0, y: 1.0, z: 1.0 };
+    let audit = audit_hooks(&mut hooks, 100.0, &(), &(), &(), &nom);
+    assert!((audit.cost_multiplier - 0.4).abs() < 1e-12);
+    assert_eq!(audit.per_hook[1].cost_multiplier, 0.5);
+
+    hooks.push(Box::new(Cost(-1.0)));
+    assert_eq!(compose_cost(10.0, &mut hooks, &(), &()), 0.0);
+}
+
+#[test]
+fn additive_target_adjustments_sum() {
+    use game_balance::systems::sdk::compose_adjust;
+
+    let nom = NominalTargets { x: 1.0, y: 1.0, z: 1.0 };
+    let up = TargetAdjust { a: 1.10, b: 1.0, c: 1.0 };
+    let more = TargetAdjust { a: 1.25, b: 1.0, c: 0.5 };
+    let mut chained: Vec<Box<dyn Hook<(), (), (), ()>>> = vec![Box::new(Nudge(up)), Box::new(Nudge(more))];
+    assert!((compose_adjust(&mut chained, &(), &(), &(), &nom).a - 1.375).abs() < 1e-12);
+
+    // (1 + 0.10 + 0.25) · 2, and c: (1 − 0.5) · 1.
+    let mut mixed: Vec<Box<dyn Hook<(), (), (), ()>>> = vec![
+        Box::new(AddNudge(up)),
+        Box::new(Nudge(TargetAdjust { a: 2.0, b: 1.0, c: 1.0 })),
+        Box::new(AddNudge(more)),
+    ];
+    let adj = compose_adjust(&mut mixed, &(), &(), &(), &nom);
+    assert!((adj.a - 2.7).abs() < 1e-12 && (adj.c - 0.5).abs() < 1e-12, "{adj:?}");
+    let audit = audit_hooks(&mut mixed, 1.0, &(), &(), &(), &nom);
+    assert!((audit.adjust.a - 2.7).abs() < 1e-12);
+    assert_eq!(audit.per_hook[0].adjust_mode, MultMode::Additive);
+}
+
+#[test]
+fn hooks_run_in_priority_order() {
+    use game_balance::systems::sdk::sort_hooks;
+
+    let log = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    let mut hooks: Vec<Box<dyn Hook<(), (), (), ()>>> = vec![
+        Box::new(Ordered("late", 10, log.clone())),
+        Box::new(Ordered("first", -1, log.clone())),
+        Box::new(Ordered("a", 0, log.clone())),
+        Box::new(Ordered("b", 0, log.clone())),
+    ];
+    let nom = NominalTargets { x: 1.0, y: 1.0, z: 1.0 };
+    let audit = audit_hooks(&mut hooks, 1.0, &(), &(), &(), &nom);
+    assert_eq!(*log.borrow(), ["first", "a", "b", "late"]);
+    assert_eq!(audit.per_hook[0].priority, 10, "per_hook keeps the given order");
+
+    log.borrow_mut().clear();
+    sort_hooks(&mut hooks);
+    compose_income(1.0, &mut hooks, &(), &());
+    assert_eq!(*log.borrow(), ["first", "a", "b", "late"]);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Borrowing closures — the harness runs against a caller-owned context
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn closures_borrow_caller_context() {
+    // A lookup table and a call counter living on this stack frame.
+    let table: Vec<f64> = (0..=100).map(|i| i as f64 * 0.5).collect();
+    let mut sims = 0usize;
+    let lookup = |x: f64| table[(x.round() as usize).min(100)];
+
+    let out = balance_with_hooks(
+        10.0,
+        (),
+        20.0,
+        (),
+        0.5,
+        Vec::new(),
+        1_000,
+        |th: &f64, _env: &(), _tgt: &f64, _hs| {
+            sims += 1;
+            lookup(*th)
+        },
+        |th, _env, tgt, o| NominalTargets { x: th * tgt / o.max(1e-9), y: 0.0, z: 0.0 },
+        |th, _b, k, nom, _adj| control::approach(*th, nom.x, *k, 0.0, 100.0),
+        |o, tgt| (o - tgt).abs() <= 0.5,
+    );
+    assert!(out.converged && (out.obs - 20.0).abs() <= 0.5, "{out:?}");
+    assert_eq!(sims, out.iters);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+System trait — registries of boxed systems, mocks, closure parity
+────────────────────────────────────────────────────────────────────────── */
+
+/// Proportional controller on obs = gain · θ.
+struct Linear {
+    gain: f64,
+    k: f64,
+}
+
+impl game_balance::systems::sdk::System for Linear {
+    type Params = f64;
+    type Env = ();
+    type Tgt = f64;
+    type Obs = f64;
+
+    fn name(&self) -> &str {
+        "linear"
+    }
+    fn simulate(&mut self, th: &f64, _env: &(), _tgt: &f64, _hooks: &mut [Box<dyn Hook<f64, (), f64, f64>>]) -> f64 {
+        self.gain * th
+    }
+    fn nominal(&mut self, th: &f64, _env: &(), tgt: &f64, o: &f64) -> NominalTargets {
+        NominalTargets { x: th * tgt / o.max(1e-9), y: 0.0, z: 0.0 }
+    }
+    fn step(&mut self, th: &f64, nom: NominalTargets, adj: TargetAdjust) -> f64 {
+        control::approach(*th, nom.x * adj.a, self.k, 0.0, 1e6)
+    }
+    fn converged(&mut self, o: &f64, tgt: &f64) -> bool {
+        (o - tgt).abs() <= 1e-3
+    }
+}
+
+/// Mock that reports success immediately and counts calls.
+#[derive(Default)]
+struct AlwaysDone {
+    sims: usize,
+}
+
+impl game_balance::systems::sdk::System for AlwaysDone {
+    type Params = f64;
+    type Env = ();
+    type Tgt = f64;
+    type Obs = f64;
+
+    fn simulate(&mut self, th: &f64, _env: &(), _tgt: &f64, _hooks: &mut [Box<dyn Hook<f64, (), f64, f64>>]) -> f64 {
+        self.sims += 1;
+        *th
+    }
+    fn nominal(&mut self, th: &f64, _env: &(), _tgt: &f64, _o: &f64) -> NominalTargets {
+        NominalTargets { x: *th, y: 0.0, z: 0.0 }
+    }
+    fn step(&mut self, th: &f64, _nom: NominalTargets, _adj: TargetAdjust) -> f64 {
+        *th
+    }
+    fn converged(&mut self, _o: &f64, _tgt: &f64) -> bool {
+        true
+    }
+}
+
+#[test]
+fn boxed_systems_run_from_a_registry() {
+    use game_balance::systems::sdk::{balance_system, System};
+
+    let mut registry: Vec<Box<dyn System<Params = f64, Env = (), Tgt = f64, Obs = f64>>> =
+        vec![Box::new(Linear { gain: 2.0, k: 0.5 }), Box::new(AlwaysDone::default())];
+    let names: Vec<&str> = registry.iter().map(|s| s.name()).collect();
+    assert_eq!(names[0], "linear");
+    assert!(names[1].ends_with("AlwaysDone"), "{}", names[1]);
+
+    let outs: Vec<_> = registry.iter_mut().map(|sys| balance_system(sys.as_mut(), 1.0, (), 10.0, Vec::new(), 1_000)).collect();
+    assert!(outs[0].converged && (outs[0].theta - 5.0).abs() < 1e-2, "{:?}", outs[0]);
+    assert_eq!((outs[1].iters, outs[1].theta), (1, 1.0));
+
+    // The closure API is the same loop.
+    let closures = toy_fresh(10.0, Vec::new());
+    let mut sys = Linear { gain: 1.0, k: 0.5 };
+    let traited = balance_system(&mut sys, 1.0, (), 10.0, Vec::new(), 1_000);
+    assert_eq!((closures.iters, closures.theta, closures.obs), (traited.iters, traited.theta, traited.obs));
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Hook registry — named hooks toggled and replaced between passes
+────────────────────────────────────────────────────────────────────────── */
+
+/// Income ×2 that counts how often it was asked.
+struct CountedDouble(std::rc::Rc<std::cell::Cell<usize>>);
+impl<T, E, G, O> Hook<T, E, G, O> for CountedDouble {
+    fn income_multiplier(&mut self, _base: f64, _th: &T, _env: &E) -> f64 {
+        self.0.set(self.0.get() + 1);
+        2.0
+    }
+}
+
+#[test]
+fn registry_toggles_hooks_between_passes() {
+    use game_balance::systems::sdk::HookRegistry;
+
+    let half = |a| TargetAdjust { a, b: 1.0, c: 1.0 };
+    let calls = std::rc::Rc::new(std::cell::Cell::new(0));
+    let mut reg: HookRegistry<f64, (), f64, f64> = HookRegistry::new();
+    assert!(!reg.register("double", Box::new(CountedDouble(calls.clone()))));
+    assert!(!reg.register("halve", Box::new(Nudge(half(0.5)))));
+    assert!(!reg.register("debug", Box::new(Income(1.0))));
+    reg.set_enabled("debug", false);
+    assert_eq!(reg.ids().collect::<Vec<_>>(), [("double", true), ("halve", true), ("debug", false)]);
+
+    // Pass 1: income ×2 with θ's target halved.
+    let with = toy_fresh(10.0, reg.active());
+    assert!(with.converged && (with.theta - 5.0).abs() < 1e-2, "{with:?}");
+    let after_first = calls.get();
+    assert!(after_first > 0);
+
+    // Pass 2: both off — the hook objects are kept, just not run.
+    reg.set_enabled("double", false);
+    reg.set_enabled("halve", false);
+    let without = toy_fresh(10.0, reg.active());
+    assert!(without.converged && (without.theta - 10.0).abs() < 1e-2, "{without:?}");
+    assert_eq!(calls.get(), after_first);
+
+    // Re-enabled, the counter keeps its state from pass 1.
+    reg.set_enabled("double", true);
+    reg.set_enabled("halve", true);
+    toy_fresh(10.0, reg.active());
+    assert!(calls.get() > after_first);
+
+    // Replace in place; unknown IDs are reported.
+    assert!(reg.register("double", Box::new(Income(4.0))));
+    assert!(reg.register("halve", Box::new(Nudge(half(0.25)))));
+    assert_eq!(reg.len(), 3);
+    assert!(!reg.set_enabled("missing", true));
+    assert!(reg.remove("debug") && !reg.remove("debug"));
+    let quad = toy_fresh(10.0, reg.active());
+    assert!(quad.converged && (quad.theta - 2.5).abs() < 1e-2, "{quad:?}");
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Signals — hooks read and publish the shared bus
+────────────────────────────────────────────────────────────────────────── */
+
+/// Publishes `cycle_minutes`, and records the `ref_income` it saw.
+struct Bus(f64, std::rc::Rc<std::cell::Cell<f64>>);
+impl<T, E, G, O> Hook<T, E, G, O> for Bus {
+    fn on_signals(&mut self, signals: &mut Signals) {
+        self.1.set(signals.ref_income);
+        signals.cycle_minutes = self.0;
+    }
+}
+
+#[test]
+fn hooks_share_the_arena_signal_bus() {
+    let seen = std::rc::Rc::new(std::cell::Cell::new(-1.0));
+    let mut arena = BalanceArena::new().with_signals(Signals { ref_income: 3.0, cycle_minutes: 0.0 });
+    let run = |arena: &mut BalanceArena<f64, (), f64, f64>, hooks: ToyHooks| {
+        arena.balance_with_hooks(
+            1.0,
+            (),
+            10.0,
+            (),
+            0.5,
+            hooks,
+            1_000,
+            |th, env, _tgt, hs| hs.iter_mut().fold(*th, |x, h| x * h.income_multiplier(x, th, env)),
+            |_th, _env, tgt, _o| NominalTargets { x: *tgt, y: 0.0, z: 0.0 },
+            |th, _b, k, nom, adj| control::approach(*th, nom.x * adj.a, *k, 0.0, 1e6),
+            |o, tgt| (o - tgt).abs() <= 1e-3,
+        )
+    };
+    let out = run(&mut arena, vec![Box::new(Bus(25.0, seen.clone()))]);
+    assert!(out.converged);
+    assert_eq!(seen.get(), 3.0);
+    assert_eq!((arena.signals().ref_income, arena.signals().cycle_minutes), (3.0, 25.0));
+
+    // The bus carries over to the next run on the arena.
+    arena.signals_mut().ref_income = 8.0;
+    run(&mut arena, vec![Box::new(Bus(40.0, seen.clone()))]);
+    assert_eq!((seen.get(), arena.signals().cycle_minutes), (8.0, 40.0));
+
+    // The free function starts from the default bus.
+    toy_fresh(10.0, vec![Box::new(Bus(1.0, seen.clone()))]);
+    assert_eq!(seen.get(), 0.0);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Chatter — a bang-bang step is reported as a period-2 cycle
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn limit_cycle_stops_the_run_early() {
+    use game_balance::systems::sdk::{Oscillation, CHATTER_REPEATS};
+
+    let out = balance_with_hooks(
+        1.0,
+        (),
+        10.0,
+        (),
+        (),
+        ToyHooks::new(),
+        1_000,
+        |th, _env, _tgt, _hs| *th,
+        |_th, _env, tgt, _o| NominalTargets { x: *tgt, y: 0.0, z: 0.0 },
+        |th, _b, _k, nom, _adj| if *th < nom.x { nom.x + 5.0 } else { nom.x - 5.0 },
+        |o, tgt| (o - tgt).abs() <= 1e-3,
+    );
+    assert!(!out.converged);
+    assert_eq!(out.oscillation, Some(Oscillation { period: 2, amplitude: 5.0, coord: 0 }));
+    assert_eq!(out.iters, 2 * CHATTER_REPEATS);
+
+    // A damped approach is never mistaken for a cycle.
+    let settled = toy_fresh(10.0, Vec::new());
+    assert!(settled.converged && settled.oscillation.is_none());
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Slew limits — RateLimiter hook and step_limited anti-windup
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn rate_limiter_caps_each_move() {
+    use game_balance::systems::sdk::RateLimiter;
+
+    /// Records θ after every earlier-priority `post_step`.
+    struct Seen(std::rc::Rc<std::cell::RefCell<Vec<f64>>>);
+    impl<E, G, O> Hook<f64, E, G, O> for Seen {
+        fn post_step(&mut self, th: &mut f64, _env: &E) {
+            self.0.borrow_mut().push(*th);
+        }
+        fn priority(&self) -> i32 {
+            1
+        }
+    }
+    let seen = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    let out = toy_fresh(10.0, vec![Box::new(RateLimiter::new(0.25, 1e-3)), Box::new(Seen(seen.clone()))]);
+    assert!(out.converged, "{out:?}");
+
+    let path = seen.borrow();
+    let mut prev = 1.0;
+    for &th in path.iter() {
+        assert!((th - prev).abs() <= 0.25 * f64::abs(prev) + 1e-12, "{prev} → {th}");
+        prev = th;
+    }
+    // 1 → 1.25 → 1.5625 …: the cap, not the 0.5 gain, sets the pace early on.
+    assert_eq!(path[0], 1.25);
+    assert!(out.iters > toy_fresh(10.0, Vec::new()).iters);
+}
+
+#[test]
+fn step_limited_freezes_pid_integral_while_capped() {
+    use control::{Controller, ControllerState};
+    use game_balance::systems::sdk::step_limited;
+
+    let pid = Controller::Pid { kp: 0.5, ki: 0.2, kd: 0.0 };
+    let mut st = ControllerState::default();
+    let mut x = 0.0;
+    for _ in 0..5 {
+        let next = step_limited(&pid, &mut st, x, 50.0, 1.0, (0.0, 100.0), 2.0);
+        assert!((next - x - 2.0).abs() < 1e-12, "{x} → {next}");
+        x = next;
+    }
+    assert_eq!(st.integral, 0.0, "capped steps must not wind up the integral");
+
+    // Uncapped, the same controller integrates from the first step.
+    let mut free = ControllerState::default();
+    pid.step(&mut free, 0.0, 50.0, 1.0, 0.0, 100.0);
+    assert!(free.integral > 0.0);
+
+    // Outside the bounds, the cap does not stop the pull back in.
+    let back = step_limited(&Controller::default(), &mut ControllerState::default(), 120.0, 50.0, 1.0, (0.0, 100.0), 2.0);
+    assert_eq!(back, 50.0);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Tolerances — AbsTol, RelTol, their union and Band
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn tolerance_types_match_hand_rolled_checks() {
+    use game_balance::systems::sdk::{within, AbsTol, Band, RelTol, Tolerance};
+
+    assert!(within(30.5, 30.0, RelTol(0.02)));
+    assert!(!within(30.7, 30.0, RelTol(0.02)));
+    assert!(within(0.905, 0.90, AbsTol(0.01)) && !within(0.92, 0.90, AbsTol(0.01)));
+
+    // ±2% of the target, never narrower than ±0.02: the old `0.02 · t.max(1)`.
+    let ttu = RelTol(0.02).or(AbsTol(0.02));
+    for (obs, target) in [(30.5, 30.0), (30.7, 30.0), (0.515, 0.5), (0.53, 0.5), (0.0, 0.0)] {
+        let old = f64::abs(obs - target) <= 0.02 * f64::max(target, 1.0);
+        assert_eq!(within(obs, target, ttu), old, "{obs} vs {target}");
+    }
+    assert!(!within(1e-9, 0.0, RelTol(0.5)), "a bare relative band is exact at 0");
+
+    let band = Band::from((4.0, 6.0));
+    assert!(band.contains(4.0) && band.contains(6.0) && !band.contains(6.01));
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Warm start — θ and hook state carry into the next run
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn warm_start_keeps_hook_state() {
+    /// Counts its simulates internally and publishes the tally as a signal.
+    struct Tally(usize);
+    impl<T, E, G, O> Hook<T, E, G, O> for Tally {
+        fn on_signals(&mut self, signals: &mut Signals) {
+            self.0 += 1;
+            signals.ref_income = self.0 as f64;
+        }
+    }
+
+    let mut arena = BalanceArena::new().with_hold_iters(0);
+    let run = |arena: &mut BalanceArena<f64, (), f64, f64>, theta0: f64, hooks: ToyHooks| {
+        arena.balance_with_hooks(
+            theta0,
+            (),
+            10.0,
+            (),
+            0.5,
+            hooks,
+            1_000,
+            |th, env, _tgt, hs| hs.iter_mut().fold(*th, |x, h| x * h.income_multiplier(x, th, env)),
+            |_th, _env, tgt, _o| NominalTargets { x: *tgt, y: 0.0, z: 0.0 },
+            |th, _b, k, nom, adj| control::approach(*th, nom.x * adj.a, *k, 0.0, 1e6),
+            |o, tgt| (o - tgt).abs() <= 1e-3,
+        )
+    };
+    let first = run(&mut arena, 1.0, vec![Box::new(Tally(0))]);
+    assert!(first.converged);
+    assert_eq!(arena.signals().ref_income, first.iters as f64);
+
+    let warm = first.clone().into_warm_start(&mut arena);
+    assert_eq!((warm.theta, warm.hooks.len()), (first.theta, 1));
+    let second = run(&mut arena, warm.theta, warm.hooks);
+    assert!(second.converged && second.iters < first.iters);
+    assert_eq!(arena.signals().ref_income, (first.iters + second.iters) as f64);
+
+    // Taken once; a second take finds nothing.
+    assert_eq!(arena.take_hooks().len(), 1);
+    assert!(arena.take_hooks().is_empty());
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Time budget — a run cut short by the wall clock says so
+────────────────────────────────────────────────────────────────────────── */
+
+#[cfg(feature = "timing")]
+#[test]
+fn exhausted_budget_returns_theta_so_far() {
+    use game_balance::TIME_CHECK_EVERY;
+    use std::time::Duration;
+
+    let run = |arena: &mut BalanceArena<f64, (), f64, f64>| {
+        arena.balance_with_hooks(
+            1.0,
+            (),
+            10.0,
+            (),
+            1e-4,
+            ToyHooks::new(),
+            100_000,
+            |th, _env, _tgt, _hs| *th,
+            |_th, _env, tgt, _o| NominalTargets { x: *tgt, y: 0.0, z: 0.0 },
+            |th, _b, k, nom, adj| control::approach(*th, nom.x * adj.a, *k, 0.0, 1e6),
+            |o, tgt| (o - tgt).abs() <= 1e-3,
+        )
+    };
+    let cut = run(&mut BalanceArena::new().with_budget(Duration::ZERO));
+    assert!(cut.budget_exhausted && !cut.converged);
+    assert_eq!(cut.iters, TIME_CHECK_EVERY);
+    assert!(cut.theta > 1.0 && cut.theta < 10.0, "{cut:?}");
+
+    let full = run(&mut BalanceArena::new().with_budget(Duration::from_secs(3_600)));
+    assert!(full.converged && !full.budget_exhausted);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Convergence score — weighted targets instead of an all-or-nothing band
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn scored_run_reports_per_target_breakdown() {
+    use game_balance::systems::sdk::{balance_scored, Closures, ConvergenceScore};
+
+    // θ = (a, b) chases (4, 9); a is weighted 10× heavier than b.
+    let score = |o: &[f64; 2], t: &[f64; 2]| {
+        ConvergenceScore::new(1e-4).with("a", o[0] / t[0] - 1.0, 10.0).with("b", o[1] / t[1] - 1.0, 1.0)
+    };
+    let closures = Closures {
+        bnd: (),
+        gains: 0.3,
+        simulate: |th: &[f64; 2], _env: &(), _tgt: &[f64; 2], _hs: &mut [_]| *th,
+        nominal: |_th: &[f64; 2], _env: &(), tgt: &[f64; 2], _o: &[f64; 2]| NominalTargets { x: tgt[0], y: tgt[1], z: 0.0 },
+        step: |th: &[f64; 2], _b: &(), k: &f64, nom: NominalTargets, _adj| {
+            [control::approach(th[0], nom.x, *k, 0.0, 1e3), control::approach(th[1], nom.y, *k, 0.0, 1e3)]
+        },
+    };
+    let out = balance_scored([1.0, 1.0], (), [4.0, 9.0], Vec::new(), 1_000, closures, score);
+    assert!(out.converged, "{out:?}");
+    let s = out.score.expect("scored runs report their score");
+    assert!(s.converged() && s.total() <= 1e-4);
+    assert_eq!(s.terms.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), ["a", "b"]);
+    assert_eq!(s.total(), s.terms.iter().map(|t| t.contribution()).sum::<f64>());
+
+    // Same residual, heavier weight: `a` would dominate the total.
+    let even = ConvergenceScore::new(0.0).with("a", 0.1, 10.0).with("b", 0.1, 1.0);
+    assert_eq!(even.worst().map(|t| t.name.as_str()), Some("a"));
+    assert!(!even.converged());
+
+    // Band-based runs carry no score.
+    assert!(toy_fresh(10.0, Vec::new()).score.is_none());
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Hook state — a drafted stack survives save → new session → load
+────────────────────────────────────────────────────────────────────────── */
+
+#[cfg(feature = "serde")]
+#[test]
+fn hook_state_saves_and_restores_with_the_stack() {
+    use game_balance::systems::sdk::{load_hook_states, save_hook_states, HookState};
+
+    /// Pity counter: a growing income bonus for every simulate seen.
+    struct Pity(u32);
+    impl HookState for Pity {
+        type State = u32;
+        fn state(&self) -> u32 {
+            self.0
+        }
+        fn restore(&mut self, state: u32) {
+            self.0 = state;
+        }
+    }
+    impl<T, E, G, O> Hook<T, E, G, O> for Pity {
+        fn on_signals(&mut self, _signals: &mut Signals) {
+            self.0 += 1;
+        }
+        fn save_state(&self) -> Option<serde_json::Value> {
+            HookState::save(self)
+        }
+        fn load_state(&mut self, state: serde_json::Value) -> Result<(), game_balance::Error> {
+            HookState::load(self, state)
+        }
+    }
+
+    let mut arena = BalanceArena::new().with_hold_iters(0);
+    let hooks: ToyHooks = vec![Box::new(Flat(1.0)), Box::new(Pity(0))];
+    let out = arena.balance_with_hooks(
+        1.0,
+        (),
+        10.0,
+        (),
+        0.5,
+        hooks,
+        1_000,
+        |th, _env, _tgt, _hs| *th,
+        |_th, _env, tgt, _o| NominalTargets { x: *tgt, y: 0.0, z: 0.0 },
+        |th, _b, k, nom, adj| control::approach(*th, nom.x * adj.a, *k, 0.0, 1e6),
+        |o, tgt| (o - tgt).abs() <= 1e-3,
+    );
+    let hooks = arena.take_hooks();
+    let json = save_hook_states(&hooks).unwrap();
+    assert_eq!(json, format!("[null,{}]", out.iters));
+
+    // A new session rebuilds the same stack, then loads the save into it.
+    let mut fresh: ToyHooks = vec![Box::new(Flat(1.0)), Box::new(Pity(0))];
+    load_hook_states(&mut fresh, &json).unwrap();
+    assert_eq!(save_hook_states(&fresh).unwrap(), json);
+
+    let mut short: ToyHooks = vec![Box::new(Pity(0))];
+    assert!(load_hook_states(&mut short, &json).is_err());
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Stepper — one iteration at a time, targets moved between steps
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn stepper_matches_the_harness_and_follows_live_edits() {
+    use game_balance::systems::sdk::{balance_system, stepper_with_hooks, Closures, SystemStepper};
+
+    let full = balance_system(&mut Linear { gain: 2.0, k: 0.5 }, 1.0, (), 10.0, Vec::new(), 1_000);
+    let mut stepper = SystemStepper::new(Linear { gain: 2.0, k: 0.5 }, 1.0, (), 10.0, Vec::new());
+    assert_eq!((stepper.iters(), *stepper.obs()), (0, 0.0));
+
+    // The first step observes θ₀ and moves it.
+    assert_eq!(*stepper.step_once(), 2.0);
+    assert!(*stepper.theta() > 1.0 && !stepper.converged());
+    assert!(stepper.step_until_converged(1_000));
+    let out = stepper.outcome();
+    assert_eq!((out.iters, out.theta, out.obs), (full.iters, full.theta, full.obs));
+
+    // A slider moves the target: the next steps chase it from where θ is.
+    *stepper.tgt_mut() = 20.0;
+    stepper.step_once();
+    assert!(!stepper.converged());
+    assert!(stepper.step_until_converged(1_000));
+    assert!((stepper.obs() - 20.0).abs() <= 1e-3);
+
+    // Closure systems step the same way and hand θ + hooks back.
+    let mut closures = stepper_with_hooks(
+        1.0,
+        (),
+        10.0,
+        vec![Box::new(Flat(1.0))],
+        Closures {
+            bnd: (),
+            gains: 0.5,
+            simulate: |th: &f64, _env: &(), _tgt: &f64, _hs: &mut [_]| *th,
+            nominal: |_th: &f64, _env: &(), tgt: &f64, _o: &f64| NominalTargets { x: *tgt, y: 0.0, z: 0.0 },
+            step: |th: &f64, _b: &(), k: &f64, nom: NominalTargets, adj: TargetAdjust| {
+                control::approach(*th, nom.x * adj.a, *k, 0.0, 1e6)
+            },
+        },
+        |o, tgt| (o - tgt).abs() <= 1e-3,
+    );
+    closures.set_theta(9.0);
+    assert_eq!(*closures.step_once(), 9.0);
+    let warm = closures.into_warm_start();
+    assert_eq!((warm.theta, warm.hooks.len()), (9.5, 1));
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+define_system! — scaffolding from a compact spec
+────────────────────────────────────────────────────────────────────────── */
+
+mod toll {
+    use game_balance::mechanics::control;
+    use game_balance::systems::sdk::{balance_with_hooks, Hook, NominalTargets, Outcome};
+    use game_balance::{Problem, Validate};
+
+    game_balance::define_system! {
+        params {
+            /// Price per crossing.
+            price: price_min..price_max = (0.0, 10.0),
+            rate: rate_min..rate_max = (1.0, 1.0),
+        }
+        env { traffic: f64 }
+        targets { revenue: f64 }
+        obs { revenue: f64 }
+        gains { k_price = 0.5 }
+    }
+
+    impl Validate for Env {
+        fn problems(&self) -> Vec<Problem> {
+            game_balance::error::Checks::default().positive("traffic", self.traffic).done()
+        }
+    }
+    impl Validate for Targets {
+        fn problems(&self) -> Vec<Problem> {
+            game_balance::error::Checks::default().positive("revenue", self.revenue).done()
+        }
+    }
+
+    pub struct StandardModel;
+    impl SimModel for StandardModel {
+        fn observe(&self, th: &Params, env: &Env, _tgt: &Targets, _hs: &mut [Box<dyn Hook<Params, Env, Targets, Obs>>]) -> Obs {
+            Obs { revenue: th.price * env.traffic }
+        }
+    }
+
+    pub fn balance_ext(theta0: Params, env: Env, tgt: Targets, opts: Options<impl SimModel>) -> Outcome<Params, Obs> {
+        let Options { bounds, gains, mechs, model, max_iters, .. } = opts;
+        balance_with_hooks(
+            theta0,
+            env,
+            tgt,
+            bounds,
+            gains,
+            mechs.into_iter().map(|m| m as Box<dyn Hook<_, _, _, _>>).collect(),
+            max_iters,
+            move |th, env, tgt, hs| model.observe(th, env, tgt, hs),
+            |th, _env, tgt, o| NominalTargets { x: th.price * tgt.revenue / o.revenue.max(1e-9), y: 0.0, z: 0.0 },
+            |th, b, g, nom, adj| Params {
+                price: control::approach(th.price, nom.x * adj.a, g.k_price, b.price_min, b.price_max),
+                ..*th
+            },
+            |o, tgt| (o.revenue - tgt.revenue).abs() <= 1e-3,
+        )
+    }
+}
+
+#[test]
+fn define_system_generates_a_working_system() {
+    use game_balance::{Flat as _, Validate as _};
+
+    let th = toll::Params { price: 2.0, rate: 1.0 };
+    assert_eq!(th.flatten(), vec![2.0, 1.0]);
+    assert_eq!(th.unflatten(&[3.0, 4.0]), toll::Params { price: 3.0, rate: 4.0 });
+
+    let bounds = toll::Bounds::soft();
+    assert_eq!((bounds.price_min, bounds.price_max, bounds.rate_min, bounds.rate_max), (0.0, 10.0, 1.0, 1.0));
+    assert!(bounds.validate().is_ok());
+    let bad = toll::Bounds { rate_min: 2.0, ..bounds };
+    assert_eq!(bad.problems().iter().map(|p| p.field).collect::<Vec<_>>(), ["rate"]);
+
+    let env = toll::Env { traffic: 5.0 };
+    let out = toll::Runner::new(th, env.clone(), toll::Targets { revenue: 20.0 }).max_iters(1_000).run();
+    assert!(out.converged);
+    assert!((out.theta.price - 4.0).abs() < 1e-3, "{:?}", out.theta);
+
+    // Options directly: same run without the builder.
+    let opts = toll::Options { max_iters: 1_000, ..toll::Options::default() };
+    let direct = toll::balance_ext(th, env, toll::Targets { revenue: 20.0 }, opts);
+    assert_eq!(direct.theta, out.theta);
+}
+
+#[test]
+fn define_system_try_run_reports_every_problem_at_once() {
+    let th = toll::Params { price: 2.0, rate: 1.0 };
+    let bounds = toll::Bounds { rate_min: 2.0, ..toll::Bounds::soft() };
+    let err = toll::Runner::new(th, toll::Env { traffic: 0.0 }, toll::Targets { revenue: -1.0 })
+        .bounds(bounds)
+        .try_run()
+        .unwrap_err();
+    let game_balance::Error::Invalid(problems) = err else { panic!("expected Invalid") };
+    // Env, then targets, then bounds; nothing runs.
+    assert_eq!(problems.iter().map(|p| p.field).collect::<Vec<_>>(), ["traffic", "revenue", "rate"]);
+
+    let ok = toll::Runner::new(th, toll::Env { traffic: 5.0 }, toll::Targets { revenue: 20.0 }).max_iters(1_000);
+    assert!(ok.try_run().unwrap().converged);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Autotune — probe gain factors, back off from the oscillating one
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn autotune_picks_gains_under_the_ultimate_gain() {
+    use game_balance::systems::sdk::{autotune_gains, Closures};
+
+    // Error feedback on obs = 4·θ: θ' = θ + k·(tgt − obs) is stable for
+    // 0 < 4k < 2 and settles in one step at 4k = 1.
+    let tune = |base: f64| {
+        let closures = Closures {
+            bnd: (0.0, 100.0),
+            gains: base,
+            simulate: |th: &f64, _env: &(), _tgt: &f64, _hs: &mut [Box<dyn Hook<f64, (), f64, f64>>]| 4.0 * th,
+            nominal: |th: &f64, _env: &(), tgt: &f64, o: &f64| NominalTargets { x: th + (tgt - o), y: 0.0, z: 0.0 },
+            step: |th: &f64, b: &(f64, f64), k: &f64, nom: NominalTargets, adj: TargetAdjust| {
+                control::approach(*th, nom.x * adj.a, *k, b.0, b.1)
+            },
+        };
+        autotune_gains(1.0, &(), &10.0, closures, |k: &f64, f| k * f, |o, tgt| (o - tgt).abs() <= 1e-6)
+    };
+    // k = 0.5 oscillates (4k = 2); the pick stays at half of that.
+    assert_eq!(tune(0.5), 0.25);
+    // A base far too hot is scaled down to the same stable region.
+    let k = tune(4.0);
+    assert!(4.0 * k < 2.0 && 4.0 * k >= 0.25, "k = {k}");
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Explain — one-line summary from status, residuals and score
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn explain_summarizes_status_and_residuals() {
+    use game_balance::systems::sdk::{Oscillation, Residual};
+
+    let done = toy_fresh(10.0, Vec::new());
+    assert_eq!(done.explain(), format!("converged in {} iters", done.iters));
+
+    let out = Outcome {
+        iters: 3_214,
+        residuals: vec![Residual::new("ttu", 29.7, 30.0), Residual::new("util", 0.8912, 0.9)],
+        ..done.clone()
+    };
+    assert_eq!(out.explain(), "converged in 3,214 iters; ttu 29.7 vs target 30; util 0.8912 vs target 0.9");
+
+    let failed = Outcome { converged: false, stable_after_converge: false, iters: 1_200_000, ..done.clone() };
+    assert_eq!(failed.explain(), "not converged after 1,200,000 iters");
+    let chatter = Outcome {
+        converged: false,
+        oscillation: Some(Oscillation { period: 2, amplitude: 0.125, coord: 1 }),
+        ..done.clone()
+    };
+    assert!(chatter.explain().starts_with("oscillating after"), "{}", chatter.explain());
+    assert!(chatter.explain().ends_with("(period 2, θ[1] ±0.125)"), "{}", chatter.explain());
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Sweep — one run per target on a grid, in grid order
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn sweep_tabulates_theta_over_a_target_grid() {
+    use game_balance::systems::sdk::{linspace, sweep, sweep_with};
+
+    assert_eq!(linspace(10.0, 40.0, 4), vec![10.0, 20.0, 30.0, 40.0]);
+    assert_eq!(linspace(5.0, 9.0, 1), vec![5.0]);
+
+    let grid = linspace(10.0, 120.0, 12);
+    let outs = sweep(&mut Linear { gain: 2.0, k: 0.5 }, &1.0, &(), grid.clone(), 1_000);
+    assert_eq!(outs.len(), 12);
+    for (tgt, out) in grid.iter().zip(&outs) {
+        assert!(out.converged, "target {tgt}");
+        assert!((out.theta - tgt / 2.0).abs() < 1e-2, "target {tgt}: θ = {}", out.theta);
+    }
+
+    let closures = sweep_with(grid, |tgt| toy_fresh(tgt, Vec::new()));
+    assert!(closures.iter().all(|o| o.converged));
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_sweep_matches_sequential() {
+    use game_balance::systems::sdk::{linspace, sweep, sweep_par};
+
+    #[derive(Clone)]
+    struct Sync2(f64);
+    impl game_balance::systems::sdk::System for Sync2 {
+        type Params = f64;
+        type Env = ();
+        type Tgt = f64;
+        type Obs = f64;
+        fn simulate(&mut self, th: &f64, _env: &(), _tgt: &f64, _hooks: &mut [Box<dyn Hook<f64, (), f64, f64>>]) -> f64 {
+            self.0 * th
+        }
+        fn nominal(&mut self, th: &f64, _env: &(), tgt: &f64, o: &f64) -> NominalTargets {
+            NominalTargets { x: th * tgt / o.max(1e-9), y: 0.0, z: 0.0 }
+        }
+        fn step(&mut self, th: &f64, nom: NominalTargets, adj: TargetAdjust) -> f64 {
+            control::approach(*th, nom.x * adj.a, 0.5, 0.0, 1e6)
+        }
+        fn converged(&mut self, o: &f64, tgt: &f64) -> bool {
+            (o - tgt).abs() <= 1e-3
+        }
+    }
+
+    let grid = linspace(10.0, 120.0, 23);
+    let par = sweep_par(&Sync2(3.0), &1.0, &(), grid.clone(), 1_000);
+    let seq = sweep(&mut Sync2(3.0), &1.0, &(), grid, 1_000);
+    let key = |o: &Outcome<f64, f64>| (o.iters, o.theta);
+    assert_eq!(par.iter().map(key).collect::<Vec<_>>(), seq.iter().map(key).collect::<Vec<_>>());
+}
@@ -0,0 +1,27 @@
+// tests/selftest.rs
+use game_balance::selftest;
+
+#[test]
+fn selftest_reports_failures_by_system_name() {
+    let names = [
+        "production_spend",
+        "upgrade_cost_curve",
+        "reset_prestige",
+        "offline_accumulation",
+        "shop_pricing",
+    ];
+    match selftest() {
+        Ok(()) => {}
+        Err(failures) => {
+            for f in &failures {
+                assert!(names.iter().any(|n| f.starts_with(n)), "unnamed failure: {f}");
+            }
+            for healthy in ["production_spend", "offline_accumulation", "shop_pricing"] {
+                assert!(
+                    !failures.iter().any(|f| f.starts_with(healthy)),
+                    "{healthy} failed: {failures:?}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+// tests/shop_pricing.rs
+use game_balance::systems::shop_pricing as shop;
+
+/* ──────────────────────────────────────────────────────────────────────────
+Shop pricing — prices settle on a 10-minute cadence with ×1.5 per tier
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn prices_hit_purchase_cadence() {
+    let env = shop::Env { income_per_sec: 5.0, shop_share: 0.2 };
+    let tgt = shop::Targets { cadence_minutes: 10.0, cadence_slope: 1.5, max_hoard: 0.1 };
+    let out = shop::balance_ext(
+        shop::Params { prices: vec![100.0, 100.0, 100.0, 100.0], restock_minutes: Vec::new() },
+        env,
+        tgt,
+        shop::Options { max_iters: 10_000, ..Default::default() },
+    );
+
+    assert!(out.converged, "not converged: {:?}", out.obs);
+    // 5/s · 20% · 60 = 60 per minute → first item ≈ 600.
+    assert!((out.theta.prices[0] - 600.0).abs() <= 0.05 * 600.0, "{:?}", out.theta.prices);
+    for w in out.obs.minutes_between.windows(2) {
+        assert!((w[1] / w[0] - 1.5).abs() < 0.1, "cadence slope off: {:?}", out.obs.minutes_between);
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Restocks — stock absorbs all but `max_hoard` of the shop budget
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn hoarded_share_is_budget_the_stock_cannot_absorb() {
+    // Two items bought every 10 and 20 minutes, restocked every 40: 1/4 + 1/2 absorbed.
+    assert_eq!(shop::hoarded_share(&[10.0, 20.0], &[40.0, 40.0]), 0.25);
+    // Restocks faster than the cadence never hoard.
+    assert_eq!(shop::hoarded_share(&[10.0, 20.0], &[5.0, 5.0]), 0.0);
+    // Unlimited stock (no restock intervals) absorbs everything.
+    assert_eq!(shop::hoarded_share(&[10.0, 20.0], &[]), 0.0);
+    // So does an item past the end of a partial list.
+    assert_eq!(shop::hoarded_share(&[10.0, 20.0], &[40.0]), 0.0);
+}
+
+#[test]
+fn try_run_rejects_partial_restock_list() {
+    let tgt = shop::Targets { cadence_minutes: 10.0, cadence_slope: 1.5, max_hoard: 0.1 };
+    let theta0 = shop::Params { prices: vec![100.0; 3], restock_minutes: vec![60.0] };
+    let err = shop::Runner::new(theta0, shop::Env { income_per_sec: 5.0, shop_share: 0.2 }, tgt).try_run().unwrap_err();
+    let game_balance::Error::Invalid(problems) = err else { panic!("expected Invalid") };
+    assert_eq!(problems.iter().map(|p| p.field).collect::<Vec<_>>(), ["restock_minutes"]);
+}
+
+#[test]
+fn restocks_stretch_until_hoard_hits_the_cap() {
+    let tgt = shop::Targets { cadence_minutes: 10.0, cadence_slope: 1.5, max_hoard: 0.1 };
+    let theta0 = shop::Params { prices: vec![100.0; 3], restock_minutes: vec![5.0; 3] };
+    let out = shop::Runner::new(theta0, shop::Env { income_per_sec: 5.0, shop_share: 0.2 }, tgt).run();
+
+    assert!(out.converged, "{}", out.explain());
+    assert!(out.obs.hoarded_share <= 0.11, "{:?}", out.obs);
+    assert!((out.obs.spend_per_min / out.obs.save_per_min - (1.0 - out.obs.hoarded_share)).abs() < 1e-12);
+    // restock_i ≈ cadence_i · 3 / 0.9: the first item restocks every ~33 minutes.
+    assert!((out.theta.restock_minutes[0] / (100.0 / 3.0) - 1.0).abs() < 0.06, "{:?}", out.theta);
+    assert_eq!(out.residuals.len(), 4);
+}
+
+#[cfg(feature = "system-production_spend")]
+#[test]
+fn env_reads_income_from_production_spend() {
+    use game_balance::systems::production_spend as ps;
+
+    let th = ps::Params { gen_per_sec: 10.0, spend_rate: 8.0, multiplier: 1.0 };
+    let o = ps::Obs { ttu: 60.0, util: 0.8, growth: 1.0, surplus: 2.0, income: 10.0 };
+    let env = shop::Env::from_production(&th, &o);
+    assert_eq!(env.income_per_sec, 10.0);
+    assert!((env.shop_share - 0.2).abs() < 1e-12);
+}
+
+#[test]
+fn try_run_rejects_a_full_hoard_cap() {
+    let tgt = shop::Targets { cadence_minutes: 10.0, cadence_slope: 1.5, max_hoard: 1.0 };
+    let theta0 = shop::Params { prices: vec![100.0; 2], restock_minutes: vec![60.0; 2] };
+    let err = shop::Runner::new(theta0, shop::Env { income_per_sec: 5.0, shop_share: 0.2 }, tgt).try_run().unwrap_err();
+    let game_balance::Error::Invalid(problems) = err else { panic!("expected Invalid") };
+    assert_eq!(problems.iter().map(|p| p.field).collect::<Vec<_>>(), ["max_hoard"]);
+}
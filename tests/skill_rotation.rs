@@ -0,0 +1,89 @@
+// tests/skill_rotation.rs
+use game_balance::systems::sdk::Hook;
+use game_balance::systems::skill_rotation as rot;
+
+fn env() -> rot::Env {
+    rot::Env { gcd_seconds: 1.5, regen_per_sec: 10.0, resource_max: 100.0, horizon_seconds: 600.0 }
+}
+
+fn targets() -> rot::Targets {
+    rot::Targets { uptime_band: (0.55, 0.65), max_dead_per_min: 6.0 }
+}
+
+fn theta0() -> rot::Params {
+    rot::Params { cooldowns: vec![6.0, 10.0, 15.0], durations: vec![4.0, 6.0, 8.0], costs: vec![20.0, 30.0, 40.0] }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Simulation — exact single-ability rotations
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn cooldown_gaps_are_dead_time() {
+    // cast every 3s on a 1s GCD: 2s of every 3 are dead, the effect is up 2s
+    let th = rot::Params { cooldowns: vec![3.0], durations: vec![2.0], costs: vec![0.0] };
+    let env = rot::Env { gcd_seconds: 1.0, regen_per_sec: 1.0, resource_max: 0.0, horizon_seconds: 60.0 };
+    let o = rot::simulate(&th, &env, 1.0);
+    assert_eq!(o.casts, [20]);
+    assert!((o.apm - 20.0).abs() < 1e-9, "{o:?}");
+    assert!((o.dead_per_min - 40.0).abs() < 1e-6, "{o:?}");
+    assert!((o.rotation_uptime - 2.0 / 3.0).abs() < 1e-9, "{o:?}");
+    assert_eq!(o.starvation, 0.0);
+}
+
+#[test]
+fn waiting_on_resource_is_starvation() {
+    // 30 per cast at 5/s: one cast, then 5s of starvation, every 6s
+    let th = rot::Params { cooldowns: vec![1.0], durations: vec![0.0], costs: vec![30.0] };
+    let env = rot::Env { gcd_seconds: 1.0, regen_per_sec: 5.0, resource_max: 30.0, horizon_seconds: 60.0 };
+    let o = rot::simulate(&th, &env, 5.0);
+    assert_eq!(o.casts, [10]);
+    assert!((o.starvation - 50.0 / 60.0).abs() < 1e-6, "{o:?}");
+    assert!((o.dead_per_min - 50.0).abs() < 1e-6, "{o:?}");
+    assert!((o.demand_ratio - 6.0).abs() < 1e-9, "{o:?}");
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Balance — uptime band, dead GCD cap, resource neutral
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn rotation_fills_the_gcd_and_keeps_effects_up() {
+    let out = rot::Runner::new(theta0(), env(), targets()).run();
+    assert!(out.converged, "{}", out.explain());
+    let o = &out.obs;
+    assert!((0.55..=0.65).contains(&o.rotation_uptime), "{o:?}");
+    assert!(o.dead_per_min <= 6.5, "{o:?}");
+    assert!((o.demand_ratio - 1.0).abs() <= 0.05, "{o:?}");
+    // cooldowns shrink together to fill the GCD, keeping their ratios
+    let cd = &out.theta.cooldowns;
+    assert!(cd[0] < 6.0 && (cd[1] / cd[0] - 10.0 / 6.0).abs() < 0.01, "{cd:?}");
+    assert!(o.apm > 30.0, "{o:?}");
+    assert_eq!(out.residuals.len(), 3);
+}
+
+struct DoubleRegen;
+impl Hook<rot::Params, rot::Env, rot::Targets, rot::Obs> for DoubleRegen {
+    fn income_multiplier(&mut self, _base: f64, _th: &rot::Params, _env: &rot::Env) -> f64 {
+        2.0
+    }
+}
+
+#[test]
+fn regen_hooks_raise_costs() {
+    let plain = rot::Runner::new(theta0(), env(), targets()).run();
+    let fast = rot::Runner::new(theta0(), env(), targets()).hook(DoubleRegen).run();
+    assert!(fast.converged, "{}", fast.explain());
+    assert_eq!(fast.obs.regen_per_sec, 20.0);
+    let ratio = fast.theta.costs[0] / plain.theta.costs[0];
+    assert!((ratio - 2.0).abs() < 0.15, "{:?} vs {:?}", fast.theta, plain.theta);
+}
+
+#[test]
+fn try_run_rejects_a_full_minute_of_dead_time() {
+    let tgt = rot::Targets { uptime_band: (0.7, 0.6), max_dead_per_min: 60.0 };
+    let err = rot::Runner::new(theta0(), env(), tgt).try_run().unwrap_err();
+    let game_balance::Error::Invalid(problems) = err else { panic!("expected Invalid") };
+    let fields: Vec<_> = problems.iter().map(|p| p.field).collect();
+    assert_eq!(fields, ["uptime_band", "max_dead_per_min"]);
+}
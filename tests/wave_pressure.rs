@@ -0,0 +1,95 @@
+// tests/wave_pressure.rs
+use game_balance::systems::sdk::Hook;
+use game_balance::systems::wave_pressure as waves;
+
+/// 30 waves of towers growing 8% per wave; every 10th wave is a boss.
+fn env() -> waves::Env {
+    waves::Env {
+        tower_dps: (0..30).map(|w| 50.0 * 1.08f64.powi(w)).collect(),
+        first_count: 10.0,
+        first_hp: 200.0,
+        path_seconds: 20.0,
+        dps_spread: 0.3,
+        boss_every: 10,
+    }
+}
+
+fn targets() -> waves::Targets {
+    waves::Targets { leak_band: (0.02, 0.06), boss_window: (0.95, 1.05), last_wave_seconds: 40.0 }
+}
+
+fn theta0() -> waves::Params {
+    waves::Params { spacing: 1.0, hp_growth: 1.05, count_growth: 1.0, boss_hp_mult: 2.0 }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Pressure and leaks — tower time needed over tower time available
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn leak_share_averages_over_the_tower_spread() {
+    // 10 enemies of 200 HP at 50 DPS: 40 s of fire over 9 · 2 + 20 = 38 s.
+    assert!((waves::pressure(10.0, 200.0, 50.0, 2.0, 20.0) - 40.0 / 38.0).abs() < 1e-12);
+
+    // No spread: the fluid leak 1 − 1/p.
+    assert_eq!(waves::leak_share(0.9, 0.0), 0.0);
+    assert!((waves::leak_share(1.25, 0.0) - 0.2).abs() < 1e-12);
+    // With spread the weakest towers leak first, and the inverse round-trips.
+    assert_eq!(waves::leak_share(0.7, 0.3), 0.0);
+    assert!(waves::leak_share(0.9, 0.3) > 0.0);
+    let p = waves::pressure_for_leak(0.04, 0.3);
+    assert!((waves::leak_share(p, 0.3) - 0.04).abs() < 1e-9 && p < 1.0, "{p}");
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Balance — small leaks on ordinary waves, boss waves at the edge
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn waves_hit_the_leak_band_and_boss_window() {
+    let out = waves::Runner::new(theta0(), env(), targets()).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!((0.02..=0.06).contains(&out.obs.leak_first) && (0.02..=0.06).contains(&out.obs.leak_last), "{:?}", out.obs);
+    assert!((0.95..=1.05).contains(&out.obs.boss_pressure), "{:?}", out.obs.boss_pressure);
+    assert!((out.obs.spawn_seconds[29] / 40.0 - 1.0).abs() <= 0.05, "{:?}", out.obs.spawn_seconds);
+    // A smooth tower curve keeps every ordinary wave close to the band.
+    assert!(out.obs.worst_leak < 0.08, "{:?}", out.obs);
+    // Boss waves press harder than their neighbours.
+    assert!(out.obs.pressure[9] > out.obs.pressure[8] && out.obs.pressure[19] > out.obs.pressure[20]);
+    assert_eq!(out.residuals.len(), 4);
+}
+
+#[test]
+fn without_bosses_the_multiplier_is_kept() {
+    let env = waves::Env { boss_every: 0, ..env() };
+    let out = waves::Runner::new(theta0(), env, targets()).run();
+    assert!(out.converged, "{}", out.explain());
+    assert_eq!(out.theta.boss_hp_mult, 2.0);
+    assert_eq!(out.obs.boss_pressure, 0.0);
+    assert_eq!(out.residuals.len(), 3);
+}
+
+struct TowerBuff;
+impl Hook<waves::Params, waves::Env, waves::Targets, waves::Obs> for TowerBuff {
+    fn income_multiplier(&mut self, _base: f64, _th: &waves::Params, _env: &waves::Env) -> f64 {
+        1.2
+    }
+}
+
+#[test]
+fn tower_buffs_tighten_the_spacing() {
+    let base = waves::Runner::new(theta0(), env(), targets()).run();
+    let out = waves::Runner::new(theta0(), env(), targets()).hook(TowerBuff).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!(out.theta.spacing < base.theta.spacing, "{:?} vs {:?}", out.theta, base.theta);
+    assert!((out.obs.leak_first - base.obs.leak_first).abs() < 0.02);
+}
+
+#[test]
+fn try_run_rejects_all_boss_waves() {
+    let env = waves::Env { boss_every: 1, dps_spread: 1.0, ..env() };
+    let err = waves::Runner::new(theta0(), env, targets()).try_run().unwrap_err();
+    let game_balance::Error::Invalid(problems) = err else { panic!("expected Invalid") };
+    let fields: Vec<_> = problems.iter().map(|p| p.field).collect();
+    assert_eq!(fields, ["tower_dps", "boss_every", "dps_spread"]);
+}
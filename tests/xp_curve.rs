@@ -0,0 +1,78 @@
+// tests/xp_curve.rs
+use game_balance::systems::xp_curve as xp;
+use game_balance::systems::sdk::Hook;
+
+fn env() -> xp::Env {
+    xp::Env { levels: 60, activity_minutes: 2.0 }
+}
+
+fn targets() -> xp::Targets {
+    xp::Targets { minutes_band: (5.0, 120.0), hours_to_cap: 50.0, activity_share: 0.75 }
+}
+
+fn theta0() -> xp::Params {
+    xp::Params { xp_base: 100.0, xp_growth: 1.1, xp_per_activity: 20.0 }
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Curve math — geometric requirement, steepest growth that fits
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn steepest_growth_fills_the_band() {
+    // Flat: every level is the mean.
+    assert_eq!(xp::first_level_minutes(600.0, 1.0, 10), 60.0);
+    // Doubling: 1 + 2 + 4 = 7 parts.
+    assert!((xp::first_level_minutes(70.0, 2.0, 3) - 10.0).abs() < 1e-12);
+
+    // 60 levels over 50 h: the binding edge sits 5% inside the band.
+    let g = xp::steepest_growth(&env(), &targets(), 2.0);
+    let first = xp::first_level_minutes(3000.0, g, 60);
+    let last = first * g.powi(59);
+    assert!(g > 1.0 && first >= 5.0 * 1.05 - 1e-9 && last <= 120.0 / 1.05 + 1e-9, "first {first}, last {last}");
+    assert!((first / 5.25 - 1.0).abs() < 1e-6 || (last / (120.0 / 1.05) - 1.0).abs() < 1e-6);
+
+    // A band that misses the mean level time leaves the curve flat.
+    let narrow = xp::Targets { minutes_band: (60.0, 70.0), ..targets() };
+    assert_eq!(xp::steepest_growth(&env(), &narrow, 2.0), 1.0);
+}
+
+/* ──────────────────────────────────────────────────────────────────────────
+Balance — minutes per level, hours to cap and activity share
+────────────────────────────────────────────────────────────────────────── */
+
+#[test]
+fn curve_hits_minutes_band_and_hours_to_cap() {
+    let out = xp::Runner::new(theta0(), env(), targets(), 10.0).run();
+    assert!(out.converged, "{}", out.explain());
+    assert!((out.obs.hours_to_cap / 50.0 - 1.0).abs() <= 0.05, "{:?}", out.obs.hours_to_cap);
+    assert!(out.obs.fastest_level >= 5.0 && out.obs.slowest_level <= 120.0, "{:?}", out.obs);
+    // 75% of XP from activities next to 10 XP/min upstream: 30 XP/min, 60 per 2-minute activity.
+    assert!((out.theta.xp_per_activity / 60.0 - 1.0).abs() < 0.06, "{:?}", out.theta);
+    assert_eq!(out.obs.minutes_per_level.len(), 60);
+    assert_eq!(out.residuals.len(), 4);
+}
+
+#[test]
+fn without_upstream_xp_the_activity_reward_is_kept() {
+    let tgt = xp::Targets { activity_share: 1.0, ..targets() };
+    let out = xp::Runner::new(theta0(), env(), tgt, 0.0).run();
+    assert!(out.converged, "{}", out.explain());
+    assert_eq!(out.theta.xp_per_activity, 20.0);
+    assert_eq!(out.obs.activity_share, 1.0);
+}
+
+struct DoubleXp;
+impl Hook<xp::Params, xp::Env, xp::Targets, xp::Obs> for DoubleXp {
+    fn income_multiplier(&mut self, _base: f64, _th: &xp::Params, _env: &xp::Env) -> f64 {
+        2.0
+    }
+}
+
+#[test]
+fn xp_boosts_raise_the_requirement() {
+    let base = xp::Runner::new(theta0(), env(), targets(), 10.0).run();
+    let boosted = xp::Runner::new(theta0(), env(), targets(), 10.0).hook(DoubleXp).run();
+    assert!(boosted.converged, "{}", boosted.explain());
+    assert!((boosted.theta.xp_base / base.theta.xp_base - 2.0).abs() < 0.15, "{:?} vs {:?}", boosted.theta, base.theta);
+}